@@ -7,23 +7,26 @@ use arena_core::{
         inspector::{EmptyInspector, Inspector},
         Engine,
     },
+    error::ArenaError,
     feed::OrnsteinUhlenbeck,
     strategy::Strategy,
     AnvilProvider, Signal,
 };
 use async_trait::async_trait;
+use rand::rngs::StdRng;
 
 struct TemplateStrategy;
 
 #[async_trait]
 impl<T> Strategy<T> for TemplateStrategy {
     async fn init(
-        &self,
+        &mut self,
         provider: AnvilProvider,
         _signal: Signal,
         _inspector: &mut Box<dyn Inspector<T>>,
         engine: Engine,
-    ) {
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
         // provide a fixed amount of liquidity upon runtime initialization to the pool across the full tick range.
         engine
             .modify_liquidity(
@@ -34,15 +37,16 @@ impl<T> Strategy<T> for TemplateStrategy {
                 provider,
             )
             .await
-            .unwrap();
     }
     async fn process(
-        &self,
+        &mut self,
         _provider: AnvilProvider,
         _signal: Signal,
         _inspector: &mut Box<dyn Inspector<T>>,
         _engine: Engine,
-    ) {
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        Ok(())
     }
 }
 
@@ -57,7 +61,8 @@ async fn main() {
         .with_arbitrageur(Box::new(FixedArbitrageur {
             depth: Signed::try_from(10000).unwrap(),
         }))
-        .build();
+        .build()
+        .unwrap();
 
     arena
         .run(Config::new(
@@ -0,0 +1,103 @@
+#![warn(missing_docs)]
+//! Derive macros for arena-core.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, DeriveInput};
+
+/// Implements `Strategy` for a struct with inherent `on_init` and `on_step` methods.
+///
+/// Both methods are `async`, take `&mut self`, an `AnvilProvider`, a `Signal` and an `Engine`, and return
+/// `Result<(), ArenaError>`. The generated implementation works with any inspector, and the struct gains a `boxed`
+/// method returning it as a `Box<dyn Strategy<V>>`, ready for `ArenaBuilder::with_strategy`.
+///
+/// With `#[arena(from_config)]`, the struct also implements `FromConfig` using itself as its parameters, so it must
+/// implement `serde::Deserialize`.
+#[proc_macro_derive(ArenaStrategy, attributes(arena))]
+pub fn derive_arena_strategy(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut from_config = false;
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("arena"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("from_config") {
+                from_config = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported arena attribute"))
+            }
+        })?;
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut strategy_generics = input.generics.clone();
+    strategy_generics.params.push(parse_quote!(__V));
+    let (strategy_impl_generics, _, _) = strategy_generics.split_for_impl();
+
+    let mut tokens = quote! {
+        #[::arena_core::__private::async_trait]
+        impl #strategy_impl_generics ::arena_core::strategy::Strategy<__V> for #ident #ty_generics #where_clause {
+            async fn init(
+                &mut self,
+                provider: ::arena_core::AnvilProvider,
+                signal: ::arena_core::Signal,
+                _inspector: &mut ::std::boxed::Box<dyn ::arena_core::engine::inspector::Inspector<__V>>,
+                engine: ::arena_core::engine::Engine,
+                _rng: &mut ::arena_core::__private::StdRng,
+            ) -> ::std::result::Result<(), ::arena_core::error::ArenaError> {
+                self.on_init(provider, signal, engine).await
+            }
+
+            async fn process(
+                &mut self,
+                provider: ::arena_core::AnvilProvider,
+                signal: ::arena_core::Signal,
+                _inspector: &mut ::std::boxed::Box<dyn ::arena_core::engine::inspector::Inspector<__V>>,
+                engine: ::arena_core::engine::Engine,
+                _rng: &mut ::arena_core::__private::StdRng,
+            ) -> ::std::result::Result<(), ::arena_core::error::ArenaError> {
+                self.on_step(provider, signal, engine).await
+            }
+        }
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Returns this strategy boxed, ready to be added to an arena.
+            pub fn boxed<__V>(self) -> ::std::boxed::Box<dyn ::arena_core::strategy::Strategy<__V>>
+            where
+                Self: 'static,
+            {
+                ::std::boxed::Box::new(self)
+            }
+        }
+    };
+
+    if from_config {
+        tokens.extend(quote! {
+            impl #impl_generics ::arena_core::strategy::FromConfig for #ident #ty_generics #where_clause {
+                type Params = Self;
+
+                fn from_params(
+                    params: Self::Params,
+                ) -> ::std::result::Result<Self, ::arena_core::error::ArenaError> {
+                    ::std::result::Result::Ok(params)
+                }
+            }
+        });
+    }
+
+    Ok(tokens)
+}
@@ -0,0 +1,128 @@
+use async_trait::async_trait;
+use rand::rngs::StdRng;
+
+use crate::{
+    engine::{arbitrageur::Arbitrageur, noise::NoiseTrader, DeferredSwap, Engine},
+    error::ArenaError,
+    AnvilProvider, Signal,
+};
+
+/// A market participant acting once per step, such as a liquidator, keeper or noise trader, added to an arena without
+/// changing the arena itself.
+///
+/// Agents act after the feeds, arbitrageur and noise traders of a step and before strategies, in the order set when
+/// they are registered with [`crate::arena::ArenaBuilder::with_agent`], or in a random order drawn every step with
+/// [`crate::arena::ArenaBuilder::with_random_agent_order`]. Each agent acts from its own wallet, funded like a
+/// strategy's, and draws from its own random number stream.
+///
+/// Actors that only send a transaction when a condition is met, such as liquidators and keepers, can implement
+/// [`crate::keeper::Keeper`] instead and be added as a [`crate::keeper::KeeperAgent`].
+///
+/// Noise traders implement `Agent`, and an arbitrageur acts as one wrapped in an [`ArbitrageurAgent`], so either can
+/// be registered to act once per step at its position in the ordering. Registered with
+/// [`crate::arena::ArenaBuilder::with_noise_trader`] and [`crate::arena::ArenaBuilder::with_arbitrageur`] instead, they
+/// trade on every sub-tick of every pool. The just-in-time liquidity provider,
+/// [`crate::engine::jit::JitLiquidity`], is an agent watching swaps. Strategies keep a trait of their own,
+/// [`crate::strategy::Strategy`], since they log to the inspector, are scored and are isolated when they panic.
+#[async_trait]
+pub trait Agent: Send {
+    /// Initialize the agent, before the first step of a run.
+    async fn init(
+        &mut self,
+        _signal: &Signal,
+        _provider: AnvilProvider,
+        _engine: Engine,
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    /// Bid, in wei, for an early position in the order agents act in during a step, when the arena orders agents
+    /// through a [`crate::engine::auction::BlockBuilder`]. Agents bid nothing by default.
+    async fn bid(&mut self, _signal: &Signal, _rng: &mut StdRng) -> Result<u128, ArenaError> {
+        Ok(0)
+    }
+
+    /// Act on the state of the pool at a step.
+    async fn act(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError>;
+
+    /// Returns whether the agent watches the swaps of the other participants through [`Agent::before_swap`] and
+    /// [`Agent::after_swap`], as if reading them from the mempool. Agents do not by default.
+    ///
+    /// While an agent watches swaps, the swaps every other participant decides on through [`Engine::swap`] are held
+    /// back until it returns, see [`Engine::deferring`], and then sent one at a time between the calls of the watching
+    /// agents. Only the last arbitrage of a step in mempool mode is not watched.
+    fn watches_swaps(&self) -> bool {
+        false
+    }
+
+    /// React to `swap` right before it is sent through `engine`, on a pool whose feed is at `price`. Only called if
+    /// [`Agent::watches_swaps`] returns true.
+    async fn before_swap(
+        &mut self,
+        _swap: &DeferredSwap,
+        _price: f64,
+        _provider: AnvilProvider,
+        _engine: Engine,
+    ) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    /// React to `swap` right after it was sent through `engine`, on a pool whose feed is at `price`. Only called if
+    /// [`Agent::watches_swaps`] returns true.
+    async fn after_swap(
+        &mut self,
+        _swap: &DeferredSwap,
+        _price: f64,
+        _provider: AnvilProvider,
+        _engine: Engine,
+    ) -> Result<(), ArenaError> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Agent for NoiseTrader {
+    async fn act(
+        &mut self,
+        _signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        self.trade(&engine, provider, rng).await
+    }
+}
+
+/// An [`Arbitrageur`] acting as an [`Agent`], once per step at its position in the agent ordering rather than on every
+/// sub-tick.
+pub struct ArbitrageurAgent(pub Box<dyn Arbitrageur>);
+
+#[async_trait]
+impl Agent for ArbitrageurAgent {
+    async fn init(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        self.0.init(signal, provider, engine).await
+    }
+
+    async fn act(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        self.0.arbitrage(signal, provider, engine).await
+    }
+}
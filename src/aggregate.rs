@@ -0,0 +1,377 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{engine::inspector::SaveData, error::ArenaError};
+
+/// Name of the column holding the step of a row in the files ingested by an [`Aggregate`].
+pub const STEP_COLUMN: &str = "step";
+
+/// Name of the column holding the strategy or agent of a row in the files ingested by an [`Aggregate`], if any.
+pub const AGENT_COLUMN: &str = "agent";
+
+/// Series of an [`Aggregate`], a numeric column of the ingested files for one strategy or agent.
+type Key = (String, Option<String>);
+
+/// Summary statistics of the values a series took across runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Distribution {
+    /// Name of the column the values were read from.
+    pub series: String,
+
+    /// Strategy or agent the values were recorded for, or `None` for files without an agent column.
+    pub agent: Option<String>,
+
+    /// Number of runs with a value.
+    pub count: usize,
+
+    /// Mean of the values.
+    pub mean: f64,
+
+    /// Sample standard deviation of the values, zero for a single run.
+    pub std: f64,
+
+    /// Smallest value.
+    pub min: f64,
+
+    /// Lower bound of the band holding the central [`Aggregate::confidence`] share of the values.
+    pub low: f64,
+
+    /// Median of the values.
+    pub median: f64,
+
+    /// Upper bound of the band holding the central [`Aggregate::confidence`] share of the values.
+    pub high: f64,
+
+    /// Largest value.
+    pub max: f64,
+}
+
+impl Distribution {
+    /// Summarize `values`, with a band holding the central `confidence` share of them. Returns `None` without values.
+    fn new(key: &Key, values: &[f64], confidence: f64) -> Option<Self> {
+        let count = values.len();
+        if count == 0 {
+            return None;
+        }
+
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let mean = sorted.iter().sum::<f64>() / count as f64;
+        let variance = if count > 1 {
+            sorted
+                .iter()
+                .map(|value| (value - mean).powi(2))
+                .sum::<f64>()
+                / (count - 1) as f64
+        } else {
+            0.0
+        };
+        let tail = (1.0 - confidence) / 2.0;
+
+        Some(Self {
+            series: key.0.clone(),
+            agent: key.1.clone(),
+            count,
+            mean,
+            std: variance.sqrt(),
+            min: sorted[0],
+            low: quantile(&sorted, tail),
+            median: quantile(&sorted, 0.5),
+            high: quantile(&sorted, 1.0 - tail),
+            max: sorted[count - 1],
+        })
+    }
+
+    /// Returns the standard error of the mean.
+    pub fn stderr(&self) -> f64 {
+        self.std / (self.count as f64).sqrt()
+    }
+}
+
+/// Returns the `q` quantile of `sorted`, interpolating linearly between values.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let rank = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+
+    sorted[below] + (sorted[above] - sorted[below]) * (rank - below as f64)
+}
+
+/// Statistics of a series at one step, across runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepStats {
+    /// The step.
+    pub step: usize,
+
+    /// Distribution of the values of the series at the step.
+    pub distribution: Distribution,
+}
+
+/// Aggregation of the files written by inspectors over the runs of a Monte Carlo batch, into per-step and end-of-run
+/// statistics of every series.
+///
+/// Every ingested file is one run, in the CSV format of the inspectors of this crate: a [`STEP_COLUMN`], an optional
+/// [`AGENT_COLUMN`], and any other column holding the values of a series. Rows without a step, such as marks taken
+/// before the first step, and cells that are empty or not numbers are skipped. If a run has several values of a series
+/// at a step, the last one counts, and the end-of-run value of a series is its value at the last step of the run it
+/// has one at.
+#[derive(Debug, Clone)]
+pub struct Aggregate {
+    /// Share of the values held by the band between [`Distribution::low`] and [`Distribution::high`], e.g. 0.9 for a
+    /// band from the 5th to the 95th percentile.
+    pub confidence: f64,
+
+    runs: usize,
+    // value of every series at every step, for each run holding one
+    steps: BTreeMap<Key, BTreeMap<usize, Vec<f64>>>,
+    // value of every series at the end of each run holding one
+    finals: BTreeMap<Key, Vec<f64>>,
+}
+
+impl Default for Aggregate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Aggregate {
+    /// Public constructor function for a new, empty [`Aggregate`] with 90% bands.
+    pub fn new() -> Self {
+        Self {
+            confidence: 0.9,
+            runs: 0,
+            steps: BTreeMap::new(),
+            finals: BTreeMap::new(),
+        }
+    }
+
+    /// Report bands holding the central `confidence` share of the values.
+    pub fn with_confidence(mut self, confidence: f64) -> Self {
+        self.confidence = confidence.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Returns the number of runs ingested.
+    pub fn runs(&self) -> usize {
+        self.runs
+    }
+
+    /// Ingest the file of one run at `path`.
+    pub fn ingest_csv(&mut self, path: impl AsRef<Path>) -> Result<(), ArenaError> {
+        let mut reader = csv::Reader::from_reader(File::open(path)?);
+        let headers = reader.headers()?.clone();
+        let column = |name: &str| headers.iter().position(|header| header == name);
+        let Some(step_column) = column(STEP_COLUMN) else {
+            return Err(ArenaError::Custom(format!(
+                "missing `{STEP_COLUMN}` column"
+            )));
+        };
+        let agent_column = column(AGENT_COLUMN);
+
+        let mut run: BTreeMap<Key, BTreeMap<usize, f64>> = BTreeMap::new();
+        for record in reader.records() {
+            let record = record?;
+            let Some(step) = record
+                .get(step_column)
+                .and_then(|step| step.parse::<usize>().ok())
+            else {
+                continue;
+            };
+            let agent = agent_column
+                .and_then(|idx| record.get(idx))
+                .map(String::from);
+
+            for (idx, cell) in record.iter().enumerate() {
+                if idx == step_column || Some(idx) == agent_column {
+                    continue;
+                }
+                let Ok(value) = cell.parse::<f64>() else {
+                    continue;
+                };
+
+                run.entry((headers[idx].to_string(), agent.clone()))
+                    .or_default()
+                    .insert(step, value);
+            }
+        }
+
+        self.push_run(run);
+        Ok(())
+    }
+
+    /// Ingest the files written to `save_data` by an inspector, one per run, e.g. with [`SaveData::ToNewFile`] over a
+    /// batch. Returns the number of files ingested.
+    pub fn ingest_saved(&mut self, save_data: &SaveData) -> Result<usize, ArenaError> {
+        let (SaveData::ToFile { path, name } | SaveData::ToNewFile { path, name }) = save_data;
+
+        let mut files = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let file = entry?.path();
+            let Some(stem) = file.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+
+            let run = match save_data {
+                SaveData::ToFile { .. } => (stem == name).then_some(0),
+                SaveData::ToNewFile { .. } => stem
+                    .strip_prefix(name.as_str())
+                    .and_then(|suffix| suffix.strip_prefix('_'))
+                    .and_then(|idx| idx.parse::<usize>().ok()),
+            };
+            if let Some(run) = run.filter(|_| file.extension().is_some_and(|ext| ext == "csv")) {
+                files.push((run, file));
+            }
+        }
+
+        files.sort();
+        for (_, file) in &files {
+            self.ingest_csv(file)?;
+        }
+
+        Ok(files.len())
+    }
+
+    /// Add a run with the value of every series at every step.
+    fn push_run(&mut self, run: BTreeMap<Key, BTreeMap<usize, f64>>) {
+        for (key, points) in run {
+            if let Some((_, last)) = points.last_key_value() {
+                self.finals.entry(key.clone()).or_default().push(*last);
+            }
+
+            let steps = self.steps.entry(key).or_default();
+            for (step, value) in points {
+                steps.entry(step).or_default().push(value);
+            }
+        }
+
+        self.runs += 1;
+    }
+
+    /// Returns the statistics of every series at every step, by series, agent and step.
+    pub fn per_step(&self) -> Vec<StepStats> {
+        self.steps
+            .iter()
+            .flat_map(|(key, steps)| {
+                steps.iter().filter_map(|(step, values)| {
+                    Some(StepStats {
+                        step: *step,
+                        distribution: Distribution::new(key, values, self.confidence)?,
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the distribution of the end-of-run value of every series, by series and agent.
+    pub fn end_of_run(&self) -> Vec<Distribution> {
+        self.finals
+            .iter()
+            .filter_map(|(key, values)| Distribution::new(key, values, self.confidence))
+            .collect()
+    }
+
+    /// Write the statistics of every series at every step to `path` as CSV, with the columns `step`, `series`,
+    /// `agent`, `count`, `mean`, `std`, `min`, `low`, `median`, `high` and `max`.
+    pub fn write_per_step_csv(&self, path: impl AsRef<Path>) -> Result<(), ArenaError> {
+        let mut writer = csv::Writer::from_writer(File::create(path)?);
+        writer.write_record([
+            "step", "series", "agent", "count", "mean", "std", "min", "low", "median", "high",
+            "max",
+        ])?;
+
+        for stats in self.per_step() {
+            let distribution = stats.distribution;
+            writer.serialize((
+                stats.step,
+                distribution.series,
+                distribution.agent,
+                distribution.count,
+                distribution.mean,
+                distribution.std,
+                distribution.min,
+                distribution.low,
+                distribution.median,
+                distribution.high,
+                distribution.max,
+            ))?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Write the distribution of the end-of-run value of every series to `path` as CSV, with the columns of
+    /// [`Distribution`].
+    pub fn write_end_of_run_csv(&self, path: impl AsRef<Path>) -> Result<(), ArenaError> {
+        let mut writer = csv::Writer::from_writer(File::create(path)?);
+        for distribution in self.end_of_run() {
+            writer.serialize(distribution)?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregate() {
+        let path = std::env::temp_dir().join(format!("arena-aggregate-{}", std::process::id()));
+        fs::create_dir_all(&path).unwrap();
+        let save_data = SaveData::ToNewFile {
+            path: path.clone(),
+            name: "pnl".to_string(),
+        };
+
+        for run in 0..5 {
+            let pnl = run as f64;
+            fs::write(
+                save_data.file_path("csv").unwrap(),
+                format!(
+                    "step,agent,value,pnl\n,strategy 0,100,0\n0,strategy 0,101,{}\n1,strategy 0,102,{}\n",
+                    pnl,
+                    pnl * 2.0
+                ),
+            )
+            .unwrap();
+        }
+        fs::write(path.join("other.csv"), "step,value\n0,1\n").unwrap();
+
+        let mut aggregate = Aggregate::new().with_confidence(0.5);
+        assert_eq!(aggregate.ingest_saved(&save_data).unwrap(), 5);
+        assert_eq!(aggregate.runs(), 5);
+
+        let per_step = aggregate.per_step();
+        // two series over two steps, the row without a step skipped
+        assert_eq!(per_step.len(), 4);
+        let pnl = &per_step[0];
+        assert_eq!((pnl.step, pnl.distribution.series.as_str()), (0, "pnl"));
+        assert_eq!(pnl.distribution.agent.as_deref(), Some("strategy 0"));
+        assert_eq!(pnl.distribution.mean, 2.0);
+        assert_eq!((pnl.distribution.low, pnl.distribution.high), (1.0, 3.0));
+
+        let end = aggregate.end_of_run();
+        assert_eq!(end[0].median, 4.0);
+        assert_eq!((end[0].min, end[0].max), (0.0, 8.0));
+        assert!((end[0].std - 10f64.sqrt()).abs() < 1e-9);
+        assert_eq!(end[1].std, 0.0);
+
+        aggregate
+            .write_per_step_csv(path.join("per_step.csv"))
+            .unwrap();
+        aggregate
+            .write_end_of_run_csv(path.join("end_of_run.csv"))
+            .unwrap();
+        let written = fs::read_to_string(path.join("end_of_run.csv")).unwrap();
+        assert!(written.starts_with("series,agent,count,mean"));
+
+        fs::remove_dir_all(path).unwrap();
+    }
+}
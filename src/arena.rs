@@ -1,24 +1,143 @@
-use std::{collections::HashMap, time::Instant};
+use std::{
+    any::Any,
+    collections::{BTreeMap, HashMap},
+    panic::AssertUnwindSafe,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
 use alloy::{
+    dyn_abi::DynSolValue,
+    eips::BlockNumberOrTag,
+    primitives::{keccak256, utils::parse_ether, Address, B256, I256, U256},
     providers::{Provider, ProviderBuilder, WalletProvider},
+    rpc::{
+        client::ClientBuilder,
+        types::{Transaction, TransactionReceipt, TransactionRequest},
+    },
     signers::local::PrivateKeySigner,
 };
+use futures::FutureExt;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::time::MissedTickBehavior;
 
 use super::*;
+#[cfg(feature = "revm")]
+use crate::backend::revm::RevmBackend;
+#[cfg(feature = "trace")]
+use crate::trace::{TraceLog, TraceSelection};
 use crate::{
-    config::Config,
-    engine::{arbitrageur::Arbitrageur, inspector::Inspector},
-    error::ArenaError,
+    agent::Agent,
+    backend::{self, Backend},
+    checkpoint::{Checkpoint, EngineState, RunState},
+    config::{Config, Funding, HookConfig, PanicPolicy, PoolConfig, TokenConfig},
+    engine::{
+        arbitrageur::{ArbitrageFill, ArbitrageTrade, Arbitrageur, Inventory},
+        auction::{Bid, BlockBuilder},
+        events,
+        gas::GasMarket,
+        hook,
+        inspector::Inspector,
+        jit::JitLiquidity,
+        noise::NoiseTrader,
+        pnl::AgentMark,
+        pool,
+        position::Position,
+        rng::{RngManager, Stream},
+        score::{Mark, Score, Scorecard},
+        token,
+    },
+    error::{ArenaError, BuildError},
     feed::Feed,
+    history::{History, HistoryEntry},
+    hooks::Hooks,
+    replay::{RecordedDecision, RecordedStep, ReplayArena, ReplayLog},
     strategy::Strategy,
-    types::controller::ArenaController,
+    summary::{PoolDeployment, RunSummary, WalletBalances},
+    types::{
+        controller::ArenaController::{self, PoolKey},
+        exchange::LiquidExchange,
+        manager::IPoolManager,
+        token::ArenaToken,
+    },
 };
 
+/// Interval at which the mempool is polled for the arbitrageur's pending transactions.
+const MEMPOOL_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Ether balance given to strategy wallets that are not prefunded Anvil accounts.
+const STRATEGY_ETHER: &str = "10000";
+
+/// Number of IPC sockets opened by this process, keeping the socket path of every node unique.
+static IPC_SOCKETS: AtomicUsize = AtomicUsize::new(0);
+
+/// Handle that stops a running [`Arena`] at the next step boundary.
+///
+/// Clones share the same flag, so a handle can be moved into a task waiting for Ctrl-C, e.g. with
+/// `tokio::signal::ctrl_c`, while the arena runs.
+#[derive(Debug, Clone, Default)]
+pub struct Interrupt(Arc<AtomicBool>);
+
+impl Interrupt {
+    /// Public constructor function for a new [`Interrupt`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request the arena to stop at the next step boundary.
+    pub fn interrupt(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns whether an interruption was requested and not yet handled.
+    pub fn is_interrupted(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Withdraw a pending interruption, so the next run is not stopped by it.
+    pub fn clear(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Transport the providers of an [`Arena`] use to reach its Anvil node.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Transport {
+    /// JSON-RPC over HTTP.
+    #[default]
+    Http,
+
+    /// JSON-RPC over a WebSocket connection kept open for the whole simulation.
+    Ws,
+
+    /// JSON-RPC over an IPC socket, the fastest option when the node runs on the same machine.
+    Ipc,
+}
+
+/// Chain snapshot taken after deploying the pools, reverted to by later runs.
+struct Deployment {
+    snapshot: U256,
+    first_block: u64,
+    pools: Vec<PoolConfig>,
+    engines: Vec<EngineState>,
+    feeds: (Value, Vec<Value>),
+    strategies: Vec<Value>,
+}
+
 /// Represents an [`Arena`] that can be used to run a simulation and execute strategies.
 pub struct Arena<V> {
-    /// The underlying Anvil execution environment.
-    pub env: AnvilInstance,
+    /// The underlying Anvil execution environment, unless the arena runs on the in-process backend, see
+    /// [`ArenaBuilder::with_revm`].
+    pub env: Option<AnvilInstance>,
+
+    /// Chain the arena executes on, controlled through the admin wallet.
+    backend: Arc<dyn Backend>,
 
     /// The strategies that are to be run in the simulation.
     pub strategies: Vec<Box<dyn Strategy<V>>>,
@@ -32,149 +151,725 @@ pub struct Arena<V> {
     /// The arbitrageur that is used to peg the pool.
     pub arbitrageur: Box<dyn Arbitrageur>,
 
+    /// The noise traders submitting uninformed order flow.
+    pub noise_traders: Vec<NoiseTrader>,
+
+    /// Agents acting once per step, with the priority they act in, lowest first, see [`ArenaBuilder::with_agent`].
+    pub agents: Vec<(i32, Box<dyn Agent>)>,
+
+    /// Whether agents act in a random order drawn every step instead of by priority.
+    pub random_agent_order: bool,
+
+    /// Just-in-time liquidity provider added to the agents with [`ArenaBuilder::with_jit_liquidity`], kept to report
+    /// its outcome.
+    pub jit: Option<JitLiquidity>,
+
+    /// Block builder ordering agents by their bids, see [`ArenaBuilder::with_block_builder`].
+    pub block_builder: Option<BlockBuilder>,
+
+    /// The seeded random number generator driving the feed.
+    pub rng: StdRng,
+
+    /// The manager seeding every stochastic component of the simulation.
+    pub rng_manager: RngManager,
+
+    /// Index of the Monte Carlo path this arena simulates.
+    pub path: usize,
+
+    /// Amount of each pool token minted to every strategy's wallet before initialization.
+    pub strategy_funding: U256,
+
+    /// Funding of each strategy wallet, indexed like [`Arena::strategies`], in place of [`Arena::strategy_funding`] and
+    /// the default ether balance.
+    pub strategy_fundings: Vec<Option<Funding>>,
+
+    /// Funding of the wallet the arbitrageur trades from, in place of the default funding of a strategy wallet.
+    pub arbitrageur_funding: Option<Funding>,
+
+    /// Funding of the wallet of each noise trader, in place of the default funding of a strategy wallet.
+    pub noise_trader_funding: Option<Funding>,
+
+    /// Gas each strategy may spend before it stops being processed, indexed like [`Arena::strategies`].
+    pub gas_budgets: Vec<Option<u128>>,
+
+    /// Seeds overriding the simulation seed for each strategy's random number generator, indexed like
+    /// [`Arena::strategies`].
+    pub strategy_seeds: Vec<Option<u64>>,
+
+    /// Additional pools and the feeds driving their liquid exchanges.
+    pub pools: Vec<(PoolConfig, Box<dyn Feed>)>,
+
+    /// Name, decimals and supply of token0 and token1 of every pool, in that order.
+    ///
+    /// Tokens without a configuration keep the 18 decimals they are deployed with.
+    pub tokens: Vec<TokenConfig>,
+
+    /// Hook deployed with the pool of the [`Config`], see [`ArenaBuilder::with_hook`].
+    pub hook: Option<HookConfig>,
+
+    pool_rngs: Vec<StdRng>,
+
     providers: HashMap<usize, AnvilProvider>,
+
+    /// Hooks called at the start and end of every step and at the end of the run, in the order they were added.
+    pub hooks: Vec<Box<dyn Hooks>>,
+
+    interrupt: Interrupt,
+
+    /// Whether the arena created its [`Interrupt`], and so resets it once it stopped a run. A caller-owned interrupt,
+    /// see [`ArenaBuilder::with_interrupt`], is left for the caller to reset.
+    owns_interrupt: bool,
+
+    /// Whether deployments are snapshotted and reverted to by later runs, see [`ArenaBuilder::with_deployment_reuse`].
+    pub reuse_deployment: bool,
+
+    deployment: Option<Deployment>,
+
+    /// Whether every run deploys its contracts at the same addresses, see
+    /// [`ArenaBuilder::with_deterministic_addresses`].
+    pub deterministic_addresses: bool,
+
+    /// Contracts of the previous deployment at deterministic addresses, removed before the next one is deployed at the
+    /// same addresses.
+    deployed: Vec<Address>,
+
+    /// Seconds between consecutive blocks, if block timestamps are independent of wall-clock time.
+    pub block_time: Option<u64>,
+
+    /// Log of the last run, recorded when the arena is built with [`ArenaBuilder::with_recording`].
+    pub replay_log: Option<ReplayLog>,
+
+    /// Configuration of the Anvil node the arena runs on, see [`ArenaBuilder::with_anvil`], which the node its log is
+    /// replayed on reuses.
+    anvil: Anvil,
+
+    /// Call traces of the last run, recorded when the arena is built with [`ArenaBuilder::with_tracing`].
+    #[cfg(feature = "trace")]
+    pub trace_log: Option<TraceLog>,
+
+    /// State at the end of the last run, written by [`Arena::checkpoint`].
+    progress: Option<(RunState, Vec<Engine>)>,
+
+    /// State restored by [`Arena::resume`], continued by the next run.
+    resumed: Option<(RunState, Vec<Engine>)>,
 }
 
 #[allow(clippy::redundant_closure)]
 impl<V> Arena<V> {
     /// Run all strategies in the simulation with a given configuration.
-    pub async fn run(&mut self, config: Config) -> Result<(), ArenaError> {
+    ///
+    /// Returns a [`RunSummary`] with the deployed contracts, the final balances of every strategy wallet and the
+    /// [`Scorecard`] of every strategy, computed from the value of its wallet after initialization and after every step.
+    pub async fn run(&mut self, config: Config) -> Result<RunSummary, ArenaError> {
+        let result = self.run_steps(config).await;
+
+        // a failing step returns before its blocks are traced, and they hold the transaction it failed on
+        #[cfg(feature = "trace")]
+        if let Err(ArenaError::StepError { step: Some(_), .. }) = &result {
+            let components = self.components();
+            if let Some(log) = &mut self.trace_log {
+                if let Err(e) = log.trace_since(&self.providers[&0], &components).await {
+                    tracing::warn!(error = %e, "tracing the failed step failed");
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Run the steps of [`Arena::run`].
+    async fn run_steps(&mut self, mut config: Config) -> Result<RunSummary, ArenaError> {
+        let mut problems = self.main_pool(&config).validate();
+        problems.extend(config.schedule_problems());
+        BuildError::from_problems(problems)?;
+
         let admin_provider = self.providers[&0].clone();
+        let backend = self.backend.clone();
 
-        let controller = ArenaController::deploy(
-            admin_provider.clone(),
-            config.manager_fee,
-            config.initial_price,
-        )
-        .await?;
+        if let Some(block_time) = self.block_time {
+            backend.set_block_timestamp_interval(block_time).await?;
+        }
 
-        controller
-            .setPool(
-                config.pool_fee,
-                config.tick_spacing,
-                config.hooks,
-                config.sqrt_price_x96,
-                config.hook_data,
-            )
-            .send()
-            .await
-            .map_err(ArenaError::ContractError)?
-            .watch()
-            .await
-            .map_err(|e| ArenaError::PendingTransactionError(e))?;
+        let resumed = self.resumed.take();
+        let resuming = resumed.is_some();
+        let first_step = resumed.as_ref().map_or(0, |(run, _)| run.step);
+
+        // only strategy initialization draws from these, which a resumed run skips
+        let mut strategy_rngs: Vec<StdRng> = (0..self.strategies.len())
+            .map(|idx| self.init_rng(idx))
+            .collect();
 
-        let engine = Engine {
-            controller: *controller.address(),
+        self.arbitrageur.reseed(self.rng_manager.rng_at(
+            self.path,
+            Stream::Arbitrageur,
+            first_step,
+        ));
+
+        let (mut run, engines) = match resumed {
+            Some(resumed) => {
+                self.init_arbitrageur(&resumed.1[0]).await?;
+                resumed
+            }
+            None => self.setup(&mut config, &mut strategy_rngs).await?,
         };
+        let (engine, pool_engines) = (engines[0].clone(), engines[1..].to_vec());
+        let log_sources = self.log_sources(&engines).await?;
 
-        for (idx, strategy) in self.strategies.iter_mut().enumerate() {
-            let strategy_provider = self.providers[&(idx + 1)].clone();
+        let signal = engine
+            .signal(None, self.feed.current_value(), admin_provider.clone())
+            .await?;
 
-            let signal = controller.constructSignal().call().await?._0;
-
-            let signal = Signal::new(
-                signal.lexPrice,
-                None,
-                signal.currentTick,
-                signal.sqrtPriceX96,
-                signal.manager,
-                signal.pool,
-                signal.fetcher,
-                self.feed.current_value(),
-                *controller.address(),
-            );
+        let mut agent_rngs: Vec<StdRng> = (0..self.agents.len())
+            .map(|idx| {
+                self.rng_manager
+                    .rng_at(self.path, Stream::Agent(idx), first_step)
+            })
+            .collect();
 
-            strategy
-                .init(
-                    strategy_provider.clone(),
-                    signal,
-                    &mut self.inspector,
-                    engine.clone(),
-                )
-                .await;
+        for (idx, ((_, agent), rng)) in self.agents.iter_mut().zip(&mut agent_rngs).enumerate() {
+            let agent_provider = self.providers
+                [&agent_wallet(self.strategies.len(), self.noise_traders.len(), idx)]
+                .clone();
+            agent
+                .init(&signal, agent_provider, engine.clone(), rng)
+                .await
+                .map_err(|e| e.at_step(None, format!("agent {idx}")))?;
+        }
+
+        if !resuming {
+            self.mark_strategies(&engine, &signal, &pool_engines, &mut run.marks)
+                .await?;
+            self.mark_agents(None, &engine, &signal).await?;
+            self.capture_logs(None, &log_sources, &mut run.logs_from)
+                .await?;
+            run.events_from = admin_provider.get_block_number().await? + 1;
         }
 
-        let signal = controller.constructSignal().call().await?._0;
+        let (manager, pool_key) = (signal.manager, signal.pool);
 
-        let signal = Signal::new(
-            signal.lexPrice,
-            None,
-            signal.currentTick,
-            signal.sqrtPriceX96,
-            signal.manager,
-            signal.pool,
-            signal.fetcher,
-            self.feed.current_value(),
-            *controller.address(),
-        );
+        let mut step_timestamp = latest_timestamp(&admin_provider).await?;
+
+        let components = self.components();
+        let mut recorded_block = admin_provider.get_block_number().await?;
+        if let Some(log) = &mut self.replay_log {
+            *log = ReplayLog {
+                genesis: backend.dump_state().await?,
+                steps: Vec::new(),
+            };
+        }
+
+        // ids of the pools with hooks, whose swaps and liquidity changes call into them
+        #[cfg(feature = "trace")]
+        if let Some(log) = &mut self.trace_log {
+            let mut keys = vec![pool_key.clone()];
+            if log.selection.hook_calls {
+                for ((_, feed), pool_engine) in self.pools.iter().zip(&pool_engines) {
+                    let signal = pool_engine
+                        .signal(None, feed.current_value(), admin_provider.clone())
+                        .await?;
+                    keys.push(signal.pool);
+                }
+            }
+
+            let hooked = keys
+                .iter()
+                .filter(|key| !key.hooks.is_zero())
+                .map(pool::pool_id)
+                .collect();
+            log.start(recorded_block, hooked);
+        }
+
+        if let Some(jit) = &self.jit {
+            jit.reset();
+        }
+
+        // agents watching swaps, with their wallets, see every swap of the other participants before it is sent
+        let watchers: Vec<(usize, AnvilProvider)> = self
+            .agents
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, agent))| agent.watches_swaps())
+            .map(|(idx, _)| {
+                let wallet = agent_wallet(self.strategies.len(), self.noise_traders.len(), idx);
+                (idx, self.providers[&wallet].clone())
+            })
+            .collect();
+        let trading = self.step_market(&engines, watchers);
+        if let Some(builder) = &mut self.block_builder {
+            builder.blocks.clear();
+        }
+
+        let mut interrupted = false;
+        let mut stopped = None;
+        let started = Instant::now();
+        let mut pace = config.real_time.map(|period| {
+            let mut pace = tokio::time::interval(period);
+            pace.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            pace
+        });
 
-        self.arbitrageur.init(&signal, admin_provider.clone()).await;
+        let mut step_block = None;
+
+        for step in first_step..config.steps {
+            // every stream draws from a generator of the step, so a resumed run draws exactly like an uninterrupted one
+            let mut rngs = self.reseed_market(|arena, stream| arena.step_rng(stream, step));
+            strategy_rngs = (0..self.strategies.len())
+                .map(|idx| self.step_rng(Stream::Strategy(idx), step))
+                .collect();
+            agent_rngs = (0..self.agents.len())
+                .map(|idx| self.step_rng(Stream::Agent(idx), step))
+                .collect();
+            let mut agent_order_rng = self.step_rng(Stream::AgentOrder, step);
+
+            if let Some(pace) = &mut pace {
+                pace.tick().await;
+            }
+
+            if self.interrupt.is_interrupted() {
+                if self.owns_interrupt {
+                    self.interrupt.clear();
+                }
+                interrupted = true;
+                break;
+            }
+
+            #[cfg(feature = "trace")]
+            if let Some(log) = &mut self.trace_log {
+                log.step = step;
+            }
+
+            self.price_gas(&mut config.gas_market, &mut step_block, &mut rngs.gas)
+                .await?;
 
-        for step in 0..config.steps {
             let instant = Instant::now();
-            controller
-                .setPrice(
-                    alloy::primitives::utils::parse_ether(&self.feed.step().to_string())
-                        .map_err(ArenaError::ConversionError)?,
-                )
-                .nonce(
-                    admin_provider
-                        .get_transaction_count(admin_provider.default_signer_address())
+
+            if !self.hooks.is_empty() {
+                let signal = engine
+                    .signal(
+                        Some(step),
+                        self.feed.current_value(),
+                        admin_provider.clone(),
+                    )
+                    .await?;
+
+                for (idx, hooks) in self.hooks.iter_mut().enumerate() {
+                    hooks
+                        .on_step_start(step, &signal, admin_provider.clone(), engine.clone())
                         .await
-                        .unwrap(),
+                        .map_err(|e| e.at_step(Some(step), format!("hooks {idx}")))?;
+                }
+            }
+
+            let (feed_values, deferred_arbitrage) = self
+                .trade_step(
+                    &config,
+                    &trading,
+                    Some(step),
+                    &mut rngs,
+                    &mut run.last_arbitrage,
                 )
-                .send()
-                .await
-                .map_err(ArenaError::ContractError)?
-                .watch()
-                .await
-                .map_err(|e| ArenaError::PendingTransactionError(e))?;
-
-            let signal = controller.constructSignal().call().await?._0;
-
-            let signal = Signal::new(
-                signal.lexPrice,
-                None,
-                signal.currentTick,
-                signal.sqrtPriceX96,
-                signal.manager,
-                signal.pool,
-                signal.fetcher,
-                self.feed.current_value(),
-                *controller.address(),
-            );
+                .await?;
+            let mut decisions = Vec::new();
+
+            if !self.agents.is_empty() {
+                let signal = engine
+                    .signal(
+                        Some(step),
+                        self.feed.current_value(),
+                        admin_provider.clone(),
+                    )
+                    .await?;
 
-            self.arbitrageur
-                .arbitrage(&signal, admin_provider.clone())
-                .await;
+                let mut order: Vec<(usize, u128)> =
+                    (0..self.agents.len()).map(|idx| (idx, 0)).collect();
+                if let Some(builder) = &mut self.block_builder {
+                    let mut bids = Vec::with_capacity(self.agents.len());
+                    for (idx, (priority, agent)) in self.agents.iter_mut().enumerate() {
+                        let amount = agent
+                            .bid(&signal, &mut agent_rngs[idx])
+                            .await
+                            .map_err(|e| e.at_step(Some(step), format!("agent {idx}")))?;
+                        bids.push(Bid {
+                            agent: idx,
+                            priority: *priority,
+                            amount,
+                        });
+                    }
 
-            for (idx, strategy) in self.strategies.iter_mut().enumerate() {
-                let signal = controller.constructSignal().call().await?._0;
+                    order = builder
+                        .build(step, bids, &mut agent_order_rng)
+                        .into_iter()
+                        .map(|bid| (bid.agent, bid.amount))
+                        .collect();
+                } else if self.random_agent_order {
+                    order.shuffle(&mut agent_order_rng);
+                } else {
+                    order.sort_by_key(|&(idx, _)| self.agents[idx].0);
+                }
 
-                let signal = Signal::new(
-                    signal.lexPrice,
-                    Some(step),
-                    signal.currentTick,
-                    signal.sqrtPriceX96,
-                    signal.manager,
-                    signal.pool,
-                    signal.fetcher,
-                    self.feed.current_value(),
-                    *controller.address(),
-                );
+                decisions = order
+                    .iter()
+                    .map(|&(agent, bid)| RecordedDecision { agent, bid })
+                    .collect();
 
-                strategy
-                    .process(
-                        self.providers[&(idx + 1)].clone(),
+                for (idx, bid) in order {
+                    let agent_provider = self.providers
+                        [&agent_wallet(self.strategies.len(), self.noise_traders.len(), idx)]
+                        .clone();
+
+                    if let Some(builder) = self.block_builder.as_ref().filter(|_| bid > 0) {
+                        let owner = agent_provider.default_signer_address();
+                        agent_provider
+                            .send_transaction(
+                                TransactionRequest::default()
+                                    .to(builder.recipient)
+                                    .value(U256::from(bid))
+                                    .nonce(agent_provider.get_transaction_count(owner).await?),
+                            )
+                            .await?
+                            .watch()
+                            .await
+                            .map_err(|e| ArenaError::PendingTransactionError(e))?;
+                    }
+
+                    // watching agents act on the market directly, the others through the watchers
+                    let deferring = if self.agents[idx].1.watches_swaps() {
+                        engine.clone()
+                    } else {
+                        trading.watched(&engine)
+                    };
+                    self.agents[idx]
+                        .1
+                        .act(
+                            &signal,
+                            agent_provider.clone(),
+                            deferring.clone(),
+                            &mut agent_rngs[idx],
+                        )
+                        .await
+                        .map_err(|e| e.at_step(Some(step), format!("agent {idx}")))?;
+                    send_watched(
+                        &mut self.agents,
+                        &trading.watchers,
+                        &deferring,
+                        &engine,
+                        signal.current_value,
+                        agent_provider,
+                        Some(step),
+                    )
+                    .await?;
+                }
+            }
+
+            // scoped so the deferred arbitrage, which borrows the arbitrageur, is dropped before strategies are marked
+            {
+                let mut pending = Vec::new();
+                let mut arbitrage = None;
+                let mut arbitrage_result = None;
+
+                if let Some(signal) = &deferred_arbitrage {
+                    backend.set_auto_mine(false).await?;
+
+                    let future = arbitrage.insert(self.arbitrageur.arbitrage(
                         signal,
-                        &mut self.inspector,
+                        trading.arbitrageur_provider.clone(),
                         engine.clone(),
-                    )
+                    ));
+
+                    // wait until the arbitrageur either submits a transaction or finishes without one
+                    while pending.is_empty() {
+                        tokio::select! {
+                            result = future.as_mut() => {
+                                arbitrage_result = Some(result);
+                                break;
+                            }
+                            _ = tokio::time::sleep(MEMPOOL_POLL_INTERVAL) => {
+                                pending = pending_transactions(&admin_provider).await?;
+                            }
+                        }
+                    }
+
+                    backend.set_auto_mine(true).await?;
+                }
+
+                let latest = admin_provider.get_block_number().await?;
+                let pool_events = events::fetch(
+                    manager,
+                    &pool_key,
+                    run.events_from,
+                    latest,
+                    admin_provider.clone(),
+                )
+                .await?;
+                run.events_from = latest + 1;
+
+                // every strategy sees the same snapshot of the market, taken before any of them acts
+                let mut pool_signals = vec![
+                    engine
+                        .signal(
+                            Some(step),
+                            self.feed.current_value(),
+                            admin_provider.clone(),
+                        )
+                        .await?,
+                ];
+                for ((_, feed), pool_engine) in self.pools.iter().zip(&pool_engines) {
+                    pool_signals.push(
+                        pool_engine
+                            .signal(Some(step), feed.current_value(), admin_provider.clone())
+                            .await?,
+                    );
+                }
+                let market = MarketSignal::new(Some(step), pool_signals);
+
+                for (idx, strategy) in self.strategies.iter_mut().enumerate() {
+                    if run.dropped[idx] {
+                        continue;
+                    }
+
+                    if over_budget(
+                        self.gas_budgets.get(idx).copied().flatten(),
+                        run.gas_used[idx],
+                    ) {
+                        continue;
+                    }
+
+                    let strategy_provider = self.providers[&(idx + 1)].clone();
+                    let block = admin_provider.get_block_number().await?;
+
+                    let mut signal = engine
+                        .signal(
+                            Some(step),
+                            self.feed.current_value(),
+                            admin_provider.clone(),
+                        )
+                        .await?;
+                    signal.history = run.history.clone();
+                    signal.gas_used = run.gas_used[idx];
+                    signal.pending = pending.clone();
+                    signal.events = pool_events.clone();
+                    signal.last_arbitrage = run.last_arbitrage;
+                    signal.metadata = self.feed.metadata();
+
+                    let subscribed = strategy.is_subscribed(&pool::pool_id(&signal.pool));
+
+                    let mut other_pools = Vec::new();
+                    for ((_, feed), pool_engine) in self.pools.iter().zip(&pool_engines) {
+                        let mut signal = pool_engine
+                            .signal(Some(step), feed.current_value(), admin_provider.clone())
+                            .await?;
+
+                        if strategy.is_subscribed(&pool::pool_id(&signal.pool)) {
+                            signal.gas_used = run.gas_used[idx];
+                            signal.metadata = feed.metadata();
+                            other_pools.push((
+                                signal,
+                                trading.watched(pool_engine),
+                                pool_engine,
+                                feed.current_value(),
+                            ));
+                        }
+                    }
+
+                    let inspector = &mut self.inspector;
+                    let rng = &mut strategy_rngs[idx];
+                    let deferring = trading.watched(&engine);
+
+                    let outcome = AssertUnwindSafe(async {
+                        strategy
+                            .process_market(
+                                strategy_provider.clone(),
+                                market.clone(),
+                                inspector,
+                                deferring.clone(),
+                                rng,
+                            )
+                            .await?;
+
+                        if subscribed {
+                            strategy
+                                .process(
+                                    strategy_provider.clone(),
+                                    signal,
+                                    inspector,
+                                    deferring.clone(),
+                                    rng,
+                                )
+                                .await?;
+                        }
+
+                        for (signal, pool_deferring, ..) in &other_pools {
+                            strategy
+                                .process(
+                                    strategy_provider.clone(),
+                                    signal.clone(),
+                                    inspector,
+                                    pool_deferring.clone(),
+                                    rng,
+                                )
+                                .await?;
+                        }
+
+                        Ok::<_, ArenaError>(())
+                    })
+                    .catch_unwind()
                     .await;
+
+                    match outcome {
+                        Ok(result) => {
+                            result.map_err(|e| e.at_step(Some(step), format!("strategy {idx}")))?
+                        }
+                        Err(panic) => {
+                            run.dropped[idx] = isolate_panic(
+                                &mut self.inspector,
+                                config.panic_policy,
+                                Some(step),
+                                format!("strategy {idx}"),
+                                panic,
+                            )?
+                        }
+                    }
+
+                    send_watched(
+                        &mut self.agents,
+                        &trading.watchers,
+                        &deferring,
+                        &engine,
+                        self.feed.current_value(),
+                        strategy_provider.clone(),
+                        Some(step),
+                    )
+                    .await?;
+                    for (_, pool_deferring, pool_engine, price) in &other_pools {
+                        send_watched(
+                            &mut self.agents,
+                            &trading.watchers,
+                            pool_deferring,
+                            pool_engine,
+                            *price,
+                            strategy_provider.clone(),
+                            Some(step),
+                        )
+                        .await?;
+                    }
+
+                    run.gas_used[idx] += gas_used_since(
+                        &admin_provider,
+                        block,
+                        strategy_provider.default_signer_address(),
+                    )
+                    .await?;
+                }
+
+                if let Some(future) = arbitrage {
+                    // strategy transactions mine the pending pool with them, otherwise mine it explicitly
+                    if !pending_transactions(&admin_provider).await?.is_empty() {
+                        backend.mine(1).await?;
+                    }
+
+                    match arbitrage_result {
+                        Some(result) => result,
+                        None => future.await,
+                    }
+                    .map_err(|e| e.at_step(Some(step), "arbitrageur"))?;
+                }
+
+                if let Some(signal) = &deferred_arbitrage {
+                    run.last_arbitrage = record_arbitrage(
+                        &mut self.inspector,
+                        &admin_provider,
+                        trading.arbitrageur,
+                        &engine,
+                        0,
+                        signal,
+                        step,
+                    )
+                    .await?
+                    .or(run.last_arbitrage);
+                }
+            }
+
+            let signal = engine
+                .signal(
+                    Some(step),
+                    self.feed.current_value(),
+                    admin_provider.clone(),
+                )
+                .await?;
+
+            run.history.push(HistoryEntry {
+                step,
+                feed_value: signal.current_value,
+                lex_price: signal.lex_price,
+                tick: signal.tick,
+                sqrt_price_x96: signal.sqrt_price_x96,
+                liquidity: signal.liquidity,
+                warmup: false,
+            });
+
+            self.inspector
+                .on_step(&signal)
+                .map_err(|e| e.at_step(Some(step), "inspector"))?;
+
+            engine.record_mark(step, signal.current_value);
+            for ((_, feed), pool_engine) in self.pools.iter().zip(&pool_engines) {
+                pool_engine.record_mark(step, feed.current_value());
+            }
+
+            self.mark_strategies(&engine, &signal, &pool_engines, &mut run.marks)
+                .await?;
+            self.mark_agents(Some(step), &engine, &signal).await?;
+            self.snapshot_liquidity(step, &signal).await?;
+            self.capture_logs(Some(step), &log_sources, &mut run.logs_from)
+                .await?;
+
+            for (idx, hooks) in self.hooks.iter_mut().enumerate() {
+                hooks
+                    .on_step_end(step, &signal, admin_provider.clone(), engine.clone())
+                    .await
+                    .map_err(|e| e.at_step(Some(step), format!("hooks {idx}")))?;
+            }
+
+            self.advance_feeds(&config);
+
+            mine_step_boundary(&config, &engine, &admin_provider, &mut step_timestamp).await?;
+
+            if let Some(log) = &mut self.replay_log {
+                let blocks =
+                    ReplayLog::blocks_since(&admin_provider, recorded_block, &components).await?;
+                recorded_block = blocks.last().map_or(recorded_block, |block| block.number);
+
+                log.steps.push(RecordedStep {
+                    step,
+                    feed_values,
+                    agents: decisions,
+                    blocks,
+                });
+            }
+
+            #[cfg(feature = "trace")]
+            if let Some(log) = &mut self.trace_log {
+                log.trace_since(&admin_provider, &components)
+                    .await
+                    .map_err(|e| e.at_step(Some(step), "tracer"))?;
+            }
+
+            run.step = step + 1;
+
+            if let Some((every, path)) = &config.checkpoints {
+                if run.step % every == 0 {
+                    self.write_checkpoint(&run, &engines, path).await?;
+                }
             }
 
-            self.feed.step();
-            println!("Step {} took {:?}", step, instant.elapsed());
+            tracing::debug!(step, elapsed = ?instant.elapsed(), "step finished");
+
+            stopped = config.stop_conditions.iter().find_map(|condition| {
+                condition.check(run.step, started.elapsed(), &signal, &run.marks)
+            });
+            if stopped.is_some() {
+                break;
+            }
         }
 
         // controller
@@ -186,95 +881,2514 @@ impl<V> Arena<V> {
         //     .await
         //     .map_err(|e| ArenaError::PendingTransactionError(e))?;
 
-        Ok(())
-    }
-}
+        let mut pools = Vec::with_capacity(engines.len());
+        for engine in &engines {
+            pools.push(PoolDeployment::fetch(engine, admin_provider.clone()).await?);
+        }
 
-/// A builder for an [`Arena`] that can be used to configure the simulation.
-pub struct ArenaBuilder<V> {
-    /// [`Arena::env`]
-    pub env: AnvilInstance,
+        let mut balances = Vec::with_capacity(self.strategies.len());
+        for idx in 0..self.strategies.len() {
+            let owner = self.providers[&(idx + 1)].default_signer_address();
 
-    /// [`Arena::strategies`]
-    pub strategies: Vec<Box<dyn Strategy<V>>>,
+            let mut tokens = Vec::with_capacity(pools.len());
+            for pool in &pools {
+                tokens.push(Inventory::fetch(owner, &pool.key, admin_provider.clone()).await?);
+            }
 
-    /// [`Arena::feed`]
-    pub feed: Option<Box<dyn Feed>>,
+            balances.push(WalletBalances {
+                owner,
+                ether: admin_provider.get_balance(owner).await?,
+                tokens,
+            });
+        }
 
-    /// [`Arena::inspector`]
-    pub inspector: Option<Box<dyn Inspector<V>>>,
+        let scorecard = Scorecard {
+            strategies: run
+                .marks
+                .iter()
+                .zip(&run.gas_used)
+                .map(|(marks, gas_used)| Score {
+                    gas_used: *gas_used,
+                    ..Score::from_marks(marks.clone())
+                })
+                .collect(),
+        };
 
-    /// [`Arena::arbitrageur`]
-    pub arbitrageur: Option<Box<dyn Arbitrageur>>,
-}
+        if interrupted {
+            self.inspector
+                .save()
+                .map_err(|e| e.at_step(Some(run.step), "inspector"))?;
+        }
 
-impl<V> Default for ArenaBuilder<V> {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+        let steps = run.step - first_step;
+        self.progress = Some((run, engines));
 
-impl<V> ArenaBuilder<V> {
-    /// Public constructor function for a new [`ArenaBuilder`].
-    pub fn new() -> Self {
-        ArenaBuilder {
-            env: Anvil::default().spawn(),
-            strategies: Vec::new(),
-            feed: None,
-            inspector: None,
-            arbitrageur: None,
+        let summary = RunSummary {
+            steps,
+            interrupted,
+            stopped,
+            jit: self.jit.as_ref().map(JitLiquidity::report),
+            pools,
+            balances,
+            scorecard,
+        };
+
+        for (idx, hooks) in self.hooks.iter_mut().enumerate() {
+            hooks
+                .on_run_end(&summary, admin_provider.clone())
+                .await
+                .map_err(|e| e.at_step(None, format!("hooks {idx}")))?;
         }
-    }
 
-    /// Add a strategy to the simulation.
-    pub fn with_strategy(mut self, strategy: Box<dyn Strategy<V>>) -> Self {
-        self.strategies.push(strategy);
-        self
+        Ok(summary)
     }
 
-    /// Set the feed that provides the current, theoretical value of the pool.
-    pub fn with_feed(mut self, feed: Box<dyn Feed>) -> Self {
-        self.feed = Some(feed);
-        self
+    /// Returns a handle that stops the arena at the next step boundary.
+    ///
+    /// An interrupted run saves the inspector and returns a partial [`RunSummary`], and can be continued later by
+    /// writing a checkpoint with [`Arena::checkpoint`]. The arena resets the interruption once handled, unless the
+    /// interrupt was passed to [`ArenaBuilder::with_interrupt`].
+    pub fn interrupt_handle(&self) -> Interrupt {
+        self.interrupt.clone()
     }
 
-    /// Set the inspector that is used to evaluate the performance of the strategies.
-    pub fn with_inspector(mut self, inspector: Box<dyn Inspector<V>>) -> Self {
-        self.inspector = Some(inspector);
-        self
-    }
+    /// Returns a [`ReplayArena`] replaying the log of the last run, recorded when the arena is built with
+    /// [`ArenaBuilder::with_recording`], on a node configured like the arena's, see [`ArenaBuilder::with_anvil`].
+    pub fn replay(&self) -> Result<ReplayArena, ArenaError> {
+        let log = self
+            .replay_log
+            .clone()
+            .ok_or_else(|| ArenaError::Custom("the arena does not record its runs".to_string()))?;
 
-    /// Set the inspector that is used to evaluate the performance of the strategies.
-    pub fn with_arbitrageur(mut self, arbitrageur: Box<dyn Arbitrageur>) -> Self {
-        self.arbitrageur = Some(arbitrageur);
-        self
+        ReplayArena::with_anvil(log, self.anvil.clone())
     }
 
-    /// Build the [`Arena`] with the given configuration.
-    pub fn build(self) -> Arena<V> {
-        let mut providers = HashMap::new();
+    /// Write a [`Checkpoint`] of the arena at the end of its last run to `path`.
+    ///
+    /// This saves the chain state, the state of every feed and strategy, and the bookkeeping of the run, so that
+    /// [`Arena::resume`] can continue it, e.g. with a [`Config`] running more steps. Checkpoints are only consistent
+    /// between steps, so to recover from a failure mid-run, checkpoint periodically with [`Config::with_checkpoints`].
+    pub async fn checkpoint(&self, path: impl AsRef<Path>) -> Result<(), ArenaError> {
+        let (run, engines) = self
+            .progress
+            .as_ref()
+            .ok_or_else(|| ArenaError::Custom("no completed run to checkpoint".to_string()))?;
 
-        for i in 0..9 {
-            let signer: PrivateKeySigner = self.env.keys()[i].clone().into();
-            let wallet = EthereumWallet::from(signer);
+        self.write_checkpoint(run, engines, path.as_ref()).await
+    }
 
-            let rpc_url = self.env.endpoint().parse().unwrap();
+    /// Restore a [`Checkpoint`] written to `path`, so that the next call to [`Arena::run`] continues from the step after
+    /// it instead of deploying a new simulation.
+    ///
+    /// The arena must be built with the same strategies, feeds and additional pools as the one that wrote the
+    /// checkpoint. Random number generators are reseeded from every step, see [`RngManager::rng_step`], so the resumed
+    /// run draws exactly like an uninterrupted one.
+    pub async fn resume(&mut self, path: impl AsRef<Path>) -> Result<(), ArenaError> {
+        let checkpoint = Checkpoint::load(path)?;
 
-            let provider = ProviderBuilder::new()
-                .with_recommended_fillers()
-                .wallet(wallet)
-                .on_http(rpc_url);
+        if checkpoint.strategies.len() != self.strategies.len()
+            || checkpoint.pool_feeds.len() != self.pools.len()
+        {
+            return Err(ArenaError::Custom(format!(
+                "checkpoint has {} strategies and {} additional pools, but the arena has {} and {}",
+                checkpoint.strategies.len(),
+                checkpoint.pool_feeds.len(),
+                self.strategies.len(),
+                self.pools.len(),
+            )));
+        }
 
-            providers.insert(i, provider);
+        self.backend.load_state(checkpoint.chain).await?;
+
+        self.feed.restore(checkpoint.feed)?;
+        for ((_, feed), state) in self.pools.iter_mut().zip(checkpoint.pool_feeds) {
+            feed.restore(state)?;
+        }
+        for (strategy, state) in self.strategies.iter_mut().zip(checkpoint.strategies) {
+            strategy.restore(state)?;
         }
 
-        Arena {
-            env: self.env,
-            strategies: self.strategies,
-            feed: self.feed.unwrap(),
-            inspector: self.inspector.unwrap(),
-            arbitrageur: self.arbitrageur.unwrap(),
-            providers,
+        let engines: Vec<Engine> = checkpoint.engines.into_iter().map(Engine::from).collect();
+        if self.deterministic_addresses {
+            // the next deployment replaces the contracts of the resumed run
+            self.record_deployed(&engines).await?;
         }
+        self.resumed = Some((checkpoint.run, engines));
+
+        Ok(())
+    }
+}
+
+impl<V> Arena<V> {
+    /// Deploy every pool, fund and initialize the strategies, and return the initial state of the run along with the
+    /// engine of every pool.
+    async fn setup(
+        &mut self,
+        config: &mut Config,
+        strategy_rngs: &mut [StdRng],
+    ) -> Result<(RunState, Vec<Engine>), ArenaError> {
+        let admin_provider = self.providers[&0].clone();
+
+        let (engines, first_block) = match self.revert_deployment(config).await? {
+            Some(deployed) => deployed,
+            None => self.deploy(config).await?,
+        };
+        let engine = engines[0].clone();
+
+        self.init_arbitrageur(&engine).await?;
+
+        let mut history = History::new(config.history_retention);
+        self.warm_up(config, &engines, &mut history).await?;
+
+        let mut gas_used = vec![0; self.strategies.len()];
+        let mut dropped = vec![false; self.strategies.len()];
+
+        for (idx, strategy) in self.strategies.iter_mut().enumerate() {
+            let strategy_provider = self.providers[&(idx + 1)].clone();
+            let block = admin_provider.get_block_number().await?;
+
+            let mut signal = engine
+                .signal(None, self.feed.current_value(), admin_provider.clone())
+                .await?;
+            signal.metadata = self.feed.metadata();
+
+            let outcome = AssertUnwindSafe(strategy.init(
+                strategy_provider.clone(),
+                signal,
+                &mut self.inspector,
+                engine.clone(),
+                &mut strategy_rngs[idx],
+            ))
+            .catch_unwind()
+            .await;
+
+            match outcome {
+                Ok(result) => result.map_err(|e| e.at_step(None, format!("strategy {idx}")))?,
+                Err(panic) => {
+                    dropped[idx] = isolate_panic(
+                        &mut self.inspector,
+                        config.panic_policy,
+                        None,
+                        format!("strategy {idx}"),
+                        panic,
+                    )?
+                }
+            }
+
+            gas_used[idx] += gas_used_since(
+                &admin_provider,
+                block,
+                strategy_provider.default_signer_address(),
+            )
+            .await?;
+        }
+
+        let run = RunState {
+            step: 0,
+            gas_used,
+            dropped,
+            marks: vec![Vec::new(); self.strategies.len()],
+            history,
+            last_arbitrage: None,
+            events_from: 0,
+            logs_from: first_block,
+        };
+
+        Ok((run, engines))
+    }
+
+    /// Initialize the arbitrageur on the pool `engine` acts on.
+    async fn init_arbitrageur(&mut self, engine: &Engine) -> Result<(), ArenaError> {
+        let admin_provider = self.providers[&0].clone();
+
+        let signal = engine
+            .signal(None, self.feed.current_value(), admin_provider.clone())
+            .await?;
+
+        self.arbitrageur
+            .init(
+                &signal,
+                self.providers[&self.arbitrageur_wallet()].clone(),
+                engine.clone(),
+            )
+            .await
+            .map_err(|e| e.at_step(None, "arbitrageur"))
+    }
+
+    /// Run the [`Config::warmup_steps`], each traded like a regular step, see [`Arena::trade_step`], but without
+    /// strategies, agents or hooks, and without reporting to the inspector or marking anyone, recording the state of the
+    /// pool after every warm-up step into `history`.
+    ///
+    /// Noise traders trade, base fees follow the gas market and the step boundaries are mined like in a regular step,
+    /// so time-weighted oracles and the gas market accumulate history. Warm-up steps draw from generators of their own,
+    /// see [`RngManager::rng_warmup`].
+    async fn warm_up(
+        &mut self,
+        config: &mut Config,
+        engines: &[Engine],
+        history: &mut History,
+    ) -> Result<(), ArenaError> {
+        let admin_provider = self.providers[&0].clone();
+        let market = self.step_market(engines, Vec::new());
+
+        let mut step_timestamp = latest_timestamp(&admin_provider).await?;
+        let mut step_block = None;
+        // fills of the arbitrageur during warm-up are not recorded
+        let mut last_arbitrage = None;
+
+        for step in 0..config.warmup_steps {
+            let mut rngs = self.reseed_market(|arena, stream| {
+                arena.rng_manager.rng_warmup(arena.path, stream, step)
+            });
+            self.price_gas(&mut config.gas_market, &mut step_block, &mut rngs.gas)
+                .await?;
+            self.trade_step(config, &market, None, &mut rngs, &mut last_arbitrage)
+                .await?;
+
+            let signal = engines[0]
+                .signal(None, self.feed.current_value(), admin_provider.clone())
+                .await?;
+            history.push(HistoryEntry {
+                step,
+                feed_value: signal.current_value,
+                lex_price: signal.lex_price,
+                tick: signal.tick,
+                sqrt_price_x96: signal.sqrt_price_x96,
+                liquidity: signal.liquidity,
+                warmup: true,
+            });
+
+            self.advance_feeds(config);
+            mine_step_boundary(config, &engines[0], &admin_provider, &mut step_timestamp).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the [`StepMarket`] of a run on the pools of `engines`, the agents in `watchers` watching swaps.
+    fn step_market<'a>(
+        &self,
+        engines: &'a [Engine],
+        watchers: Vec<(usize, AnvilProvider)>,
+    ) -> StepMarket<'a> {
+        let arbitrageur_provider = self.providers[&self.arbitrageur_wallet()].clone();
+
+        StepMarket {
+            engines,
+            arbitrageur: arbitrageur_provider.default_signer_address(),
+            arbitrageur_provider,
+            noise_wallets: (0..self.noise_traders.len())
+                .map(|idx| self.noise_trader_wallet(idx))
+                .collect(),
+            watchers,
+        }
+    }
+
+    /// Reseed the feeds and the arbitrageur for a step, `rng` returning the generator a stream draws from during it,
+    /// and return the generators of the other market participants.
+    fn reseed_market(&mut self, rng: impl Fn(&Self, Stream) -> StdRng) -> StepRngs {
+        self.rng = rng(self, Stream::Feed);
+        self.pool_rngs = (0..self.pools.len())
+            .map(|idx| rng(self, Stream::PoolFeed(idx)))
+            .collect();
+        let arbitrageur_rng = rng(self, Stream::Arbitrageur);
+        self.arbitrageur.reseed(arbitrageur_rng);
+
+        StepRngs {
+            noise: (0..self.noise_traders.len())
+                .map(|idx| rng(self, Stream::NoiseTrader(idx)))
+                .collect(),
+            pool_noise: (0..self.pools.len())
+                .map(|pool| {
+                    (0..self.noise_traders.len())
+                        .map(|idx| rng(self, Stream::PoolNoiseTrader(pool, idx)))
+                        .collect()
+                })
+                .collect(),
+            gas: rng(self, Stream::GasMarket),
+        }
+    }
+
+    /// Set the base fee of the next block from `gas_market`, given the gas used since `step_block`, the
+    /// block the previous step started at, and move `step_block` to the block this step starts at.
+    async fn price_gas(
+        &self,
+        gas_market: &mut GasMarket,
+        step_block: &mut Option<u64>,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        if matches!(gas_market, GasMarket::Node) {
+            return Ok(());
+        }
+
+        let admin_provider = &self.providers[&0];
+        let gas_used = match *step_block {
+            Some(block) => Some(block_gas_used_since(admin_provider, block).await?),
+            None => None,
+        };
+        if let Some(base_fee) = gas_market.next_base_fee(gas_used, rng) {
+            self.backend.set_next_block_base_fee(base_fee).await?;
+        }
+        *step_block = Some(admin_provider.get_block_number().await?);
+
+        Ok(())
+    }
+
+    /// Trade the sub-steps of `step`, or of a warm-up step if `step` is `None`: every feed ticks and updates the liquid
+    /// exchange of its pool, the arbitrageur trades each pool towards its exchange and every noise trader trades on
+    /// each pool, their swaps sent between the calls of the agents watching swaps.
+    ///
+    /// Fills of the arbitrageur are reported to the inspector and kept as the `last_arbitrage` of the run, except
+    /// during warm-up. Returns every feed value set, along with the index of its pool, and in mempool mode the signal
+    /// of the last arbitrage of the step, left for the caller to run once strategies saw it pending.
+    async fn trade_step(
+        &mut self,
+        config: &Config,
+        market: &StepMarket<'_>,
+        step: Option<usize>,
+        rngs: &mut StepRngs,
+        last_arbitrage: &mut Option<ArbitrageTrade>,
+    ) -> Result<(Vec<(usize, f64)>, Option<Signal>), ArenaError> {
+        let admin_provider = self.providers[&0].clone();
+        let (engine, pool_engines) = (&market.engines[0], &market.engines[1..]);
+
+        let mut feed_values = Vec::new();
+        let mut deferred_arbitrage = None;
+
+        for sub_step in 0..config.sub_steps {
+            let value = config.advance_feed(self.feed.as_mut(), &mut self.rng);
+            engine.set_price(value, admin_provider.clone()).await?;
+            feed_values.push((0, value));
+
+            let mut signal = engine
+                .signal(None, self.feed.current_value(), admin_provider.clone())
+                .await?;
+            signal.arbitrageur_inventory = engine
+                .inventory(market.arbitrageur, &signal.pool, admin_provider.clone())
+                .await?;
+
+            // in mempool mode the last arbitrage of the step is left pending for strategies to react to
+            if step.is_some() && config.mempool && sub_step + 1 == config.sub_steps {
+                deferred_arbitrage = Some(signal);
+            } else {
+                let deferring = market.watched(engine);
+                self.arbitrageur
+                    .arbitrage(
+                        &signal,
+                        market.arbitrageur_provider.clone(),
+                        deferring.clone(),
+                    )
+                    .await
+                    .map_err(|e| e.at_step(step, "arbitrageur"))?;
+                send_watched(
+                    &mut self.agents,
+                    &market.watchers,
+                    &deferring,
+                    engine,
+                    signal.current_value,
+                    market.arbitrageur_provider.clone(),
+                    step,
+                )
+                .await?;
+
+                if let Some(step) = step {
+                    *last_arbitrage = record_arbitrage(
+                        &mut self.inspector,
+                        &admin_provider,
+                        market.arbitrageur,
+                        engine,
+                        0,
+                        &signal,
+                        step,
+                    )
+                    .await?
+                    .or(*last_arbitrage);
+                }
+            }
+
+            for (idx, (noise_trader, rng)) in self
+                .noise_traders
+                .iter_mut()
+                .zip(&mut rngs.noise)
+                .enumerate()
+            {
+                let noise_provider = self.providers[&market.noise_wallets[idx]].clone();
+                let deferring = market.watched(engine);
+                noise_trader
+                    .trade(&deferring, noise_provider.clone(), rng)
+                    .await
+                    .map_err(|e| e.at_step(step, format!("noise trader {idx}")))?;
+                send_watched(
+                    &mut self.agents,
+                    &market.watchers,
+                    &deferring,
+                    engine,
+                    self.feed.current_value(),
+                    noise_provider,
+                    step,
+                )
+                .await?;
+            }
+
+            for (idx, ((_, feed), (pool_engine, rng))) in self
+                .pools
+                .iter_mut()
+                .zip(pool_engines.iter().zip(&mut self.pool_rngs))
+                .enumerate()
+            {
+                let value = config.advance_feed(feed.as_mut(), rng);
+                pool_engine.set_price(value, admin_provider.clone()).await?;
+                feed_values.push((idx + 1, value));
+
+                let mut signal = pool_engine
+                    .signal(None, value, admin_provider.clone())
+                    .await?;
+                signal.arbitrageur_inventory = pool_engine
+                    .inventory(market.arbitrageur, &signal.pool, admin_provider.clone())
+                    .await?;
+
+                let deferring = market.watched(pool_engine);
+                self.arbitrageur
+                    .arbitrage(
+                        &signal,
+                        market.arbitrageur_provider.clone(),
+                        deferring.clone(),
+                    )
+                    .await
+                    .map_err(|e| e.at_step(step, "arbitrageur"))?;
+                send_watched(
+                    &mut self.agents,
+                    &market.watchers,
+                    &deferring,
+                    pool_engine,
+                    value,
+                    market.arbitrageur_provider.clone(),
+                    step,
+                )
+                .await?;
+
+                if let Some(step) = step {
+                    record_arbitrage(
+                        &mut self.inspector,
+                        &admin_provider,
+                        market.arbitrageur,
+                        pool_engine,
+                        idx + 1,
+                        &signal,
+                        step,
+                    )
+                    .await?;
+                }
+
+                for (trader, (noise_trader, rng)) in self
+                    .noise_traders
+                    .iter_mut()
+                    .zip(&mut rngs.pool_noise[idx])
+                    .enumerate()
+                {
+                    let noise_provider = self.providers[&market.noise_wallets[trader]].clone();
+                    let deferring = market.watched(pool_engine);
+                    noise_trader
+                        .trade(&deferring, noise_provider.clone(), rng)
+                        .await
+                        .map_err(|e| e.at_step(step, format!("noise trader {trader}")))?;
+                    send_watched(
+                        &mut self.agents,
+                        &market.watchers,
+                        &deferring,
+                        pool_engine,
+                        value,
+                        noise_provider,
+                        step,
+                    )
+                    .await?;
+                }
+            }
+        }
+
+        Ok((feed_values, deferred_arbitrage))
+    }
+
+    /// Returns the generator strategy `idx` is initialized with, see [`RngManager::rng_at`]. Strategies with a seed of
+    /// their own draw from a manager seeded with it, like in [`Arena::step_rng`].
+    fn init_rng(&self, idx: usize) -> StdRng {
+        match self.strategy_seeds.get(idx).copied().flatten() {
+            Some(seed) => RngManager::new(seed).rng_at(0, Stream::Strategy(idx), 0),
+            None => self.rng_manager.rng_at(self.path, Stream::Strategy(idx), 0),
+        }
+    }
+
+    /// Returns the generator `stream` draws from during `step`, see [`RngManager::rng_step`]. Strategies with a seed of
+    /// their own draw from a manager seeded with it.
+    fn step_rng(&self, stream: Stream, step: usize) -> StdRng {
+        if let Stream::Strategy(idx) = stream {
+            if let Some(seed) = self.strategy_seeds.get(idx).copied().flatten() {
+                return RngManager::new(seed).rng_step(0, stream, step);
+            }
+        }
+
+        self.rng_manager.rng_step(self.path, stream, step)
+    }
+
+    /// Advance every feed by the tick ending a step, without updating the liquid exchanges.
+    fn advance_feeds(&mut self, config: &Config) {
+        config.advance_feed(self.feed.as_mut(), &mut self.rng);
+        for ((_, feed), rng) in self.pools.iter_mut().zip(&mut self.pool_rngs) {
+            config.advance_feed(feed.as_mut(), rng);
+        }
+    }
+
+    /// Deploy and fund every pool, snapshotting the chain afterwards if the deployment is reused across runs.
+    async fn deploy(&mut self, config: &Config) -> Result<(Vec<Engine>, u64), ArenaError> {
+        let admin_provider = self.providers[&0].clone();
+
+        let mut pools = vec![self.main_pool(config)];
+        pools.extend(self.pools.iter().map(|(pool, _)| pool.clone()));
+
+        let deterministic = self.deterministic_addresses;
+        if deterministic {
+            self.remove_deployed().await?;
+        }
+
+        let first_block = admin_provider.get_block_number().await? + 1;
+        let engine = deploy_pool(&admin_provider, &pools[0], &self.tokens, deterministic).await?;
+        // every pool trades the same tokens through the same routers, so funding the wallets once covers them all
+        self.fund_accounts(&engine).await?;
+
+        let mut engines = vec![engine];
+        for pool in &pools[1..] {
+            let engine = deploy_shared_pool(&admin_provider, &engines, pool, deterministic).await?;
+            engines.push(engine);
+        }
+
+        if deterministic {
+            self.record_deployed(&engines).await?;
+        }
+
+        if self.reuse_deployment {
+            self.deployment = Some(Deployment {
+                snapshot: self.backend.snapshot().await?,
+                first_block,
+                pools,
+                engines: engines.iter().map(EngineState::from).collect(),
+                feeds: self.feed_snapshots()?,
+                strategies: self.strategy_snapshots()?,
+            });
+        }
+
+        Ok((engines, first_block))
+    }
+
+    /// Record the contracts deployed for the pools of `engines`, to be removed before the next deployment at
+    /// deterministic addresses: the controller, the contracts it created, the liquid exchanges of additional pools, the
+    /// tokens configured with [`ArenaBuilder::with_token`] along with the liquid exchange of their pool, and the hooks
+    /// deployed with [`ArenaBuilder::with_hook`] or [`ArenaBuilder::with_pool`].
+    async fn record_deployed(&mut self, engines: &[Engine]) -> Result<(), ArenaError> {
+        let admin_provider = self.providers[&0].clone();
+
+        // the nonce of a contract starts at one and counts up with every contract it creates, so it tells the addresses
+        // of everything the controller created without relying on what its constructor deploys
+        let controller = engines[0].controller;
+        let nonce = admin_provider.get_transaction_count(controller).await?;
+        self.deployed = std::iter::once(controller)
+            .chain((1..nonce).map(|nonce| controller.create(nonce)))
+            .chain(engines[1..].iter().map(Engine::liquid_exchange))
+            .collect();
+        if !self.tokens.is_empty() {
+            let key = engines[0].pool_key(admin_provider.clone()).await?;
+            self.deployed
+                .extend([key.currency0, key.currency1, engines[0].liquid_exchange()]);
+        }
+
+        let hooked = std::iter::once(self.hook.is_some())
+            .chain(self.pools.iter().map(|(pool, _)| pool.hook.is_some()));
+        for (engine, hooked) in engines.iter().zip(hooked) {
+            if hooked {
+                let hooks = engine.pool_key(admin_provider.clone()).await?.hooks;
+                self.deployed.push(hooks);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove the contracts of the previous deployment at deterministic addresses, clearing their code and nonce so the
+    /// deployment proxy can create contracts at their addresses again. The node starts a contract created at the
+    /// address of a removed one with empty storage, as for any newly created account.
+    async fn remove_deployed(&mut self) -> Result<(), ArenaError> {
+        for contract in std::mem::take(&mut self.deployed) {
+            self.backend.set_code(contract, Bytes::new()).await?;
+            self.backend.set_nonce(contract, 0).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Revert the chain to the snapshot taken after a previous deployment of the same pools, restoring the feeds and
+    /// strategies to their state at that point and resetting the inspector, see [`Inspector::reset`].
+    ///
+    /// Returns `None` if there is no such deployment to reuse.
+    async fn revert_deployment(
+        &mut self,
+        config: &Config,
+    ) -> Result<Option<(Vec<Engine>, u64)>, ArenaError> {
+        let mut pools = vec![self.main_pool(config)];
+        pools.extend(self.pools.iter().map(|(pool, _)| pool.clone()));
+
+        let Some(deployment) = &mut self.deployment else {
+            return Ok(None);
+        };
+        if deployment.pools != pools {
+            return Ok(None);
+        }
+
+        self.backend.revert(deployment.snapshot).await?;
+        // reverting consumes the snapshot, so take it again for the next run
+        deployment.snapshot = self.backend.snapshot().await?;
+
+        let (feed, pool_feeds) = deployment.feeds.clone();
+        self.feed.restore(feed)?;
+        for ((_, feed), state) in self.pools.iter_mut().zip(pool_feeds) {
+            feed.restore(state)?;
+        }
+        for (strategy, state) in self
+            .strategies
+            .iter_mut()
+            .zip(deployment.strategies.clone())
+        {
+            strategy.restore(state)?;
+        }
+        // the inspector observed the previous run on this deployment, which this run replaces
+        self.inspector.reset()?;
+
+        self.rng = self.rng_manager.rng(self.path, Stream::Feed);
+        self.pool_rngs = (0..self.pools.len())
+            .map(|idx| self.rng_manager.rng(self.path, Stream::PoolFeed(idx)))
+            .collect();
+
+        let engines = deployment
+            .engines
+            .iter()
+            .cloned()
+            .map(Engine::from)
+            .collect();
+
+        Ok(Some((engines, deployment.first_block)))
+    }
+
+    /// Returns the parameters of the pool of `config`, deploying the hook set with [`ArenaBuilder::with_hook`].
+    fn main_pool(&self, config: &Config) -> PoolConfig {
+        PoolConfig {
+            hook: self.hook.clone(),
+            ..config.pool()
+        }
+    }
+
+    /// Returns the state of the feed and of the feed of every additional pool, or an error if one of them cannot be
+    /// snapshotted.
+    fn feed_snapshots(&self) -> Result<(Value, Vec<Value>), ArenaError> {
+        let pool_feeds = self
+            .pools
+            .iter()
+            .enumerate()
+            .map(|(idx, (_, feed))| {
+                feed.snapshot()
+                    .map_err(|e| e.at_step(None, format!("pool feed {idx}")))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok((
+            self.feed.snapshot().map_err(|e| e.at_step(None, "feed"))?,
+            pool_feeds,
+        ))
+    }
+
+    /// Returns the state of every strategy, or an error if one of them cannot be snapshotted.
+    fn strategy_snapshots(&self) -> Result<Vec<Value>, ArenaError> {
+        self.strategies
+            .iter()
+            .enumerate()
+            .map(|(idx, strategy)| {
+                strategy
+                    .snapshot()
+                    .map_err(|e| e.at_step(None, format!("strategy {idx}")))
+            })
+            .collect()
+    }
+
+    /// Write a [`Checkpoint`] of a run to `path`.
+    async fn write_checkpoint(
+        &self,
+        run: &RunState,
+        engines: &[Engine],
+        path: &Path,
+    ) -> Result<(), ArenaError> {
+        let chain = self.backend.dump_state().await?;
+
+        let (feed, pool_feeds) = self.feed_snapshots()?;
+
+        Checkpoint {
+            chain,
+            feed,
+            pool_feeds,
+            strategies: self.strategy_snapshots()?,
+            engines: engines.iter().map(EngineState::from).collect(),
+            run: run.clone(),
+        }
+        .save(path)
+    }
+
+    /// Names of the simulation components sending transactions from each wallet, as recorded in a [`ReplayLog`].
+    fn components(&self) -> HashMap<Address, String> {
+        let mut components = HashMap::new();
+        components.insert(
+            self.providers[&0].default_signer_address(),
+            "arena".to_string(),
+        );
+
+        for idx in 0..self.strategies.len() {
+            components.insert(
+                self.providers[&(idx + 1)].default_signer_address(),
+                format!("strategy {idx}"),
+            );
+        }
+
+        components.insert(
+            self.providers[&self.arbitrageur_wallet()].default_signer_address(),
+            "arbitrageur".to_string(),
+        );
+
+        for idx in 0..self.agents.len() {
+            components.insert(
+                self.providers[&agent_wallet(self.strategies.len(), self.noise_traders.len(), idx)]
+                    .default_signer_address(),
+                format!("agent {idx}"),
+            );
+        }
+
+        for idx in 0..self.noise_traders.len() {
+            components.insert(
+                self.providers[&self.noise_trader_wallet(idx)].default_signer_address(),
+                format!("noise trader {idx}"),
+            );
+        }
+
+        components
+    }
+
+    /// Returns the index of the wallet the arbitrageur trades from, which follows the wallets of the strategies.
+    fn arbitrageur_wallet(&self) -> usize {
+        self.strategies.len() + 1
+    }
+
+    /// Returns the index of the wallet noise trader `idx` trades from, which follows the wallet of the arbitrageur.
+    fn noise_trader_wallet(&self, idx: usize) -> usize {
+        self.strategies.len() + 2 + idx
+    }
+
+    /// Mark the wallet of every strategy to market, across the main pool and every additional pool.
+    async fn mark_strategies(
+        &self,
+        engine: &Engine,
+        signal: &Signal,
+        pool_engines: &[Engine],
+        marks: &mut [Vec<Mark>],
+    ) -> Result<(), ArenaError> {
+        if marks.is_empty() {
+            return Ok(());
+        }
+
+        let mut pools = vec![(engine, signal.clone())];
+        for ((_, feed), pool_engine) in self.pools.iter().zip(pool_engines) {
+            let pool_signal = pool_engine
+                .signal(
+                    signal.step,
+                    feed.current_value(),
+                    self.providers[&0].clone(),
+                )
+                .await?;
+            pools.push((pool_engine, pool_signal));
+        }
+
+        for (idx, marks) in marks.iter_mut().enumerate() {
+            marks.push(Mark::fetch_pools(&pools, self.providers[&(idx + 1)].clone()).await?);
+        }
+
+        Ok(())
+    }
+
+    /// Mark the wallet of every strategy, agent, the arbitrageur and every noise trader at the spot price for the
+    /// inspector, if it asks for marks.
+    async fn mark_agents(
+        &mut self,
+        step: Option<usize>,
+        engine: &Engine,
+        signal: &Signal,
+    ) -> Result<(), ArenaError> {
+        if !self.inspector.marks_agents() {
+            return Ok(());
+        }
+
+        let mut marks = Vec::with_capacity(
+            self.strategies.len() + self.agents.len() + self.noise_traders.len() + 2,
+        );
+        for idx in 0..self.strategies.len() {
+            let provider = self.providers[&(idx + 1)].clone();
+            marks
+                .push(AgentMark::fetch(format!("strategy {idx}"), engine, signal, provider).await?);
+        }
+        for idx in 0..self.agents.len() {
+            let provider = self.providers
+                [&agent_wallet(self.strategies.len(), self.noise_traders.len(), idx)]
+                .clone();
+            marks.push(AgentMark::fetch(format!("agent {idx}"), engine, signal, provider).await?);
+        }
+
+        let provider = self.providers[&self.arbitrageur_wallet()].clone();
+        marks.push(AgentMark::fetch("arbitrageur", engine, signal, provider).await?);
+        for idx in 0..self.noise_traders.len() {
+            let provider = self.providers[&self.noise_trader_wallet(idx)].clone();
+            marks.push(
+                AgentMark::fetch(format!("noise trader {idx}"), engine, signal, provider).await?,
+            );
+        }
+
+        self.inspector
+            .on_marks(step, &marks)
+            .map_err(|e| e.at_step(step, "inspector"))
+    }
+
+    /// Read the liquidity of the pool around the current tick for the inspector, if it asks for it at `step`.
+    async fn snapshot_liquidity(&mut self, step: usize, signal: &Signal) -> Result<(), ArenaError> {
+        let Some(words) = self.inspector.liquidity_words(step) else {
+            return Ok(());
+        };
+
+        let profile = pool::liquidity_profile(
+            signal.manager,
+            &signal.pool,
+            U256::from(signal.sqrt_price_x96),
+            signal.tick.as_i32(),
+            words,
+            self.providers[&0].clone(),
+        )
+        .await?;
+
+        self.inspector
+            .on_liquidity(step, &profile)
+            .map_err(|e| e.at_step(Some(step), "inspector"))
+    }
+
+    /// Returns the contracts whose logs are captured for the inspector, the `PoolManager` and the tokens of every pool,
+    /// or none if the inspector does not capture logs.
+    async fn log_sources(&self, engines: &[Engine]) -> Result<Vec<Address>, ArenaError> {
+        let mut sources = Vec::new();
+        if !self.inspector.captures_logs() {
+            return Ok(sources);
+        }
+
+        for engine in engines {
+            let pool = PoolDeployment::fetch(engine, self.providers[&0].clone()).await?;
+            for source in [pool.manager, pool.key.currency0, pool.key.currency1] {
+                if !source.is_zero() && !sources.contains(&source) {
+                    sources.push(source);
+                }
+            }
+        }
+
+        Ok(sources)
+    }
+
+    /// Fetch the logs `sources` emitted since block `from` for the inspector, if it captures them, and move `from` past
+    /// them.
+    async fn capture_logs(
+        &mut self,
+        step: Option<usize>,
+        sources: &[Address],
+        from: &mut u64,
+    ) -> Result<(), ArenaError> {
+        if !self.inspector.captures_logs() {
+            return Ok(());
+        }
+
+        let provider = self.providers[&0].clone();
+        let latest = provider.get_block_number().await?;
+        let logs = events::fetch_logs(*from, latest, sources, provider).await?;
+        *from = latest + 1;
+
+        self.inspector
+            .on_logs(step, &logs)
+            .map_err(|e| e.at_step(step, "inspector"))
+    }
+
+    /// Fund the wallet of every strategy, agent and noise trader and of the arbitrageur, with ether for gas and a
+    /// balance of both pool tokens.
+    async fn fund_accounts(&self, engine: &Engine) -> Result<(), ArenaError> {
+        let controller = engine.controller;
+        let pool = engine.pool_key(self.providers[&0].clone()).await?;
+        let currencies = [pool.currency0, pool.currency1];
+        let spenders = [
+            backend::read(
+                &*self.backend,
+                controller,
+                ArenaController::getRouterCall {},
+            )
+            .await?
+            ._0,
+            engine.swap_router(),
+            backend::read(
+                &*self.backend,
+                controller,
+                ArenaController::constructSignalCall {},
+            )
+            .await?
+            ._0
+            .manager,
+        ];
+
+        let default = Funding::new(
+            parse_ether(STRATEGY_ETHER)?,
+            self.strategy_funding,
+            self.strategy_funding,
+        );
+
+        let mut accounts: Vec<(usize, Funding)> = (0..self.strategies.len())
+            .map(|idx| {
+                let funding = self.strategy_fundings.get(idx).cloned().flatten();
+                (idx + 1, funding.unwrap_or_else(|| default.clone()))
+            })
+            .collect();
+        accounts.extend((0..self.agents.len()).map(|idx| {
+            (
+                agent_wallet(self.strategies.len(), self.noise_traders.len(), idx),
+                default.clone(),
+            )
+        }));
+        accounts.push((
+            self.arbitrageur_wallet(),
+            self.arbitrageur_funding
+                .clone()
+                .unwrap_or_else(|| default.clone()),
+        ));
+        let noise_funding = self
+            .noise_trader_funding
+            .clone()
+            .unwrap_or_else(|| default.clone());
+        accounts.extend(
+            (0..self.noise_traders.len())
+                .map(|idx| (self.noise_trader_wallet(idx), noise_funding.clone())),
+        );
+
+        for (wallet, funding) in accounts {
+            self.fund(&self.providers[&wallet], &funding, currencies, spenders)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Fund the wallet behind `provider` with ether and the pool `currencies` and, if requested, approve `spenders` to
+    /// spend its tokens.
+    async fn fund(
+        &self,
+        provider: &AnvilProvider,
+        funding: &Funding,
+        currencies: [Address; 2],
+        spenders: [Address; 3],
+    ) -> Result<(), ArenaError> {
+        let admin_provider = self.providers[&0].clone();
+        let account = provider.default_signer_address();
+
+        self.backend.set_balance(account, funding.ether).await?;
+
+        for (currency, amount) in currencies.into_iter().zip([funding.token0, funding.token1]) {
+            if amount.is_zero() {
+                continue;
+            }
+
+            ArenaToken::new(currency, admin_provider.clone())
+                .mint(account, amount)
+                .nonce(
+                    admin_provider
+                        .get_transaction_count(admin_provider.default_signer_address())
+                        .await?,
+                )
+                .send()
+                .await?
+                .watch()
+                .await?;
+        }
+
+        if !funding.approve {
+            return Ok(());
+        }
+
+        for currency in currencies {
+            for spender in spenders {
+                ArenaToken::new(currency, provider.clone())
+                    .approve(spender, U256::MAX)
+                    .nonce(provider.get_transaction_count(account).await?)
+                    .send()
+                    .await?
+                    .watch()
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Deploy a controller along with its `PoolManager`, tokens and liquid exchange, initialize its pool and provide its
+/// bootstrap liquidity, if any.
+///
+/// If `deterministic`, the controller is deployed through the deployment proxy, see [`hook::create2`], so it and every
+/// contract it creates get the same address on every deployment. Returns an [`Engine`] acting through the controller.
+///
+/// With `tokens` configured, the pool trades a pair of tokens deployed with the name, symbol and decimals of the first
+/// of `tokens` for token0 and of the second for token1, see [`token::deploy_pair`], rather than the tokens of the
+/// controller. The pool is then initialized on the `PoolManager` of the controller next to its own pool, and arbitraged
+/// against a liquid exchange of its own, which the supply of each token is minted to.
+async fn deploy_pool(
+    provider: &AnvilProvider,
+    pool: &PoolConfig,
+    tokens: &[TokenConfig],
+    deterministic: bool,
+) -> Result<Engine, ArenaError> {
+    let decimal = |idx: usize| {
+        tokens
+            .get(idx)
+            .map_or(18, |token: &TokenConfig| token.decimals)
+    };
+    let decimals = (decimal(0), decimal(1));
+    let sqrt_price_x96 = initial_sqrt_price(pool, decimals)?;
+
+    let controller = if deterministic {
+        let init_code =
+            ArenaController::deploy_builder(provider.clone(), pool.manager_fee, pool.initial_price)
+                .calldata()
+                .to_vec();
+        let address = hook::create2(B256::ZERO, &init_code, provider.clone()).await?;
+        ArenaController::new(address, provider.clone())
+    } else {
+        ArenaController::deploy(provider.clone(), pool.manager_fee, pool.initial_price).await?
+    };
+    let manager = backend::read(
+        provider,
+        *controller.address(),
+        ArenaController::constructSignalCall {},
+    )
+    .await?
+    ._0
+    .manager;
+
+    let hooks = match &pool.hook {
+        Some(hook) => hook::deploy(hook, manager, provider.clone()).await?,
+        None => pool.hooks,
+    };
+
+    let mut engine = if tokens.is_empty() {
+        controller
+            .setPool(
+                pool.fee,
+                pool.tick_spacing,
+                hooks,
+                sqrt_price_x96,
+                pool.hook_data.clone(),
+            )
+            .send()
+            .await
+            .map_err(ArenaError::ContractError)?
+            .watch()
+            .await
+            .map_err(ArenaError::PendingTransactionError)?;
+
+        Engine::new(*controller.address())
+    } else {
+        // the tokens of the controller are named like this, for a pair configuring only token0
+        let configs = [0, 1].map(|idx| {
+            tokens.get(idx).cloned().unwrap_or_else(|| {
+                TokenConfig::new(
+                    &format!("currency{idx}"),
+                    &format!("c{idx}"),
+                    18,
+                    U256::ZERO,
+                )
+            })
+        });
+        let (currency0, currency1) = token::deploy_pair(
+            *controller.address(),
+            [&configs[0], &configs[1]],
+            provider.clone(),
+        )
+        .await?;
+
+        let key = PoolKey {
+            currency0,
+            currency1,
+            fee: pool.fee,
+            tickSpacing: pool.tick_spacing,
+            hooks,
+        };
+        initialize_pool(provider, manager, &key, sqrt_price_x96, pool).await?;
+        let exchange = deploy_exchange(provider, &key, pool, deterministic).await?;
+
+        for (currency, config) in [currency0, currency1].into_iter().zip(&configs) {
+            if !config.supply.is_zero() {
+                token::mint(currency, exchange, config.supply, provider.clone()).await?;
+            }
+        }
+
+        Engine::shared(*controller.address(), key, exchange)
+    };
+
+    engine.decimals = decimals;
+    bootstrap_liquidity(provider, &engine, pool, sqrt_price_x96).await?;
+
+    Ok(engine)
+}
+
+/// Initialize another pool of the tokens of the first of `engines` on its `PoolManager`, deploy a liquid exchange for
+/// it and provide its bootstrap liquidity, if any.
+///
+/// The pool shares the tokens, routers and manager of the first pool, so it differs from every pool of `engines` by its
+/// fee, tick spacing or hooks. If `deterministic`, the liquid exchange is deployed through the deployment proxy with the
+/// pool id as salt. Returns an [`Engine`] acting on it.
+async fn deploy_shared_pool(
+    provider: &AnvilProvider,
+    engines: &[Engine],
+    pool: &PoolConfig,
+    deterministic: bool,
+) -> Result<Engine, ArenaError> {
+    let main = &engines[0];
+    let signal = main.signal(None, 0.0, provider.clone()).await?;
+    let sqrt_price_x96 = initial_sqrt_price(pool, main.decimals)?;
+
+    let hooks = match &pool.hook {
+        Some(hook) => hook::deploy(hook, signal.manager, provider.clone()).await?,
+        None => pool.hooks,
+    };
+    let key = PoolKey {
+        currency0: signal.pool.currency0,
+        currency1: signal.pool.currency1,
+        fee: pool.fee,
+        tickSpacing: pool.tick_spacing,
+        hooks,
+    };
+
+    for engine in engines {
+        if pool::pool_id(&engine.pool_key(provider.clone()).await?) == pool::pool_id(&key) {
+            return Err(ArenaError::Custom(format!(
+                "pool with fee {} and tick spacing {} and hooks {hooks} is deployed twice",
+                pool.fee, pool.tick_spacing
+            )));
+        }
+    }
+
+    initialize_pool(provider, signal.manager, &key, sqrt_price_x96, pool).await?;
+    let exchange = deploy_exchange(provider, &key, pool, deterministic).await?;
+
+    let mut engine = Engine::shared(main.controller, key, exchange);
+    engine.decimals = main.decimals;
+    bootstrap_liquidity(provider, &engine, pool, sqrt_price_x96).await?;
+
+    Ok(engine)
+}
+
+/// Initialize the pool of `key` on `manager` at `sqrt_price_x96`, passing the hook data of `pool`.
+async fn initialize_pool(
+    provider: &AnvilProvider,
+    manager: Address,
+    key: &PoolKey,
+    sqrt_price_x96: Uint<160, 3>,
+    pool: &PoolConfig,
+) -> Result<(), ArenaError> {
+    let admin = provider.default_signer_address();
+    IPoolManager::new(manager, provider.clone())
+        .initialize(
+            pool::manager_key(key),
+            sqrt_price_x96,
+            pool.hook_data.clone(),
+        )
+        .nonce(provider.get_transaction_count(admin).await?)
+        .send()
+        .await
+        .map_err(ArenaError::ContractError)?
+        .watch()
+        .await
+        .map_err(ArenaError::PendingTransactionError)?;
+
+    Ok(())
+}
+
+/// Deploy a liquid exchange trading the tokens of `key` at the initial price of `pool`, and return its address.
+///
+/// If `deterministic`, the exchange is deployed through the deployment proxy with the pool id as salt.
+async fn deploy_exchange(
+    provider: &AnvilProvider,
+    key: &PoolKey,
+    pool: &PoolConfig,
+    deterministic: bool,
+) -> Result<Address, ArenaError> {
+    let deployment = LiquidExchange::deploy_builder(
+        provider.clone(),
+        key.currency0,
+        key.currency1,
+        pool.initial_price,
+    );
+
+    if deterministic {
+        return hook::create2(pool::pool_id(key), deployment.calldata(), provider.clone()).await;
+    }
+
+    let admin = provider.default_signer_address();
+    deployment
+        .nonce(provider.get_transaction_count(admin).await?)
+        .deploy()
+        .await
+        .map_err(ArenaError::ContractError)
+}
+
+/// Returns the sqrt price `pool` is initialized at, converting [`PoolConfig::price`] with the decimals of the tokens.
+fn initial_sqrt_price(pool: &PoolConfig, decimals: (u8, u8)) -> Result<Uint<160, 3>, ArenaError> {
+    match pool.price {
+        Some(price) => pool::sqrt_price_x96(price, decimals).ok_or_else(|| {
+            ArenaError::Custom(format!("pool price {price} has no valid sqrt price"))
+        }),
+        None => Ok(pool.sqrt_price_x96),
+    }
+}
+
+/// Provide the bootstrap liquidity of `pool` from the admin wallet, minting it the tokens backing the liquidity.
+async fn bootstrap_liquidity(
+    provider: &AnvilProvider,
+    engine: &Engine,
+    pool: &PoolConfig,
+    sqrt_price_x96: Uint<160, 3>,
+) -> Result<(), ArenaError> {
+    let Some((tick_lower, tick_upper, liquidity)) = pool.liquidity else {
+        return Ok(());
+    };
+
+    // the admin pays for the liquidity, so mint it the tokens backing it, rounded up
+    let amounts = Position::new(tick_lower.as_i32(), tick_upper.as_i32(), liquidity)
+        .amounts(f64::from(sqrt_price_x96) / 2f64.powi(96));
+    let key = engine.pool_key(provider.clone()).await?;
+    let admin = provider.default_signer_address();
+    for (currency, amount) in [key.currency0, key.currency1]
+        .into_iter()
+        .zip([amounts.0, amounts.1])
+    {
+        let amount = U256::try_from((amount * (1.0 + 1e-9)).ceil() + 1.0)
+            .map_err(|e| ArenaError::Custom(format!("invalid bootstrap liquidity amount: {e}")))?;
+
+        ArenaToken::new(currency, provider.clone())
+            .mint(admin, amount)
+            .nonce(provider.get_transaction_count(admin).await?)
+            .send()
+            .await?
+            .watch()
+            .await?;
+    }
+
+    engine
+        .modify_liquidity(
+            I256::try_from(liquidity).unwrap(),
+            tick_lower,
+            tick_upper,
+            pool.hook_data.clone(),
+            provider.clone(),
+        )
+        .await
+}
+
+/// Returns the index of the wallet of agent `idx`, which follows the wallets of the strategies, the arbitrageur and the
+/// noise traders.
+fn agent_wallet(strategies: usize, noise_traders: usize, idx: usize) -> usize {
+    strategies + 2 + noise_traders + idx
+}
+
+/// Returns the timestamp of the latest block.
+async fn latest_timestamp(provider: &AnvilProvider) -> Result<u64, ArenaError> {
+    Ok(provider
+        .get_block_by_number(BlockNumberOrTag::Latest, false)
+        .await?
+        .map_or(0, |block| block.header.timestamp))
+}
+
+/// Returns the timestamp of the block closing a step, `seconds_per_step` after the previous step boundary, or right
+/// after the `latest` block when blocks mined during the step already went past it.
+fn next_step_timestamp(previous: u64, seconds_per_step: u64, latest: u64) -> u64 {
+    (previous + seconds_per_step).max(latest + 1)
+}
+
+/// Report a panic caught in a strategy to the inspector and apply the [`PanicPolicy`].
+///
+/// Returns whether the strategy should be dropped from the simulation.
+fn isolate_panic<V>(
+    inspector: &mut Box<dyn Inspector<V>>,
+    policy: PanicPolicy,
+    step: Option<usize>,
+    component: String,
+    panic: Box<dyn Any + Send>,
+) -> Result<bool, ArenaError> {
+    let message = panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+
+    inspector.on_failure(step, &component, &message)?;
+
+    match policy {
+        PanicPolicy::Abort => Err(ArenaError::Panic(message).at_step(step, component)),
+        PanicPolicy::Continue => Ok(false),
+        PanicPolicy::Drop => Ok(true),
+    }
+}
+
+/// Participants and wallets the market of every step of a run trades through, see [`Arena::trade_step`].
+struct StepMarket<'a> {
+    /// Engine of the pool of the [`Config`], followed by those of the additional pools.
+    engines: &'a [Engine],
+
+    /// Wallet of the arbitrageur.
+    arbitrageur: Address,
+    arbitrageur_provider: AnvilProvider,
+
+    /// Index of the wallet of every noise trader.
+    noise_wallets: Vec<usize>,
+
+    /// Agents watching swaps, by their index and with their wallets, see [`send_watched`].
+    watchers: Vec<(usize, AnvilProvider)>,
+}
+
+impl StepMarket<'_> {
+    /// Returns an engine acting on the pool of `engine`, holding its swaps back for the watchers if there are any.
+    fn watched(&self, engine: &Engine) -> Engine {
+        if self.watchers.is_empty() {
+            engine.clone()
+        } else {
+            engine.deferring()
+        }
+    }
+}
+
+/// Generators the market participants other than the feeds and the arbitrageur draw from during a step, see
+/// [`Arena::reseed_market`].
+struct StepRngs {
+    /// Generator of every noise trader on the pool of the [`Config`].
+    noise: Vec<StdRng>,
+
+    /// Generators of every noise trader on each additional pool.
+    pool_noise: Vec<Vec<StdRng>>,
+
+    /// Generator of the gas market.
+    gas: StdRng,
+}
+
+/// Mine the boundary of a step [`Config::seconds_per_step`] after `step_timestamp`, the timestamp of the previous
+/// boundary, unless blocks mined during the step already went past it, and move `step_timestamp` to it.
+async fn mine_step_boundary(
+    config: &Config,
+    engine: &Engine,
+    provider: &AnvilProvider,
+    step_timestamp: &mut u64,
+) -> Result<(), ArenaError> {
+    let Some(seconds_per_step) = config.seconds_per_step else {
+        return Ok(());
+    };
+
+    let latest = latest_timestamp(provider).await?;
+    // validated to be a positive whole number of seconds before the run starts
+    *step_timestamp = next_step_timestamp(*step_timestamp, seconds_per_step as u64, latest);
+
+    engine
+        .set_next_block_timestamp(*step_timestamp, provider.clone())
+        .await?;
+    engine.mine(1, provider.clone()).await
+}
+
+/// Send the swaps `deferring` held back through `engine`, from the wallet behind `provider`, one at a time between the
+/// calls of the agents in `watchers`, given by their index in `agents` and their wallet, on a pool whose feed is at
+/// `price`. Agent errors are attributed to `step`.
+#[allow(clippy::too_many_arguments)]
+async fn send_watched(
+    agents: &mut [(i32, Box<dyn Agent>)],
+    watchers: &[(usize, AnvilProvider)],
+    deferring: &Engine,
+    engine: &Engine,
+    price: f64,
+    provider: AnvilProvider,
+    step: Option<usize>,
+) -> Result<(), ArenaError> {
+    for swap in deferring.take_deferred() {
+        for (idx, watcher) in watchers {
+            agents[*idx]
+                .1
+                .before_swap(&swap, price, watcher.clone(), engine.clone())
+                .await
+                .map_err(|e| e.at_step(step, format!("agent {idx}")))?;
+        }
+
+        swap.send(engine, provider.clone()).await?;
+
+        for (idx, watcher) in watchers.iter().rev() {
+            agents[*idx]
+                .1
+                .after_swap(&swap, price, watcher.clone(), engine.clone())
+                .await
+                .map_err(|e| e.at_step(step, format!("agent {idx}")))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Record the swap of the arbitrageur on the pool at index `pool`, from the inventory and block of the `signal` it acted
+/// on, in the [`crate::engine::arbitrageur::ArbitrageLedger`] of `engine`, and report it to the inspector.
+///
+/// Returns the swap, or `None` if the arbitrageur did not trade.
+async fn record_arbitrage<V>(
+    inspector: &mut Box<dyn Inspector<V>>,
+    provider: &AnvilProvider,
+    arbitrageur: Address,
+    engine: &Engine,
+    pool: usize,
+    signal: &Signal,
+    step: usize,
+) -> Result<Option<ArbitrageTrade>, ArenaError> {
+    let inventory = engine
+        .inventory(arbitrageur, &signal.pool, provider.clone())
+        .await?;
+    let Some(trade) = ArbitrageTrade::between(&signal.arbitrageur_inventory, &inventory, step)
+    else {
+        return Ok(None);
+    };
+
+    let gas_used = gas_used_since(provider, signal.block_number, arbitrageur).await?;
+    let fill = ArbitrageFill::new(
+        &trade,
+        engine.decimals,
+        signal.current_value,
+        gas_used,
+        signal.gas_price,
+    );
+
+    engine.record_fill(fill);
+    inspector
+        .on_arbitrage(pool, &fill)
+        .map_err(|e| e.at_step(Some(step), "inspector"))?;
+
+    Ok(Some(trade))
+}
+
+/// Transactions waiting in Anvil's mempool, as returned by `txpool_content`.
+#[derive(Debug, Deserialize)]
+struct TxpoolContent {
+    pending: BTreeMap<Address, BTreeMap<String, Transaction>>,
+}
+
+impl TxpoolContent {
+    /// Flatten the pending pool, ordered by sender and then numerically by nonce.
+    fn into_pending(self) -> Vec<Transaction> {
+        self.pending
+            .into_values()
+            .flat_map(|transactions| {
+                let mut transactions: Vec<_> = transactions.into_values().collect();
+                // nonces are keyed by their decimal string, which does not sort numerically
+                transactions.sort_by_key(|transaction| transaction.nonce);
+                transactions
+            })
+            .collect()
+    }
+}
+
+/// Returns the transactions waiting in the mempool, ordered by sender and nonce.
+async fn pending_transactions(provider: &AnvilProvider) -> Result<Vec<Transaction>, ArenaError> {
+    let content: TxpoolContent = provider.client().request_noparams("txpool_content").await?;
+
+    Ok(content.into_pending())
+}
+
+/// Sum the gas used by the blocks mined after `block`.
+async fn block_gas_used_since(provider: &AnvilProvider, block: u64) -> Result<u128, ArenaError> {
+    let mut gas_used = 0;
+
+    for number in block + 1..=provider.get_block_number().await? {
+        if let Some(block) = provider
+            .get_block_by_number(BlockNumberOrTag::Number(number), false)
+            .await?
+        {
+            gas_used += block.header.gas_used;
+        }
+    }
+
+    Ok(gas_used)
+}
+
+/// Sum the gas used by transactions sent from `owner` in the blocks mined after `block`.
+async fn gas_used_since(
+    provider: &AnvilProvider,
+    block: u64,
+    owner: Address,
+) -> Result<u128, ArenaError> {
+    let mut gas_used = 0;
+
+    for number in block + 1..=provider.get_block_number().await? {
+        let receipts = provider
+            .get_block_receipts(BlockNumberOrTag::Number(number).into())
+            .await?
+            .unwrap_or_default();
+
+        gas_used += gas_used_by(&receipts, owner);
+    }
+
+    Ok(gas_used)
+}
+
+/// Sum the gas used by the transactions of `receipts` sent from `owner`.
+fn gas_used_by(receipts: &[TransactionReceipt], owner: Address) -> u128 {
+    receipts
+        .iter()
+        .filter(|receipt| receipt.from == owner)
+        .map(|receipt| receipt.gas_used)
+        .sum()
+}
+
+/// Returns whether a strategy allowed to spend `budget` gas, if limited, has spent it all with `gas_used`.
+fn over_budget(budget: Option<u128>, gas_used: u128) -> bool {
+    budget.is_some_and(|budget| gas_used >= budget)
+}
+
+/// A builder for an [`Arena`] that can be used to configure the simulation.
+pub struct ArenaBuilder<V> {
+    /// Configuration of the Anvil node spawned as [`Arena::env`] when the arena is built.
+    pub anvil: Anvil,
+
+    /// [`Arena::strategies`]
+    pub strategies: Vec<Box<dyn Strategy<V>>>,
+
+    /// [`Arena::feed`]
+    pub feed: Option<Box<dyn Feed>>,
+
+    /// [`Arena::inspector`]
+    pub inspector: Option<Box<dyn Inspector<V>>>,
+
+    /// [`Arena::arbitrageur`]
+    pub arbitrageur: Option<Box<dyn Arbitrageur>>,
+
+    /// [`Arena::noise_traders`]
+    pub noise_traders: Vec<NoiseTrader>,
+
+    /// [`Arena::agents`]
+    pub agents: Vec<(i32, Box<dyn Agent>)>,
+
+    /// [`Arena::random_agent_order`]
+    pub random_agent_order: bool,
+
+    /// [`Arena::jit`]
+    pub jit: Option<JitLiquidity>,
+
+    /// [`Arena::block_builder`]
+    pub block_builder: Option<BlockBuilder>,
+
+    /// Seed used to construct [`Arena::rng_manager`]. If unset, the manager is seeded from system entropy.
+    pub seed: Option<u64>,
+
+    /// [`Arena::path`]
+    pub path: usize,
+
+    /// Whether paths are sampled in antithetic pairs.
+    pub antithetic: bool,
+
+    /// [`Arena::strategy_funding`]
+    pub strategy_funding: U256,
+
+    /// [`Arena::strategy_fundings`]
+    pub strategy_fundings: Vec<Option<Funding>>,
+
+    /// [`Arena::arbitrageur_funding`]
+    pub arbitrageur_funding: Option<Funding>,
+
+    /// [`Arena::noise_trader_funding`]
+    pub noise_trader_funding: Option<Funding>,
+
+    /// [`Arena::gas_budgets`]
+    pub gas_budgets: Vec<Option<u128>>,
+
+    /// [`Arena::strategy_seeds`]
+    pub strategy_seeds: Vec<Option<u64>>,
+
+    /// [`Arena::pools`]
+    pub pools: Vec<(PoolConfig, Box<dyn Feed>)>,
+
+    /// [`Arena::tokens`]
+    pub tokens: Vec<TokenConfig>,
+
+    /// [`Arena::hook`]
+    pub hook: Option<HookConfig>,
+
+    /// Whether runs are recorded into [`Arena::replay_log`].
+    pub recording: bool,
+
+    /// Transactions traced into [`Arena::trace_log`], see [`ArenaBuilder::with_tracing`].
+    #[cfg(feature = "trace")]
+    pub tracing: Option<TraceSelection>,
+
+    /// [`Arena::block_time`]
+    pub block_time: Option<u64>,
+
+    /// [`Arena::hooks`]
+    pub hooks: Vec<Box<dyn Hooks>>,
+
+    /// Handle stopping the arena, see [`Arena::interrupt_handle`].
+    pub interrupt: Interrupt,
+
+    /// Whether [`ArenaBuilder::interrupt`] is owned by the caller, see [`ArenaBuilder::with_interrupt`].
+    pub shared_interrupt: bool,
+
+    /// [`Arena::reuse_deployment`]
+    pub reuse_deployment: bool,
+
+    /// [`Arena::deterministic_addresses`]
+    pub deterministic_addresses: bool,
+
+    /// Transport the providers reach the Anvil node over.
+    pub transport: Transport,
+
+    /// Whether the arena runs on an in-process `revm` database rather than an Anvil node, see
+    /// [`ArenaBuilder::with_revm`].
+    #[cfg(feature = "revm")]
+    pub revm: bool,
+}
+
+impl<V> Default for ArenaBuilder<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> ArenaBuilder<V> {
+    /// Public constructor function for a new [`ArenaBuilder`].
+    pub fn new() -> Self {
+        ArenaBuilder {
+            anvil: Anvil::default(),
+            strategies: Vec::new(),
+            feed: None,
+            inspector: None,
+            arbitrageur: None,
+            noise_traders: Vec::new(),
+            agents: Vec::new(),
+            random_agent_order: false,
+            jit: None,
+            block_builder: None,
+            seed: None,
+            path: 0,
+            antithetic: false,
+            strategy_funding: parse_ether("1000000").unwrap(),
+            strategy_fundings: Vec::new(),
+            arbitrageur_funding: None,
+            noise_trader_funding: None,
+            gas_budgets: Vec::new(),
+            strategy_seeds: Vec::new(),
+            pools: Vec::new(),
+            tokens: Vec::new(),
+            hook: None,
+            recording: false,
+            #[cfg(feature = "trace")]
+            tracing: None,
+            block_time: None,
+            hooks: Vec::new(),
+            interrupt: Interrupt::new(),
+            shared_interrupt: false,
+            reuse_deployment: false,
+            deterministic_addresses: false,
+            transport: Transport::default(),
+            #[cfg(feature = "revm")]
+            revm: false,
+        }
+    }
+
+    /// Add a strategy to the simulation.
+    ///
+    /// Can be called multiple times. Each strategy gets its own wallet, funded with ether and a balance of both pool
+    /// tokens, and all strategies act on every step in the order they were added. Wrap a strategy in
+    /// [`crate::strategy::Scheduled`] to have it act less often.
+    pub fn with_strategy(mut self, strategy: Box<dyn Strategy<V>>) -> Self {
+        self.strategies.push(strategy);
+        self.strategy_fundings.push(None);
+        self.gas_budgets.push(None);
+        self.strategy_seeds.push(None);
+        self
+    }
+
+    /// Add a strategy that stops being processed once its transactions have used `gas_budget` gas.
+    pub fn with_strategy_gas_budget(
+        mut self,
+        strategy: Box<dyn Strategy<V>>,
+        gas_budget: u128,
+    ) -> Self {
+        self.strategies.push(strategy);
+        self.strategy_fundings.push(None);
+        self.gas_budgets.push(Some(gas_budget));
+        self.strategy_seeds.push(None);
+        self
+    }
+
+    /// Add a strategy whose random number generator is seeded with `seed`, rather than derived from the simulation
+    /// seed.
+    ///
+    /// The generator is passed to every [`Strategy::init`] and [`Strategy::process`] call, so randomized strategies
+    /// replay identically for a given seed, whatever other strategies are in the arena.
+    pub fn with_strategy_seeded(mut self, strategy: Box<dyn Strategy<V>>, seed: u64) -> Self {
+        self.strategies.push(strategy);
+        self.strategy_fundings.push(None);
+        self.gas_budgets.push(None);
+        self.strategy_seeds.push(Some(seed));
+        self
+    }
+
+    /// Set the feed that provides the current, theoretical value of the pool.
+    pub fn with_feed(mut self, feed: Box<dyn Feed>) -> Self {
+        self.feed = Some(feed);
+        self
+    }
+
+    /// Set the inspector that is used to evaluate the performance of the strategies.
+    pub fn with_inspector(mut self, inspector: Box<dyn Inspector<V>>) -> Self {
+        self.inspector = Some(inspector);
+        self
+    }
+
+    /// Set the inspector that is used to evaluate the performance of the strategies.
+    pub fn with_arbitrageur(mut self, arbitrageur: Box<dyn Arbitrageur>) -> Self {
+        self.arbitrageur = Some(arbitrageur);
+        self
+    }
+
+    /// Add a pool next to the one described by the [`Config`], with a feed driving its own liquid exchange.
+    ///
+    /// Every pool is initialized on the `PoolManager` of the pool of the [`Config`], with the same token pair, so pools
+    /// differ by their fee, tick spacing or hooks, and share the manager fee of the main pool. Each pool is arbitraged
+    /// against a liquid exchange of its own. The arbitrageur pegs every pool on every sub-tick, noise traders trade on
+    /// every pool, and strategies receive one signal per subscribed pool each step, all of them in
+    /// [`crate::MarketSignal`], and are scored on their positions in every pool. Signals of additional pools carry no
+    /// history, events or pending transactions.
+    pub fn with_pool(mut self, pool: PoolConfig, feed: Box<dyn Feed>) -> Self {
+        self.pools.push((pool, feed));
+        self
+    }
+
+    /// Configure the next pool token: the first call sets token0 of every pool and the second token1.
+    ///
+    /// The token is deployed with the given name, symbol and decimals as constructor arguments. `supply`, in its
+    /// smallest unit, is minted to the liquid exchange of the pool as its starting reserves, see [`TokenConfig::supply`],
+    /// while wallets are funded separately, see [`ArenaBuilder::with_strategy_funding`]. Feed values, noise trader
+    /// orders and [`crate::Signal::price`] are in whole tokens, and converted to raw units using the decimals of each
+    /// token.
+    pub fn with_token(mut self, name: &str, symbol: &str, decimals: u8, supply: U256) -> Self {
+        self.tokens
+            .push(TokenConfig::new(name, symbol, decimals, supply));
+        self
+    }
+
+    /// Deploy a hook with the pool of the [`Config`] and initialize the pool with it.
+    ///
+    /// `artifact` is the Foundry or Hardhat JSON artifact of the hook, whose constructor takes the `PoolManager` followed
+    /// by `constructor_args`. The hook is deployed with `CREATE2` at an address mined so its low bits match the
+    /// permission `flags`, and replaces [`Config::hooks`] in the pool key. Additional pools deploy their own hook through
+    /// [`PoolConfig::with_hook_deployment`].
+    pub fn with_hook(
+        mut self,
+        artifact: impl Into<PathBuf>,
+        constructor_args: Vec<DynSolValue>,
+        flags: u16,
+    ) -> Self {
+        self.hook = Some(HookConfig::new(artifact, constructor_args, flags));
+        self
+    }
+
+    /// Record every transaction and feed value of each run into [`Arena::replay_log`], to be re-executed by a
+    /// [`crate::replay::ReplayArena`].
+    pub fn with_recording(mut self) -> Self {
+        self.recording = true;
+        self
+    }
+
+    /// Record the call traces of the transactions matching `selection` into [`Arena::trace_log`], e.g. those that
+    /// reverted or called into a hook, to debug a step without rerunning it.
+    ///
+    /// Traces are taken with `debug_traceTransaction` once each step is mined, from the first step on, which costs a
+    /// call to the node per block and per traced transaction.
+    #[cfg(feature = "trace")]
+    pub fn with_tracing(mut self, selection: TraceSelection) -> Self {
+        self.tracing = Some(selection);
+        self
+    }
+
+    /// Give every block a timestamp `secs` seconds after the previous one, rather than the wall-clock time it was mined
+    /// at.
+    ///
+    /// Together with [`Config::with_seconds_per_step`], this makes every block timestamp of a run deterministic, which
+    /// hooks and TWAP oracles depend on.
+    pub fn with_block_time(mut self, secs: u64) -> Self {
+        self.block_time = Some(secs);
+        self
+    }
+
+    /// Reuse the deployed pools across runs instead of deploying them again.
+    ///
+    /// The chain is snapshotted once the pools are deployed and strategy wallets funded, and every later run with the
+    /// same pool parameters reverts to that snapshot, with the feeds, strategies and random number generators back in
+    /// their state at that point and the inspector reset, see [`Inspector::reset`], before initializing strategies.
+    /// Strategies must support [`Strategy::snapshot`] and [`Strategy::restore`], or the first run fails. This makes
+    /// parameter sweeps over short runs much faster, on a single Anvil node.
+    pub fn with_deployment_reuse(mut self) -> Self {
+        self.reuse_deployment = true;
+        self
+    }
+
+    /// Deploy the managers, tokens and liquid exchanges of every run at the same addresses.
+    ///
+    /// The controller of the pool is deployed through the `CREATE2` deployment proxy, so its address and those of the
+    /// contracts it creates do not depend on the nonce of the admin wallet, and neither do those of hooks and of the
+    /// liquid exchanges of additional pools. Every deployment removes the contracts of the previous one from the chain
+    /// first, which is otherwise kept, and deploys its own at the same addresses, so logs, replays and calldata compare
+    /// run to run. Arenas deploying the same pools share addresses as well.
+    pub fn with_deterministic_addresses(mut self) -> Self {
+        self.deterministic_addresses = true;
+        self
+    }
+
+    /// Stop the arena when `interrupt` is triggered, e.g. to share a single Ctrl-C handler between several arenas.
+    ///
+    /// The arena never resets a caller-owned interrupt, so every arena sharing it stops. The caller must call
+    /// [`Interrupt::clear`] before running again, or the next run stops at its first step.
+    pub fn with_interrupt(mut self, interrupt: Interrupt) -> Self {
+        self.interrupt = interrupt;
+        self.shared_interrupt = true;
+        self
+    }
+
+    /// Add [`Hooks`] called at the start and end of every step and at the end of the run.
+    ///
+    /// Can be called multiple times, and hooks are called in the order they were added.
+    pub fn with_hooks(mut self, hooks: Box<dyn Hooks>) -> Self {
+        self.hooks.push(hooks);
+        self
+    }
+
+    /// Add an agent acting once per step, after the feeds, arbitrageur and noise traders and before strategies.
+    ///
+    /// Agents act by increasing `priority`, and agents of equal priority in the order they were added. Each agent gets
+    /// its own wallet, funded like a strategy's with the default ether balance and
+    /// [`ArenaBuilder::with_strategy_funding`].
+    pub fn with_agent(mut self, agent: Box<dyn Agent>, priority: i32) -> Self {
+        self.agents.push((priority, agent));
+        self
+    }
+
+    /// Add a just-in-time liquidity provider as an agent, minting liquidity right before every large swap of the other
+    /// participants and burning it right after, from its agent wallet.
+    ///
+    /// Its outcome over a run is reported in [`RunSummary::jit`].
+    pub fn with_jit_liquidity(mut self, jit: JitLiquidity) -> Self {
+        self.agents.push((0, Box::new(jit.clone())));
+        self.jit = Some(jit);
+        self
+    }
+
+    /// Order agents every step by the bids they make through [`Agent::bid`], with `builder`, instead of by priority.
+    ///
+    /// Each included agent pays its bid before acting, and the blocks built are recorded in [`BlockBuilder::blocks`].
+    /// This takes precedence over [`ArenaBuilder::with_random_agent_order`].
+    pub fn with_block_builder(mut self, builder: BlockBuilder) -> Self {
+        self.block_builder = Some(builder);
+        self
+    }
+
+    /// Have agents act in a random order, drawn every step from its own random number stream, instead of by priority.
+    pub fn with_random_agent_order(mut self) -> Self {
+        self.random_agent_order = true;
+        self
+    }
+
+    /// Add a noise trader submitting uninformed order flow on every sub-tick.
+    pub fn with_noise_trader(mut self, noise_trader: NoiseTrader) -> Self {
+        self.noise_traders.push(noise_trader);
+        self
+    }
+
+    /// Set the amount of each pool token minted to every strategy's wallet.
+    pub fn with_strategy_funding(mut self, amount: U256) -> Self {
+        self.strategy_funding = amount;
+        self
+    }
+
+    /// Add a strategy whose wallet is funded with `funding` rather than the default ether balance and
+    /// [`ArenaBuilder::with_strategy_funding`].
+    pub fn with_strategy_funded(
+        mut self,
+        strategy: Box<dyn Strategy<V>>,
+        funding: Funding,
+    ) -> Self {
+        self.strategies.push(strategy);
+        self.strategy_fundings.push(Some(funding));
+        self.gas_budgets.push(None);
+        self.strategy_seeds.push(None);
+        self
+    }
+
+    /// Fund the wallet the arbitrageur trades from with `funding`, instead of the default funding of a strategy wallet.
+    ///
+    /// The arbitrageur swaps from its own wallet, so its balances bound the trades it can make.
+    pub fn with_arbitrageur_account(mut self, funding: Funding) -> Self {
+        self.arbitrageur_funding = Some(funding);
+        self
+    }
+
+    /// Fund the wallet every noise trader trades from with `funding`, instead of the default funding of a strategy
+    /// wallet.
+    ///
+    /// Noise traders swap from their own wallets, so their flow stops once a wallet runs out of the token it sells.
+    pub fn with_noise_trader_accounts(mut self, funding: Funding) -> Self {
+        self.noise_trader_funding = Some(funding);
+        self
+    }
+
+    /// Set the seed for the simulation's random number generator, making runs reproducible.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Set the index of the Monte Carlo path to simulate.
+    ///
+    /// Arenas sharing a seed and path see common random numbers, regardless of their other parameters.
+    pub fn with_path(mut self, path: usize) -> Self {
+        self.path = path;
+        self
+    }
+
+    /// Sample paths in antithetic pairs, with odd paths negating the shocks of the preceding even path.
+    pub fn with_antithetic(mut self) -> Self {
+        self.antithetic = true;
+        self
+    }
+
+    /// Configure the Anvil node the arena runs on, e.g.
+    /// `with_anvil(|anvil| anvil.port(8545_u16).chain_id(1).args(["--gas-limit", "30000000"]))`.
+    ///
+    /// The node is spawned when the arena is built, so the port, mnemonic, chain id, hardfork, gas limit or fork URL set
+    /// here replace the defaults. Strategy wallets are derived from the keys of the spawned node.
+    pub fn with_anvil(mut self, configure: impl FnOnce(Anvil) -> Anvil) -> Self {
+        self.anvil = configure(self.anvil);
+        self
+    }
+
+    /// Set the transport the providers reach the Anvil node over.
+    ///
+    /// WebSocket and IPC connections are established asynchronously, so an arena using them must be built with
+    /// [`ArenaBuilder::connect`].
+    pub fn with_transport(mut self, transport: Transport) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Run the arena on an in-process `revm` database rather than spawning an Anvil node, see [`RevmBackend`].
+    ///
+    /// Strategies still send transactions through an [`AnvilProvider`], served by the backend without the round trips
+    /// to a node. The configuration of [`ArenaBuilder::with_anvil`] is ignored, and recording, tracing and transports
+    /// other than HTTP are unavailable, as they rely on the node.
+    ///
+    /// [`RevmBackend`]: crate::backend::revm::RevmBackend
+    #[cfg(feature = "revm")]
+    pub fn with_revm(mut self) -> Self {
+        self.revm = true;
+        self
+    }
+
+    /// Check the configuration, returning every problem found.
+    ///
+    /// A feed and an inspector are required, while the arbitrageur defaults to an [`EmptyArbitrageur`]. The pools added
+    /// with [`ArenaBuilder::with_pool`] are checked with [`PoolConfig::validate`], and the pool of the [`Config`] when
+    /// the arena runs.
+    pub fn validate(&self) -> Result<(), BuildError> {
+        let mut problems = Vec::new();
+
+        if self.feed.is_none() {
+            problems.push("no feed, set one with `with_feed`".to_string());
+        }
+        if self.inspector.is_none() {
+            problems.push("no inspector, set one with `with_inspector`".to_string());
+        }
+        #[cfg(feature = "revm")]
+        if self.revm {
+            if self.recording {
+                problems.push(
+                    "recording replays on an Anvil node, unavailable with `with_revm`".to_string(),
+                );
+            }
+            #[cfg(feature = "trace")]
+            if self.tracing.is_some() {
+                problems.push(
+                    "tracing relies on `debug_traceTransaction`, unavailable with `with_revm`"
+                        .to_string(),
+                );
+            }
+            if self.transport != Transport::Http {
+                problems.push(
+                    "the in-process backend of `with_revm` is reached without a transport"
+                        .to_string(),
+                );
+            }
+        }
+        if self.tokens.len() > 2 {
+            problems.push(format!(
+                "{} tokens configured, pools have two",
+                self.tokens.len()
+            ));
+        }
+        for (idx, (pool, _)) in self.pools.iter().enumerate() {
+            problems.extend(
+                pool.validate()
+                    .into_iter()
+                    .map(|problem| format!("pool {}: {problem}", idx + 1)),
+            );
+
+            if !pool.manager_fee.is_zero() {
+                problems.push(format!(
+                    "pool {}: sets a manager fee, while it shares the `PoolManager` of the main pool",
+                    idx + 1
+                ));
+            }
+            // pools deploying a hook get a hook address of their own
+            let same_key = |other: &PoolConfig| {
+                other.hook.is_none()
+                    && (other.fee, other.tick_spacing, other.hooks)
+                        == (pool.fee, pool.tick_spacing, pool.hooks)
+            };
+            if pool.hook.is_none() && self.pools[..idx].iter().any(|(other, _)| same_key(other)) {
+                problems.push(format!(
+                    "pool {}: has the fee, tick spacing and hooks of an earlier pool on the same `PoolManager`",
+                    idx + 1
+                ));
+            }
+        }
+
+        BuildError::from_problems(problems)
+    }
+
+    /// Build the [`Arena`] with the given configuration, reaching the node over HTTP.
+    ///
+    /// Fails with every problem found by [`ArenaBuilder::validate`], if another [`Transport`] is set, see
+    /// [`ArenaBuilder::connect`], or if the Anvil node cannot be spawned.
+    pub fn build(self) -> Result<Arena<V>, BuildError> {
+        let mut problems = self.validate().err().map_or_else(Vec::new, |e| e.problems);
+        if self.transport != Transport::Http {
+            problems
+                .push("a websocket or IPC transport requires building with `connect`".to_string());
+        }
+        BuildError::from_problems(problems)?;
+
+        #[cfg(feature = "revm")]
+        if self.revm {
+            let wallets: Vec<_> = self.wallets(None).collect();
+            let backend = RevmBackend::new(wallets.iter().map(|(_, wallet)| {
+                alloy::network::NetworkWallet::<Ethereum>::default_signer_address(wallet)
+            }));
+            let providers = wallets
+                .into_iter()
+                .map(|(i, wallet)| (i, backend.provider(wallet)))
+                .collect();
+
+            return Ok(self.assemble(None, Arc::new(backend), providers));
+        }
+
+        let env = self.spawn(self.anvil.clone())?;
+        let rpc_url = env.endpoint_url();
+
+        let providers: HashMap<usize, AnvilProvider> = self
+            .wallets(Some(&env))
+            .map(|(i, wallet)| {
+                let provider = ProviderBuilder::new()
+                    .with_recommended_fillers()
+                    .wallet(wallet)
+                    .on_client(ClientBuilder::default().http(rpc_url.clone()).boxed());
+
+                (i, provider)
+            })
+            .collect();
+
+        let backend = Arc::new(providers[&0].clone());
+        Ok(self.assemble(Some(env), backend, providers))
+    }
+
+    /// Build the [`Arena`] with the given configuration, connecting to the node over the configured [`Transport`].
+    pub async fn connect(self) -> Result<Arena<V>, ArenaError> {
+        #[cfg(feature = "revm")]
+        if self.revm {
+            return Ok(self.build()?);
+        }
+
+        self.validate()?;
+
+        let ipc_path = std::env::temp_dir().join(format!(
+            "arena-{}-{}.ipc",
+            std::process::id(),
+            IPC_SOCKETS.fetch_add(1, Ordering::SeqCst)
+        ));
+        let env = match self.transport {
+            Transport::Ipc => self.spawn(
+                self.anvil
+                    .clone()
+                    .arg("--ipc")
+                    .arg(ipc_path.display().to_string()),
+            )?,
+            _ => self.spawn(self.anvil.clone())?,
+        };
+        let endpoint = match self.transport {
+            Transport::Http => env.endpoint(),
+            Transport::Ws => env.ws_endpoint(),
+            Transport::Ipc => ipc_path.display().to_string(),
+        };
+
+        let mut providers = HashMap::new();
+        for (i, wallet) in self.wallets(Some(&env)) {
+            let provider = ProviderBuilder::new()
+                .with_recommended_fillers()
+                .wallet(wallet)
+                .on_builtin(&endpoint)
+                .await?;
+
+            providers.insert(i, provider);
+        }
+
+        let backend = Arc::new(providers[&0].clone());
+        Ok(self.assemble(Some(env), backend, providers))
+    }
+
+    /// Spawn the Anvil node configured by `anvil`.
+    fn spawn(&self, anvil: Anvil) -> Result<AnvilInstance, BuildError> {
+        anvil.try_spawn().map_err(|e| BuildError {
+            problems: vec![format!("could not spawn anvil: {e}")],
+        })
+    }
+
+    /// Returns the wallet of the admin, at index 0, followed by one wallet per strategy, one for the arbitrageur, one
+    /// per noise trader and one per agent, holding the keys of the accounts of `env` if some.
+    fn wallets(
+        &self,
+        env: Option<&AnvilInstance>,
+    ) -> impl Iterator<Item = (usize, EthereumWallet)> {
+        let keys = env.map_or_else(Vec::new, |env| env.keys().to_vec());
+
+        // strategies are followed by the arbitrageur and the noise traders, which may have accounts of their own, and the
+        // agents
+        let count = agent_wallet(
+            self.strategies.len(),
+            self.noise_traders.len(),
+            self.agents.len(),
+        );
+
+        (0..count.max(9)).map(move |i| {
+            let signer: PrivateKeySigner = match keys.get(i) {
+                Some(key) => key.clone().into(),
+                // derive further wallets deterministically, so addresses are stable across runs
+                None => {
+                    PrivateKeySigner::from_bytes(&keccak256(format!("arena-wallet-{i}"))).unwrap()
+                }
+            };
+
+            (i, EthereumWallet::from(signer))
+        })
+    }
+
+    /// Assemble the [`Arena`] running on `backend`, the node `env` if some, reached through `providers`.
+    fn assemble(
+        self,
+        env: Option<AnvilInstance>,
+        backend: Arc<dyn Backend>,
+        providers: HashMap<usize, AnvilProvider>,
+    ) -> Arena<V> {
+        let mut rng_manager =
+            RngManager::new(self.seed.unwrap_or_else(|| rand::thread_rng().gen()));
+        if self.antithetic {
+            rng_manager = rng_manager.with_antithetic();
+        }
+
+        let mut feed = self.feed.expect("checked by validate");
+        feed.set_antithetic(rng_manager.is_antithetic(self.path));
+
+        let mut pools = self.pools;
+        for (_, feed) in pools.iter_mut() {
+            feed.set_antithetic(rng_manager.is_antithetic(self.path));
+        }
+        let pool_rngs = (0..pools.len())
+            .map(|idx| rng_manager.rng(self.path, Stream::PoolFeed(idx)))
+            .collect();
+
+        Arena {
+            env,
+            backend,
+            strategies: self.strategies,
+            feed,
+            inspector: self.inspector.expect("checked by validate"),
+            arbitrageur: self
+                .arbitrageur
+                .unwrap_or_else(|| Box::new(EmptyArbitrageur)),
+            noise_traders: self.noise_traders,
+            agents: self.agents,
+            random_agent_order: self.random_agent_order,
+            jit: self.jit,
+            block_builder: self.block_builder,
+            rng: rng_manager.rng(self.path, Stream::Feed),
+            rng_manager,
+            path: self.path,
+            strategy_funding: self.strategy_funding,
+            strategy_fundings: self.strategy_fundings,
+            arbitrageur_funding: self.arbitrageur_funding,
+            noise_trader_funding: self.noise_trader_funding,
+            gas_budgets: self.gas_budgets,
+            strategy_seeds: self.strategy_seeds,
+            pools,
+            tokens: self.tokens,
+            hook: self.hook,
+            pool_rngs,
+            providers,
+            hooks: self.hooks,
+            interrupt: self.interrupt,
+            owns_interrupt: !self.shared_interrupt,
+            reuse_deployment: self.reuse_deployment,
+            deployment: None,
+            deterministic_addresses: self.deterministic_addresses,
+            deployed: Vec::new(),
+            block_time: self.block_time,
+            replay_log: self.recording.then(ReplayLog::default),
+            anvil: self.anvil,
+            #[cfg(feature = "trace")]
+            trace_log: self.tracing.map(TraceLog::new),
+            progress: None,
+            resumed: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::B256;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct FailureLog(Vec<(Option<usize>, String, String)>);
+
+    impl Inspector<()> for FailureLog {
+        fn log(&mut self, _value: ()) -> Result<(), ArenaError> {
+            Ok(())
+        }
+
+        fn inspect(&self, _step: usize) -> Option<()> {
+            None
+        }
+
+        fn save(&self) -> Result<(), ArenaError> {
+            Ok(())
+        }
+
+        fn on_failure(
+            &mut self,
+            step: Option<usize>,
+            component: &str,
+            message: &str,
+        ) -> Result<(), ArenaError> {
+            self.0
+                .push((step, component.to_string(), message.to_string()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_interrupt() {
+        let interrupt = Interrupt::new();
+        let handle = interrupt.clone();
+        assert!(!interrupt.is_interrupted());
+
+        handle.interrupt();
+        assert!(interrupt.is_interrupted());
+
+        interrupt.clear();
+        assert!(!handle.is_interrupted());
+
+        assert!(!ArenaBuilder::<()>::new().shared_interrupt);
+        let builder = ArenaBuilder::<()>::new().with_interrupt(handle);
+        assert!(builder.shared_interrupt);
+    }
+
+    struct Idle;
+
+    #[async_trait::async_trait]
+    impl Strategy<()> for Idle {
+        async fn init(
+            &mut self,
+            _provider: AnvilProvider,
+            _signal: Signal,
+            _inspector: &mut Box<dyn Inspector<()>>,
+            _engine: Engine,
+            _rng: &mut StdRng,
+        ) -> Result<(), ArenaError> {
+            Ok(())
+        }
+
+        async fn process(
+            &mut self,
+            _provider: AnvilProvider,
+            _signal: Signal,
+            _inspector: &mut Box<dyn Inspector<()>>,
+            _engine: Engine,
+            _rng: &mut StdRng,
+        ) -> Result<(), ArenaError> {
+            Ok(())
+        }
+    }
+
+    /// Returns the receipt of a successful transaction sent from `from` that used `gas_used` gas.
+    fn receipt(from: Address, gas_used: u128) -> TransactionReceipt {
+        serde_json::from_value(serde_json::json!({
+            "type": "0x2",
+            "status": "0x1",
+            "cumulativeGasUsed": "0x0",
+            "logs": [],
+            "logsBloom": format!("0x{}", "0".repeat(512)),
+            "transactionHash": B256::repeat_byte(1),
+            "transactionIndex": "0x0",
+            "blockHash": B256::repeat_byte(2),
+            "blockNumber": "0x1",
+            "gasUsed": format!("{gas_used:#x}"),
+            "effectiveGasPrice": "0x1",
+            "from": from,
+            "to": Address::repeat_byte(9),
+            "contractAddress": null,
+        }))
+        .unwrap()
+    }
+
+    /// Returns a pending transaction sent from `from` with the given `nonce`.
+    fn pending(from: Address, nonce: u64) -> Value {
+        serde_json::json!({
+            "hash": B256::repeat_byte(nonce as u8),
+            "nonce": format!("{nonce:#x}"),
+            "blockHash": null,
+            "blockNumber": null,
+            "transactionIndex": null,
+            "from": from,
+            "to": Address::repeat_byte(9),
+            "value": "0x0",
+            "gasPrice": "0x1",
+            "gas": "0x5208",
+            "input": "0x",
+            "v": "0x1b",
+            "r": "0x1",
+            "s": "0x1",
+            "type": "0x0",
+        })
+    }
+
+    #[test]
+    fn test_pending_transactions() {
+        let (first, second) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let content: TxpoolContent = serde_json::from_value(serde_json::json!({
+            "pending": {
+                second.to_string(): { "0": pending(second, 0) },
+                first.to_string(): {
+                    "10": pending(first, 10),
+                    "9": pending(first, 9),
+                },
+            },
+            "queued": {},
+        }))
+        .unwrap();
+
+        let pending: Vec<_> = content
+            .into_pending()
+            .into_iter()
+            .map(|transaction| (transaction.from, transaction.nonce))
+            .collect();
+        assert_eq!(pending, vec![(first, 9), (first, 10), (second, 0)]);
+    }
+
+    #[test]
+    fn test_step_timestamps() {
+        // boundaries stay on a fixed grid while the step's blocks remain behind it
+        assert_eq!(next_step_timestamp(1_000, 12, 1_000), 1_012);
+        assert_eq!(next_step_timestamp(1_012, 12, 1_020), 1_024);
+
+        // a step that mined past the boundary closes right after its last block
+        assert_eq!(next_step_timestamp(1_000, 12, 1_030), 1_031);
+
+        let builder = ArenaBuilder::<()>::new();
+        assert_eq!(builder.block_time, None);
+        assert_eq!(builder.with_block_time(12).block_time, Some(12));
+    }
+
+    #[test]
+    fn test_gas_budgets() {
+        let builder = ArenaBuilder::<()>::new()
+            .with_strategy(Box::new(Idle))
+            .with_strategy_gas_budget(Box::new(Idle), 100_000)
+            .with_strategy_seeded(Box::new(Idle), 7);
+        assert_eq!(builder.gas_budgets, vec![None, Some(100_000), None]);
+
+        let (strategy, other) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let receipts = [
+            receipt(strategy, 60_000),
+            receipt(other, 21_000),
+            receipt(strategy, 45_000),
+        ];
+        let gas_used = gas_used_by(&receipts, strategy);
+        assert_eq!(gas_used, 105_000);
+        assert_eq!(gas_used_by(&receipts, Address::ZERO), 0);
+
+        assert!(!over_budget(None, gas_used));
+        assert!(over_budget(Some(100_000), gas_used));
+        assert!(!over_budget(Some(100_000), 60_000));
+    }
+
+    #[test]
+    fn test_isolate_panic() {
+        let mut inspector: Box<dyn Inspector<()>> = Box::new(FailureLog::default());
+
+        let panic = std::panic::catch_unwind(|| panic!("boom")).unwrap_err();
+        assert!(isolate_panic(
+            &mut inspector,
+            PanicPolicy::Drop,
+            Some(3),
+            "strategy 0".to_string(),
+            panic
+        )
+        .unwrap());
+
+        let panic = std::panic::catch_unwind(|| panic!("{}", 42)).unwrap_err();
+        assert!(!isolate_panic(
+            &mut inspector,
+            PanicPolicy::Continue,
+            None,
+            "strategy 1".to_string(),
+            panic
+        )
+        .unwrap());
+
+        let panic = std::panic::catch_unwind(|| panic!("boom")).unwrap_err();
+        assert!(matches!(
+            isolate_panic(
+                &mut inspector,
+                PanicPolicy::default(),
+                Some(4),
+                "strategy 0".to_string(),
+                panic
+            ),
+            Err(ArenaError::StepError { step: Some(4), .. })
+        ));
     }
 }
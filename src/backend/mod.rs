@@ -0,0 +1,185 @@
+use alloy::{
+    primitives::{Address, Bytes, B256, U256},
+    providers::{ext::AnvilApi, Provider},
+    rpc::types::TransactionRequest,
+    sol_types::SolCall,
+};
+use async_trait::async_trait;
+
+use crate::{error::ArenaError, AnvilProvider};
+
+/// Executes against an in-process `revm` database rather than an Anvil node.
+#[cfg(feature = "revm")]
+pub mod revm;
+
+/// Chain the arena executes on: calls against its latest state, and the control over the chain the arena needs beyond
+/// submitting transactions.
+///
+/// The arena and the engine read contracts and manipulate the chain only through this trait. It is implemented by the
+/// [`AnvilProvider`] reaching an Anvil node and, with the `revm` feature, by an in-process `revm` database, see
+/// [`revm::RevmBackend`].
+#[async_trait]
+pub trait Backend: Send + Sync {
+    /// Execute `tx` against the latest state without committing it, returning its output.
+    async fn call(&self, tx: TransactionRequest) -> Result<Bytes, ArenaError>;
+
+    /// Snapshot the chain, returning an id to [`Backend::revert`] to.
+    async fn snapshot(&self) -> Result<U256, ArenaError>;
+
+    /// Revert the chain to a snapshot, consuming it along with every later snapshot. Returns whether the snapshot
+    /// existed.
+    async fn revert(&self, id: U256) -> Result<bool, ArenaError>;
+
+    /// Serialize the state of the chain.
+    async fn dump_state(&self) -> Result<Bytes, ArenaError>;
+
+    /// Replace the state of the chain with one returned by [`Backend::dump_state`].
+    async fn load_state(&self, state: Bytes) -> Result<bool, ArenaError>;
+
+    /// Enable or disable mining a block for every submitted transaction.
+    async fn set_auto_mine(&self, enabled: bool) -> Result<(), ArenaError>;
+
+    /// Mine `blocks` blocks, the first of which includes any pending transactions.
+    async fn mine(&self, blocks: u64) -> Result<(), ArenaError>;
+
+    /// Advance the chain clock by `seconds`, taking effect at the next mined block.
+    async fn increase_time(&self, seconds: u64) -> Result<(), ArenaError>;
+
+    /// Set the timestamp of the next mined block, which must be later than the latest block.
+    async fn set_next_block_timestamp(&self, timestamp: u64) -> Result<(), ArenaError>;
+
+    /// Space the timestamps of consecutive blocks by `seconds`.
+    async fn set_block_timestamp_interval(&self, seconds: u64) -> Result<(), ArenaError>;
+
+    /// Set the base fee of the next mined block.
+    async fn set_next_block_base_fee(&self, base_fee: u128) -> Result<(), ArenaError>;
+
+    /// Set the ether balance of an account.
+    async fn set_balance(&self, account: Address, balance: U256) -> Result<(), ArenaError>;
+
+    /// Replace the code of an account.
+    async fn set_code(&self, account: Address, code: Bytes) -> Result<(), ArenaError>;
+
+    /// Set the nonce of an account.
+    async fn set_nonce(&self, account: Address, nonce: u64) -> Result<(), ArenaError>;
+
+    /// Write a storage slot of an account.
+    async fn set_storage_at(
+        &self,
+        account: Address,
+        slot: U256,
+        value: B256,
+    ) -> Result<(), ArenaError>;
+}
+
+/// Call `call` on the contract at `to` against the latest state of `backend`, decoding what it returns.
+pub async fn read<C: SolCall>(
+    backend: &(impl Backend + ?Sized),
+    to: Address,
+    call: C,
+) -> Result<C::Return, ArenaError> {
+    let output = backend
+        .call(
+            TransactionRequest::default()
+                .to(to)
+                .input(call.abi_encode().into()),
+        )
+        .await?;
+
+    C::abi_decode_returns(&output, true)
+        .map_err(|e| ArenaError::ContractError(alloy_contract::Error::AbiError(e.into())))
+}
+
+#[async_trait]
+impl Backend for AnvilProvider {
+    async fn call(&self, tx: TransactionRequest) -> Result<Bytes, ArenaError> {
+        Ok(Provider::call(self, &tx).await?)
+    }
+
+    async fn snapshot(&self) -> Result<U256, ArenaError> {
+        self.anvil_snapshot().await.map_err(ArenaError::RpcError)
+    }
+
+    async fn revert(&self, id: U256) -> Result<bool, ArenaError> {
+        self.anvil_revert(id).await.map_err(ArenaError::RpcError)
+    }
+
+    async fn dump_state(&self) -> Result<Bytes, ArenaError> {
+        self.anvil_dump_state().await.map_err(ArenaError::RpcError)
+    }
+
+    async fn load_state(&self, state: Bytes) -> Result<bool, ArenaError> {
+        self.anvil_load_state(state)
+            .await
+            .map_err(ArenaError::RpcError)
+    }
+
+    async fn set_auto_mine(&self, enabled: bool) -> Result<(), ArenaError> {
+        self.anvil_set_auto_mine(enabled)
+            .await
+            .map_err(ArenaError::RpcError)
+    }
+
+    async fn mine(&self, blocks: u64) -> Result<(), ArenaError> {
+        self.anvil_mine(Some(U256::from(blocks)), None)
+            .await
+            .map_err(ArenaError::RpcError)
+    }
+
+    async fn increase_time(&self, seconds: u64) -> Result<(), ArenaError> {
+        self.anvil_increase_time(U256::from(seconds))
+            .await
+            .map_err(ArenaError::RpcError)?;
+
+        Ok(())
+    }
+
+    async fn set_next_block_timestamp(&self, timestamp: u64) -> Result<(), ArenaError> {
+        self.anvil_set_next_block_timestamp(timestamp)
+            .await
+            .map_err(ArenaError::RpcError)
+    }
+
+    async fn set_block_timestamp_interval(&self, seconds: u64) -> Result<(), ArenaError> {
+        self.anvil_set_block_timestamp_interval(seconds)
+            .await
+            .map_err(ArenaError::RpcError)
+    }
+
+    async fn set_next_block_base_fee(&self, base_fee: u128) -> Result<(), ArenaError> {
+        self.anvil_set_next_block_base_fee_per_gas(U256::from(base_fee))
+            .await
+            .map_err(ArenaError::RpcError)
+    }
+
+    async fn set_balance(&self, account: Address, balance: U256) -> Result<(), ArenaError> {
+        self.anvil_set_balance(account, balance)
+            .await
+            .map_err(ArenaError::RpcError)
+    }
+
+    async fn set_code(&self, account: Address, code: Bytes) -> Result<(), ArenaError> {
+        self.anvil_set_code(account, code)
+            .await
+            .map_err(ArenaError::RpcError)
+    }
+
+    async fn set_nonce(&self, account: Address, nonce: u64) -> Result<(), ArenaError> {
+        self.anvil_set_nonce(account, U256::from(nonce))
+            .await
+            .map_err(ArenaError::RpcError)
+    }
+
+    async fn set_storage_at(
+        &self,
+        account: Address,
+        slot: U256,
+        value: B256,
+    ) -> Result<(), ArenaError> {
+        self.anvil_set_storage_at(account, slot, value)
+            .await
+            .map_err(ArenaError::RpcError)?;
+
+        Ok(())
+    }
+}
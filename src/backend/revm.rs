@@ -0,0 +1,1638 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Context, Poll},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use alloy::{
+    consensus::{
+        Eip658Value, Receipt, ReceiptEnvelope, ReceiptWithBloom, Transaction as _, TxEnvelope,
+    },
+    eips::{
+        eip1559::{calc_next_block_base_fee, BaseFeeParams, INITIAL_BASE_FEE},
+        eip2718::Decodable2718,
+        BlockNumberOrTag,
+    },
+    network::EthereumWallet,
+    primitives::{
+        keccak256, utils::parse_ether, Address, Bloom, Bytes, TxKind, B256, B64, U256, U64,
+    },
+    providers::ProviderBuilder,
+    rpc::{
+        client::ClientBuilder,
+        json_rpc::{
+            ErrorPayload, RequestPacket, Response, ResponsePacket, ResponsePayload,
+            SerializedRequest,
+        },
+        types::{
+            Block, BlockTransactions, FeeHistory, Filter, FilterBlockOption, Header, Log, Parity,
+            Signature, Transaction, TransactionReceipt, TransactionRequest,
+        },
+    },
+    transports::{RpcError, TransportError, TransportFut},
+};
+use async_trait::async_trait;
+use revm::{
+    db::{CacheDB, EmptyDB},
+    primitives::{
+        AccountInfo, BlobExcessGasAndPrice, BlockEnv, Bytecode, ExecutionResult, Output, SpecId,
+        TxEnv, KECCAK_EMPTY,
+    },
+    DatabaseRef, Evm,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::Backend;
+use crate::{error::ArenaError, AnvilProvider};
+
+/// Chain id of the chain, the default of Anvil.
+const CHAIN_ID: u64 = 31337;
+
+/// Gas limit of every block, the default of Anvil.
+const GAS_LIMIT: u64 = 30_000_000;
+
+/// Ether every account the backend is created with holds, like the accounts of Anvil.
+const ACCOUNT_ETHER: &str = "10000";
+
+/// Interval at which providers of the backend poll it for new blocks, see [`RevmBackend::provider`].
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Hardfork transactions execute under.
+const SPEC: SpecId = SpecId::CANCUN;
+
+/// An in-process chain executing transactions against a `revm` [`CacheDB`], without the round trips to an Anvil node.
+///
+/// The backend implements [`Backend`] directly, and serves the Ethereum and Anvil JSON-RPC methods the arena, the
+/// engine and the fillers of an [`AnvilProvider`] use, so that strategies send transactions to it through a provider
+/// returned by [`RevmBackend::provider`] exactly as they would to Anvil. Blocks are mined like Anvil mines them, a block
+/// per transaction unless automine is disabled, with timestamps following the clock of the node and base fees the
+/// EIP-1559 rule. The backend cannot fork another chain, trace transactions or load the state of an Anvil node.
+///
+/// Only the methods in [`RevmBackend::METHODS`] are served. Any other fails with a JSON-RPC "method not found" error
+/// when called, so a strategy calling one is only caught by running it.
+#[derive(Clone)]
+pub struct RevmBackend {
+    node: Arc<Mutex<Node>>,
+}
+
+impl RevmBackend {
+    /// JSON-RPC methods the backend serves.
+    pub const METHODS: &'static [&'static str] = &[
+        "eth_chainId",
+        "eth_blockNumber",
+        "eth_gasPrice",
+        "eth_getBalance",
+        "eth_getTransactionCount",
+        "eth_getCode",
+        "eth_getStorageAt",
+        "eth_call",
+        "eth_estimateGas",
+        "eth_feeHistory",
+        "eth_getBlockByNumber",
+        "eth_getBlockByHash",
+        "eth_getBlockReceipts",
+        "eth_getTransactionReceipt",
+        "eth_getTransactionByHash",
+        "eth_getLogs",
+        "eth_sendRawTransaction",
+        "txpool_content",
+        "evm_snapshot",
+        "evm_revert",
+        "evm_mine",
+        "anvil_mine",
+        "evm_increaseTime",
+        "evm_setNextBlockTimestamp",
+        "anvil_setBlockTimestampInterval",
+        "anvil_removeBlockTimestampInterval",
+        "anvil_setNextBlockBaseFeePerGas",
+        "anvil_setAutomine",
+        "anvil_setBalance",
+        "anvil_setCode",
+        "anvil_setNonce",
+        "anvil_setStorageAt",
+        "anvil_dumpState",
+        "anvil_loadState",
+    ];
+
+    /// Public constructor function for a new [`RevmBackend`], at a genesis block funding each of `accounts` with 10000
+    /// ether.
+    pub fn new(accounts: impl IntoIterator<Item = Address>) -> Self {
+        Self {
+            node: Arc::new(Mutex::new(Node::new(accounts))),
+        }
+    }
+
+    /// Returns an [`AnvilProvider`] sending transactions from `wallet` to the backend.
+    pub fn provider(&self, wallet: EthereumWallet) -> AnvilProvider {
+        let client = ClientBuilder::default()
+            .transport(self.clone(), true)
+            .with_poll_interval(POLL_INTERVAL)
+            .boxed();
+
+        ProviderBuilder::new()
+            .with_recommended_fillers()
+            .wallet(wallet)
+            .on_client(client)
+    }
+
+    fn node(&self) -> MutexGuard<'_, Node> {
+        self.node.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Serve a single JSON-RPC request.
+    fn respond(&self, request: &SerializedRequest) -> Response {
+        let result = request
+            .params()
+            .map_or(Ok(Value::Null), |params| serde_json::from_str(params.get()))
+            .map_err(|e| Failure::invalid(e.to_string()))
+            .map(|params| match params {
+                Value::Array(params) => params,
+                Value::Null => Vec::new(),
+                params => vec![params],
+            })
+            .and_then(|params: Vec<Value>| self.node().dispatch(request.method(), &params));
+
+        Response {
+            id: request.id().clone(),
+            payload: match result.and_then(|value| {
+                serde_json::value::to_raw_value(&value)
+                    .map_err(|e| Failure::internal(e.to_string()))
+            }) {
+                Ok(value) => ResponsePayload::Success(value),
+                Err(failure) => ResponsePayload::Failure(failure.into()),
+            },
+        }
+    }
+}
+
+impl std::fmt::Debug for RevmBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RevmBackend")
+            .field("block_number", &self.node().latest().header.number)
+            .finish()
+    }
+}
+
+impl tower::Service<RequestPacket> for RevmBackend {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let response = match request {
+            RequestPacket::Single(request) => ResponsePacket::Single(self.respond(&request)),
+            RequestPacket::Batch(requests) => ResponsePacket::Batch(
+                requests
+                    .iter()
+                    .map(|request| self.respond(request))
+                    .collect(),
+            ),
+        };
+
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+#[async_trait]
+impl Backend for RevmBackend {
+    async fn call(&self, tx: TransactionRequest) -> Result<Bytes, ArenaError> {
+        Ok(self.node().call(&tx)?)
+    }
+
+    async fn snapshot(&self) -> Result<U256, ArenaError> {
+        Ok(self.node().snapshot())
+    }
+
+    async fn revert(&self, id: U256) -> Result<bool, ArenaError> {
+        Ok(self.node().revert(id))
+    }
+
+    async fn dump_state(&self) -> Result<Bytes, ArenaError> {
+        Ok(self.node().dump_state()?)
+    }
+
+    async fn load_state(&self, state: Bytes) -> Result<bool, ArenaError> {
+        self.node().load_state(&state)?;
+
+        Ok(true)
+    }
+
+    async fn set_auto_mine(&self, enabled: bool) -> Result<(), ArenaError> {
+        self.node().auto_mine = enabled;
+
+        Ok(())
+    }
+
+    async fn mine(&self, blocks: u64) -> Result<(), ArenaError> {
+        self.node().mine(blocks, None);
+
+        Ok(())
+    }
+
+    async fn increase_time(&self, seconds: u64) -> Result<(), ArenaError> {
+        self.node().clock.offset += seconds as i64;
+
+        Ok(())
+    }
+
+    async fn set_next_block_timestamp(&self, timestamp: u64) -> Result<(), ArenaError> {
+        Ok(self.node().clock.set_next(timestamp)?)
+    }
+
+    async fn set_block_timestamp_interval(&self, seconds: u64) -> Result<(), ArenaError> {
+        self.node().clock.interval = Some(seconds);
+
+        Ok(())
+    }
+
+    async fn set_next_block_base_fee(&self, base_fee: u128) -> Result<(), ArenaError> {
+        self.node().next_base_fee = Some(base_fee);
+
+        Ok(())
+    }
+
+    async fn set_balance(&self, account: Address, balance: U256) -> Result<(), ArenaError> {
+        self.node()
+            .modify_account(account, |info| info.balance = balance);
+
+        Ok(())
+    }
+
+    async fn set_code(&self, account: Address, code: Bytes) -> Result<(), ArenaError> {
+        self.node().set_code(account, code);
+
+        Ok(())
+    }
+
+    async fn set_nonce(&self, account: Address, nonce: u64) -> Result<(), ArenaError> {
+        self.node()
+            .modify_account(account, |info| info.nonce = nonce);
+
+        Ok(())
+    }
+
+    async fn set_storage_at(
+        &self,
+        account: Address,
+        slot: U256,
+        value: B256,
+    ) -> Result<(), ArenaError> {
+        self.node().set_storage_at(account, slot, value);
+
+        Ok(())
+    }
+}
+
+/// State of the chain behind a [`RevmBackend`].
+struct Node {
+    db: CacheDB<EmptyDB>,
+
+    /// Every block mined, indexed by number, starting with the genesis block.
+    blocks: Vec<MinedBlock>,
+
+    /// Block number and index in the block of every mined transaction, by hash.
+    mined: HashMap<B256, (u64, usize)>,
+
+    /// Transactions waiting to be mined, in the order they were sent.
+    pending: Vec<PendingTransaction>,
+
+    /// Whether a block is mined for every transaction sent.
+    auto_mine: bool,
+
+    clock: Clock,
+
+    /// Base fee of the next block, if set rather than following from the latest block.
+    next_base_fee: Option<u128>,
+
+    /// States to revert to, by snapshot id.
+    snapshots: BTreeMap<U256, NodeSnapshot>,
+    next_snapshot: U256,
+}
+
+/// A mined block, along with its transactions and their receipts.
+#[derive(Clone)]
+struct MinedBlock {
+    header: Header,
+    transactions: Vec<Transaction>,
+    receipts: Vec<TransactionReceipt>,
+}
+
+/// A transaction sent to the node, decoded.
+#[derive(Clone)]
+struct PendingTransaction {
+    envelope: TxEnvelope,
+    sender: Address,
+}
+
+/// State of a [`Node`] when it was snapshotted.
+struct NodeSnapshot {
+    db: CacheDB<EmptyDB>,
+    blocks: usize,
+    pending: Vec<PendingTransaction>,
+    clock: Clock,
+    next_base_fee: Option<u128>,
+}
+
+/// Timestamps of the blocks of a [`Node`], following those of Anvil: the system clock shifted by an offset, unless the
+/// next timestamp is set or blocks are spaced by an interval, and always later than the latest block.
+#[derive(Debug, Clone, Copy)]
+struct Clock {
+    /// Seconds added to the system clock.
+    offset: i64,
+
+    /// Timestamp of the next block, if set.
+    next: Option<u64>,
+
+    /// Seconds between consecutive blocks, if set.
+    interval: Option<u64>,
+
+    /// Timestamp of the latest block.
+    last: u64,
+}
+
+impl Clock {
+    /// Set the timestamp of the next block, which cannot precede the latest block.
+    fn set_next(&mut self, timestamp: u64) -> Result<(), Failure> {
+        if timestamp < self.last {
+            return Err(Failure::invalid(format!(
+                "timestamp {timestamp} is lower than the timestamp {} of the latest block",
+                self.last
+            )));
+        }
+        self.next = Some(timestamp);
+
+        Ok(())
+    }
+
+    /// Returns the timestamp of the next block, and moves the clock to it.
+    fn advance(&mut self) -> u64 {
+        let timestamp = match (self.next.take(), self.interval) {
+            (Some(timestamp), _) => timestamp,
+            (None, Some(interval)) => self.last + interval,
+            (None, None) => now().saturating_add_signed(self.offset),
+        };
+        self.last = timestamp.max(self.last + 1);
+
+        self.last
+    }
+}
+
+/// Accounts and latest block of a [`Node`], as serialized by [`Backend::dump_state`].
+#[derive(Serialize, Deserialize)]
+struct DumpedState {
+    accounts: BTreeMap<Address, DumpedAccount>,
+    number: u64,
+    timestamp: u64,
+    base_fee: u128,
+}
+
+/// An account of a [`DumpedState`].
+#[derive(Serialize, Deserialize)]
+struct DumpedAccount {
+    balance: U256,
+    nonce: u64,
+    code: Bytes,
+    storage: BTreeMap<U256, U256>,
+}
+
+impl Node {
+    fn new(accounts: impl IntoIterator<Item = Address>) -> Self {
+        let mut db = CacheDB::new(EmptyDB::default());
+        let balance = parse_ether(ACCOUNT_ETHER).expect("valid ether amount");
+        for account in accounts {
+            db.insert_account_info(
+                account.convert(),
+                AccountInfo {
+                    balance,
+                    ..Default::default()
+                },
+            );
+        }
+
+        let timestamp = now();
+        let mut node = Self {
+            db,
+            blocks: Vec::new(),
+            mined: HashMap::new(),
+            pending: Vec::new(),
+            auto_mine: true,
+            clock: Clock {
+                offset: 0,
+                next: None,
+                interval: None,
+                last: timestamp,
+            },
+            next_base_fee: None,
+            snapshots: BTreeMap::new(),
+            next_snapshot: U256::from(1),
+        };
+        node.push_block(
+            empty_header(B256::ZERO, 0, timestamp, INITIAL_BASE_FEE as u128),
+            Vec::new(),
+            Vec::new(),
+        );
+
+        node
+    }
+
+    /// Serve the JSON-RPC `method` with `params`.
+    fn dispatch(&mut self, method: &str, params: &[Value]) -> Result<Value, Failure> {
+        let value = match method {
+            "eth_chainId" => json!(U64::from(CHAIN_ID)),
+            "eth_blockNumber" => json!(U64::from(self.latest().header.number)),
+            "eth_gasPrice" => json!(U256::from(self.peek_base_fee())),
+            "eth_getBalance" => json!(self.account(param(params, 0)?).balance),
+            "eth_getTransactionCount" => {
+                let account = param(params, 0)?;
+                let pending = matches!(
+                    optional::<BlockNumberOrTag>(params, 1)?,
+                    Some(BlockNumberOrTag::Pending)
+                );
+                json!(U64::from(self.nonce(account, pending)))
+            }
+            "eth_getCode" => json!(self.code(param(params, 0)?)),
+            "eth_getStorageAt" => {
+                json!(B256::from(
+                    self.storage(param(params, 0)?, param(params, 1)?)
+                ))
+            }
+            "eth_call" => json!(self.call(&param(params, 0)?)?),
+            "eth_estimateGas" => json!(U64::from(self.estimate_gas(&param(params, 0)?)?)),
+            "eth_feeHistory" => json!(self.fee_history(
+                quantity(params, 0)?,
+                param(params, 1)?,
+                &optional::<Vec<f64>>(params, 2)?.unwrap_or_default(),
+            )),
+            "eth_getBlockByNumber" => {
+                let full = optional(params, 1)?.unwrap_or(false);
+                json!(self
+                    .block_number(param(params, 0)?)
+                    .map(|number| self.rpc_block(number, full)))
+            }
+            "eth_getBlockByHash" => {
+                let hash: B256 = param(params, 0)?;
+                let full = optional(params, 1)?.unwrap_or(false);
+                json!(self
+                    .blocks
+                    .iter()
+                    .position(|block| block.header.hash == hash)
+                    .map(|number| self.rpc_block(number as u64, full)))
+            }
+            "eth_getBlockReceipts" => json!(self
+                .block_number(param(params, 0)?)
+                .map(|number| self.blocks[number as usize].receipts.clone())),
+            "eth_getTransactionReceipt" => {
+                json!(self.find(param(params, 0)?, |block, idx| block.receipts[idx].clone()))
+            }
+            "eth_getTransactionByHash" => {
+                let hash: B256 = param(params, 0)?;
+                match self.find(hash, |block, idx| block.transactions[idx].clone()) {
+                    Some(transaction) => json!(transaction),
+                    None => json!(self
+                        .pending
+                        .iter()
+                        .find(|tx| *tx.envelope.tx_hash() == hash)
+                        .map(|tx| rpc_transaction(tx, None))),
+                }
+            }
+            "eth_getLogs" => json!(self.logs(&param(params, 0)?)),
+            "eth_sendRawTransaction" => json!(self.send_raw_transaction(&param(params, 0)?)?),
+            "txpool_content" => {
+                let mut pending: BTreeMap<Address, BTreeMap<String, Transaction>> = BTreeMap::new();
+                for tx in &self.pending {
+                    pending
+                        .entry(tx.sender)
+                        .or_default()
+                        .insert(tx.envelope.nonce().to_string(), rpc_transaction(tx, None));
+                }
+                json!({ "pending": pending, "queued": {} })
+            }
+            "evm_snapshot" => json!(self.snapshot()),
+            "evm_revert" => json!(self.revert(param(params, 0)?)),
+            "evm_mine" => {
+                self.mine(1, None);
+                json!("0x0")
+            }
+            "anvil_mine" => {
+                let blocks = optional::<U256>(params, 0)?.map_or(1, |blocks| blocks.to::<u64>());
+                let interval = optional::<U256>(params, 1)?.map(|interval| interval.to::<u64>());
+                self.mine(blocks, interval);
+                Value::Null
+            }
+            "evm_increaseTime" => {
+                self.clock.offset += quantity(params, 0)? as i64;
+                json!(self.clock.offset)
+            }
+            "evm_setNextBlockTimestamp" => {
+                self.clock.set_next(quantity(params, 0)?)?;
+                Value::Null
+            }
+            "anvil_setBlockTimestampInterval" => {
+                self.clock.interval = Some(quantity(params, 0)?);
+                Value::Null
+            }
+            "anvil_removeBlockTimestampInterval" => json!(self.clock.interval.take().is_some()),
+            "anvil_setNextBlockBaseFeePerGas" => {
+                self.next_base_fee = Some(param::<U256>(params, 0)?.to::<u128>());
+                Value::Null
+            }
+            "anvil_setAutomine" => {
+                self.auto_mine = param(params, 0)?;
+                Value::Null
+            }
+            "anvil_setBalance" => {
+                let balance: U256 = param(params, 1)?;
+                self.modify_account(param(params, 0)?, |info| info.balance = balance);
+                Value::Null
+            }
+            "anvil_setCode" => {
+                self.set_code(param(params, 0)?, param(params, 1)?);
+                Value::Null
+            }
+            "anvil_setNonce" => {
+                let nonce = param::<U256>(params, 1)?.to::<u64>();
+                self.modify_account(param(params, 0)?, |info| info.nonce = nonce);
+                Value::Null
+            }
+            "anvil_setStorageAt" => {
+                self.set_storage_at(param(params, 0)?, param(params, 1)?, param(params, 2)?);
+                json!(true)
+            }
+            "anvil_dumpState" => json!(self.dump_state()?),
+            "anvil_loadState" => {
+                self.load_state(&param::<Bytes>(params, 0)?)?;
+                json!(true)
+            }
+            method => {
+                return Err(Failure {
+                    code: -32601,
+                    message: format!("method {method} is not supported by the revm backend"),
+                    data: None,
+                })
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn latest(&self) -> &MinedBlock {
+        self.blocks.last().expect("the genesis block is mined")
+    }
+
+    /// Returns the number of the block `tag` refers to, if it exists. Pending blocks are the latest block.
+    fn block_number(&self, tag: BlockNumberOrTag) -> Option<u64> {
+        let latest = self.latest().header.number;
+        match tag {
+            BlockNumberOrTag::Number(number) => (number <= latest).then_some(number),
+            BlockNumberOrTag::Earliest => Some(0),
+            _ => Some(latest),
+        }
+    }
+
+    /// Returns the mined block `number` as served over JSON-RPC, with its full transactions if `full`.
+    fn rpc_block(&self, number: u64, full: bool) -> Block {
+        let block = &self.blocks[number as usize];
+        let transactions = if full {
+            BlockTransactions::Full(block.transactions.clone())
+        } else {
+            BlockTransactions::Hashes(block.transactions.iter().map(|tx| tx.hash).collect())
+        };
+
+        Block {
+            header: block.header.clone(),
+            transactions,
+            ..Default::default()
+        }
+    }
+
+    /// Apply `get` to the block of the mined transaction `hash` and its index in the block, if it was mined.
+    fn find<T>(&self, hash: B256, get: impl Fn(&MinedBlock, usize) -> T) -> Option<T> {
+        let (number, idx) = *self.mined.get(&hash)?;
+        self.blocks
+            .get(number as usize)
+            .filter(|block| {
+                block
+                    .transactions
+                    .get(idx)
+                    .is_some_and(|tx| tx.hash == hash)
+            })
+            .map(|block| get(block, idx))
+    }
+
+    fn account(&self, address: Address) -> AccountInfo {
+        self.db
+            .basic_ref(address.convert())
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    }
+
+    /// Returns the nonce of `account`, counting its transactions waiting to be mined if `pending`.
+    fn nonce(&self, account: Address, pending: bool) -> u64 {
+        let mut nonce = self.account(account).nonce;
+        if pending {
+            while self
+                .pending
+                .iter()
+                .any(|tx| tx.sender == account && tx.envelope.nonce() == nonce)
+            {
+                nonce += 1;
+            }
+        }
+
+        nonce
+    }
+
+    fn code(&self, address: Address) -> Bytes {
+        let info = self.account(address);
+        let code = match info.code {
+            Some(code) => code,
+            None => self.db.code_by_hash_ref(info.code_hash).unwrap_or_default(),
+        };
+
+        code.original_bytes().convert()
+    }
+
+    fn storage(&self, address: Address, slot: U256) -> U256 {
+        self.db
+            .storage_ref(address.convert(), slot)
+            .unwrap_or_default()
+    }
+
+    /// Modify the info of `address`, creating the account if it does not exist.
+    fn modify_account(&mut self, address: Address, modify: impl FnOnce(&mut AccountInfo)) {
+        let mut info = self.account(address);
+        modify(&mut info);
+        self.db.insert_account_info(address.convert(), info);
+    }
+
+    fn set_code(&mut self, address: Address, code: Bytes) {
+        self.modify_account(address, |info| {
+            // the database hashes the code again when it is inserted
+            info.code_hash = KECCAK_EMPTY;
+            info.code = Some(Bytecode::new_raw(code.convert()));
+        });
+    }
+
+    fn set_storage_at(&mut self, address: Address, slot: U256, value: B256) {
+        self.db
+            .insert_account_storage(address.convert(), slot, U256::from_be_bytes(value.0))
+            .expect("the empty database is infallible");
+    }
+
+    /// Returns the base fee of the next block.
+    fn peek_base_fee(&self) -> u128 {
+        self.next_base_fee.unwrap_or_else(|| {
+            let header = &self.latest().header;
+            calc_next_block_base_fee(
+                header.gas_used,
+                header.gas_limit,
+                header.base_fee_per_gas.unwrap_or_default(),
+                BaseFeeParams::ethereum(),
+            )
+        })
+    }
+
+    /// Returns the environment of a block at `number`, mined at `timestamp` with `base_fee`.
+    fn block_env(number: u64, timestamp: u64, base_fee: u128) -> BlockEnv {
+        BlockEnv {
+            number: U256::from(number),
+            timestamp: U256::from(timestamp),
+            gas_limit: U256::from(GAS_LIMIT),
+            basefee: U256::from(base_fee),
+            prevrandao: Some(keccak256(number.to_be_bytes()).convert()),
+            blob_excess_gas_and_price: Some(BlobExcessGasAndPrice::new(0)),
+            ..Default::default()
+        }
+    }
+
+    /// Execute `tx` against the latest state with `gas_limit`, without committing it and without charging for gas.
+    fn simulate(
+        &self,
+        tx: &TransactionRequest,
+        gas_limit: u64,
+    ) -> Result<ExecutionResult, Failure> {
+        let header = &self.latest().header;
+        let mut block_env = Self::block_env(header.number, header.timestamp, 0);
+        block_env.gas_limit = U256::from(gas_limit.max(GAS_LIMIT));
+
+        Evm::builder()
+            .with_ref_db(&self.db)
+            .with_spec_id(SPEC)
+            .modify_cfg_env(|cfg| cfg.chain_id = CHAIN_ID)
+            .with_block_env(block_env)
+            .with_tx_env(TxEnv {
+                caller: tx.from.unwrap_or_default().convert(),
+                gas_limit,
+                gas_price: Default::default(),
+                transact_to: tx_kind(tx.to.unwrap_or(TxKind::Create)),
+                value: tx.value.unwrap_or_default(),
+                data: tx.input.input().cloned().unwrap_or_default().convert(),
+                chain_id: Some(CHAIN_ID),
+                ..Default::default()
+            })
+            .build()
+            .transact()
+            .map(|result| result.result)
+            .map_err(|e| Failure::internal(e.to_string()))
+    }
+
+    /// Execute `tx` against the latest state without committing it, returning its output.
+    fn call(&self, tx: &TransactionRequest) -> Result<Bytes, Failure> {
+        match self.simulate(tx, tx.gas.map_or(GAS_LIMIT, |gas| gas as u64))? {
+            ExecutionResult::Success { output, .. } => Ok(output.into_data().convert()),
+            result => Err(Failure::execution(result)),
+        }
+    }
+
+    /// Returns the gas limit `tx` needs to succeed against the latest state, found by bisection like Anvil does.
+    fn estimate_gas(&self, tx: &TransactionRequest) -> Result<u64, Failure> {
+        let mut high = tx.gas.map_or(GAS_LIMIT, |gas| gas as u64);
+        let mut low = match self.simulate(tx, high)? {
+            ExecutionResult::Success { gas_used, .. } => gas_used - 1,
+            result => return Err(Failure::execution(result)),
+        };
+
+        let succeeds = |gas_limit| {
+            self.simulate(tx, gas_limit)
+                .is_ok_and(|result| result.is_success())
+        };
+
+        // most calls succeed with the gas they used plus the 1/64 withheld from their subcalls
+        let optimistic = ((low + 1) * 64 / 63).min(high);
+        if succeeds(optimistic) {
+            high = optimistic;
+        } else {
+            low = optimistic;
+        }
+        while high - low > 1 && (high - low) * 100 > high {
+            let mid = low + (high - low) / 2;
+            if succeeds(mid) {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+
+        Ok(high)
+    }
+
+    /// Returns the base fees, gas used ratios and priority fees at `percentiles` of the `count` blocks up to `newest`.
+    fn fee_history(&self, count: u64, newest: BlockNumberOrTag, percentiles: &[f64]) -> FeeHistory {
+        let newest = self
+            .block_number(newest)
+            .unwrap_or(self.latest().header.number);
+        let oldest = (newest + 1).saturating_sub(count.max(1));
+        let blocks = &self.blocks[oldest as usize..=newest as usize];
+
+        let mut base_fee_per_gas: Vec<u128> = blocks
+            .iter()
+            .map(|block| block.header.base_fee_per_gas.unwrap_or_default())
+            .collect();
+        base_fee_per_gas.push(if newest == self.latest().header.number {
+            self.peek_base_fee()
+        } else {
+            self.blocks[newest as usize + 1]
+                .header
+                .base_fee_per_gas
+                .unwrap_or_default()
+        });
+
+        let reward = blocks
+            .iter()
+            .map(|block| {
+                let base_fee = block.header.base_fee_per_gas.unwrap_or_default();
+                let mut tips: Vec<(u128, u128)> = block
+                    .transactions
+                    .iter()
+                    .zip(&block.receipts)
+                    .map(|(tx, receipt)| {
+                        (
+                            tx.gas_price.unwrap_or_default().saturating_sub(base_fee),
+                            receipt.gas_used,
+                        )
+                    })
+                    .collect();
+                tips.sort_unstable();
+
+                percentiles
+                    .iter()
+                    .map(|percentile| {
+                        let threshold = block.header.gas_used as f64 * percentile / 100.0;
+                        let mut cumulative = 0;
+                        tips.iter()
+                            .find(|(_, gas_used)| {
+                                cumulative += gas_used;
+                                cumulative as f64 >= threshold
+                            })
+                            .or(tips.last())
+                            .map_or(0, |(tip, _)| *tip)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        FeeHistory {
+            base_fee_per_gas,
+            gas_used_ratio: blocks
+                .iter()
+                .map(|block| block.header.gas_used as f64 / block.header.gas_limit as f64)
+                .collect(),
+            oldest_block: oldest,
+            reward: Some(reward),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the logs of the mined blocks matching `filter`.
+    fn logs(&self, filter: &Filter) -> Vec<Log> {
+        let latest = self.latest().header.number;
+        let (from, to) = match &filter.block_option {
+            FilterBlockOption::AtBlockHash(hash) => {
+                match self
+                    .blocks
+                    .iter()
+                    .position(|block| block.header.hash == *hash)
+                {
+                    Some(number) => (number as u64, number as u64),
+                    None => return Vec::new(),
+                }
+            }
+            FilterBlockOption::Range {
+                from_block,
+                to_block,
+            } => (
+                from_block
+                    .and_then(|tag| self.block_number(tag))
+                    .unwrap_or(latest),
+                to_block
+                    .and_then(|tag| self.block_number(tag))
+                    .unwrap_or(latest),
+            ),
+        };
+        if from > to {
+            return Vec::new();
+        }
+
+        self.blocks[from as usize..=to as usize]
+            .iter()
+            .flat_map(|block| &block.receipts)
+            .flat_map(|receipt| receipt.inner.logs())
+            .filter(|log| {
+                filter.address.matches(&log.address())
+                    && filter.topics.iter().enumerate().all(|(idx, topics)| {
+                        topics.is_empty()
+                            || log
+                                .topics()
+                                .get(idx)
+                                .is_some_and(|topic| topics.matches(topic))
+                    })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Add a signed transaction to the pending transactions, mining it right away if automine is enabled.
+    fn send_raw_transaction(&mut self, raw: &Bytes) -> Result<B256, Failure> {
+        let envelope = TxEnvelope::decode_2718(&mut raw.as_ref())
+            .map_err(|e| Failure::invalid(format!("invalid transaction: {e}")))?;
+        let sender = envelope
+            .recover_signer()
+            .map_err(|e| Failure::invalid(format!("invalid signature: {e}")))?;
+        let hash = *envelope.tx_hash();
+
+        if envelope
+            .chain_id()
+            .is_some_and(|chain_id| chain_id != CHAIN_ID)
+        {
+            return Err(Failure::invalid("invalid chain id".to_string()));
+        }
+        if envelope.nonce() < self.account(sender).nonce {
+            return Err(Failure::invalid("nonce too low".to_string()));
+        }
+        if self
+            .pending
+            .iter()
+            .any(|tx| tx.sender == sender && tx.envelope.nonce() == envelope.nonce())
+        {
+            return Err(Failure::invalid(
+                "a transaction with the same nonce is pending".to_string(),
+            ));
+        }
+        if envelope.max_fee_per_gas() < self.peek_base_fee() {
+            return Err(Failure::invalid(
+                "max fee per gas less than block base fee".to_string(),
+            ));
+        }
+
+        self.pending.push(PendingTransaction { envelope, sender });
+
+        if self.auto_mine {
+            if let Some((_, error)) = self
+                .mine_block()
+                .into_iter()
+                .find(|(rejected, _)| *rejected == hash)
+            {
+                return Err(Failure::invalid(error));
+            }
+        }
+
+        Ok(hash)
+    }
+
+    /// Mine `blocks` blocks, the first of which includes the pending transactions, spaced by `interval` seconds if set.
+    fn mine(&mut self, blocks: u64, interval: Option<u64>) {
+        for idx in 0..blocks {
+            if let Some(interval) = interval.filter(|_| idx > 0) {
+                self.clock.next = Some(self.clock.last + interval);
+            }
+            self.mine_block();
+        }
+    }
+
+    /// Mine a block including the pending transactions that are ready, ordered by priority fee.
+    ///
+    /// Returns the hash of every transaction the EVM rejected, e.g. for lack of funds, along with the reason.
+    fn mine_block(&mut self) -> Vec<(B256, String)> {
+        let parent_hash = self.latest().header.hash;
+        let number = self.latest().header.number + 1;
+        let base_fee = self.peek_base_fee();
+        self.next_base_fee = None;
+        let timestamp = self.clock.advance();
+        let block_env = Self::block_env(number, timestamp, base_fee);
+
+        let mut executed = Vec::new();
+        let mut rejected = Vec::new();
+        for tx in self.take_ready(base_fee) {
+            let result = Evm::builder()
+                .with_db(&mut self.db)
+                .with_spec_id(SPEC)
+                .modify_cfg_env(|cfg| cfg.chain_id = CHAIN_ID)
+                .with_block_env(block_env.clone())
+                .with_tx_env(tx_env(&tx))
+                .build()
+                .transact_commit();
+
+            match result {
+                Ok(result) => executed.push((tx, result)),
+                Err(e) => rejected.push((*tx.envelope.tx_hash(), e.to_string())),
+            }
+        }
+        // the database keeps every log committed, which the receipts hold instead
+        self.db.logs.clear();
+
+        let mut header = empty_header(parent_hash, number, timestamp, base_fee);
+        header.hash = keccak256(
+            [
+                parent_hash.as_slice(),
+                &number.to_be_bytes(),
+                &timestamp.to_be_bytes(),
+            ]
+            .into_iter()
+            .chain(
+                executed
+                    .iter()
+                    .map(|(tx, _)| tx.envelope.tx_hash().as_slice()),
+            )
+            .collect::<Vec<_>>()
+            .concat(),
+        );
+
+        let mut transactions = Vec::with_capacity(executed.len());
+        let mut receipts = Vec::with_capacity(executed.len());
+        let mut log_index = 0;
+        for (idx, (tx, result)) in executed.into_iter().enumerate() {
+            let gas_price = tx
+                .envelope
+                .effective_tip_per_gas(base_fee as u64)
+                .unwrap_or_default()
+                + base_fee;
+            let transaction = rpc_transaction(&tx, Some((&header, idx as u64, gas_price)));
+            header.gas_used += result.gas_used() as u128;
+
+            let contract_address = match &result {
+                ExecutionResult::Success {
+                    output: Output::Create(_, Some(address)),
+                    ..
+                } => Some(address.convert()),
+                _ => None,
+            };
+            let logs: Vec<Log> = result
+                .logs()
+                .iter()
+                .map(|log| {
+                    log_index += 1;
+                    Log {
+                        inner: alloy::primitives::Log::new_unchecked(
+                            log.address.convert(),
+                            log.data
+                                .topics()
+                                .iter()
+                                .map(|topic| topic.convert())
+                                .collect(),
+                            log.data.data.clone().convert(),
+                        ),
+                        block_hash: Some(header.hash),
+                        block_number: Some(number),
+                        block_timestamp: Some(timestamp),
+                        transaction_hash: Some(transaction.hash),
+                        transaction_index: Some(idx as u64),
+                        log_index: Some(log_index - 1),
+                        removed: false,
+                    }
+                })
+                .collect();
+
+            let mut logs_bloom = Bloom::default();
+            for log in &logs {
+                logs_bloom.accrue_log(&log.inner);
+            }
+            header.logs_bloom.accrue_bloom(&logs_bloom);
+
+            let receipt = ReceiptWithBloom {
+                receipt: Receipt {
+                    status: Eip658Value::Eip658(result.is_success()),
+                    cumulative_gas_used: header.gas_used,
+                    logs,
+                },
+                logs_bloom,
+            };
+            receipts.push(TransactionReceipt {
+                inner: match tx.envelope {
+                    TxEnvelope::Legacy(_) => ReceiptEnvelope::Legacy(receipt),
+                    TxEnvelope::Eip2930(_) => ReceiptEnvelope::Eip2930(receipt),
+                    TxEnvelope::Eip4844(_) => ReceiptEnvelope::Eip4844(receipt),
+                    TxEnvelope::Eip7702(_) => ReceiptEnvelope::Eip7702(receipt),
+                    _ => ReceiptEnvelope::Eip1559(receipt),
+                },
+                transaction_hash: transaction.hash,
+                transaction_index: Some(idx as u64),
+                block_hash: Some(header.hash),
+                block_number: Some(number),
+                gas_used: result.gas_used() as u128,
+                effective_gas_price: gas_price,
+                blob_gas_used: None,
+                blob_gas_price: None,
+                from: tx.sender,
+                to: transaction.to,
+                contract_address,
+                state_root: None,
+                authorization_list: None,
+            });
+            transactions.push(transaction);
+        }
+
+        self.push_block(header, transactions, receipts);
+
+        rejected
+    }
+
+    /// Take the pending transactions that can be mined in a block with `base_fee`, highest priority fee first and
+    /// every sender's in nonce order, dropping those whose nonce was already used.
+    fn take_ready(&mut self, base_fee: u128) -> Vec<PendingTransaction> {
+        let mut nonces: HashMap<Address, u64> = HashMap::new();
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.retain(|tx| tx.envelope.nonce() >= self.account(tx.sender).nonce);
+
+        let mut ready = Vec::new();
+        loop {
+            let next = pending
+                .iter()
+                .enumerate()
+                .filter(|(_, tx)| {
+                    let nonce = *nonces
+                        .entry(tx.sender)
+                        .or_insert_with(|| self.account(tx.sender).nonce);
+                    tx.envelope.nonce() == nonce
+                })
+                .filter_map(|(idx, tx)| {
+                    Some((idx, tx.envelope.effective_tip_per_gas(base_fee as u64)?))
+                })
+                // the earliest of the transactions paying the highest tip
+                .max_by(|(a, a_tip), (b, b_tip)| a_tip.cmp(b_tip).then(b.cmp(a)));
+
+            let Some((idx, _)) = next else {
+                break;
+            };
+            let tx = pending.remove(idx);
+            *nonces.get_mut(&tx.sender).expect("inserted when filtering") += 1;
+            ready.push(tx);
+        }
+        self.pending = pending;
+
+        ready
+    }
+
+    fn push_block(
+        &mut self,
+        header: Header,
+        transactions: Vec<Transaction>,
+        receipts: Vec<TransactionReceipt>,
+    ) {
+        for (idx, tx) in transactions.iter().enumerate() {
+            self.mined.insert(tx.hash, (header.number, idx));
+        }
+        self.db
+            .block_hashes
+            .insert(U256::from(header.number), header.hash.convert());
+        self.blocks.push(MinedBlock {
+            header,
+            transactions,
+            receipts,
+        });
+    }
+
+    fn snapshot(&mut self) -> U256 {
+        let id = self.next_snapshot;
+        self.next_snapshot += U256::from(1);
+        self.snapshots.insert(
+            id,
+            NodeSnapshot {
+                db: self.db.clone(),
+                blocks: self.blocks.len(),
+                pending: self.pending.clone(),
+                clock: self.clock,
+                next_base_fee: self.next_base_fee,
+            },
+        );
+
+        id
+    }
+
+    /// Revert to snapshot `id`, discarding it and every later snapshot. Returns whether the snapshot existed.
+    fn revert(&mut self, id: U256) -> bool {
+        let Some(snapshot) = self.snapshots.remove(&id) else {
+            return false;
+        };
+        self.snapshots.split_off(&id);
+
+        for block in self.blocks.drain(snapshot.blocks..) {
+            for tx in block.transactions {
+                self.mined.remove(&tx.hash);
+            }
+        }
+        self.db = snapshot.db;
+        self.pending = snapshot.pending;
+        self.clock = snapshot.clock;
+        self.next_base_fee = snapshot.next_base_fee;
+
+        true
+    }
+
+    /// Serialize the accounts and the latest block, as JSON.
+    fn dump_state(&self) -> Result<Bytes, Failure> {
+        let accounts = self
+            .db
+            .accounts
+            .iter()
+            .filter_map(|(address, account)| {
+                let info = account.info()?;
+                let address = address.convert();
+                Some((
+                    address,
+                    DumpedAccount {
+                        balance: info.balance,
+                        nonce: info.nonce,
+                        code: self.code(address),
+                        storage: account
+                            .storage
+                            .iter()
+                            .map(|(slot, value)| (*slot, *value))
+                            .collect(),
+                    },
+                ))
+            })
+            .collect();
+
+        let header = &self.latest().header;
+        serde_json::to_vec(&DumpedState {
+            accounts,
+            number: header.number,
+            timestamp: header.timestamp,
+            base_fee: self.peek_base_fee(),
+        })
+        .map(Bytes::from)
+        .map_err(|e| Failure::internal(e.to_string()))
+    }
+
+    /// Replace the accounts with those of a state returned by [`Node::dump_state`], and mine empty blocks up to its
+    /// latest block if the chain is behind it.
+    fn load_state(&mut self, state: &Bytes) -> Result<(), Failure> {
+        let state: DumpedState = serde_json::from_slice(state)
+            .map_err(|e| Failure::invalid(format!("invalid state: {e}")))?;
+
+        self.db = CacheDB::new(EmptyDB::default());
+        for (address, account) in state.accounts {
+            self.modify_account(address, |info| {
+                info.balance = account.balance;
+                info.nonce = account.nonce;
+            });
+            if !account.code.is_empty() {
+                self.set_code(address, account.code);
+            }
+            for (slot, value) in account.storage {
+                self.set_storage_at(address, slot, value.into());
+            }
+        }
+        self.pending.clear();
+
+        while self.latest().header.number < state.number {
+            let parent = &self.latest().header;
+            let header = empty_header(
+                parent.hash,
+                parent.number + 1,
+                state.timestamp.max(parent.timestamp),
+                state.base_fee,
+            );
+            self.push_block(
+                Header {
+                    hash: keccak256([parent.hash.as_slice(), b"loaded"].concat()),
+                    ..header
+                },
+                Vec::new(),
+                Vec::new(),
+            );
+        }
+        self.clock.last = self.clock.last.max(state.timestamp);
+        self.next_base_fee = Some(state.base_fee);
+
+        Ok(())
+    }
+}
+
+/// JSON-RPC error returned by a [`Node`].
+#[derive(Debug)]
+struct Failure {
+    code: i64,
+    message: String,
+    data: Option<Bytes>,
+}
+
+impl Failure {
+    fn invalid(message: String) -> Self {
+        Self {
+            code: -32602,
+            message,
+            data: None,
+        }
+    }
+
+    fn internal(message: String) -> Self {
+        Self {
+            code: -32603,
+            message,
+            data: None,
+        }
+    }
+
+    /// Returns the error of a call that did not succeed, carrying its revert data like Anvil's.
+    fn execution(result: ExecutionResult) -> Self {
+        match result {
+            ExecutionResult::Revert { output, .. } => Self {
+                code: 3,
+                message: "execution reverted".to_string(),
+                data: Some(output.convert()),
+            },
+            ExecutionResult::Halt { reason, .. } => Self {
+                code: -32003,
+                message: format!("execution halted: {reason:?}"),
+                data: None,
+            },
+            ExecutionResult::Success { .. } => Self::internal("execution succeeded".to_string()),
+        }
+    }
+}
+
+impl From<Failure> for ErrorPayload {
+    fn from(failure: Failure) -> Self {
+        Self {
+            code: failure.code,
+            message: failure.message,
+            data: failure
+                .data
+                .and_then(|data| serde_json::value::to_raw_value(&data).ok()),
+        }
+    }
+}
+
+impl From<Failure> for ArenaError {
+    fn from(failure: Failure) -> Self {
+        ArenaError::RpcError(RpcError::ErrorResp(failure.into()))
+    }
+}
+
+/// Returns the header of a block without transactions.
+fn empty_header(parent_hash: B256, number: u64, timestamp: u64, base_fee: u128) -> Header {
+    Header {
+        hash: keccak256([parent_hash.as_slice(), &number.to_be_bytes()].concat()),
+        parent_hash,
+        number,
+        timestamp,
+        gas_limit: GAS_LIMIT as u128,
+        base_fee_per_gas: Some(base_fee),
+        mix_hash: Some(keccak256(number.to_be_bytes())),
+        nonce: Some(B64::ZERO),
+        total_difficulty: Some(U256::ZERO),
+        blob_gas_used: Some(0),
+        excess_blob_gas: Some(0),
+        ..Default::default()
+    }
+}
+
+/// Returns the environment of the pending transaction `tx`.
+fn tx_env(tx: &PendingTransaction) -> TxEnv {
+    let envelope = &tx.envelope;
+
+    TxEnv {
+        caller: tx.sender.convert(),
+        gas_limit: envelope.gas_limit() as u64,
+        gas_price: U256::from(envelope.gas_price().unwrap_or(envelope.max_fee_per_gas())),
+        gas_priority_fee: envelope.max_priority_fee_per_gas().map(U256::from),
+        transact_to: tx_kind(envelope.to()),
+        value: envelope.value(),
+        data: Bytes::copy_from_slice(envelope.input()).convert(),
+        nonce: Some(envelope.nonce()),
+        chain_id: envelope.chain_id(),
+        access_list: envelope
+            .access_list()
+            .map(|list| {
+                list.iter()
+                    .map(|item| {
+                        (
+                            item.address.convert(),
+                            item.storage_keys
+                                .iter()
+                                .map(|key| U256::from_be_bytes(key.0))
+                                .collect(),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+/// Returns `tx` as served over JSON-RPC, mined at index `idx` of the block of `header` at `gas_price` if mined.
+fn rpc_transaction(tx: &PendingTransaction, mined: Option<(&Header, u64, u128)>) -> Transaction {
+    let envelope = &tx.envelope;
+    let signature = match envelope {
+        TxEnvelope::Legacy(signed) => signed.signature(),
+        TxEnvelope::Eip2930(signed) => signed.signature(),
+        TxEnvelope::Eip1559(signed) => signed.signature(),
+        TxEnvelope::Eip4844(signed) => signed.signature(),
+        TxEnvelope::Eip7702(signed) => signed.signature(),
+        _ => unreachable!("unknown transaction type"),
+    };
+    let dynamic_fee = envelope.gas_price().is_none();
+
+    Transaction {
+        hash: *envelope.tx_hash(),
+        nonce: envelope.nonce(),
+        block_hash: mined.map(|(header, ..)| header.hash),
+        block_number: mined.map(|(header, ..)| header.number),
+        transaction_index: mined.map(|(_, idx, _)| idx),
+        from: tx.sender,
+        to: envelope.to().to().copied(),
+        value: envelope.value(),
+        gas_price: Some(mined.map_or(envelope.max_fee_per_gas(), |(.., gas_price)| gas_price)),
+        gas: envelope.gas_limit(),
+        max_fee_per_gas: dynamic_fee.then(|| envelope.max_fee_per_gas()),
+        max_priority_fee_per_gas: envelope.max_priority_fee_per_gas(),
+        max_fee_per_blob_gas: None,
+        input: Bytes::copy_from_slice(envelope.input()),
+        signature: Some(Signature {
+            r: signature.r(),
+            s: signature.s(),
+            v: U256::from(signature.v().to_u64()),
+            y_parity: Some(Parity(signature.v().y_parity())),
+        }),
+        chain_id: envelope.chain_id(),
+        blob_versioned_hashes: None,
+        access_list: envelope.access_list().cloned(),
+        transaction_type: Some(envelope.ty()),
+        authorization_list: None,
+    }
+}
+
+fn tx_kind(kind: TxKind) -> revm::primitives::TxKind {
+    match kind {
+        TxKind::Call(address) => revm::primitives::TxKind::Call(address.convert()),
+        TxKind::Create => revm::primitives::TxKind::Create,
+    }
+}
+
+/// Returns the seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// Deserialize the parameter at `idx`.
+fn param<T: DeserializeOwned>(params: &[Value], idx: usize) -> Result<T, Failure> {
+    optional(params, idx)?.ok_or_else(|| Failure::invalid(format!("missing parameter {idx}")))
+}
+
+/// Deserialize the parameter at `idx`, if given.
+fn optional<T: DeserializeOwned>(params: &[Value], idx: usize) -> Result<Option<T>, Failure> {
+    match params.get(idx) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => T::deserialize(value)
+            .map(Some)
+            .map_err(|e| Failure::invalid(format!("invalid parameter {idx}: {e}"))),
+    }
+}
+
+/// Deserialize the parameter at `idx` as an integer, given either as a JSON number or as a hex quantity.
+fn quantity(params: &[Value], idx: usize) -> Result<u64, Failure> {
+    match params.get(idx) {
+        Some(Value::Number(number)) => number
+            .as_u64()
+            .ok_or_else(|| Failure::invalid(format!("invalid parameter {idx}: {number}"))),
+        _ => Ok(param::<U256>(params, idx)?.to::<u64>()),
+    }
+}
+
+/// Conversion between the primitives of alloy and those of the older version of alloy `revm` is built on, sharing
+/// only their integers.
+trait Convert<T> {
+    fn convert(self) -> T;
+}
+
+impl Convert<revm::primitives::Address> for Address {
+    fn convert(self) -> revm::primitives::Address {
+        revm::primitives::Address::new(self.into_array())
+    }
+}
+
+impl Convert<Address> for revm::primitives::Address {
+    fn convert(self) -> Address {
+        Address::new(self.into_array())
+    }
+}
+
+impl Convert<revm::primitives::B256> for B256 {
+    fn convert(self) -> revm::primitives::B256 {
+        revm::primitives::B256::new(self.0)
+    }
+}
+
+impl Convert<B256> for revm::primitives::B256 {
+    fn convert(self) -> B256 {
+        B256::new(self.0)
+    }
+}
+
+impl Convert<revm::primitives::Bytes> for Bytes {
+    fn convert(self) -> revm::primitives::Bytes {
+        revm::primitives::Bytes(self.0)
+    }
+}
+
+impl Convert<Bytes> for revm::primitives::Bytes {
+    fn convert(self) -> Bytes {
+        Bytes(self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        eips::eip2718::Encodable2718,
+        network::{Ethereum, NetworkWallet, TransactionBuilder},
+        signers::local::PrivateKeySigner,
+    };
+
+    use super::*;
+
+    /// Returns a signed transfer from `signer`, encoded as sent to `eth_sendRawTransaction`.
+    async fn transfer(signer: &PrivateKeySigner, nonce: u64, tip: u128) -> Bytes {
+        let request = TransactionRequest::default()
+            .with_from(signer.address())
+            .with_to(Address::repeat_byte(9))
+            .with_value(U256::from(1))
+            .with_nonce(nonce)
+            .with_gas_limit(21_000)
+            .with_max_fee_per_gas(100_000_000_000)
+            .with_max_priority_fee_per_gas(tip)
+            .with_chain_id(CHAIN_ID);
+
+        let wallet = EthereumWallet::from(signer.clone());
+        let envelope = NetworkWallet::<Ethereum>::sign_request(&wallet, request)
+            .await
+            .unwrap();
+        envelope.encoded_2718().into()
+    }
+
+    #[test]
+    fn test_snapshot_revert() {
+        let account = Address::repeat_byte(1);
+        let mut node = Node::new([account]);
+
+        let first = node.snapshot();
+        node.modify_account(account, |info| info.balance = U256::ZERO);
+        node.mine(2, None);
+        let second = node.snapshot();
+        node.set_storage_at(account, U256::from(1), B256::repeat_byte(1));
+
+        assert!(node.revert(first));
+        assert_eq!(
+            node.account(account).balance,
+            parse_ether(ACCOUNT_ETHER).unwrap()
+        );
+        assert_eq!(node.storage(account, U256::from(1)), U256::ZERO);
+        assert_eq!(node.latest().header.number, 0);
+
+        // reverting consumes the snapshot along with every later one
+        assert!(!node.revert(second));
+        assert!(!node.revert(first));
+    }
+
+    #[test]
+    fn test_load_state() {
+        let (account, contract) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let mut node = Node::new([account]);
+        node.set_code(contract, Bytes::from_static(&[0x60, 0x00]));
+        node.set_storage_at(contract, U256::from(3), B256::with_last_byte(7));
+        node.mine(3, None);
+
+        let mut loaded = Node::new([]);
+        loaded.load_state(&node.dump_state().unwrap()).unwrap();
+
+        assert_eq!(
+            loaded.account(account).balance,
+            parse_ether(ACCOUNT_ETHER).unwrap()
+        );
+        assert_eq!(loaded.code(contract), node.code(contract));
+        assert_eq!(loaded.storage(contract, U256::from(3)), U256::from(7));
+        assert_eq!(loaded.latest().header.number, 3);
+        assert_eq!(loaded.peek_base_fee(), node.peek_base_fee());
+
+        assert!(loaded
+            .load_state(&Bytes::from_static(b"not a state"))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_take_ready() {
+        let (a, b, c) = (
+            PrivateKeySigner::random(),
+            PrivateKeySigner::random(),
+            PrivateKeySigner::random(),
+        );
+        let mut node = Node::new([a.address(), b.address(), c.address()]);
+        node.auto_mine = false;
+
+        let gwei = 1_000_000_000;
+        let mut hashes = Vec::new();
+        for (signer, nonce, tip) in [
+            (&a, 0, gwei),
+            (&a, 1, 5 * gwei),
+            (&b, 0, 3 * gwei),
+            (&c, 1, gwei),
+        ] {
+            let raw = transfer(signer, nonce, tip).await;
+            hashes.push(node.send_raw_transaction(&raw).unwrap());
+        }
+        assert!(node
+            .send_raw_transaction(&transfer(&a, 0, 2 * gwei).await)
+            .is_err());
+
+        // the highest tip first, but every sender in nonce order, leaving the nonce gap of c pending
+        let ready: Vec<_> = node
+            .take_ready(node.peek_base_fee())
+            .iter()
+            .map(|tx| *tx.envelope.tx_hash())
+            .collect();
+        assert_eq!(ready, vec![hashes[2], hashes[0], hashes[1]]);
+        assert_eq!(node.pending.len(), 1);
+
+        // a pending transaction whose nonce was used in the meantime is dropped
+        node.modify_account(c.address(), |info| info.nonce = 2);
+        assert!(node.take_ready(node.peek_base_fee()).is_empty());
+        assert!(node.pending.is_empty());
+    }
+
+    #[test]
+    fn test_methods() {
+        let mut node = Node::new([]);
+
+        for method in RevmBackend::METHODS {
+            let code = node.dispatch(method, &[]).err().map(|failure| failure.code);
+            assert_ne!(code, Some(-32601), "{method} is not served");
+        }
+        assert_eq!(
+            node.dispatch("debug_traceTransaction", &[])
+                .unwrap_err()
+                .code,
+            -32601
+        );
+    }
+}
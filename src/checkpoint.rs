@@ -0,0 +1,143 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use alloy::primitives::{Address, Bytes};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    engine::{
+        arbitrageur::{ArbitrageLedger, ArbitrageTrade},
+        position::Holdings,
+        score::Mark,
+        Engine,
+    },
+    error::ArenaError,
+    history::History,
+    PoolKey,
+};
+
+/// Bookkeeping of a simulation between two steps, beyond the state of the chain, feeds and strategies.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunState {
+    /// Next step to simulate.
+    pub step: usize,
+
+    /// Gas used so far by each strategy.
+    pub gas_used: Vec<u128>,
+
+    /// Whether each strategy stopped being processed after a panic.
+    pub dropped: Vec<bool>,
+
+    /// Marks of each strategy, from initialization up to the last simulated step.
+    pub marks: Vec<Vec<Mark>>,
+
+    /// Pool state observed at previous steps.
+    pub history: History,
+
+    /// Latest swap executed by the arbitrageur, if any.
+    pub last_arbitrage: Option<ArbitrageTrade>,
+
+    /// First block whose events have not yet been delivered to strategies.
+    pub events_from: u64,
+
+    /// First block whose logs have not yet been captured for the inspector.
+    #[serde(default)]
+    pub logs_from: u64,
+}
+
+/// State of the [`Engine`] acting on a pool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineState {
+    /// Controller of the pool.
+    pub controller: Address,
+
+    /// Key of the pool, if not the pool of the controller but another pool of its `PoolManager`.
+    #[serde(default)]
+    pub key: Option<PoolKey>,
+
+    /// Liquid exchange of the pool, if not the one of the controller.
+    #[serde(default)]
+    pub exchange: Option<Address>,
+
+    /// Decimals of token0 and token1.
+    pub decimals: (u8, u8),
+
+    /// Positions of every wallet.
+    pub ledger: HashMap<Address, Holdings>,
+
+    /// Fills of the arbitrageur.
+    #[serde(default)]
+    pub arbitrage: ArbitrageLedger,
+}
+
+impl From<&Engine> for EngineState {
+    fn from(engine: &Engine) -> Self {
+        Self {
+            controller: engine.controller,
+            key: engine.key.clone(),
+            exchange: engine.exchange,
+            decimals: engine.decimals,
+            ledger: engine
+                .ledger
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone(),
+            arbitrage: engine.arbitrage_ledger(),
+        }
+    }
+}
+
+impl From<EngineState> for Engine {
+    fn from(state: EngineState) -> Self {
+        Self {
+            controller: state.controller,
+            key: state.key,
+            exchange: state.exchange,
+            ledger: Arc::new(Mutex::new(state.ledger)),
+            decimals: state.decimals,
+            arbitrage: Arc::new(Mutex::new(state.arbitrage)),
+            deferred: None,
+        }
+    }
+}
+
+/// Everything needed to continue a simulation from the end of a step, as written by
+/// [`crate::arena::Arena::checkpoint`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// State of the chain, as returned by `anvil_dumpState`.
+    pub chain: Bytes,
+
+    /// State of the feed, see [`crate::feed::Feed::snapshot`].
+    pub feed: Value,
+
+    /// State of the feed of every additional pool.
+    pub pool_feeds: Vec<Value>,
+
+    /// State of every strategy, see [`crate::strategy::Strategy::snapshot`].
+    pub strategies: Vec<Value>,
+
+    /// Engines of every pool, the pool of the [`crate::Config`] first.
+    pub engines: Vec<EngineState>,
+
+    /// Bookkeeping of the simulation.
+    pub run: RunState,
+}
+
+impl Checkpoint {
+    /// Write the checkpoint to `path` as JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ArenaError> {
+        fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Read a checkpoint previously written with [`Checkpoint::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ArenaError> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+}
@@ -1,6 +1,396 @@
-use alloy::primitives::U256;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use alloy::{dyn_abi::DynSolValue, primitives::U256};
+use rand::rngs::StdRng;
+use serde_json::{json, Map, Value};
 
 use super::*;
+use crate::{
+    engine::{
+        gas::GasMarket,
+        hook::HOOK_FLAGS_MASK,
+        position::{MAX_TICK, MIN_TICK},
+        score::Mark,
+    },
+    error::{ArenaError, BuildError},
+    history::DEFAULT_RETENTION,
+};
+
+/// Largest pool fee, in hundredths of a bip, i.e. 100%.
+pub const MAX_LP_FEE: u32 = 1_000_000;
+
+/// Pool fee marking the fee as dynamic, set by the pool hooks.
+pub const DYNAMIC_FEE_FLAG: u32 = 0x800000;
+
+/// Smallest tick spacing accepted by the `PoolManager`.
+pub const MIN_TICK_SPACING: i32 = 1;
+
+/// Largest tick spacing accepted by the `PoolManager`.
+pub const MAX_TICK_SPACING: i32 = i16::MAX as i32;
+
+/// Smallest sqrt price x96 a pool can be initialized at.
+pub const MIN_SQRT_PRICE: Uint<160, 3> = Uint::from_limbs([4295128739, 0, 0]);
+
+/// Bound on the sqrt price x96 a pool can be initialized at, exclusive.
+pub const MAX_SQRT_PRICE: Uint<160, 3> =
+    Uint::from_limbs([0xe3dc8a697e4902dc, 0xefd1fc6a5064a457, 0xfffd8963]);
+
+/// What the arena does when a strategy panics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PanicPolicy {
+    /// Stop the simulation with an [`crate::error::ArenaError::Panic`].
+    #[default]
+    Abort,
+
+    /// Keep processing the strategy on later steps.
+    Continue,
+
+    /// Stop processing the strategy for the rest of the simulation.
+    Drop,
+}
+
+/// Condition ending a run early, evaluated at the end of every step so degenerate scenarios stop early.
+pub enum StopCondition {
+    /// Stop once this many steps have been simulated, including those before a resumed checkpoint.
+    MaxSteps(usize),
+
+    /// Stop once the run has taken this much wall-clock time.
+    WallClock(Duration),
+
+    /// Stop once the pool price of token0 in terms of token1, in whole tokens, leaves `min..=max`.
+    PriceBounds {
+        /// Lowest price allowed.
+        min: f64,
+
+        /// Highest price allowed.
+        max: f64,
+    },
+
+    /// Stop once the wallet of any strategy is worth nothing.
+    Bankruptcy,
+
+    /// Stop once the predicate holds for the signal at the end of a step.
+    Custom(Box<dyn Fn(&Signal) -> bool + Send + Sync>),
+}
+
+impl StopCondition {
+    /// Returns why the run stops, if the condition is met after `steps` steps taking `elapsed`, with the pool in the
+    /// state of `signal` and every strategy marked to `marks`.
+    pub fn check(
+        &self,
+        steps: usize,
+        elapsed: Duration,
+        signal: &Signal,
+        marks: &[Vec<Mark>],
+    ) -> Option<String> {
+        match self {
+            StopCondition::MaxSteps(max) if steps >= *max => Some(format!("reached {max} steps")),
+            StopCondition::WallClock(budget) if elapsed >= *budget => {
+                Some(format!("exceeded the wall-clock budget of {budget:?}"))
+            }
+            StopCondition::PriceBounds { min, max } => {
+                let price = signal.price();
+                (!(*min..=*max).contains(&price))
+                    .then(|| format!("price {price} left {min}..={max}"))
+            }
+            StopCondition::Bankruptcy => marks
+                .iter()
+                .position(|marks| marks.last().is_some_and(|mark| mark.value <= 0.0))
+                .map(|idx| format!("strategy {idx} went bankrupt")),
+            StopCondition::Custom(predicate) if predicate(signal) => {
+                Some("custom stop condition met".to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns a short description of the condition.
+    pub fn describe(&self) -> String {
+        match self {
+            StopCondition::MaxSteps(max) => format!("max steps {max}"),
+            StopCondition::WallClock(budget) => format!("wall clock {budget:?}"),
+            StopCondition::PriceBounds { min, max } => format!("price bounds {min}..={max}"),
+            StopCondition::Bankruptcy => "bankruptcy".to_string(),
+            StopCondition::Custom(_) => "custom".to_string(),
+        }
+    }
+}
+
+/// Parameters of a pool and of the contracts deployed around it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolConfig {
+    /// Pool manager fee.
+    pub manager_fee: U256,
+
+    /// Pool fee.
+    pub fee: Uint<24, 1>,
+
+    /// Pool tick spacing.
+    pub tick_spacing: Signed<24, 1>,
+
+    /// Pool hooks.
+    pub hooks: Address,
+
+    /// Hook data passed when initializing the pool.
+    pub hook_data: Bytes,
+
+    /// Initial pool sqrt price x96.
+    pub sqrt_price_x96: Uint<160, 3>,
+
+    /// Initial pool price of token0 in terms of token1, in whole tokens, which takes precedence over
+    /// [`PoolConfig::sqrt_price_x96`] and is converted using the decimals of the pool tokens.
+    pub price: Option<f64>,
+
+    /// Liquidity provided between a lower and upper tick once the pool is initialized, held by the admin wallet, which
+    /// is minted the tokens backing it.
+    pub liquidity: Option<(Signed<24, 1>, Signed<24, 1>, u128)>,
+
+    /// Hook deployed alongside the pool, which takes precedence over [`PoolConfig::hooks`].
+    pub hook: Option<HookConfig>,
+
+    /// Initial price of the liquid exchange the pool is arbitraged against.
+    pub initial_price: U256,
+}
+
+impl PoolConfig {
+    /// Public constructor function for a new [`PoolConfig`] without hooks or manager fee.
+    pub fn new(
+        fee: Uint<24, 1>,
+        tick_spacing: Signed<24, 1>,
+        sqrt_price_x96: Uint<160, 3>,
+        initial_price: U256,
+    ) -> Self {
+        Self {
+            manager_fee: U256::ZERO,
+            fee,
+            tick_spacing,
+            hooks: Address::ZERO,
+            hook_data: Bytes::new(),
+            sqrt_price_x96,
+            price: None,
+            liquidity: None,
+            hook: None,
+            initial_price,
+        }
+    }
+
+    /// Initialize the pool at `price`, token0 in terms of token1 in whole tokens, rather than at a raw sqrt price.
+    pub fn with_price(mut self, price: f64) -> Self {
+        self.price = Some(price);
+        self
+    }
+
+    /// Bootstrap the pool with `liquidity` between `tick_lower` and `tick_upper`, so strategies start from a pool that
+    /// can be traded against.
+    pub fn with_liquidity(
+        mut self,
+        tick_lower: Signed<24, 1>,
+        tick_upper: Signed<24, 1>,
+        liquidity: u128,
+    ) -> Self {
+        self.liquidity = Some((tick_lower, tick_upper, liquidity));
+        self
+    }
+
+    /// Deploy `hook` with the pool and initialize the pool with it, see [`crate::arena::ArenaBuilder::with_hook`].
+    pub fn with_hook_deployment(mut self, hook: HookConfig) -> Self {
+        self.hook = Some(hook);
+        self
+    }
+
+    /// Set the pool hooks and the hook data passed when initializing the pool.
+    pub fn with_hooks(mut self, hooks: Address, hook_data: Bytes) -> Self {
+        self.hooks = hooks;
+        self.hook_data = hook_data;
+        self
+    }
+
+    /// Set the pool manager fee.
+    pub fn with_manager_fee(mut self, manager_fee: U256) -> Self {
+        self.manager_fee = manager_fee;
+        self
+    }
+
+    /// Returns every reason the `PoolManager` would reject the pool, or the liquid exchange could not quote it.
+    ///
+    /// The fee is in hundredths of a bip and at most [`MAX_LP_FEE`], unless it is [`DYNAMIC_FEE_FLAG`], which requires
+    /// hooks to set it. The tick spacing and initial sqrt price must lie within the bounds the `PoolManager` enforces,
+    /// and bootstrap liquidity must span a non-empty range aligned to the tick spacing.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        let fee = self.fee.to::<u32>();
+        if fee == DYNAMIC_FEE_FLAG {
+            if self.hooks.is_zero() && self.hook.is_none() {
+                problems.push("a dynamic fee requires hooks to set it".to_string());
+            }
+        } else if fee > MAX_LP_FEE {
+            problems.push(format!("fee {fee} exceeds the maximum of {MAX_LP_FEE}"));
+        }
+
+        let tick_spacing = self.tick_spacing.as_i32();
+        if !(MIN_TICK_SPACING..=MAX_TICK_SPACING).contains(&tick_spacing) {
+            problems.push(format!(
+                "tick spacing {tick_spacing} is outside {MIN_TICK_SPACING}..={MAX_TICK_SPACING}"
+            ));
+        }
+
+        match self.price {
+            Some(price) if !(price > 0.0 && price.is_finite()) => {
+                problems.push(format!("price {price} is not a positive number"));
+            }
+            Some(_) => {}
+            None if self.sqrt_price_x96 < MIN_SQRT_PRICE
+                || self.sqrt_price_x96 >= MAX_SQRT_PRICE =>
+            {
+                problems.push(format!(
+                    "sqrt price {} is outside {MIN_SQRT_PRICE}..{MAX_SQRT_PRICE}",
+                    self.sqrt_price_x96
+                ));
+            }
+            None => {}
+        }
+
+        if let Some((tick_lower, tick_upper, liquidity)) = self.liquidity {
+            let (lower, upper) = (tick_lower.as_i32(), tick_upper.as_i32());
+
+            if lower >= upper || lower < MIN_TICK || upper > MAX_TICK {
+                problems.push(format!(
+                    "liquidity range {lower}..{upper} is empty or outside {MIN_TICK}..{MAX_TICK}"
+                ));
+            }
+            if tick_spacing > 0 && (lower % tick_spacing != 0 || upper % tick_spacing != 0) {
+                problems.push(format!(
+                    "liquidity range {lower}..{upper} is not aligned to tick spacing {tick_spacing}"
+                ));
+            }
+            if liquidity == 0 || liquidity > i128::MAX as u128 {
+                problems.push(format!("liquidity {liquidity} is zero or overflows int128"));
+            }
+        }
+
+        if let Some(hook) = &self.hook {
+            if hook.flags > HOOK_FLAGS_MASK {
+                problems.push(format!(
+                    "hook flags {:#x} exceed the permission bits {HOOK_FLAGS_MASK:#x}",
+                    hook.flags
+                ));
+            }
+            if !hook.artifact.is_file() {
+                problems.push(format!(
+                    "hook artifact {} does not exist",
+                    hook.artifact.display()
+                ));
+            }
+        }
+
+        if self.initial_price.is_zero() {
+            problems.push("initial price is zero".to_string());
+        }
+
+        problems
+    }
+}
+
+/// A hook contract deployed with each pool.
+///
+/// The `PoolManager` reads the permissions of a hook from the low bits of its address, so the hook is deployed with
+/// `CREATE2` at an address mined to match [`HookConfig::flags`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HookConfig {
+    /// Compiled hook, as a Foundry or Hardhat JSON artifact.
+    pub artifact: PathBuf,
+
+    /// Arguments of the hook constructor following the `PoolManager`, which is always passed first.
+    pub constructor_args: Vec<DynSolValue>,
+
+    /// Permission flags of the hook, e.g. `Hooks.BEFORE_SWAP_FLAG | Hooks.AFTER_SWAP_FLAG`.
+    pub flags: u16,
+}
+
+impl HookConfig {
+    /// Public constructor function for a new [`HookConfig`].
+    pub fn new(
+        artifact: impl Into<PathBuf>,
+        constructor_args: Vec<DynSolValue>,
+        flags: u16,
+    ) -> Self {
+        Self {
+            artifact: artifact.into(),
+            constructor_args,
+            flags,
+        }
+    }
+}
+
+/// Ether and token balances an account is funded with, for every pool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Funding {
+    /// Ether balance, in wei.
+    pub ether: U256,
+
+    /// Amount of token0 minted to the account, in its smallest unit.
+    pub token0: U256,
+
+    /// Amount of token1 minted to the account, in its smallest unit.
+    pub token1: U256,
+
+    /// Whether the account approves the `PoolManager`, liquidity router and swap router of every pool to spend its
+    /// tokens up front, rather than the engine approving the routers before the first trade of the account.
+    pub approve: bool,
+}
+
+impl Funding {
+    /// Public constructor function for a new [`Funding`] without approvals.
+    pub fn new(ether: U256, token0: U256, token1: U256) -> Self {
+        Self {
+            ether,
+            token0,
+            token1,
+            approve: false,
+        }
+    }
+
+    /// Approve the `PoolManager`, liquidity router and swap router of every pool to spend the tokens of the account.
+    pub fn with_approvals(mut self) -> Self {
+        self.approve = true;
+        self
+    }
+}
+
+/// Name, decimals and initial supply of a pool token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenConfig {
+    /// Token name.
+    pub name: String,
+
+    /// Token symbol.
+    pub symbol: String,
+
+    /// Token decimals.
+    pub decimals: u8,
+
+    /// Amount of the token, in its smallest unit, minted to the liquid exchange of the pool as the reserves it pays out
+    /// of when the arbitrageur trades on it. The exchange is minted whatever it lacks for a trade, so the supply only
+    /// sets its starting balance, e.g. for inspectors reading it.
+    pub supply: U256,
+}
+
+impl TokenConfig {
+    /// Public constructor function for a new [`TokenConfig`].
+    pub fn new(name: &str, symbol: &str, decimals: u8, supply: U256) -> Self {
+        Self {
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            decimals,
+            supply,
+        }
+    }
+}
 
 /// Configuration for the simulation.
 pub struct Config {
@@ -27,6 +417,76 @@ pub struct Config {
 
     /// Pool hooks.
     pub hooks: Address,
+
+    /// Initial pool price in whole tokens, see [`PoolConfig::price`].
+    pub price: Option<f64>,
+
+    /// Liquidity the pool is bootstrapped with, see [`PoolConfig::liquidity`].
+    pub liquidity: Option<(Signed<24, 1>, Signed<24, 1>, u128)>,
+
+    /// Seconds of chain time that elapse per simulation step.
+    ///
+    /// A block is mined at the end of every step, timestamped this many seconds after the one ending the previous step,
+    /// or right after the latest block if blocks mined during the step already went past it. If unset, the chain clock
+    /// is left untouched. Block timestamps are whole seconds, so it must be a positive whole number.
+    pub seconds_per_step: Option<f64>,
+
+    /// Seconds of chain time in one unit of the time of the feeds, the unit of [`Feed::advance`].
+    ///
+    /// Feeds tick on every sub-step and once more at the end of every step. With [`Config::seconds_per_step`], every
+    /// tick advances them by an even share of the chain time of a step, converted to their unit, see
+    /// [`Config::feed_dt`]. If unset, each tick is a [`Feed::step`] of the feed's own default time step, unrelated to
+    /// chain time.
+    pub feed_time_unit: Option<f64>,
+
+    /// Wall-clock duration of a simulation step, if steps are paced to real time.
+    ///
+    /// A paced run starts a step at most once per period, so strategies can be rehearsed in near real time against a
+    /// live feed, such as a [`crate::feed::live::ChannelFeed`]. A step taking longer than the period delays the next
+    /// ones rather than being caught up on.
+    pub real_time: Option<Duration>,
+
+    /// Number of feed sub-ticks per simulation step.
+    ///
+    /// The feed and arbitrageur act on every sub-tick, while strategies act once per step.
+    pub sub_steps: usize,
+
+    /// Number of steps simulated before strategies are initialized, in which only the feeds, liquid exchanges,
+    /// arbitrageur and noise traders act.
+    ///
+    /// Warm-up builds pool state and oracle history for strategies to start from, and its steps are recorded into
+    /// [`crate::Signal::history`], but are not part of [`Config::steps`]. They are not inspected or scored.
+    pub warmup_steps: usize,
+
+    /// Number of previous steps retained in [`crate::Signal::history`].
+    pub history_retention: usize,
+
+    /// Whether the last arbitrage of each step is left in the mempool while strategies act.
+    ///
+    /// Strategies see the pending transactions in [`crate::Signal::pending`], and their own transactions are mined in
+    /// the same block, ordered by fee, which allows front-running and back-running the arbitrageur.
+    pub mempool: bool,
+
+    /// How the base fee of the chain evolves from step to step.
+    ///
+    /// Unless left to the node, the base fee is set at the start of every step, and blocks mined during the step follow
+    /// the node's own EIP-1559 rule from there. Strategies and the arbitrageur see the resulting gas price in
+    /// [`crate::Signal::gas_price`].
+    pub gas_market: GasMarket,
+
+    /// What to do when a strategy panics. Panics are always reported to the inspector first.
+    pub panic_policy: PanicPolicy,
+
+    /// Parameters strategies are constructed from through [`crate::strategy::FromConfig`].
+    pub strategy_params: Value,
+
+    /// Conditions ending the run before [`Config::steps`], checked in order at the end of every step.
+    pub stop_conditions: Vec<StopCondition>,
+
+    /// Interval in steps at which the arena writes a checkpoint, and the file it writes it to.
+    ///
+    /// See [`crate::arena::Arena::checkpoint`].
+    pub checkpoints: Option<(usize, PathBuf)>,
 }
 
 impl Config {
@@ -51,6 +511,394 @@ impl Config {
             pool_fee,
             initial_price,
             hooks,
+            price: None,
+            liquidity: None,
+            seconds_per_step: None,
+            feed_time_unit: None,
+            real_time: None,
+            sub_steps: 1,
+            warmup_steps: 0,
+            history_retention: DEFAULT_RETENTION,
+            mempool: false,
+            gas_market: GasMarket::default(),
+            panic_policy: PanicPolicy::default(),
+            strategy_params: Value::Object(Map::new()),
+            stop_conditions: Vec::new(),
+            checkpoints: None,
         }
     }
+
+    /// Initialize the pool at `price`, token0 in terms of token1 in whole tokens, rather than at
+    /// [`Config::sqrt_price_x96`].
+    pub fn with_price(mut self, price: f64) -> Self {
+        self.price = Some(price);
+        self
+    }
+
+    /// Bootstrap the pool with `liquidity` between `tick_lower` and `tick_upper`.
+    pub fn with_liquidity(
+        mut self,
+        tick_lower: Signed<24, 1>,
+        tick_upper: Signed<24, 1>,
+        liquidity: u128,
+    ) -> Self {
+        self.liquidity = Some((tick_lower, tick_upper, liquidity));
+        self
+    }
+
+    /// Set the number of seconds of chain time that elapse per simulation step.
+    ///
+    /// Fails unless `seconds_per_step` is a positive whole number, as block timestamps are whole seconds.
+    pub fn with_seconds_per_step(mut self, seconds_per_step: f64) -> Result<Self, BuildError> {
+        self.seconds_per_step = Some(seconds_per_step);
+        BuildError::from_problems(self.schedule_problems())?;
+        Ok(self)
+    }
+
+    /// Measure the time of the feeds in units of `seconds` of chain time, e.g. `86_400.0` for feeds parameterized per
+    /// day, so they advance with the chain clock, see [`Config::feed_time_unit`].
+    pub fn with_feed_time_unit(mut self, seconds: f64) -> Self {
+        self.feed_time_unit = Some(seconds);
+        self
+    }
+
+    /// Pace steps to real time, starting one every `period`.
+    ///
+    /// Unless set already, [`Config::seconds_per_step`] is set to the period, so chain time follows the wall clock. A
+    /// period that is not a whole number of seconds then fails validation.
+    pub fn with_real_time(mut self, period: Duration) -> Self {
+        self.real_time = Some(period);
+        self.seconds_per_step.get_or_insert(period.as_secs_f64());
+        self
+    }
+
+    /// Set the number of feed sub-ticks per simulation step, separating market microstructure frequency from strategy
+    /// decision frequency.
+    ///
+    /// Fails if `sub_steps` is zero, as the feed would never move.
+    pub fn with_sub_steps(mut self, sub_steps: usize) -> Result<Self, BuildError> {
+        self.sub_steps = sub_steps;
+        BuildError::from_problems(self.schedule_problems())?;
+        Ok(self)
+    }
+
+    /// Set the number of warm-up steps simulated before strategies are initialized.
+    pub fn with_warmup(mut self, warmup_steps: usize) -> Self {
+        self.warmup_steps = warmup_steps;
+        self
+    }
+
+    /// Set the number of previous steps retained in the history passed to strategies.
+    pub fn with_history_retention(mut self, history_retention: usize) -> Self {
+        self.history_retention = history_retention;
+        self
+    }
+
+    /// Leave the last arbitrage of each step pending in the mempool while strategies act.
+    pub fn with_mempool(mut self) -> Self {
+        self.mempool = true;
+        self
+    }
+
+    /// Set how the base fee of the chain evolves from step to step.
+    pub fn with_gas_market(mut self, gas_market: GasMarket) -> Self {
+        self.gas_market = gas_market;
+        self
+    }
+
+    /// Set what the arena does when a strategy panics.
+    pub fn with_panic_policy(mut self, panic_policy: PanicPolicy) -> Self {
+        self.panic_policy = panic_policy;
+        self
+    }
+
+    /// Set the parameters strategies are constructed from.
+    pub fn with_strategy_params(mut self, strategy_params: Value) -> Self {
+        self.strategy_params = strategy_params;
+        self
+    }
+
+    /// Set the strategy parameters from the `[strategy.params]` table of a TOML document.
+    ///
+    /// A document without the table leaves the parameters empty.
+    pub fn with_strategy_params_toml(mut self, toml: &str) -> Result<Self, ArenaError> {
+        let mut document: Value = toml::from_str(toml)?;
+
+        self.strategy_params = match document.pointer_mut("/strategy/params") {
+            Some(params) => params.take(),
+            None => Value::Object(Map::new()),
+        };
+
+        Ok(self)
+    }
+
+    /// Set the strategy parameters from the `[strategy.params]` table of the TOML file at `path`.
+    pub fn with_strategy_params_file(self, path: impl AsRef<Path>) -> Result<Self, ArenaError> {
+        let toml = fs::read_to_string(path)?;
+        self.with_strategy_params_toml(&toml)
+    }
+
+    /// Add a condition ending the run early, reported in [`crate::summary::RunSummary::stopped`].
+    pub fn with_stop_condition(mut self, condition: StopCondition) -> Self {
+        self.stop_conditions.push(condition);
+        self
+    }
+
+    /// Checkpoint the simulation to `path` every `every` steps, overwriting the previous checkpoint.
+    ///
+    /// A simulation that fails can then be continued from its last checkpoint with
+    /// [`crate::arena::Arena::resume`] rather than restarted from zero.
+    ///
+    /// Fails if `every` is zero, as no step would ever be checkpointed.
+    pub fn with_checkpoints(
+        mut self,
+        every: usize,
+        path: impl Into<PathBuf>,
+    ) -> Result<Self, BuildError> {
+        self.checkpoints = Some((every, path.into()));
+        BuildError::from_problems(self.schedule_problems())?;
+        Ok(self)
+    }
+
+    /// Returns the parameters of the configuration as JSON, for the record of a run.
+    ///
+    /// Stop conditions are only described, as custom predicates cannot be serialized.
+    pub fn describe(&self) -> Value {
+        json!({
+            "steps": self.steps,
+            "manager_fee": self.manager_fee,
+            "tick_spacing": self.tick_spacing,
+            "hook_data": self.hook_data,
+            "sqrt_price_x96": self.sqrt_price_x96,
+            "pool_fee": self.pool_fee,
+            "initial_price": self.initial_price,
+            "hooks": self.hooks,
+            "price": self.price,
+            "liquidity": self.liquidity,
+            "seconds_per_step": self.seconds_per_step,
+            "feed_time_unit": self.feed_time_unit,
+            "real_time": self.real_time.map(|period| period.as_secs_f64()),
+            "sub_steps": self.sub_steps,
+            "warmup_steps": self.warmup_steps,
+            "history_retention": self.history_retention,
+            "mempool": self.mempool,
+            "gas_market": self.gas_market.describe(),
+            "panic_policy": format!("{:?}", self.panic_policy),
+            "strategy_params": self.strategy_params,
+            "stop_conditions": self.stop_conditions.iter().map(StopCondition::describe).collect::<Vec<_>>(),
+            "checkpoints": self.checkpoints,
+        })
+    }
+
+    /// Check the parameters of the main pool, see [`PoolConfig::validate`], and of the step schedule.
+    pub fn validate(&self) -> Result<(), BuildError> {
+        let mut problems = self.pool().validate();
+        problems.extend(self.schedule_problems());
+        BuildError::from_problems(problems)
+    }
+
+    /// Returns every reason the steps of a run cannot be simulated as configured.
+    pub(crate) fn schedule_problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if self.sub_steps == 0 {
+            problems.push("sub_steps must be at least one".to_string());
+        }
+
+        if let Some(seconds) = self.seconds_per_step {
+            if !(seconds.is_finite() && seconds > 0.0 && seconds.fract() == 0.0) {
+                problems.push(format!(
+                    "seconds_per_step must be a positive whole number, got {seconds}"
+                ));
+            }
+        }
+
+        match (self.feed_time_unit, self.seconds_per_step) {
+            (Some(unit), _) if !(unit.is_finite() && unit > 0.0) => {
+                problems.push(format!("feed_time_unit must be positive, got {unit}"));
+            }
+            (Some(_), None) => {
+                problems.push("feed_time_unit requires seconds_per_step".to_string());
+            }
+            _ => {}
+        }
+
+        if matches!(self.checkpoints, Some((0, _))) {
+            problems.push("checkpoints must be taken at least every one step".to_string());
+        }
+
+        problems
+    }
+
+    /// Returns the parameters of the main pool.
+    pub fn pool(&self) -> PoolConfig {
+        PoolConfig {
+            manager_fee: self.manager_fee,
+            fee: self.pool_fee,
+            tick_spacing: self.tick_spacing,
+            hooks: self.hooks,
+            hook_data: self.hook_data.clone(),
+            sqrt_price_x96: self.sqrt_price_x96,
+            price: self.price,
+            liquidity: self.liquidity,
+            hook: None,
+            initial_price: self.initial_price,
+        }
+    }
+
+    /// Returns the time a feed advances per tick, in the unit of [`Config::feed_time_unit`]: the chain time of a step
+    /// split evenly across the ticks of the step, or `None` if feed time is not tied to chain time.
+    pub fn feed_dt(&self) -> Option<f64> {
+        let ticks = (self.sub_steps + 1) as f64;
+        Some(self.seconds_per_step? / self.feed_time_unit? / ticks)
+    }
+
+    /// Advance a feed by one tick, of [`Config::feed_dt`] if set.
+    pub fn advance_feed(&self, feed: &mut dyn Feed, rng: &mut StdRng) -> f64 {
+        match self.feed_dt() {
+            Some(dt) => feed.advance(dt, rng),
+            None => feed.step(rng),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_validate() {
+        let pool = PoolConfig::new(
+            Uint::from(3000),
+            Signed::try_from(60).unwrap(),
+            Uint::from(79228162514264337593543950336_u128),
+            U256::from(1),
+        );
+        assert!(pool.validate().is_empty());
+
+        let invalid = PoolConfig::new(
+            Uint::from(DYNAMIC_FEE_FLAG),
+            Signed::try_from(0).unwrap(),
+            MAX_SQRT_PRICE,
+            U256::ZERO,
+        );
+        assert_eq!(invalid.validate().len(), 4);
+
+        assert!(PoolConfig::new(
+            Uint::from(MAX_LP_FEE + 1),
+            Signed::try_from(1).unwrap(),
+            MIN_SQRT_PRICE,
+            U256::from(1),
+        )
+        .validate()[0]
+            .starts_with("fee"));
+    }
+
+    #[test]
+    fn test_sub_steps() {
+        let config = || {
+            Config::new(
+                10,
+                U256::from(5000),
+                Signed::try_from(60).unwrap(),
+                Bytes::new(),
+                Uint::from(79228162514264337593543950336_u128),
+                Uint::from(3000),
+                U256::from(10).pow(U256::from(18)),
+                Address::ZERO,
+            )
+        };
+
+        assert_eq!(config().with_sub_steps(4).unwrap().sub_steps, 4);
+        assert!(config().with_sub_steps(0).is_err());
+        assert!(config().with_checkpoints(0, "checkpoint.json").is_err());
+    }
+
+    #[test]
+    fn test_seconds_per_step() {
+        let config = || {
+            Config::new(
+                10,
+                U256::from(5000),
+                Signed::try_from(60).unwrap(),
+                Bytes::new(),
+                Uint::from(79228162514264337593543950336_u128),
+                Uint::from(3000),
+                U256::from(10).pow(U256::from(18)),
+                Address::ZERO,
+            )
+        };
+
+        for seconds in [0.0, -12.0, 0.4, 12.5, f64::NAN, f64::INFINITY] {
+            assert!(config().with_seconds_per_step(seconds).is_err());
+        }
+
+        // feeds advance by the chain time of a step, split across its two ticks, in days
+        let tied = config()
+            .with_seconds_per_step(43_200.0)
+            .unwrap()
+            .with_feed_time_unit(86_400.0);
+        assert_eq!(tied.feed_dt(), Some(0.25));
+        assert_eq!(config().with_feed_time_unit(86_400.0).feed_dt(), None);
+
+        assert_eq!(
+            config()
+                .with_feed_time_unit(86_400.0)
+                .validate()
+                .unwrap_err()
+                .problems,
+            vec!["feed_time_unit requires seconds_per_step".to_string()]
+        );
+        assert!(tied.with_feed_time_unit(0.0).validate().is_err());
+
+        let mut config = config();
+        config.sub_steps = 0;
+        assert_eq!(
+            config.validate().unwrap_err().problems,
+            vec!["sub_steps must be at least one".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_stop_condition() {
+        let signal = Signal::new(
+            Uint::from(1),
+            Some(3),
+            Signed::ZERO,
+            Uint::from(79228162514264337593543950336_u128),
+            Address::ZERO,
+            PoolKey {
+                currency0: Address::ZERO,
+                currency1: Address::repeat_byte(1),
+                fee: Uint::from(3000),
+                tickSpacing: Signed::try_from(60).unwrap(),
+                hooks: Address::ZERO,
+            },
+            Address::ZERO,
+            1.0,
+            Address::ZERO,
+        );
+        let mark = |value| Mark {
+            value,
+            fees: 0.0,
+            hodl: 0.0,
+        };
+        let marks = vec![vec![mark(10.0), mark(5.0)], vec![mark(10.0), mark(0.0)]];
+        let check =
+            |condition: StopCondition| condition.check(4, Duration::from_secs(1), &signal, &marks);
+
+        assert!(check(StopCondition::MaxSteps(4)).is_some());
+        assert!(check(StopCondition::MaxSteps(5)).is_none());
+        assert!(check(StopCondition::WallClock(Duration::from_secs(2))).is_none());
+        assert!(check(StopCondition::PriceBounds { min: 0.5, max: 2.0 }).is_none());
+        assert!(check(StopCondition::PriceBounds { min: 1.5, max: 2.0 }).is_some());
+        assert_eq!(
+            check(StopCondition::Bankruptcy).as_deref(),
+            Some("strategy 1 went bankrupt")
+        );
+        assert!(check(StopCondition::Custom(Box::new(
+            |signal| signal.step == Some(3)
+        )))
+        .is_some());
+    }
 }
@@ -0,0 +1,219 @@
+use alloy::{
+    primitives::{Address, Bytes, I256},
+    providers::{Provider, WalletProvider},
+    rpc::types::TransactionRequest,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    position::{Holdings, Position, MAX_TICK, MIN_TICK},
+    *,
+};
+use crate::Signal;
+
+/// A state changing operation a strategy asks the [`Engine`] to perform on its behalf.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Provide liquidity over a tick range.
+    AddLiquidity {
+        /// Lower tick of the range.
+        tick_lower: i32,
+
+        /// Upper tick of the range.
+        tick_upper: i32,
+
+        /// Liquidity to add.
+        liquidity: u128,
+    },
+
+    /// Withdraw liquidity from a tick range.
+    RemoveLiquidity {
+        /// Lower tick of the range.
+        tick_lower: i32,
+
+        /// Upper tick of the range.
+        tick_upper: i32,
+
+        /// Liquidity to remove.
+        liquidity: u128,
+    },
+
+    /// Swap an exact amount of one token against the pool.
+    Swap {
+        /// Whether token0 is sold for token1.
+        zero_for_one: bool,
+
+        /// Raw amount of the input token.
+        amount_in: u128,
+    },
+
+    /// Send arbitrary calldata from the strategy's wallet.
+    Custom {
+        /// Address called.
+        to: Address,
+
+        /// Calldata sent.
+        calldata: Bytes,
+    },
+}
+
+/// An [`Action`] executed by the engine, with the step at which it was executed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionRecord {
+    /// Step at which the action was executed, or `None` during initialization.
+    pub step: Option<usize>,
+
+    /// The action.
+    pub action: Action,
+}
+
+impl Action {
+    /// Check that an action can be executed against the pool described by `signal` by a wallet with `holdings`.
+    pub fn validate(&self, signal: &Signal, holdings: &Holdings) -> Result<(), ArenaError> {
+        let invalid = |reason: String| Err(ArenaError::InvalidAction(reason));
+
+        match self {
+            Action::AddLiquidity {
+                tick_lower,
+                tick_upper,
+                liquidity,
+            }
+            | Action::RemoveLiquidity {
+                tick_lower,
+                tick_upper,
+                liquidity,
+            } => {
+                let tick_spacing = signal.pool.tickSpacing.as_i32();
+
+                if tick_lower >= tick_upper {
+                    return invalid(format!("range [{tick_lower}, {tick_upper}) is empty"));
+                }
+                if *tick_lower < MIN_TICK || *tick_upper > MAX_TICK {
+                    return invalid(format!(
+                        "range [{tick_lower}, {tick_upper}) is out of bounds"
+                    ));
+                }
+                if tick_lower % tick_spacing != 0 || tick_upper % tick_spacing != 0 {
+                    return invalid(format!(
+                        "range [{tick_lower}, {tick_upper}) is not aligned to tick spacing {tick_spacing}"
+                    ));
+                }
+                if *liquidity == 0 {
+                    return invalid("liquidity is zero".to_string());
+                }
+
+                if let Action::RemoveLiquidity { .. } = self {
+                    let held = holdings.liquidity(*tick_lower, *tick_upper);
+                    if *liquidity > held {
+                        return invalid(format!(
+                            "cannot remove {liquidity} liquidity from [{tick_lower}, {tick_upper}), only {held} held"
+                        ));
+                    }
+                }
+
+                Ok(())
+            }
+            Action::Swap { amount_in, .. } => {
+                if *amount_in == 0 {
+                    return invalid("swap amount is zero".to_string());
+                }
+
+                Ok(())
+            }
+            Action::Custom { .. } => Ok(()),
+        }
+    }
+}
+
+#[allow(clippy::redundant_closure)]
+impl Engine {
+    /// Validate, execute and record a list of actions in order with the wallet behind `provider`.
+    ///
+    /// Execution stops at the first action that fails validation or execution.
+    pub async fn apply(
+        &self,
+        actions: &[Action],
+        signal: &Signal,
+        hook_data: Bytes,
+        provider: AnvilProvider,
+    ) -> Result<(), ArenaError> {
+        let owner = provider.default_signer_address();
+
+        for action in actions {
+            action.validate(signal, &self.holdings(owner))?;
+
+            self.execute(action, hook_data.clone(), provider.clone())
+                .await?;
+
+            self.ledger
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .entry(owner)
+                .or_default()
+                .actions
+                .push(ActionRecord {
+                    step: signal.step,
+                    action: action.clone(),
+                });
+        }
+
+        Ok(())
+    }
+
+    /// Perform an [`Action`] with the wallet behind `provider`.
+    pub async fn execute(
+        &self,
+        action: &Action,
+        hook_data: Bytes,
+        provider: AnvilProvider,
+    ) -> Result<(), ArenaError> {
+        match action {
+            Action::AddLiquidity {
+                tick_lower,
+                tick_upper,
+                liquidity,
+            } => {
+                self.open_position(
+                    &Position::new(*tick_lower, *tick_upper, *liquidity),
+                    hook_data,
+                    provider,
+                )
+                .await
+            }
+            Action::RemoveLiquidity {
+                tick_lower,
+                tick_upper,
+                liquidity,
+            } => {
+                self.close_position(
+                    &Position::new(*tick_lower, *tick_upper, *liquidity),
+                    hook_data,
+                    provider,
+                )
+                .await
+            }
+            Action::Swap {
+                zero_for_one,
+                amount_in,
+            } => {
+                let amount = I256::try_from(*amount_in)
+                    .map_err(|_| ArenaError::Custom("swap amount overflows int256".to_string()))?;
+
+                self.swap(*zero_for_one, -amount, None, provider).await
+            }
+            Action::Custom { to, calldata } => {
+                let tx = TransactionRequest::default()
+                    .to(*to)
+                    .input(calldata.clone().into())
+                    .nonce(
+                        provider
+                            .get_transaction_count(provider.default_signer_address())
+                            .await?,
+                    );
+
+                submit(tx, &provider).await
+            }
+        }
+    }
+}
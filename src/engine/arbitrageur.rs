@@ -1,16 +1,216 @@
+use std::collections::BTreeMap;
+
 use async_trait::async_trait;
+use rand::{rngs::StdRng, Rng};
+use rand_distr::{Distribution, Exp};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    math::{self, LiquidityProfile, OptimalSwap},
+    *,
+};
+use crate::{
+    config::{DYNAMIC_FEE_FLAG, MAX_LP_FEE},
+    engine::rng::{RngManager, Stream},
+    error::{ArenaError, BuildError},
+    types::{controller::ArenaController::PoolKey, token::ArenaToken},
+    AnvilProvider, Signal,
+};
+
+/// Pool token balances of the account the arbitrageur trades from.
+///
+/// The arbitrageur trades from a wallet of its own, funded by the arena, which no other participant trades from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Inventory {
+    /// Balance of token0.
+    pub token0: U256,
+
+    /// Balance of token1.
+    pub token1: U256,
+}
+
+impl Inventory {
+    /// Read the balances of `owner` in both tokens of a pool.
+    pub async fn fetch(
+        owner: Address,
+        key: &PoolKey,
+        provider: AnvilProvider,
+    ) -> Result<Self, ArenaError> {
+        Ok(Self {
+            token0: pool::balance_of(key.currency0, owner, provider.clone()).await?,
+            token1: pool::balance_of(key.currency1, owner, provider).await?,
+        })
+    }
+}
+
+/// Swap executed by the arbitrageur, measured from the change in its [`Inventory`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArbitrageTrade {
+    /// Step at which the swap was executed.
+    pub step: usize,
+
+    /// Whether token0 was sold for token1.
+    pub zero_for_one: bool,
+
+    /// Change in the arbitrageur's balance of token0.
+    pub amount0: I256,
+
+    /// Change in the arbitrageur's balance of token1.
+    pub amount1: I256,
+}
+
+impl ArbitrageTrade {
+    /// Returns the trade between two readings of the inventory, or `None` if it did not change.
+    pub fn between(before: &Inventory, after: &Inventory, step: usize) -> Option<Self> {
+        if before == after {
+            return None;
+        }
+
+        let delta =
+            |before: U256, after: U256| I256::from_raw(after).wrapping_sub(I256::from_raw(before));
+        let amount0 = delta(before.token0, after.token0);
+
+        Some(Self {
+            step,
+            zero_for_one: amount0.is_negative(),
+            amount0,
+            amount1: delta(before.token1, after.token1),
+        })
+    }
+}
+
+/// Fill of the arbitrageur, as recorded in the [`ArbitrageLedger`] of the engine of its pool.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ArbitrageFill {
+    /// Step at which the swap was executed.
+    pub step: usize,
+
+    /// Whether token0 was sold for token1.
+    pub zero_for_one: bool,
+
+    /// Change in the arbitrageur's balance of token0, in whole tokens.
+    pub amount0: f64,
+
+    /// Change in the arbitrageur's balance of token1, in whole tokens.
+    pub amount1: f64,
+
+    /// Feed value when the swap was executed, in whole units of token1 per whole unit of token0.
+    pub mark_price: f64,
+
+    /// Gas used by the transactions of the arbitrageur.
+    pub gas_used: u128,
+
+    /// Gas price when the swap was executed, in wei.
+    pub gas_price: u128,
+}
 
-use super::*;
-use crate::{types::controller::ArenaController, AnvilProvider, Signal};
+impl ArbitrageFill {
+    /// Returns the fill of `trade`, for a pool whose tokens have `decimals`.
+    pub fn new(
+        trade: &ArbitrageTrade,
+        decimals: (u8, u8),
+        mark_price: f64,
+        gas_used: u128,
+        gas_price: u128,
+    ) -> Self {
+        let whole = |amount: I256, decimals: u8| {
+            let abs = f64::from(amount.unsigned_abs()) / 10f64.powi(decimals as i32);
+            if amount.is_negative() {
+                -abs
+            } else {
+                abs
+            }
+        };
+
+        Self {
+            step: trade.step,
+            zero_for_one: trade.zero_for_one,
+            amount0: whole(trade.amount0, decimals.0),
+            amount1: whole(trade.amount1, decimals.1),
+            mark_price,
+            gas_used,
+            gas_price,
+        }
+    }
+
+    /// Returns the execution price of the swap, in whole units of token1 per whole unit of token0.
+    pub fn price(&self) -> f64 {
+        -self.amount1 / self.amount0
+    }
+
+    /// Returns the profit of the swap at the feed value it was executed at, in whole units of token1, excluding gas.
+    pub fn profit(&self) -> f64 {
+        self.profit_at(self.mark_price)
+    }
+
+    /// Returns the profit of the swap with token0 marked at `price` whole units of token1, excluding gas.
+    pub fn profit_at(&self, price: f64) -> f64 {
+        self.amount0 * price + self.amount1
+    }
+
+    /// Returns the fee paid for the gas of the swap, in wei.
+    pub fn gas_fee(&self) -> u128 {
+        self.gas_used * self.gas_price
+    }
+}
+
+/// Every fill of the arbitrageur on a pool, along with the feed value at the end of every step to mark them at later.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArbitrageLedger {
+    /// Fills, oldest first.
+    pub fills: Vec<ArbitrageFill>,
+
+    /// Feed value at the end of every step, keyed by step.
+    pub marks: BTreeMap<usize, f64>,
+}
+
+impl ArbitrageLedger {
+    /// Returns the total profit of the fills at the feed value they were executed at, excluding gas.
+    pub fn realized_profit(&self) -> f64 {
+        self.fills.iter().map(ArbitrageFill::profit).sum()
+    }
+
+    /// Returns the profit of `fill` marked at the feed value at the end of the step `steps` after its own, or `None`
+    /// if that step was not simulated.
+    pub fn markout(&self, fill: &ArbitrageFill, steps: usize) -> Option<f64> {
+        self.marks
+            .get(&(fill.step + steps))
+            .map(|&price| fill.profit_at(price))
+    }
+
+    /// Returns the total markout of the fills `steps` steps after each, over the fills for which that step was
+    /// simulated.
+    pub fn total_markout(&self, steps: usize) -> f64 {
+        self.fills
+            .iter()
+            .filter_map(|fill| self.markout(fill, steps))
+            .sum()
+    }
+}
 
 /// Generic trait allowing user defined arbitrage strategies.
 #[async_trait]
-pub trait Arbitrageur {
-    /// Initialize arbitrageur agent.
-    async fn init(&mut self, signal: &Signal, provider: AnvilProvider);
+pub trait Arbitrageur: Send {
+    /// Initialize arbitrageur agent on the pool `engine` acts on.
+    async fn init(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+    ) -> Result<(), ArenaError>;
 
-    /// Perform an arbitrage based on a [`Signal`].
-    async fn arbitrage(&mut self, signal: &Signal, provider: AnvilProvider);
+    /// Replace the generator of any random draws, called by the arena before [`Arbitrageur::init`] with the
+    /// [`Stream::Arbitrageur`] stream of the run, so draws follow the seed and Monte Carlo path of the arena. Does
+    /// nothing by default.
+    fn reseed(&mut self, _rng: StdRng) {}
+
+    /// Perform an arbitrage based on a [`Signal`], swapping through `engine`, which acts on the pool of the signal.
+    async fn arbitrage(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+    ) -> Result<(), ArenaError>;
 }
 
 /// Default implementation of an [`Arbitrageur`] that uses the closed-form optimal swap amount to determine the optimal arbitrage.
@@ -22,26 +222,810 @@ pub struct FixedArbitrageur {
 
 #[async_trait]
 impl Arbitrageur for FixedArbitrageur {
-    async fn init(&mut self, _signal: &Signal, _provider: AnvilProvider) {}
+    async fn init(
+        &mut self,
+        _signal: &Signal,
+        _provider: AnvilProvider,
+        _engine: Engine,
+    ) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    async fn arbitrage(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+    ) -> Result<(), ArenaError> {
+        if signal.divergence == 0.0 {
+            return Ok(());
+        }
+
+        // sell token0 while the pool prices it above the liquid exchange, stopping at the liquid exchange price
+        let zero_for_one = signal.divergence > 0.0;
+        let limit = pool::sqrt_price_x96(f64::from(signal.lex_price) / 1e18, (18, 18));
+
+        engine.swap(zero_for_one, self.depth, limit, provider).await
+    }
+}
+
+/// Reference [`Arbitrageur`] trading the pool to the edge of its no-arbitrage band around the `LiquidExchange` price.
+///
+/// Every step, if the pool price differs from the liquid exchange price by more than the pool fee, it swaps the
+/// profit-maximizing amount through the pool: the amount moving the pool price to where the marginal trade, net of the
+/// fee, no longer profits. The amount is sized across the initialized ticks around the current one, see
+/// [`math::optimal_swap`].
+///
+/// The swap is only sent if its expected profit covers its gas cost plus a minimum edge, which together with the pool
+/// fee widens the band the pool price moves in without being arbitraged.
+///
+/// The arbitrageur trades the inventory of its wallet, which can be brought to a starting inventory by minting or
+/// returning tokens, so that its swaps are limited by its capital and by a maximum position rather than infinitely
+/// funded.
+#[derive(Debug, Clone)]
+pub struct DefaultArbitrageur {
+    /// Gas an arbitrage swap is expected to use, charged at [`Signal::gas_price`].
+    pub swap_gas: u64,
+
+    /// Value of one ether in whole token1, to price gas in pools without the native currency. Gas is free in such
+    /// pools if unset, so the gas cost never holds back a swap there.
+    pub ether_price: Option<f64>,
+
+    /// Minimum profit, net of gas, as a fraction of the notional of the swap in token1.
+    pub min_edge: f64,
+
+    /// Balances of token0 and token1, in whole tokens, the wallet is brought to at initialization. The wallet keeps its
+    /// funding if unset.
+    pub inventory: Option<(f64, f64)>,
+
+    /// Largest change of the token0 balance from the starting inventory, in whole token0, either way.
+    pub max_position: Option<f64>,
+
+    /// Number of calls to [`Arbitrageur::arbitrage`] for a pool, i.e. feed updates of the pool, between resets of the
+    /// inventory held for it to the composition of its starting inventory. Resets trade at the liquid exchange price, preserving the value of the
+    /// inventory.
+    pub reset_every: Option<usize>,
+
+    /// Largest move of the pool price past the price a swap was sized to end at, as a fraction. It bounds the swap on
+    /// chain through its sqrt price limit, so a pool that moved before the swap executes only fills the part of the
+    /// swap within the limit.
+    pub max_slippage: Option<f64>,
+
+    /// Largest input of a single swap, as a fraction of the pool's reserve of the input token. Larger trades are split
+    /// across feed updates, as the remaining discrepancy is arbitraged at the next one.
+    pub participation_cap: Option<f64>,
+
+    /// Starting inventory in every pool traded, by pool id.
+    starts: HashMap<B256, Inventory>,
+
+    /// Number of calls to [`Arbitrageur::arbitrage`] so far, by pool id.
+    calls: HashMap<B256, usize>,
+}
+
+impl Default for DefaultArbitrageur {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DefaultArbitrageur {
+    /// Number of tick bitmap words read on either side of the current tick, see [`pool::liquidity_profile`].
+    pub const PROFILE_WORDS: i16 = 2;
+
+    /// Gas a swap through the swap router uses, roughly.
+    pub const DEFAULT_SWAP_GAS: u64 = 150_000;
+
+    /// Public constructor function for a new [`DefaultArbitrageur`] without a minimum edge.
+    pub fn new() -> Self {
+        Self {
+            swap_gas: Self::DEFAULT_SWAP_GAS,
+            ether_price: None,
+            min_edge: 0.0,
+            inventory: None,
+            max_position: None,
+            reset_every: None,
+            max_slippage: None,
+            participation_cap: None,
+            starts: HashMap::new(),
+            calls: HashMap::new(),
+        }
+    }
+
+    /// Set the gas an arbitrage swap is expected to use.
+    pub fn with_swap_gas(mut self, swap_gas: u64) -> Self {
+        self.swap_gas = swap_gas;
+        self
+    }
+
+    /// Set the value of one ether in whole token1, see [`DefaultArbitrageur::ether_price`].
+    pub fn with_ether_price(mut self, ether_price: f64) -> Self {
+        self.ether_price = Some(ether_price);
+        self
+    }
+
+    /// Set the minimum profit net of gas, as a fraction of the notional of the swap.
+    pub fn with_min_edge(mut self, min_edge: f64) -> Self {
+        self.min_edge = min_edge;
+        self
+    }
+
+    /// Start from `token0` and `token1`, in whole tokens, rather than from the funding of the wallet.
+    pub fn with_inventory(mut self, token0: f64, token1: f64) -> Self {
+        self.inventory = Some((token0, token1));
+        self
+    }
+
+    /// Limit the change of the token0 balance from the starting inventory to `max_position` whole token0.
+    pub fn with_max_position(mut self, max_position: f64) -> Self {
+        self.max_position = Some(max_position);
+        self
+    }
+
+    /// Reset the inventory to the composition of the starting inventory every `every` feed updates.
+    ///
+    /// Fails if `every` is zero, as the inventory would never be reset.
+    pub fn with_inventory_reset(mut self, every: usize) -> Result<Self, BuildError> {
+        if every == 0 {
+            return Err(BuildError {
+                problems: vec![
+                    "inventory resets must be at least one feed update apart".to_string()
+                ],
+            });
+        }
+
+        self.reset_every = Some(every);
+        Ok(self)
+    }
+
+    /// Stop swaps once the pool price moves more than `max_slippage`, as a fraction, past the price they were sized to
+    /// end at.
+    pub fn with_max_slippage(mut self, max_slippage: f64) -> Self {
+        self.max_slippage = Some(max_slippage);
+        self
+    }
+
+    /// Limit the input of a single swap to `participation_cap` of the pool's reserve of the input token.
+    pub fn with_participation_cap(mut self, participation_cap: f64) -> Self {
+        self.participation_cap = Some(participation_cap);
+        self
+    }
+
+    /// Returns the largest input, in raw units, of a single swap against the pool in the state of `signal`, given the
+    /// participation cap.
+    pub fn participation_limit(&self, signal: &Signal, zero_for_one: bool) -> f64 {
+        let Some(cap) = self.participation_cap else {
+            return f64::INFINITY;
+        };
+
+        let reserve = if zero_for_one {
+            signal.pool_state.balance0
+        } else {
+            signal.pool_state.balance1
+        };
+        f64::from(reserve) * cap
+    }
+
+    /// Returns the sqrt price limit of `swap` given the maximum slippage, or `None` to let it fill entirely.
+    ///
+    /// Selling token0 lowers the price, so the limit is below the price the swap was sized to end at, and above it
+    /// when buying token0. Limits outside the range of the pool are clamped to [`pool::price_limit`].
+    pub fn price_limit(&self, swap: &OptimalSwap) -> Option<Uint<160, 3>> {
+        let slippage = self.max_slippage?.clamp(0.0, 1.0);
+        let sqrt_price = if swap.zero_for_one {
+            swap.sqrt_price_after * (1.0 - slippage).sqrt()
+        } else {
+            swap.sqrt_price_after / (1.0 - slippage).sqrt()
+        };
 
-    async fn arbitrage(&mut self, signal: &Signal, provider: AnvilProvider) {
-        let controller = ArenaController::new(signal.controller, provider.clone());
+        let bound = pool::price_limit(swap.zero_for_one);
+        let limit = Uint::<160, 3>::try_from(sqrt_price * 2f64.powi(96)).unwrap_or(bound);
+        Some(if swap.zero_for_one {
+            limit.max(bound)
+        } else {
+            limit.min(bound)
+        })
+    }
+
+    /// Returns the largest input and output amounts, in raw units, of a swap from `balances` that keeps within the
+    /// capital of the arbitrageur and its maximum position from `start`.
+    pub fn limits(
+        &self,
+        start: &Inventory,
+        balances: &Inventory,
+        zero_for_one: bool,
+        decimals: (u8, u8),
+    ) -> (f64, f64) {
+        let max_position = self
+            .max_position
+            .map_or(f64::INFINITY, |max| max * 10f64.powi(decimals.0 as i32));
+        let position = f64::from(balances.token0) - f64::from(start.token0);
+
+        if zero_for_one {
+            (
+                f64::from(balances.token0).min(position + max_position),
+                f64::INFINITY,
+            )
+        } else {
+            (f64::from(balances.token1), max_position - position)
+        }
+    }
+
+    /// Count a call to [`Arbitrageur::arbitrage`] for the pool of id `pool`, returning whether it is due an inventory
+    /// reset.
+    pub fn count_call(&mut self, pool: B256) -> bool {
+        let calls = self.calls.entry(pool).or_default();
+        *calls += 1;
+
+        self.reset_every
+            .is_some_and(|every| calls.is_multiple_of(every))
+    }
 
-        controller
-            .equalizePrice(self.depth)
-            .nonce(
-                provider
-                    .clone()
-                    .get_transaction_count(provider.clone().default_signer_address())
+    /// Bring the balances of the wallet behind `provider` to `inventory`, minting it what it lacks of either token and
+    /// sending what it holds in excess back to the controller of the pool in the state of `signal`.
+    async fn set_inventory(
+        signal: &Signal,
+        inventory: Inventory,
+        provider: AnvilProvider,
+    ) -> Result<(), ArenaError> {
+        let owner = provider.default_signer_address();
+        let balances = Inventory::fetch(owner, &signal.pool, provider.clone()).await?;
+
+        for (currency, balance, target) in [
+            (signal.pool.currency0, balances.token0, inventory.token0),
+            (signal.pool.currency1, balances.token1, inventory.token1),
+        ] {
+            let token = ArenaToken::new(currency, provider.clone());
+            let nonce = provider.get_transaction_count(owner).await?;
+            if target > balance {
+                token
+                    .mint(owner, target - balance)
+                    .nonce(nonce)
+                    .send()
+                    .await
+                    .map_err(ArenaError::ContractError)?
+                    .watch()
+                    .await
+                    .map_err(ArenaError::PendingTransactionError)?;
+            } else if target < balance {
+                token
+                    .transfer(signal.controller, balance - target)
+                    .nonce(nonce)
+                    .send()
                     .await
-                    .unwrap(),
+                    .map_err(ArenaError::ContractError)?
+                    .watch()
+                    .await
+                    .map_err(ArenaError::PendingTransactionError)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rebalance the inventory to the composition of `start` by selling the excess of either token on the liquid
+    /// exchange, at its price, preserving the value of the inventory.
+    async fn reset_inventory(
+        signal: &Signal,
+        start: &Inventory,
+        engine: &Engine,
+        provider: AnvilProvider,
+    ) -> Result<(), ArenaError> {
+        let balances = Inventory::fetch(
+            provider.default_signer_address(),
+            &signal.pool,
+            provider.clone(),
+        )
+        .await?;
+        let lex_price = f64::from(signal.lex_price) / 1e18;
+
+        let value = |inventory: &Inventory| {
+            f64::from(inventory.token0) * lex_price + f64::from(inventory.token1)
+        };
+        let start_value = value(start);
+        if start_value <= 0.0 || lex_price <= 0.0 {
+            return Ok(());
+        }
+        let target0 = f64::from(start.token0) * value(&balances) / start_value;
+
+        let excess0 = f64::from(balances.token0) - target0;
+        let (zero_for_one, amount_in) = if excess0 >= 0.0 {
+            (true, excess0)
+        } else {
+            (
+                false,
+                (-excess0 * lex_price).min(f64::from(balances.token1)),
+            )
+        };
+
+        engine
+            .swap_on_exchange(
+                zero_for_one,
+                U256::try_from(amount_in.floor()).unwrap_or_default(),
+                provider,
             )
-            .send()
             .await
-            .unwrap()
-            .watch()
+    }
+
+    /// Returns the starting inventory of the arbitrageur in the pool of `signal`, reading the balances of its wallet if
+    /// it did not trade the pool before.
+    async fn start(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+    ) -> Result<Inventory, ArenaError> {
+        if let Some(start) = self.starts.get(&pool::pool_id(&signal.pool)) {
+            return Ok(*start);
+        }
+
+        let start = match self.inventory {
+            Some((token0, token1)) => {
+                let raw = |amount: f64, decimals: u8| {
+                    U256::try_from((amount * 10f64.powi(decimals as i32)).floor()).map_err(|e| {
+                        ArenaError::Custom(format!("invalid arbitrageur inventory: {e}"))
+                    })
+                };
+                let start = Inventory {
+                    token0: raw(token0, signal.decimals.0)?,
+                    token1: raw(token1, signal.decimals.1)?,
+                };
+                Self::set_inventory(signal, start, provider).await?;
+                start
+            }
+            None => {
+                Inventory::fetch(provider.default_signer_address(), &signal.pool, provider).await?
+            }
+        };
+
+        self.starts.insert(pool::pool_id(&signal.pool), start);
+        Ok(start)
+    }
+
+    /// Returns whether the pool in the state of `signal` is within its no-arbitrage band, judged from
+    /// [`Signal::divergence`]: the pool price net of the fee is neither above nor below the liquid exchange price.
+    pub fn within_band(signal: &Signal) -> bool {
+        let fee = pool_fee(&signal.pool);
+        let ratio = 1.0 + signal.divergence;
+
+        ratio * (1.0 - fee) <= 1.0 && ratio >= 1.0 - fee
+    }
+
+    /// Returns the profit-maximizing swap against the pool in the state of `signal`, whose liquidity is `profile`, or
+    /// `None` if the pool is within its no-arbitrage band.
+    pub fn optimal_swap(signal: &Signal, profile: &LiquidityProfile) -> Option<OptimalSwap> {
+        // the liquid exchange quotes raw units of token1 per raw unit of token0, scaled by 1e18
+        let lex_price = f64::from(signal.lex_price) / 1e18;
+
+        math::optimal_swap(profile, lex_price, pool_fee(&signal.pool))
+            .filter(|swap| swap.amount_in >= 1.0)
+    }
+
+    /// Returns the profit of `swap` at the liquid exchange price and its notional, both in whole token1.
+    pub fn expected_profit(signal: &Signal, swap: &OptimalSwap) -> (f64, f64) {
+        let lex_price = f64::from(signal.lex_price) / 1e18;
+        let scale = 10f64.powi(signal.decimals.1 as i32);
+
+        let (profit, notional) = if swap.zero_for_one {
+            (
+                swap.amount_out - swap.amount_in * lex_price,
+                swap.amount_out,
+            )
+        } else {
+            (swap.amount_out * lex_price - swap.amount_in, swap.amount_in)
+        };
+
+        (profit / scale, notional / scale)
+    }
+
+    /// Returns the cost of the gas of a swap at the gas price of `signal`, in whole token1.
+    ///
+    /// Currencies of a pool key are sorted, so the native currency can only be currency0, in which case ether is priced
+    /// at the feed value. Other pools price it at [`DefaultArbitrageur::ether_price`], without which gas is free and
+    /// only the pool fee and the minimum edge hold back a swap.
+    pub fn gas_cost(&self, signal: &Signal) -> f64 {
+        let ether = signal.gas_cost(self.swap_gas) as f64 / 1e18;
+
+        if signal.pool.currency0.is_zero() {
+            ether * signal.current_value
+        } else {
+            ether * self.ether_price.unwrap_or_default()
+        }
+    }
+
+    /// Returns whether `swap` profits by at least the minimum edge once gas is paid.
+    pub fn is_worth(&self, signal: &Signal, swap: &OptimalSwap) -> bool {
+        let (profit, notional) = Self::expected_profit(signal, swap);
+
+        profit - self.gas_cost(signal) >= self.min_edge * notional
+    }
+}
+
+#[async_trait]
+impl Arbitrageur for DefaultArbitrageur {
+    async fn init(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        _engine: Engine,
+    ) -> Result<(), ArenaError> {
+        self.start(signal, provider).await?;
+        Ok(())
+    }
+
+    async fn arbitrage(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+    ) -> Result<(), ArenaError> {
+        let start = self.start(signal, provider.clone()).await?;
+
+        if self.count_call(pool::pool_id(&signal.pool)) {
+            Self::reset_inventory(signal, &start, &engine, provider.clone()).await?;
+        }
+
+        // the engine already compared the pool with the liquid exchange, so skip reading liquidity when it is in band
+        if Self::within_band(signal) {
+            return Ok(());
+        }
+
+        let profile = pool::liquidity_profile(
+            signal.manager,
+            &signal.pool,
+            U256::from(signal.sqrt_price_x96),
+            signal.tick.as_i32(),
+            Self::PROFILE_WORDS,
+            provider.clone(),
+        )
+        .await?;
+
+        let Some(swap) = Self::optimal_swap(signal, &profile) else {
+            return Ok(());
+        };
+        let balances = Inventory::fetch(
+            provider.default_signer_address(),
+            &signal.pool,
+            provider.clone(),
+        )
+        .await?;
+        let (max_in, max_out) = self.limits(&start, &balances, swap.zero_for_one, signal.decimals);
+        let max_in = max_in.min(self.participation_limit(signal, swap.zero_for_one));
+        let Some(swap) = math::cap_swap(&profile, swap, pool_fee(&signal.pool), max_in, max_out)
+        else {
+            return Ok(());
+        };
+
+        if !self.is_worth(signal, &swap) {
+            return Ok(());
+        }
+
+        let amount_in = U256::try_from(swap.amount_in.floor())
+            .map_err(|e| ArenaError::Custom(format!("invalid arbitrage amount: {e}")))?;
+        let amount_specified = I256::try_from(amount_in)
+            .map_err(|e| ArenaError::Custom(format!("invalid arbitrage amount: {e}")))?;
+
+        // a negative amount is an exact input, swapped in the direction and size decided above, the price limit turning
+        // the maximum slippage into a minimum output the pool manager enforces
+        engine
+            .swap(
+                swap.zero_for_one,
+                -amount_specified,
+                self.price_limit(&swap),
+                provider,
+            )
             .await
-            .unwrap();
+    }
+}
+
+/// Returns the fee of the pool of `key` as a fraction, or zero for a dynamic fee, which the pool does not expose.
+pub(crate) fn pool_fee(key: &PoolKey) -> f64 {
+    let fee = key.fee.to::<u32>();
+
+    if fee & DYNAMIC_FEE_FLAG != 0 {
+        return 0.0;
+    }
+    fee as f64 / MAX_LP_FEE as f64
+}
+
+/// Wrapper around an [`Arbitrageur`] trading every pool against its liquid exchange, which then equalizes the price of
+/// the pool with every other pool of the same pair it traded on, closing the discrepancies between fee tiers.
+///
+/// Cross-pool trades are only routed between pools held by the same `PoolManager` whose keys share both currencies,
+/// as is the case of the additional pools of an arena, see [`crate::arena::ArenaBuilder::with_pool`]. They are sized by
+/// [`math::cross_pool_swap`] and executed through [`Engine::swap`].
+pub struct CrossPoolArbitrageur<A = DefaultArbitrageur> {
+    /// The wrapped arbitrageur, trading against the liquid exchanges.
+    pub inner: A,
+
+    /// Minimum profit of a cross-pool trade, in whole token1, e.g. to cover the gas of its two swaps.
+    pub min_profit: f64,
+
+    /// Pools traded on so far, with their `PoolManager` and the engine acting on them.
+    pools: Vec<(Address, PoolKey, Engine)>,
+}
+
+impl<A> CrossPoolArbitrageur<A> {
+    /// Public constructor function for a new [`CrossPoolArbitrageur`] wrapping `inner`, trading across pools at any
+    /// profit.
+    pub fn new(inner: A) -> Self {
+        Self {
+            inner,
+            min_profit: 0.0,
+            pools: Vec::new(),
+        }
+    }
+
+    /// Set the minimum profit of a cross-pool trade, in whole token1.
+    pub fn with_min_profit(mut self, min_profit: f64) -> Self {
+        self.min_profit = min_profit;
+        self
+    }
+
+    /// Remember the pool of `signal`, acted on through `engine`, to equalize the others with.
+    fn register(&mut self, signal: &Signal, engine: &Engine) {
+        let id = pool::pool_id(&signal.pool);
+        if !self
+            .pools
+            .iter()
+            .any(|(manager, key, _)| *manager == signal.manager && pool::pool_id(key) == id)
+        {
+            self.pools
+                .push((signal.manager, signal.pool.clone(), engine.clone()));
+        }
+    }
+
+    /// Returns the pools known so far that a cross-pool trade with the pool of `signal` can be routed through: other
+    /// pools of the same `PoolManager` trading the same currencies.
+    fn counterparts(&self, signal: &Signal) -> Vec<(PoolKey, Engine)> {
+        let id = pool::pool_id(&signal.pool);
+
+        self.pools
+            .iter()
+            .filter(|(manager, key, _)| {
+                *manager == signal.manager
+                    && key.currency0 == signal.pool.currency0
+                    && key.currency1 == signal.pool.currency1
+                    && pool::pool_id(key) != id
+            })
+            .map(|(_, key, engine)| (key.clone(), engine.clone()))
+            .collect()
+    }
+
+    /// Read the liquidity around the current price of the pool of `key` held by `manager`.
+    async fn profile(
+        manager: Address,
+        key: &PoolKey,
+        provider: AnvilProvider,
+    ) -> Result<LiquidityProfile, ArenaError> {
+        let (sqrt_price_x96, tick) = pool::slot0(manager, key, provider.clone()).await?;
+
+        pool::liquidity_profile(
+            manager,
+            key,
+            U256::from(sqrt_price_x96),
+            tick.as_i32(),
+            DefaultArbitrageur::PROFILE_WORDS,
+            provider,
+        )
+        .await
+    }
+
+    /// Equalize the price of the pool of `signal`, acted on through `engine`, with the pool of `other_key`, acted on
+    /// through `other`, if it profits by at least `min_profit` whole token1.
+    async fn equalize(
+        min_profit: f64,
+        signal: &Signal,
+        engine: &Engine,
+        (other_key, other): (PoolKey, Engine),
+        provider: AnvilProvider,
+    ) -> Result<(), ArenaError> {
+        let profile = Self::profile(signal.manager, &signal.pool, provider.clone()).await?;
+        let other_profile = Self::profile(signal.manager, &other_key, provider.clone()).await?;
+
+        let Some(swaps) = math::cross_pool_swap(
+            &profile,
+            pool_fee(&signal.pool),
+            &other_profile,
+            pool_fee(&other_key),
+        ) else {
+            return Ok(());
+        };
+
+        let (sell, buy) = if swaps.0.zero_for_one {
+            swaps
+        } else {
+            (swaps.1, swaps.0)
+        };
+        let profit = (sell.amount_out - buy.amount_in) / 10f64.powi(signal.decimals.1 as i32);
+        if profit < min_profit {
+            return Ok(());
+        }
+
+        for (engine, swap) in [(engine, swaps.0), (&other, swaps.1)] {
+            let amount_in = U256::try_from(swap.amount_in.floor())
+                .map_err(|e| ArenaError::Custom(format!("invalid arbitrage amount: {e}")))?;
+            let amount_specified = I256::try_from(amount_in)
+                .map_err(|e| ArenaError::Custom(format!("invalid arbitrage amount: {e}")))?;
+
+            engine
+                .swap(swap.zero_for_one, -amount_specified, None, provider.clone())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<A: Arbitrageur> Arbitrageur for CrossPoolArbitrageur<A> {
+    async fn init(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+    ) -> Result<(), ArenaError> {
+        self.register(signal, &engine);
+        self.inner.init(signal, provider, engine).await
+    }
+
+    fn reseed(&mut self, rng: StdRng) {
+        self.inner.reseed(rng);
+    }
+
+    async fn arbitrage(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+    ) -> Result<(), ArenaError> {
+        self.inner
+            .arbitrage(signal, provider.clone(), engine.clone())
+            .await?;
+        self.register(signal, &engine);
+
+        for other in self.counterparts(signal) {
+            Self::equalize(self.min_profit, signal, &engine, other, provider.clone()).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Process deciding when an [`Intermittent`] arbitrageur acts, counted in calls to [`Arbitrageur::arbitrage`], i.e.
+/// feed updates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arrival {
+    /// Act on every feed update independently with the given probability.
+    Bernoulli(f64),
+
+    /// Arrive as a Poisson process with the given rate per feed update, acting on a feed update if at least one arrival
+    /// fell since the previous one.
+    Poisson(f64),
+
+    /// Act on every `n`-th feed update, modelling a fixed reaction latency.
+    Periodic(usize),
+}
+
+/// Wrapper around an [`Arbitrageur`] that only acts when an [`Arrival`] process arrives, to study how LP returns
+/// depend on the responsiveness of arbitrageurs rather than assuming every feed update is arbitraged.
+///
+/// The wrapped arbitrageur is always initialized.
+pub struct Intermittent<A> {
+    /// The wrapped arbitrageur.
+    pub inner: A,
+
+    /// Process deciding when the arbitrageur acts.
+    pub arrival: Arrival,
+
+    rng: StdRng,
+
+    /// Number of feed updates so far.
+    clock: usize,
+
+    /// Time of the next arrival of a Poisson process.
+    next_arrival: f64,
+}
+
+impl Arrival {
+    /// Returns an error if the parameter of the process is out of range: a probability outside `[0, 1]`, a negative
+    /// or non-finite rate, or a period of zero.
+    pub fn validate(&self) -> Result<(), ArenaError> {
+        match *self {
+            Arrival::Bernoulli(p) if !(0.0..=1.0).contains(&p) => Err(ArenaError::Custom(format!(
+                "arrival probability must be within [0, 1], got {p}"
+            ))),
+            Arrival::Poisson(rate) if !rate.is_finite() || rate < 0.0 => Err(ArenaError::Custom(
+                format!("arrival rate must be finite and non-negative, got {rate}"),
+            )),
+            Arrival::Periodic(0) => Err(ArenaError::Custom(
+                "arrival period must be at least one".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<A> Intermittent<A> {
+    /// Public constructor function for a new [`Intermittent`] arbitrageur, returning an error if `arrival` is invalid.
+    ///
+    /// Arrivals are drawn from the [`Stream::Arbitrageur`] stream of the arena running it, see
+    /// [`Arbitrageur::reseed`], or of an [`RngManager`] seeded with 0 until then.
+    pub fn new(inner: A, arrival: Arrival) -> Result<Self, ArenaError> {
+        arrival.validate()?;
+
+        let mut intermittent = Self {
+            inner,
+            arrival,
+            rng: RngManager::new(0).rng(0, Stream::Arbitrageur),
+            clock: 0,
+            next_arrival: 0.0,
+        };
+        intermittent.schedule();
+
+        Ok(intermittent)
+    }
+
+    /// Draw the next arrival of a Poisson process from the current feed update.
+    fn schedule(&mut self) {
+        if let Arrival::Poisson(rate) = self.arrival {
+            self.next_arrival = self.clock as f64 + Self::waiting_time(rate, &mut self.rng);
+        }
+    }
+
+    /// Advance the process by one feed update, returning whether the arbitrageur acts on it.
+    pub fn arrives(&mut self) -> bool {
+        self.clock += 1;
+
+        match self.arrival {
+            Arrival::Bernoulli(p) => self.rng.gen_bool(p),
+            Arrival::Poisson(rate) => {
+                let now = self.clock as f64;
+                if self.next_arrival > now {
+                    return false;
+                }
+
+                // arrivals in between feed updates collapse into one
+                while self.next_arrival <= now {
+                    self.next_arrival += Self::waiting_time(rate, &mut self.rng);
+                }
+                true
+            }
+            Arrival::Periodic(n) => self.clock.is_multiple_of(n),
+        }
+    }
+
+    /// Draws the time until the next arrival of a Poisson process, infinite if the rate is not positive.
+    fn waiting_time(rate: f64, rng: &mut StdRng) -> f64 {
+        Exp::new(rate).map_or(f64::INFINITY, |exp| exp.sample(rng))
+    }
+}
+
+#[async_trait]
+impl<A: Arbitrageur> Arbitrageur for Intermittent<A> {
+    async fn init(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+    ) -> Result<(), ArenaError> {
+        self.inner.init(signal, provider, engine).await
+    }
+
+    fn reseed(&mut self, rng: StdRng) {
+        self.rng = rng;
+        self.schedule();
+    }
+
+    async fn arbitrage(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+    ) -> Result<(), ArenaError> {
+        if !self.arrives() {
+            return Ok(());
+        }
+
+        self.inner.arbitrage(signal, provider, engine).await
     }
 }
 
@@ -50,6 +1034,363 @@ pub struct EmptyArbitrageur;
 
 #[async_trait]
 impl Arbitrageur for EmptyArbitrageur {
-    async fn init(&mut self, _signal: &Signal, _provider: AnvilProvider) {}
-    async fn arbitrage(&mut self, _signal: &Signal, _provider: AnvilProvider) {}
+    async fn init(
+        &mut self,
+        _signal: &Signal,
+        _provider: AnvilProvider,
+        _engine: Engine,
+    ) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    async fn arbitrage(
+        &mut self,
+        _signal: &Signal,
+        _provider: AnvilProvider,
+        _engine: Engine,
+    ) -> Result<(), ArenaError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arbitrage_trade() {
+        let before = Inventory {
+            token0: U256::from(100),
+            token1: U256::from(100),
+        };
+        let after = Inventory {
+            token0: U256::from(90),
+            token1: U256::from(111),
+        };
+
+        assert!(ArbitrageTrade::between(&before, &before, 0).is_none());
+
+        let trade = ArbitrageTrade::between(&before, &after, 3).unwrap();
+        assert!(trade.zero_for_one);
+        assert_eq!(trade.amount0, I256::try_from(-10).unwrap());
+        assert_eq!(trade.amount1, I256::try_from(11).unwrap());
+    }
+
+    #[test]
+    fn test_optimal_swap() {
+        let mut signal = Signal::new(
+            U256::from(10).pow(U256::from(18)),
+            None,
+            Signed::ZERO,
+            Uint::from(79228162514264337593543950336_u128),
+            Address::ZERO,
+            PoolKey {
+                currency0: Address::ZERO,
+                currency1: Address::repeat_byte(1),
+                fee: Uint::from(3000),
+                tickSpacing: Signed::try_from(60).unwrap(),
+                hooks: Address::ZERO,
+            },
+            Address::ZERO,
+            1.0,
+            Address::ZERO,
+        );
+        let profile = LiquidityProfile {
+            sqrt_price: 1.0,
+            tick: 0,
+            liquidity: 1_000_000,
+            ticks: Vec::new(),
+        };
+
+        // within the fee band, on either side
+        assert!(DefaultArbitrageur::within_band(&signal));
+        signal.divergence = 0.002;
+        assert!(DefaultArbitrageur::within_band(&signal));
+        signal.divergence = -0.002;
+        assert!(DefaultArbitrageur::within_band(&signal));
+        assert!(DefaultArbitrageur::optimal_swap(&signal, &profile).is_none());
+
+        // pool at 1, liquid exchange at 1.21: buy token0 until the pool price reaches 1.21 * 0.997
+        signal.lex_price = U256::from(121) * U256::from(10).pow(U256::from(16));
+        signal.divergence = 1.0 / 1.21 - 1.0;
+        assert!(!DefaultArbitrageur::within_band(&signal));
+        let swap = DefaultArbitrageur::optimal_swap(&signal, &profile).unwrap();
+        let expected = 1_000_000.0 * ((1.21f64 * 0.997).sqrt() - 1.0) / 0.997;
+        assert!(!swap.zero_for_one);
+        assert!((swap.amount_in - expected).abs() < 1e-6);
+
+        // pool at 1, liquid exchange at 0.81: sell token0 until the pool price reaches 0.81 / 0.997
+        signal.lex_price = U256::from(81) * U256::from(10).pow(U256::from(16));
+        signal.divergence = 1.0 / 0.81 - 1.0;
+        assert!(!DefaultArbitrageur::within_band(&signal));
+        let swap = DefaultArbitrageur::optimal_swap(&signal, &profile).unwrap();
+        let expected = 1_000_000.0 * (1.0 / (0.81f64 / 0.997).sqrt() - 1.0) / 0.997;
+        assert!(swap.zero_for_one);
+        assert!((swap.amount_in - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_arbitrage_threshold() {
+        let mut signal = Signal::new(
+            U256::from(121) * U256::from(10).pow(U256::from(16)),
+            None,
+            Signed::ZERO,
+            Uint::from(79228162514264337593543950336_u128),
+            Address::ZERO,
+            PoolKey {
+                currency0: Address::ZERO,
+                currency1: Address::repeat_byte(1),
+                fee: Uint::from(0),
+                tickSpacing: Signed::try_from(60).unwrap(),
+                hooks: Address::ZERO,
+            },
+            Address::ZERO,
+            1.21,
+            Address::ZERO,
+        );
+        let profile = LiquidityProfile {
+            sqrt_price: 1.0,
+            tick: 0,
+            liquidity: 10u128.pow(18),
+            ticks: Vec::new(),
+        };
+
+        // buying 0.1 token1 worth of token0 at an average price of 1.1 when it is worth 1.21
+        let swap = DefaultArbitrageur::optimal_swap(&signal, &profile).unwrap();
+        let (profit, notional) = DefaultArbitrageur::expected_profit(&signal, &swap);
+        assert!((notional - 0.1).abs() < 1e-12);
+        assert!((profit - (0.1 / 1.1 * 1.21 - 0.1)).abs() < 1e-12);
+
+        let arbitrageur = DefaultArbitrageur::new();
+        assert!(arbitrageur.is_worth(&signal, &swap));
+        assert!(!arbitrageur
+            .clone()
+            .with_min_edge(0.2)
+            .is_worth(&signal, &swap));
+
+        // 150k gas at 10 gwei costs 0.0015 ether, the native token0, worth 1.21 token1 each
+        signal.gas_price = 10_000_000_000;
+        assert!((arbitrageur.gas_cost(&signal) - 0.0015 * 1.21).abs() < 1e-12);
+        assert!(arbitrageur.is_worth(&signal, &swap));
+        signal.gas_price *= 1000;
+        assert!(!arbitrageur.is_worth(&signal, &swap));
+
+        // without the native currency, gas is only priced with an ether price, and is free otherwise
+        signal.pool.currency0 = Address::repeat_byte(1);
+        signal.pool.currency1 = Address::repeat_byte(2);
+        assert_eq!(arbitrageur.gas_cost(&signal), 0.0);
+        assert!(arbitrageur.is_worth(&signal, &swap));
+
+        let arbitrageur = arbitrageur.with_ether_price(2000.0);
+        assert!((arbitrageur.gas_cost(&signal) - 1.5 * 2000.0).abs() < 1e-9);
+        assert!(!arbitrageur.is_worth(&signal, &swap));
+    }
+
+    #[test]
+    fn test_arbitrage_ledger() {
+        // buys 2 token0 for 3 token1 with 6 decimals
+        let trade = ArbitrageTrade {
+            step: 1,
+            zero_for_one: false,
+            amount0: I256::try_from(2_000_000_000_000_000_000u128).unwrap(),
+            amount1: I256::try_from(-3_000_000).unwrap(),
+        };
+        let fill = ArbitrageFill::new(&trade, (18, 6), 1.6, 100_000, 10);
+        assert_eq!((fill.amount0, fill.amount1), (2.0, -3.0));
+        assert_eq!(fill.price(), 1.5);
+        assert!((fill.profit() - 0.2).abs() < 1e-12);
+        assert_eq!(fill.gas_fee(), 1_000_000);
+
+        let ledger = ArbitrageLedger {
+            fills: vec![fill],
+            marks: BTreeMap::from([(1, 1.6), (2, 1.4)]),
+        };
+        assert!((ledger.realized_profit() - 0.2).abs() < 1e-12);
+        assert!((ledger.markout(&fill, 1).unwrap() + 0.2).abs() < 1e-12);
+        assert_eq!(ledger.markout(&fill, 2), None);
+        assert!((ledger.total_markout(0) - 0.2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_intermittent_arrivals() {
+        let count = |arrival| {
+            let mut arbitrageur = Intermittent::new(EmptyArbitrageur, arrival).unwrap();
+            arbitrageur.reseed(RngManager::new(7).rng(0, Stream::Arbitrageur));
+            (0..10_000).filter(|_| arbitrageur.arrives()).count()
+        };
+
+        assert_eq!(count(Arrival::Periodic(4)), 2_500);
+        assert_eq!(count(Arrival::Bernoulli(0.0)), 0);
+        assert_eq!(count(Arrival::Bernoulli(1.0)), 10_000);
+        assert!((count(Arrival::Bernoulli(0.3)) as f64 - 3_000.0).abs() < 200.0);
+
+        // a feed update sees at least one arrival with probability 1 - e^-rate
+        let expected = 10_000.0 * (1.0 - (-0.5f64).exp());
+        assert!((count(Arrival::Poisson(0.5)) as f64 - expected).abs() < 200.0);
+        assert_eq!(count(Arrival::Poisson(0.0)), 0);
+
+        // the same stream draws the same arrivals
+        let arrivals = |seed| {
+            let mut arbitrageur =
+                Intermittent::new(EmptyArbitrageur, Arrival::Bernoulli(0.5)).unwrap();
+            arbitrageur.reseed(RngManager::new(seed).rng(0, Stream::Arbitrageur));
+            (0..64).map(|_| arbitrageur.arrives()).collect::<Vec<_>>()
+        };
+        assert_eq!(arrivals(7), arrivals(7));
+        assert_ne!(arrivals(7), arrivals(8));
+
+        for arrival in [
+            Arrival::Bernoulli(f64::NAN),
+            Arrival::Bernoulli(1.5),
+            Arrival::Bernoulli(-0.1),
+            Arrival::Poisson(-1.0),
+            Arrival::Poisson(f64::INFINITY),
+            Arrival::Periodic(0),
+        ] {
+            assert!(Intermittent::new(EmptyArbitrageur, arrival).is_err());
+        }
+    }
+
+    #[test]
+    fn test_arbitrage_limits() {
+        let start = Inventory {
+            token0: U256::from(100),
+            token1: U256::from(100),
+        };
+        let balances = Inventory {
+            token0: U256::from(120),
+            token1: U256::from(70),
+        };
+
+        let unlimited = DefaultArbitrageur::new();
+        assert_eq!(
+            unlimited.limits(&start, &balances, true, (0, 0)),
+            (120.0, f64::INFINITY)
+        );
+        assert_eq!(
+            unlimited.limits(&start, &balances, false, (0, 0)),
+            (70.0, f64::INFINITY)
+        );
+
+        // 20 token0 long with a limit of 30 either way
+        let limited = DefaultArbitrageur::new().with_max_position(30.0);
+        assert_eq!(
+            limited.limits(&start, &balances, true, (0, 0)),
+            (50.0, f64::INFINITY)
+        );
+        assert_eq!(
+            limited.limits(&start, &balances, false, (0, 0)),
+            (70.0, 10.0)
+        );
+
+        let mut signal = Signal::new(
+            U256::ZERO,
+            None,
+            Signed::ZERO,
+            Uint::ZERO,
+            Address::ZERO,
+            PoolKey {
+                currency0: Address::ZERO,
+                currency1: Address::repeat_byte(1),
+                fee: Uint::from(3000),
+                tickSpacing: Signed::try_from(60).unwrap(),
+                hooks: Address::ZERO,
+            },
+            Address::ZERO,
+            1.0,
+            Address::ZERO,
+        );
+        signal.pool_state.balance0 = U256::from(1000);
+        let capped = DefaultArbitrageur::new()
+            .with_participation_cap(0.1)
+            .with_max_slippage(0.01);
+        assert_eq!(capped.participation_limit(&signal, true), 100.0);
+        assert_eq!(capped.participation_limit(&signal, false), 0.0);
+        assert_eq!(unlimited.participation_limit(&signal, true), f64::INFINITY);
+
+        let swap = OptimalSwap {
+            zero_for_one: true,
+            amount_in: 100.0,
+            amount_out: 200.0,
+            sqrt_price_after: 1.0,
+        };
+        let limit = f64::from(capped.price_limit(&swap).unwrap()) / 2f64.powi(96);
+        assert!((limit - 0.99f64.sqrt()).abs() < 1e-12);
+        let limit = capped
+            .price_limit(&OptimalSwap {
+                zero_for_one: false,
+                ..swap
+            })
+            .unwrap();
+        assert!((f64::from(limit) / 2f64.powi(96) - 1.0 / 0.99f64.sqrt()).abs() < 1e-12);
+        assert_eq!(unlimited.price_limit(&swap), None);
+
+        // a limit past the range of the pool is clamped to it
+        let full = DefaultArbitrageur::new().with_max_slippage(1.0);
+        assert_eq!(full.price_limit(&swap), Some(pool::MIN_PRICE_LIMIT));
+    }
+
+    #[test]
+    fn test_inventory_reset() {
+        assert!(DefaultArbitrageur::new().with_inventory_reset(0).is_err());
+
+        // each pool resets on its own feed updates, however the calls for different pools interleave
+        let mut resetting = DefaultArbitrageur::new().with_inventory_reset(2).unwrap();
+        let (a, b) = (B256::repeat_byte(1), B256::repeat_byte(2));
+        let resets: Vec<_> = [a, b, b, a, b, a, a, b]
+            .into_iter()
+            .map(|pool| resetting.count_call(pool))
+            .collect();
+        assert_eq!(resets, [false, false, true, true, false, false, true, true]);
+        assert!(!DefaultArbitrageur::new().count_call(a));
+        assert_eq!(
+            DefaultArbitrageur::new()
+                .with_inventory_reset(4)
+                .unwrap()
+                .reset_every,
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn test_cross_pool_counterparts() {
+        let signal = |manager: Address, currency1: Address, fee: u32| {
+            Signal::new(
+                U256::ZERO,
+                None,
+                Signed::ZERO,
+                Uint::ZERO,
+                manager,
+                PoolKey {
+                    currency0: Address::repeat_byte(1),
+                    currency1,
+                    fee: Uint::from(fee),
+                    tickSpacing: Signed::try_from(60).unwrap(),
+                    hooks: Address::ZERO,
+                },
+                Address::ZERO,
+                1.0,
+                Address::ZERO,
+            )
+        };
+        let (manager, token) = (Address::repeat_byte(9), Address::repeat_byte(2));
+
+        let mut arbitrageur = CrossPoolArbitrageur::new(EmptyArbitrageur);
+        let engine = Engine::new(Address::ZERO);
+        for pool in [
+            signal(manager, token, 3000),
+            signal(manager, token, 500),
+            signal(manager, token, 500),
+            signal(manager, Address::repeat_byte(3), 100),
+            signal(Address::repeat_byte(8), token, 100),
+        ] {
+            arbitrageur.register(&pool, &engine);
+        }
+        assert_eq!(arbitrageur.pools.len(), 4);
+
+        // only the other fee tier of the same pair on the same manager is routable
+        let counterparts = arbitrageur.counterparts(&signal(manager, token, 3000));
+        assert_eq!(counterparts.len(), 1);
+        assert_eq!(counterparts[0].0.fee, Uint::from(500));
+    }
 }
@@ -0,0 +1,162 @@
+use alloy::primitives::Address;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+/// Bid of an agent for its position in the order agents act in during a step, see [`crate::agent::Agent::bid`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bid {
+    /// Index of the agent, in the order agents were added to the arena.
+    pub agent: usize,
+
+    /// Priority the agent was added with.
+    pub priority: i32,
+
+    /// Amount bid, in wei.
+    pub amount: u128,
+}
+
+/// Rule a [`BlockBuilder`] orders the bids of a step by.
+pub trait OrderingPolicy: Send {
+    /// Returns the indices of `bids` in the order their agents act.
+    ///
+    /// Bids left out of the order are not included, and their agents do not act during the step.
+    fn order(&mut self, bids: &[Bid], rng: &mut StdRng) -> Vec<usize>;
+}
+
+/// Orders bids from the highest down, breaking ties by the priority of the agents and then the order they were added.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HighestBid;
+
+impl OrderingPolicy for HighestBid {
+    fn order(&mut self, bids: &[Bid], _rng: &mut StdRng) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..bids.len()).collect();
+        order.sort_by_key(|&idx| {
+            (
+                std::cmp::Reverse(bids[idx].amount),
+                bids[idx].priority,
+                bids[idx].agent,
+            )
+        });
+        order
+    }
+}
+
+/// Bids of a step and the order the builder included them in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuiltBlock {
+    /// Step the bids were made at.
+    pub step: usize,
+
+    /// Bids of every agent, in the order agents were added.
+    pub bids: Vec<Bid>,
+
+    /// Included bids, in the order their agents acted.
+    pub included: Vec<Bid>,
+}
+
+/// Block builder ordering agents by their bids every step, in place of their priorities.
+///
+/// Every agent bids at the start of the agents' turn of a step, and the included agents act in the order of the
+/// [`OrderingPolicy`], each first paying its bid from its wallet to [`BlockBuilder::recipient`].
+pub struct BlockBuilder {
+    /// Rule the bids are ordered by.
+    pub policy: Box<dyn OrderingPolicy>,
+
+    /// Address bids are paid to. The zero address, the default, burns them.
+    pub recipient: Address,
+
+    /// Blocks built so far.
+    pub blocks: Vec<BuiltBlock>,
+}
+
+impl Default for BlockBuilder {
+    fn default() -> Self {
+        Self::new(Box::new(HighestBid))
+    }
+}
+
+impl BlockBuilder {
+    /// Public constructor function for a new [`BlockBuilder`] ordering bids by `policy`.
+    pub fn new(policy: Box<dyn OrderingPolicy>) -> Self {
+        Self {
+            policy,
+            recipient: Address::ZERO,
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Pay bids to `recipient`.
+    pub fn with_recipient(mut self, recipient: Address) -> Self {
+        self.recipient = recipient;
+        self
+    }
+
+    /// Order the `bids` made at `step` by the policy, record the block and return the included bids in order.
+    pub fn build(&mut self, step: usize, bids: Vec<Bid>, rng: &mut StdRng) -> Vec<Bid> {
+        let included: Vec<Bid> = self
+            .policy
+            .order(&bids, rng)
+            .into_iter()
+            .filter_map(|idx| bids.get(idx).copied())
+            .collect();
+
+        self.blocks.push(BuiltBlock {
+            step,
+            bids,
+            included: included.clone(),
+        });
+        included
+    }
+
+    /// Returns the total of the included bids, in wei.
+    pub fn revenue(&self) -> u128 {
+        self.blocks
+            .iter()
+            .flat_map(|block| &block.included)
+            .map(|bid| bid.amount)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn test_block_builder() {
+        let bid = |agent, priority, amount| Bid {
+            agent,
+            priority,
+            amount,
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut builder = BlockBuilder::default();
+
+        let included = builder.build(
+            3,
+            vec![bid(0, 0, 10), bid(1, 5, 20), bid(2, -1, 10), bid(3, 0, 0)],
+            &mut rng,
+        );
+        let agents: Vec<usize> = included.iter().map(|bid| bid.agent).collect();
+        assert_eq!(agents, vec![1, 2, 0, 3]);
+        assert_eq!(builder.revenue(), 40);
+        assert_eq!(builder.blocks[0].step, 3);
+
+        // a policy leaving out zero bids
+        struct PaidOnly;
+        impl OrderingPolicy for PaidOnly {
+            fn order(&mut self, bids: &[Bid], rng: &mut StdRng) -> Vec<usize> {
+                let mut order = HighestBid.order(bids, rng);
+                order.retain(|&idx| bids[idx].amount > 0);
+                order
+            }
+        }
+        let mut builder = BlockBuilder::new(Box::new(PaidOnly));
+        assert_eq!(
+            builder.build(0, vec![bid(0, 0, 0), bid(1, 0, 1)], &mut rng),
+            vec![bid(1, 0, 1)]
+        );
+    }
+}
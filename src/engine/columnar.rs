@@ -0,0 +1,941 @@
+use std::{
+    cell::RefCell,
+    fmt::{self, Display},
+    fs::File,
+    slice,
+    sync::Arc,
+};
+
+use arrow::{
+    array::{ArrayRef, BooleanArray, NullArray, PrimitiveArray, StringArray, UInt64Array},
+    datatypes::{
+        ArrowPrimitiveType, DataType, Field, Float32Type, Float64Type, Int16Type, Int32Type,
+        Int64Type, Int8Type, Schema, SchemaRef, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+    },
+    record_batch::RecordBatch,
+};
+use parquet::arrow::ArrowWriter;
+use serde::{
+    de::{
+        self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess,
+        VariantAccess, Visitor,
+    },
+    ser::{self, Impossible, SerializeStruct},
+    Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    engine::inspector::{Inspector, SaveData},
+    error::ArenaError,
+    Signal,
+};
+
+/// Returns an [`ArenaError`] for a failure of the Arrow or Parquet writers.
+fn parquet_error(e: impl Display) -> ArenaError {
+    ArenaError::ParquetError(e.to_string())
+}
+
+/// Open Parquet file and the schema of its columns, derived from the type of the logged values.
+struct Output {
+    writer: ArrowWriter<File>,
+    schema: SchemaRef,
+}
+
+/// Implementation of an [`Inspector`] writing logged values to a columnar Parquet file, for analysis of large runs in
+/// e.g. pandas or polars.
+///
+/// Every logged value becomes a row with a `step` column. The other columns are derived from the type of the logged
+/// values rather than from the values themselves, so a field that is `None` throughout the first row group still gets
+/// its type. A struct gets one nullable column per field, with the fields of nested structs flattened into columns
+/// named `parent.child`, and any other type a single `value` column. Integers, floats, booleans and strings keep their
+/// types, while enums without data are written as the names of their variants and 128-bit integers as decimal strings.
+/// Sequences, maps and enum variants holding data have no column and fail the write.
+///
+/// Values are buffered and written as a row group every [`ParquetInspector::row_group_size`] values, and
+/// [`Inspector::save`] writes the remaining values and finishes the file. Values logged after a save go to a new file,
+/// resolved again from the [`SaveData`]. Only buffered values can be inspected.
+pub struct ParquetInspector<T> {
+    /// Destination of the file written.
+    pub save_data: SaveData,
+
+    /// Number of buffered values at which they are written as a row group.
+    pub row_group_size: usize,
+
+    pending: RefCell<Vec<(usize, T)>>,
+    output: RefCell<Option<Output>>,
+    step: usize,
+}
+
+impl<T> ParquetInspector<T> {
+    /// Public constructor function for a new [`ParquetInspector`] writing to `save_data`, in row groups of 65536
+    /// values.
+    pub fn new(save_data: SaveData) -> Self {
+        Self {
+            save_data,
+            row_group_size: 65536,
+            pending: RefCell::new(Vec::new()),
+            output: RefCell::new(None),
+            step: 0,
+        }
+    }
+
+    /// Write buffered values as a row group every `row_group_size` values.
+    pub fn with_row_group_size(mut self, row_group_size: usize) -> Self {
+        self.row_group_size = row_group_size.max(1);
+        self
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> ParquetInspector<T> {
+    /// Write the buffered values as a row group and clear the buffer, opening the file if needed.
+    fn flush(&self) -> Result<(), ArenaError> {
+        let rows = self
+            .pending
+            .borrow_mut()
+            .drain(..)
+            .map(|(step, value)| {
+                let mut scalars = Vec::new();
+                value.serialize(Flattener {
+                    name: None,
+                    row: &mut scalars,
+                })?;
+                Ok((step, scalars))
+            })
+            .collect::<Result<Vec<_>, ColumnError>>()?;
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let mut output = self.output.borrow_mut();
+        let output = match output.as_mut() {
+            Some(output) => output,
+            None => {
+                let schema = Arc::new(schema::<T>()?);
+                let file = File::create(self.save_data.file_path("parquet")?)?;
+                let writer =
+                    ArrowWriter::try_new(file, schema.clone(), None).map_err(parquet_error)?;
+
+                output.insert(Output { writer, schema })
+            }
+        };
+
+        let batch = record_batch(&output.schema, &rows)?;
+        output.writer.write(&batch).map_err(parquet_error)?;
+        output.writer.flush().map_err(parquet_error)?;
+
+        Ok(())
+    }
+}
+
+impl<T: Serialize + DeserializeOwned + Clone + Send> Inspector<T> for ParquetInspector<T> {
+    fn log(&mut self, value: T) -> Result<(), ArenaError> {
+        let pending = self.pending.get_mut();
+        pending.push((self.step, value));
+
+        if pending.len() >= self.row_group_size {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn inspect(&self, step: usize) -> Option<T> {
+        self.pending
+            .borrow()
+            .iter()
+            .rev()
+            .find(|(row_step, _)| *row_step == step)
+            .map(|(_, value)| value.clone())
+    }
+
+    fn save(&self) -> Result<(), ArenaError> {
+        self.flush()?;
+
+        if let Some(output) = self.output.borrow_mut().take() {
+            output.writer.close().map_err(parquet_error)?;
+        }
+        Ok(())
+    }
+
+    fn on_step(&mut self, signal: &Signal) -> Result<(), ArenaError> {
+        if let Some(step) = signal.step {
+            self.step = step + 1;
+        }
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), ArenaError> {
+        // a file open for the previous run is finished, and the values of this run go to a new one
+        self.pending.get_mut().clear();
+        if let Some(output) = self.output.get_mut().take() {
+            output.writer.close().map_err(parquet_error)?;
+        }
+        self.step = 0;
+        Ok(())
+    }
+}
+
+/// Failure to derive the columns of a type or to flatten a value into them.
+#[derive(Debug)]
+struct ColumnError(String);
+
+impl Display for ColumnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ColumnError {}
+
+impl de::Error for ColumnError {
+    fn custom<M: Display>(msg: M) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl ser::Error for ColumnError {
+    fn custom<M: Display>(msg: M) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl From<ColumnError> for ArenaError {
+    fn from(e: ColumnError) -> Self {
+        ArenaError::ParquetError(e.0)
+    }
+}
+
+/// Returns the schema of the rows written for values of type `T`, a `step` column followed by the columns of `T`.
+fn schema<T: DeserializeOwned>() -> Result<Schema, ColumnError> {
+    let mut fields = vec![Field::new("step", DataType::UInt64, false)];
+    T::deserialize(Tracer {
+        name: None,
+        columns: &mut fields,
+    })?;
+
+    Ok(Schema::new(fields))
+}
+
+/// Name of the column of a value at `name`, `None` for the logged value itself.
+fn column_name(name: &Option<String>) -> String {
+    name.clone().unwrap_or_else(|| "value".to_string())
+}
+
+/// Name of the column of the field `field` of a struct at `name`, `None` for the logged value itself.
+fn field_name(name: &Option<String>, field: &str) -> String {
+    match name {
+        Some(name) => format!("{name}.{field}"),
+        None => field.to_string(),
+    }
+}
+
+/// Deserializer walking the shape of a type instead of data, adding a column for every scalar the type holds and
+/// handing placeholder values to its [`Deserialize`](serde::Deserialize) implementation.
+struct Tracer<'a> {
+    name: Option<String>,
+    columns: &'a mut Vec<Field>,
+}
+
+impl Tracer<'_> {
+    fn column(&mut self, data_type: DataType) {
+        self.columns
+            .push(Field::new(column_name(&self.name), data_type, true));
+    }
+
+    fn unsupported(&self, kind: &str) -> ColumnError {
+        ColumnError(format!(
+            "column `{}` holds {kind}, which have no Parquet column",
+            column_name(&self.name)
+        ))
+    }
+}
+
+macro_rules! trace_scalars {
+    ($($method:ident => $data_type:expr, $visit:ident($placeholder:expr);)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, ColumnError> {
+                self.column($data_type);
+                visitor.$visit($placeholder)
+            }
+        )*
+    };
+}
+
+macro_rules! trace_unsupported {
+    ($($method:ident($($arg:ty),*) => $kind:literal;)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $(_: $arg,)* _: V) -> Result<V::Value, ColumnError> {
+                Err(self.unsupported($kind))
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Tracer<'_> {
+    type Error = ColumnError;
+
+    trace_scalars! {
+        deserialize_bool => DataType::Boolean, visit_bool(false);
+        deserialize_i8 => DataType::Int8, visit_i8(0);
+        deserialize_i16 => DataType::Int16, visit_i16(0);
+        deserialize_i32 => DataType::Int32, visit_i32(0);
+        deserialize_i64 => DataType::Int64, visit_i64(0);
+        deserialize_i128 => DataType::Utf8, visit_i128(0);
+        deserialize_u8 => DataType::UInt8, visit_u8(0);
+        deserialize_u16 => DataType::UInt16, visit_u16(0);
+        deserialize_u32 => DataType::UInt32, visit_u32(0);
+        deserialize_u64 => DataType::UInt64, visit_u64(0);
+        deserialize_u128 => DataType::Utf8, visit_u128(0);
+        deserialize_f32 => DataType::Float32, visit_f32(0.0);
+        deserialize_f64 => DataType::Float64, visit_f64(0.0);
+        deserialize_char => DataType::Utf8, visit_char(' ');
+        deserialize_str => DataType::Utf8, visit_str("");
+        deserialize_string => DataType::Utf8, visit_string(String::new());
+    }
+
+    trace_unsupported! {
+        deserialize_any() => "values of a self-describing type";
+        deserialize_bytes() => "byte strings";
+        deserialize_byte_buf() => "byte strings";
+        deserialize_seq() => "sequences";
+        deserialize_tuple(usize) => "tuples";
+        deserialize_tuple_struct(&'static str, usize) => "tuples";
+        deserialize_map() => "maps";
+        deserialize_identifier() => "identifiers";
+        deserialize_ignored_any() => "ignored values";
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ColumnError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, ColumnError> {
+        self.column(DataType::Null);
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ColumnError> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ColumnError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ColumnError> {
+        visitor.visit_map(FieldTracer {
+            name: self.name,
+            columns: self.columns,
+            fields: fields.iter(),
+            field: "",
+        })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        mut self,
+        _: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ColumnError> {
+        self.column(DataType::Utf8);
+        let variant = *variants
+            .first()
+            .ok_or_else(|| self.unsupported("enums without variants"))?;
+
+        visitor.visit_enum(VariantTracer(variant))
+    }
+}
+
+/// Access to the fields of a struct traced by a [`Tracer`], in order of declaration.
+struct FieldTracer<'a> {
+    name: Option<String>,
+    columns: &'a mut Vec<Field>,
+    fields: slice::Iter<'static, &'static str>,
+    field: &'static str,
+}
+
+impl<'de> MapAccess<'de> for FieldTracer<'_> {
+    type Error = ColumnError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, ColumnError> {
+        let Some(field) = self.fields.next() else {
+            return Ok(None);
+        };
+        self.field = field;
+
+        seed.deserialize(field.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, ColumnError> {
+        seed.deserialize(Tracer {
+            name: Some(field_name(&self.name, self.field)),
+            columns: self.columns,
+        })
+    }
+}
+
+/// Access to an enum traced by a [`Tracer`] through its first variant, which must not hold data.
+struct VariantTracer(&'static str);
+
+impl<'de> EnumAccess<'de> for VariantTracer {
+    type Error = ColumnError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self), ColumnError> {
+        let variant = seed.deserialize(self.0.into_deserializer())?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for VariantTracer {
+    type Error = ColumnError;
+
+    fn unit_variant(self) -> Result<(), ColumnError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, _: T) -> Result<T::Value, ColumnError> {
+        Err(variant_error(self.0))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _: usize, _: V) -> Result<V::Value, ColumnError> {
+        Err(variant_error(self.0))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _: &'static [&'static str],
+        _: V,
+    ) -> Result<V::Value, ColumnError> {
+        Err(variant_error(self.0))
+    }
+}
+
+/// Returns a [`ColumnError`] for the enum variant `variant` holding data.
+fn variant_error(variant: &str) -> ColumnError {
+    ColumnError(format!(
+        "enum variant `{variant}` holds data, which has no Parquet column"
+    ))
+}
+
+/// Scalar of a logged value, written to the column of the same name.
+#[derive(Debug)]
+enum Scalar {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Str(String),
+}
+
+/// Serializer flattening a logged value into named scalars, one per column derived by a [`Tracer`].
+struct Flattener<'a> {
+    name: Option<String>,
+    row: &'a mut Vec<(String, Scalar)>,
+}
+
+impl Flattener<'_> {
+    fn push(self, scalar: Scalar) -> Result<(), ColumnError> {
+        self.row.push((column_name(&self.name), scalar));
+        Ok(())
+    }
+
+    fn unsupported<S>(&self, kind: &str) -> Result<S, ColumnError> {
+        Err(ColumnError(format!(
+            "column `{}` holds {kind}, which have no Parquet column",
+            column_name(&self.name)
+        )))
+    }
+}
+
+impl<'a> Serializer for Flattener<'a> {
+    type Ok = ();
+    type Error = ColumnError;
+    type SerializeSeq = Impossible<(), ColumnError>;
+    type SerializeTuple = Impossible<(), ColumnError>;
+    type SerializeTupleStruct = Impossible<(), ColumnError>;
+    type SerializeTupleVariant = Impossible<(), ColumnError>;
+    type SerializeMap = Impossible<(), ColumnError>;
+    type SerializeStruct = FieldFlattener<'a>;
+    type SerializeStructVariant = Impossible<(), ColumnError>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), ColumnError> {
+        self.push(Scalar::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), ColumnError> {
+        self.push(Scalar::Int(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), ColumnError> {
+        self.push(Scalar::Int(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), ColumnError> {
+        self.push(Scalar::Int(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), ColumnError> {
+        self.push(Scalar::Int(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), ColumnError> {
+        self.push(Scalar::Str(v.to_string()))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), ColumnError> {
+        self.push(Scalar::UInt(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), ColumnError> {
+        self.push(Scalar::UInt(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), ColumnError> {
+        self.push(Scalar::UInt(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), ColumnError> {
+        self.push(Scalar::UInt(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), ColumnError> {
+        self.push(Scalar::Str(v.to_string()))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), ColumnError> {
+        self.push(Scalar::Float(v.into()))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), ColumnError> {
+        self.push(Scalar::Float(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), ColumnError> {
+        self.push(Scalar::Str(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), ColumnError> {
+        self.push(Scalar::Str(v.to_string()))
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<(), ColumnError> {
+        self.unsupported("byte strings")
+    }
+
+    fn serialize_none(self) -> Result<(), ColumnError> {
+        self.push(Scalar::Null)
+    }
+
+    fn serialize_some<S: Serialize + ?Sized>(self, value: &S) -> Result<(), ColumnError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), ColumnError> {
+        self.push(Scalar::Null)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), ColumnError> {
+        self.push(Scalar::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<(), ColumnError> {
+        self.push(Scalar::Str(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<S: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &S,
+    ) -> Result<(), ColumnError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<S: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: &S,
+    ) -> Result<(), ColumnError> {
+        Err(variant_error(variant))
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, ColumnError> {
+        self.unsupported("sequences")
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, ColumnError> {
+        self.unsupported("tuples")
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, ColumnError> {
+        self.unsupported("tuples")
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, ColumnError> {
+        Err(variant_error(variant))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, ColumnError> {
+        self.unsupported("maps")
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<FieldFlattener<'a>, ColumnError> {
+        Ok(FieldFlattener {
+            name: self.name,
+            row: self.row,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, ColumnError> {
+        Err(variant_error(variant))
+    }
+}
+
+/// Flattener of the fields of a struct, into the columns named after them.
+struct FieldFlattener<'a> {
+    name: Option<String>,
+    row: &'a mut Vec<(String, Scalar)>,
+}
+
+impl SerializeStruct for FieldFlattener<'_> {
+    type Ok = ();
+    type Error = ColumnError;
+
+    fn serialize_field<S: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &S,
+    ) -> Result<(), ColumnError> {
+        value.serialize(Flattener {
+            name: Some(field_name(&self.name, key)),
+            row: self.row,
+        })
+    }
+
+    fn end(self) -> Result<(), ColumnError> {
+        Ok(())
+    }
+}
+
+/// Returns the record batch of `rows`, each a step and the scalars of a logged value, in the columns of `schema`.
+/// Columns missing from a row, such as fields skipped when serializing, are null.
+fn record_batch(
+    schema: &SchemaRef,
+    rows: &[(usize, Vec<(String, Scalar)>)],
+) -> Result<RecordBatch, ArenaError> {
+    let mut columns: Vec<ArrayRef> = vec![Arc::new(
+        rows.iter()
+            .map(|(step, _)| *step as u64)
+            .collect::<UInt64Array>(),
+    )];
+
+    for field in schema.fields().iter().skip(1) {
+        let values = rows.iter().map(|(_, row)| {
+            row.iter()
+                .find(|(name, _)| name == field.name())
+                .map_or(&Scalar::Null, |(_, scalar)| scalar)
+        });
+        columns.push(column(field, values)?);
+    }
+
+    RecordBatch::try_new(schema.clone(), columns).map_err(parquet_error)
+}
+
+/// Returns a [`ColumnError`] for the scalar `value` not fitting the column `field`.
+fn mismatch(field: &Field, value: &Scalar) -> ColumnError {
+    ColumnError(format!(
+        "column `{}` of type {} cannot hold {value:?}",
+        field.name(),
+        field.data_type()
+    ))
+}
+
+/// Returns the array of the column `field` holding `values`.
+fn column<'a>(
+    field: &Field,
+    values: impl Iterator<Item = &'a Scalar>,
+) -> Result<ArrayRef, ColumnError> {
+    Ok(match field.data_type() {
+        DataType::Null => Arc::new(NullArray::new(values.count())),
+        DataType::Boolean => Arc::new(
+            values
+                .map(|value| match value {
+                    Scalar::Null => Ok(None),
+                    Scalar::Bool(v) => Ok(Some(*v)),
+                    value => Err(mismatch(field, value)),
+                })
+                .collect::<Result<BooleanArray, _>>()?,
+        ),
+        DataType::Int8 => integers::<Int8Type>(field, values)?,
+        DataType::Int16 => integers::<Int16Type>(field, values)?,
+        DataType::Int32 => integers::<Int32Type>(field, values)?,
+        DataType::Int64 => integers::<Int64Type>(field, values)?,
+        DataType::UInt8 => integers::<UInt8Type>(field, values)?,
+        DataType::UInt16 => integers::<UInt16Type>(field, values)?,
+        DataType::UInt32 => integers::<UInt32Type>(field, values)?,
+        DataType::UInt64 => integers::<UInt64Type>(field, values)?,
+        DataType::Float32 => Arc::new(
+            values
+                .map(|value| float(field, value).map(|v| v.map(|v| v as f32)))
+                .collect::<Result<PrimitiveArray<Float32Type>, _>>()?,
+        ),
+        DataType::Float64 => Arc::new(
+            values
+                .map(|value| float(field, value))
+                .collect::<Result<PrimitiveArray<Float64Type>, _>>()?,
+        ),
+        DataType::Utf8 => Arc::new(
+            values
+                .map(|value| match value {
+                    Scalar::Null => Ok(None),
+                    Scalar::Str(v) => Ok(Some(v.as_str())),
+                    value => Err(mismatch(field, value)),
+                })
+                .collect::<Result<StringArray, _>>()?,
+        ),
+        data_type => {
+            return Err(ColumnError(format!(
+                "column `{}` has the unsupported type {data_type}",
+                field.name()
+            )))
+        }
+    })
+}
+
+/// Returns the array of the integer column `field` holding `values`, failing on values out of its range.
+fn integers<'a, A>(
+    field: &Field,
+    values: impl Iterator<Item = &'a Scalar>,
+) -> Result<ArrayRef, ColumnError>
+where
+    A: ArrowPrimitiveType,
+    A::Native: TryFrom<i64> + TryFrom<u64>,
+{
+    let array = values
+        .map(|value| match value {
+            Scalar::Null => Ok(None),
+            Scalar::Int(v) => A::Native::try_from(*v)
+                .map(Some)
+                .map_err(|_| mismatch(field, value)),
+            Scalar::UInt(v) => A::Native::try_from(*v)
+                .map(Some)
+                .map_err(|_| mismatch(field, value)),
+            value => Err(mismatch(field, value)),
+        })
+        .collect::<Result<PrimitiveArray<A>, _>>()?;
+
+    Ok(Arc::new(array))
+}
+
+/// Returns the value of a scalar in the float column `field`.
+fn float(field: &Field, value: &Scalar) -> Result<Option<f64>, ColumnError> {
+    match value {
+        Scalar::Null => Ok(None),
+        Scalar::Float(v) => Ok(Some(*v)),
+        Scalar::Int(v) => Ok(Some(*v as f64)),
+        Scalar::UInt(v) => Ok(Some(*v as f64)),
+        value => Err(mismatch(field, value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct Row {
+        price: f64,
+        trades: u64,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    enum Side {
+        Buy,
+        Sell,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct Quote {
+        bid: f64,
+        ask: f64,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct Fill {
+        side: Side,
+        fee: Option<u32>,
+        quote: Quote,
+    }
+
+    #[test]
+    fn test_parquet_inspector() {
+        let path = std::env::temp_dir().join(format!("arena-parquet-{}", std::process::id()));
+        let save_data = SaveData::ToFile {
+            path: path.clone(),
+            name: "run".to_string(),
+        };
+        let mut inspector = ParquetInspector::new(save_data.clone()).with_row_group_size(2);
+
+        for trades in 0..5 {
+            inspector
+                .log(Row {
+                    price: trades as f64,
+                    trades,
+                })
+                .unwrap();
+        }
+        assert_eq!(inspector.inspect(0).map(|row| row.trades), Some(4));
+        inspector.save().unwrap();
+
+        let reader =
+            SerializedFileReader::new(File::open(save_data.file_path("parquet").unwrap()).unwrap())
+                .unwrap();
+        let metadata = reader.metadata();
+        assert_eq!(metadata.num_row_groups(), 3);
+        assert_eq!(metadata.file_metadata().num_rows(), 5);
+
+        let columns: Vec<&str> = metadata
+            .file_metadata()
+            .schema_descr()
+            .columns()
+            .iter()
+            .map(|column| column.name())
+            .collect();
+        assert!(columns.contains(&"step") && columns.contains(&"price"));
+
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn test_parquet_schema() {
+        let path =
+            std::env::temp_dir().join(format!("arena-parquet-schema-{}", std::process::id()));
+        let save_data = SaveData::ToFile {
+            path: path.clone(),
+            name: "run".to_string(),
+        };
+        let mut inspector = ParquetInspector::new(save_data.clone()).with_row_group_size(2);
+
+        // the fee is null throughout the first row group, and still written as an integer column
+        for step in 0..4 {
+            inspector
+                .log(Fill {
+                    side: if step % 2 == 0 { Side::Buy } else { Side::Sell },
+                    fee: (step >= 2).then_some(step * 100),
+                    quote: Quote {
+                        bid: step as f64,
+                        ask: step as f64 + 0.5,
+                    },
+                })
+                .unwrap();
+        }
+        inspector.save().unwrap();
+
+        let file = File::open(save_data.file_path("parquet").unwrap()).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        let batch = arrow::compute::concat_batches(&batches[0].schema(), &batches).unwrap();
+
+        let written = batch.schema();
+        let columns: Vec<(&str, &DataType)> = written
+            .fields()
+            .iter()
+            .map(|field| (field.name().as_str(), field.data_type()))
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                ("step", &DataType::UInt64),
+                ("side", &DataType::Utf8),
+                ("fee", &DataType::UInt32),
+                ("quote.bid", &DataType::Float64),
+                ("quote.ask", &DataType::Float64),
+            ]
+        );
+
+        let fees = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<PrimitiveArray<UInt32Type>>()
+            .unwrap();
+        assert_eq!(
+            fees.iter().collect::<Vec<_>>(),
+            vec![None, None, Some(200), Some(300)]
+        );
+        let sides = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(sides.value(1), "Sell");
+
+        // a value that is not a struct gets a single column
+        assert_eq!(
+            schema::<f64>().unwrap().field(1),
+            &Field::new("value", DataType::Float64, true)
+        );
+        assert!(schema::<Vec<f64>>().is_err());
+
+        fs::remove_dir_all(path).unwrap();
+    }
+}
@@ -0,0 +1,342 @@
+use std::{
+    cell::Cell,
+    collections::{BTreeMap, VecDeque},
+    io::{self, Stdout},
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    backend::{Backend, CrosstermBackend},
+    crossterm::{
+        execute,
+        terminal::{EnterAlternateScreen, LeaveAlternateScreen},
+    },
+    layout::{Constraint, Layout},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Gauge, List, Paragraph, Row, Table},
+    Frame, Terminal,
+};
+
+use crate::{
+    engine::{
+        events::PoolEvent,
+        inspector::Inspector,
+        metrics::{Metric, PNL_METRIC},
+    },
+    error::ArenaError,
+    Signal,
+};
+
+/// Number of recent pool events shown by a [`Dashboard`].
+const RECENT_EVENTS: usize = 10;
+
+/// State of the run shown by a [`Dashboard`].
+#[derive(Debug, Clone, Default)]
+struct View {
+    step: Option<usize>,
+    feed_value: f64,
+    pool_price: f64,
+    divergence: f64,
+    liquidity: u128,
+    base_fee: Option<u128>,
+    pnl: BTreeMap<Option<usize>, (usize, f64)>,
+    events: VecDeque<String>,
+}
+
+/// Implementation of an [`Inspector`] showing a live terminal dashboard of a run: the progress through its steps, the
+/// current prices and divergence of the pool, the PnL of strategies and the latest pool events.
+///
+/// Strategies report their PnL by logging [`Metric`]s named [`PNL_METRIC`], and other metrics are ignored. The
+/// dashboard redraws at the end of a step at most every [`Dashboard::refresh`], and always at the last step.
+///
+/// [`Dashboard::new`] takes over the terminal with an alternate screen, which is left on [`Inspector::save`] or when
+/// the dashboard is dropped, so the final state of the run stays on screen until then. Anything printed to the
+/// terminal during the run garbles the dashboard until it redraws.
+pub struct Dashboard<B: Backend = CrosstermBackend<Stdout>> {
+    /// Number of steps of the run, to show progress against.
+    pub steps: usize,
+
+    /// Minimum time between two redraws.
+    pub refresh: Duration,
+
+    terminal: Terminal<B>,
+    alternate_screen: Cell<bool>,
+    view: View,
+    started: Instant,
+    drawn: Option<Instant>,
+}
+
+impl Dashboard {
+    /// Public constructor function for a new [`Dashboard`] of a run of `steps` steps, drawn to the standard output
+    /// every 100 milliseconds.
+    pub fn new(steps: usize) -> Result<Self, ArenaError> {
+        execute!(io::stdout(), EnterAlternateScreen)?;
+
+        let mut dashboard = Self::with_backend(steps, CrosstermBackend::new(io::stdout()))?;
+        dashboard.alternate_screen.set(true);
+        dashboard.terminal.clear()?;
+
+        Ok(dashboard)
+    }
+}
+
+impl<B: Backend> Dashboard<B> {
+    /// Public constructor function for a new [`Dashboard`] of a run of `steps` steps, drawn to `backend`.
+    pub fn with_backend(steps: usize, backend: B) -> Result<Self, ArenaError> {
+        Ok(Self {
+            steps,
+            refresh: Duration::from_millis(100),
+            terminal: Terminal::new(backend)?,
+            alternate_screen: Cell::new(false),
+            view: View::default(),
+            started: Instant::now(),
+            drawn: None,
+        })
+    }
+
+    /// Redraw at most every `refresh`.
+    pub fn with_refresh(mut self, refresh: Duration) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Returns the terminal the dashboard is drawn to.
+    pub fn terminal(&self) -> &Terminal<B> {
+        &self.terminal
+    }
+
+    /// Draw the dashboard.
+    pub fn draw(&mut self) -> Result<(), ArenaError> {
+        let (view, steps, elapsed) = (&self.view, self.steps, self.started.elapsed());
+        self.terminal
+            .draw(|frame| render(frame, view, steps, elapsed))?;
+        self.drawn = Some(Instant::now());
+
+        Ok(())
+    }
+
+    /// Leave the alternate screen, if the dashboard entered it.
+    fn restore(&self) -> Result<(), ArenaError> {
+        if self.alternate_screen.take() {
+            execute!(io::stdout(), LeaveAlternateScreen)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<B: Backend> Drop for Dashboard<B> {
+    fn drop(&mut self) {
+        let _ = self.restore();
+    }
+}
+
+/// Returns a one-line description of `event`.
+fn describe(event: &PoolEvent) -> String {
+    match event {
+        PoolEvent::Swap {
+            block,
+            amount0,
+            amount1,
+            tick,
+            ..
+        } => format!("block {block}: swap {amount0} token0, {amount1} token1 to tick {tick}"),
+        PoolEvent::ModifyLiquidity {
+            block,
+            tick_lower,
+            tick_upper,
+            liquidity_delta,
+            ..
+        } => format!("block {block}: liquidity {liquidity_delta} in [{tick_lower}, {tick_upper})"),
+    }
+}
+
+/// Render `view` of a run of `steps` steps, running for `elapsed`, to `frame`.
+fn render(frame: &mut Frame, view: &View, steps: usize, elapsed: Duration) {
+    let [progress, body, events] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(8),
+        Constraint::Length(RECENT_EVENTS as u16 + 2),
+    ])
+    .areas(frame.area());
+    let [market, pnl] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(body);
+
+    let done = view.step.map_or(0, |step| step + 1);
+    let rate = done as f64 / elapsed.as_secs_f64().max(1e-9);
+    frame.render_widget(
+        Gauge::default()
+            .block(Block::default().borders(Borders::ALL).title("Progress"))
+            .gauge_style(Style::default().fg(Color::Cyan))
+            .ratio((done as f64 / steps.max(1) as f64).min(1.0))
+            .label(format!(
+                "step {done}/{steps}, {:.0?} elapsed, {rate:.1} steps/s",
+                elapsed
+            )),
+        progress,
+    );
+
+    let base_fee = view.base_fee.map_or("node".to_string(), |fee| {
+        format!("{:.3} gwei", fee as f64 / 1e9)
+    });
+    frame.render_widget(
+        Paragraph::new(vec![
+            Line::from(format!("feed value   {:.6}", view.feed_value)),
+            Line::from(format!("pool price   {:.6}", view.pool_price)),
+            Line::from(format!("divergence   {:.4}%", view.divergence * 100.0)),
+            Line::from(format!("liquidity    {}", view.liquidity)),
+            Line::from(format!("base fee     {base_fee}")),
+        ])
+        .block(Block::default().borders(Borders::ALL).title("Market")),
+        market,
+    );
+
+    let rows = view.pnl.iter().map(|(agent, (_, value))| {
+        Row::new(vec![
+            agent.map_or("run".to_string(), |agent| format!("strategy {agent}")),
+            format!("{value:.6}"),
+        ])
+    });
+    frame.render_widget(
+        Table::new(
+            rows,
+            [Constraint::Percentage(50), Constraint::Percentage(50)],
+        )
+        .header(Row::new(vec!["agent", "pnl"]).style(Style::default().fg(Color::Yellow)))
+        .block(Block::default().borders(Borders::ALL).title("PnL")),
+        pnl,
+    );
+
+    frame.render_widget(
+        List::new(view.events.iter().map(String::as_str)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Recent events"),
+        ),
+        events,
+    );
+}
+
+impl<B: Backend + Send> Inspector<Metric> for Dashboard<B> {
+    fn log(&mut self, value: Metric) -> Result<(), ArenaError> {
+        if value.name == PNL_METRIC {
+            let step = self.view.step.map_or(0, |step| step + 1);
+            self.view.pnl.insert(value.agent, (step, value.value));
+        }
+
+        Ok(())
+    }
+
+    fn inspect(&self, step: usize) -> Option<Metric> {
+        self.view
+            .pnl
+            .iter()
+            .find(|(_, (at, _))| *at == step)
+            .map(|(agent, (_, value))| Metric {
+                name: PNL_METRIC.to_string(),
+                value: *value,
+                unit: None,
+                agent: *agent,
+            })
+    }
+
+    fn save(&self) -> Result<(), ArenaError> {
+        self.restore()
+    }
+
+    fn on_step(&mut self, signal: &Signal) -> Result<(), ArenaError> {
+        let view = &mut self.view;
+        view.step = signal.step;
+        view.feed_value = signal.current_value;
+        view.pool_price = signal.price();
+        view.divergence = signal.divergence;
+        view.liquidity = signal.liquidity;
+        view.base_fee = signal.base_fee;
+
+        for event in &signal.events {
+            view.events.push_front(describe(event));
+        }
+        view.events.truncate(RECENT_EVENTS);
+
+        let last = signal.step.is_some_and(|step| step + 1 >= self.steps);
+        if last
+            || self
+                .drawn
+                .is_none_or(|drawn| drawn.elapsed() >= self.refresh)
+        {
+            self.draw()?;
+        }
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), ArenaError> {
+        self.view = View::default();
+        self.started = Instant::now();
+        self.drawn = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, Signed, Uint, U256};
+    use ratatui::backend::TestBackend;
+
+    use super::*;
+    use crate::types::controller::ArenaController::PoolKey;
+
+    #[test]
+    fn test_dashboard() {
+        let mut signal = Signal::new(
+            U256::ZERO,
+            Some(4),
+            Signed::ZERO,
+            Uint::from(1u128 << 96),
+            Address::ZERO,
+            PoolKey {
+                currency0: Address::ZERO,
+                currency1: Address::ZERO,
+                fee: Uint::ZERO,
+                tickSpacing: Signed::ZERO,
+                hooks: Address::ZERO,
+            },
+            Address::ZERO,
+            1.25,
+            Address::ZERO,
+        );
+        signal.decimals = (18, 18);
+        signal.events.push(PoolEvent::Swap {
+            block: 7,
+            sender: Address::ZERO,
+            amount0: -5,
+            amount1: 6,
+            sqrt_price_x96: U256::ZERO,
+            liquidity: 0,
+            tick: 12,
+            fee: 0,
+        });
+
+        let mut dashboard = Dashboard::with_backend(5, TestBackend::new(100, 30)).unwrap();
+        dashboard
+            .log(Metric::new(PNL_METRIC, 2.5).for_agent(0))
+            .unwrap();
+        dashboard.on_step(&signal).unwrap();
+
+        let screen: String = dashboard
+            .terminal()
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("step 5/5"));
+        assert!(screen.contains("feed value   1.250000"));
+        assert!(screen.contains("pool price   1.000000"));
+        assert!(screen.contains("strategy 0"));
+        assert!(screen.contains("block 7: swap -5 token0, 6 token1 to tick 12"));
+        assert_eq!(dashboard.inspect(0).unwrap().value, 2.5);
+    }
+}
@@ -0,0 +1,216 @@
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    inspector::{Inspector, SaveData},
+    math::LiquidityProfile,
+};
+use crate::error::ArenaError;
+
+/// Liquidity active over a range of ticks between two consecutive initialized ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiquidityBand {
+    /// Lower tick of the range, inclusive.
+    pub tick_lower: i32,
+
+    /// Upper tick of the range, exclusive.
+    pub tick_upper: i32,
+
+    /// Liquidity active while the pool price is in the range.
+    pub liquidity: u128,
+}
+
+/// Returns the liquidity of `profile` by range between consecutive initialized ticks, sorted by tick and skipping
+/// ranges without liquidity.
+///
+/// Liquidity is walked outwards from the in-range liquidity at the current tick, so ranges beyond the ticks read into
+/// the profile are missing.
+pub fn bands(profile: &LiquidityProfile) -> Vec<LiquidityBand> {
+    let ticks = &profile.ticks;
+    // liquidity below the first tick, between each pair of ticks, and above the last tick
+    let mut segments = vec![0i128; ticks.len() + 1];
+    let current = ticks.partition_point(|(tick, _)| *tick <= profile.tick);
+
+    segments[current] = profile.liquidity as i128;
+    for idx in current..ticks.len() {
+        segments[idx + 1] = segments[idx] + ticks[idx].1;
+    }
+    for idx in (0..current).rev() {
+        segments[idx] = segments[idx + 1] - ticks[idx].1;
+    }
+
+    ticks
+        .windows(2)
+        .zip(&segments[1..])
+        .filter(|(_, liquidity)| **liquidity > 0)
+        .map(|(pair, liquidity)| LiquidityBand {
+            tick_lower: pair[0].0,
+            tick_upper: pair[1].0,
+            liquidity: *liquidity as u128,
+        })
+        .collect()
+}
+
+/// Liquidity of the pool by range at the end of a step, taken by a [`DistributionInspector`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiquiditySnapshot {
+    /// Step the snapshot was taken at.
+    pub step: usize,
+
+    /// Current tick of the pool.
+    pub tick: i32,
+
+    /// Liquidity by range, see [`bands`].
+    pub bands: Vec<LiquidityBand>,
+}
+
+/// Implementation of an [`Inspector`] taking periodic snapshots of the liquidity of the pool by tick, to visualize how
+/// the liquidity profile evolves under competing strategies.
+///
+/// Every `interval` steps, starting with the first, the arena walks the initialized ticks of `words` tick bitmap words
+/// on either side of the current tick. A word covers 256 tick spacings, so the default of 8 words covers 2048 tick
+/// spacings either side, and ranges beyond them are missing from the snapshots.
+///
+/// On [`Inspector::save`], the snapshots are written to a CSV file in long format, one row per range, if a destination
+/// was set with [`DistributionInspector::with_save_data`]. The inspector observes snapshots only, so logged values are
+/// dropped and cannot be inspected.
+#[derive(Debug, Clone)]
+pub struct DistributionInspector<V = f64> {
+    /// Number of steps between two snapshots.
+    pub interval: usize,
+
+    /// Number of tick bitmap words read on either side of the current tick.
+    pub words: i16,
+
+    /// Destination of the file written on save.
+    pub save_data: Option<SaveData>,
+
+    snapshots: Vec<LiquiditySnapshot>,
+    _value: PhantomData<fn(V)>,
+}
+
+impl<V> DistributionInspector<V> {
+    /// Public constructor function for a new [`DistributionInspector`] taking a snapshot every `interval` steps.
+    pub fn new(interval: usize) -> Self {
+        Self {
+            interval: interval.max(1),
+            words: 8,
+            save_data: None,
+            snapshots: Vec::new(),
+            _value: PhantomData,
+        }
+    }
+
+    /// Read `words` tick bitmap words on either side of the current tick.
+    pub fn with_words(mut self, words: i16) -> Self {
+        self.words = words;
+        self
+    }
+
+    /// Write the snapshots to `save_data` on save.
+    pub fn with_save_data(mut self, save_data: SaveData) -> Self {
+        self.save_data = Some(save_data);
+        self
+    }
+
+    /// Returns the snapshots taken so far, oldest first.
+    pub fn snapshots(&self) -> &[LiquiditySnapshot] {
+        &self.snapshots
+    }
+
+    /// Record a snapshot of `profile`, taken at `step`.
+    pub fn record(&mut self, step: usize, profile: &LiquidityProfile) {
+        self.snapshots.push(LiquiditySnapshot {
+            step,
+            tick: profile.tick,
+            bands: bands(profile),
+        });
+    }
+}
+
+impl<V: Send> Inspector<V> for DistributionInspector<V> {
+    fn log(&mut self, _value: V) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    fn inspect(&self, _step: usize) -> Option<V> {
+        None
+    }
+
+    fn save(&self) -> Result<(), ArenaError> {
+        let Some(save_data) = &self.save_data else {
+            return Ok(());
+        };
+
+        let mut writer = csv::Writer::from_path(save_data.file_path("csv")?)?;
+        writer.write_record(["step", "tick", "tick_lower", "tick_upper", "liquidity"])?;
+        for snapshot in &self.snapshots {
+            for band in &snapshot.bands {
+                writer.serialize((
+                    snapshot.step,
+                    snapshot.tick,
+                    band.tick_lower,
+                    band.tick_upper,
+                    band.liquidity.to_string(),
+                ))?;
+            }
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    fn liquidity_words(&self, step: usize) -> Option<i16> {
+        step.is_multiple_of(self.interval).then_some(self.words)
+    }
+
+    fn on_liquidity(&mut self, step: usize, profile: &LiquidityProfile) -> Result<(), ArenaError> {
+        self.record(step, profile);
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), ArenaError> {
+        self.snapshots.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distribution_inspector() {
+        // a wide position of 100 and a narrow one of 50 around the current tick
+        let profile = LiquidityProfile {
+            sqrt_price: 1.0,
+            tick: 0,
+            liquidity: 150,
+            ticks: vec![(-120, 100), (-60, 50), (60, -50), (120, -100)],
+        };
+        let band = |tick_lower, tick_upper, liquidity| LiquidityBand {
+            tick_lower,
+            tick_upper,
+            liquidity,
+        };
+        assert_eq!(
+            bands(&profile),
+            vec![band(-120, -60, 100), band(-60, 60, 150), band(60, 120, 100)]
+        );
+
+        // the same bands with the price above every position
+        let above = LiquidityProfile {
+            tick: 200,
+            liquidity: 0,
+            ..profile.clone()
+        };
+        assert_eq!(bands(&above), bands(&profile));
+
+        let mut inspector = DistributionInspector::<f64>::new(5).with_words(2);
+        assert_eq!(inspector.liquidity_words(0), Some(2));
+        assert_eq!(inspector.liquidity_words(3), None);
+        inspector.on_liquidity(10, &profile).unwrap();
+        assert_eq!(inspector.snapshots()[0].bands.len(), 3);
+    }
+}
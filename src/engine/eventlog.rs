@@ -0,0 +1,202 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    marker::PhantomData,
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    events::{ContractEvent, ContractLog},
+    inspector::{Inspector, SaveData},
+};
+use crate::error::ArenaError;
+
+/// A [`ContractLog`] captured by an [`EventLogInspector`], with the step it was captured at.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapturedLog {
+    /// Step the log was captured at, or `None` for logs emitted during deployment and initialization.
+    pub step: Option<usize>,
+
+    /// The log.
+    pub log: ContractLog,
+}
+
+/// Implementation of an [`Inspector`] capturing the `Initialize`, `Swap`, `ModifyLiquidity` and `Transfer` events of
+/// every contract of a simulation, deployment included, into a structured history for analysis after the run.
+///
+/// On [`Inspector::save`], the history is written as newline-delimited JSON, one [`CapturedLog`] per line, if a
+/// destination was set with [`EventLogInspector::with_save_data`], and can be read back with
+/// [`EventLogInspector::load`]. The inspector observes logs only, so logged values are dropped and cannot be
+/// inspected.
+#[derive(Debug, Clone)]
+pub struct EventLogInspector<V = f64> {
+    /// Destination of the file written on save.
+    pub save_data: Option<SaveData>,
+
+    logs: Vec<CapturedLog>,
+    _value: PhantomData<fn(V)>,
+}
+
+impl<V> Default for EventLogInspector<V> {
+    fn default() -> Self {
+        Self {
+            save_data: None,
+            logs: Vec::new(),
+            _value: PhantomData,
+        }
+    }
+}
+
+impl<V> EventLogInspector<V> {
+    /// Public constructor function for a new, empty [`EventLogInspector`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write the history to `save_data` on save.
+    pub fn with_save_data(mut self, save_data: SaveData) -> Self {
+        self.save_data = Some(save_data);
+        self
+    }
+
+    /// Returns the logs captured so far, oldest first.
+    pub fn logs(&self) -> &[CapturedLog] {
+        &self.logs
+    }
+
+    /// Returns the events captured so far that match `filter`, oldest first.
+    pub fn events<'a>(
+        &'a self,
+        filter: impl Fn(&ContractEvent) -> bool + 'a,
+    ) -> impl Iterator<Item = &'a CapturedLog> + 'a {
+        self.logs
+            .iter()
+            .filter(move |captured| filter(&captured.log.event))
+    }
+
+    /// Read a history written by [`Inspector::save`] from `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Vec<CapturedLog>, ArenaError> {
+        BufReader::new(File::open(path)?)
+            .lines()
+            .filter(|line| !line.as_ref().is_ok_and(|line| line.trim().is_empty()))
+            .map(|line| Ok(serde_json::from_str(&line?)?))
+            .collect()
+    }
+}
+
+impl<V: Send> Inspector<V> for EventLogInspector<V> {
+    fn log(&mut self, _value: V) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    fn inspect(&self, _step: usize) -> Option<V> {
+        None
+    }
+
+    fn save(&self) -> Result<(), ArenaError> {
+        let Some(save_data) = &self.save_data else {
+            return Ok(());
+        };
+
+        let mut writer = BufWriter::new(File::create(save_data.file_path("ndjson")?)?);
+        for captured in &self.logs {
+            serde_json::to_writer(&mut writer, captured)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    fn captures_logs(&self) -> bool {
+        true
+    }
+
+    fn on_logs(&mut self, step: Option<usize>, logs: &[ContractLog]) -> Result<(), ArenaError> {
+        self.logs.extend(logs.iter().map(|log| CapturedLog {
+            step,
+            log: log.clone(),
+        }));
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), ArenaError> {
+        self.logs.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use alloy::primitives::{Address, B256, U256};
+
+    use super::*;
+
+    #[test]
+    fn test_event_log_inspector() {
+        let path = std::env::temp_dir().join(format!("arena-eventlog-{}", std::process::id()));
+        let save_data = SaveData::ToFile {
+            path: path.clone(),
+            name: "events".to_string(),
+        };
+        let log = |block, event| ContractLog {
+            block,
+            transaction: Some(B256::repeat_byte(block as u8)),
+            log_index: Some(0),
+            address: Address::repeat_byte(1),
+            event,
+        };
+
+        let mut inspector = EventLogInspector::<f64>::new().with_save_data(save_data.clone());
+        inspector
+            .on_logs(
+                None,
+                &[log(
+                    1,
+                    ContractEvent::Transfer {
+                        from: Address::ZERO,
+                        to: Address::repeat_byte(2),
+                        amount: U256::from(100),
+                    },
+                )],
+            )
+            .unwrap();
+        inspector
+            .on_logs(
+                Some(0),
+                &[log(
+                    2,
+                    ContractEvent::Swap {
+                        id: B256::ZERO,
+                        sender: Address::repeat_byte(3),
+                        amount0: -5,
+                        amount1: 6,
+                        sqrt_price_x96: U256::from(1u128 << 96),
+                        liquidity: 1000,
+                        tick: 0,
+                        fee: 3000,
+                    },
+                )],
+            )
+            .unwrap();
+
+        assert_eq!(inspector.logs().len(), 2);
+        let swaps: Vec<_> = inspector
+            .events(|event| matches!(event, ContractEvent::Swap { .. }))
+            .collect();
+        assert_eq!(swaps.len(), 1);
+        assert_eq!(swaps[0].step, Some(0));
+
+        inspector.save().unwrap();
+        let loaded =
+            EventLogInspector::<f64>::load(save_data.file_path("ndjson").unwrap()).unwrap();
+        assert_eq!(loaded, inspector.logs());
+
+        fs::remove_dir_all(path).unwrap();
+    }
+}
@@ -0,0 +1,337 @@
+use alloy::{
+    primitives::{Address, B256, I256, U256},
+    rpc::types::Filter,
+    sol_types::SolEvent,
+};
+use serde::{Deserialize, Serialize};
+
+use super::*;
+use crate::types::{
+    controller::ArenaController::PoolKey, manager::IPoolManager, token::ArenaToken,
+};
+
+/// A `Swap` or `ModifyLiquidity` event emitted by the `PoolManager` for a pool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PoolEvent {
+    /// A swap against the pool.
+    Swap {
+        /// Block the swap was mined in.
+        block: u64,
+
+        /// Address that initiated the swap through the `PoolManager`.
+        sender: Address,
+
+        /// Change in token0 from the swapper's perspective.
+        amount0: i128,
+
+        /// Change in token1 from the swapper's perspective.
+        amount1: i128,
+
+        /// Price of the pool after the swap.
+        sqrt_price_x96: U256,
+
+        /// In-range liquidity of the pool after the swap.
+        liquidity: u128,
+
+        /// Tick of the pool after the swap.
+        tick: i32,
+
+        /// Fee charged on the swap, in hundredths of a bip.
+        fee: u32,
+    },
+
+    /// A change to a liquidity position of the pool.
+    ModifyLiquidity {
+        /// Block the modification was mined in.
+        block: u64,
+
+        /// Address that modified the position through the `PoolManager`.
+        sender: Address,
+
+        /// Lower tick of the position.
+        tick_lower: i32,
+
+        /// Upper tick of the position.
+        tick_upper: i32,
+
+        /// Liquidity added to, or removed from, the position.
+        liquidity_delta: I256,
+    },
+}
+
+impl PoolEvent {
+    /// Block the event was mined in.
+    pub fn block(&self) -> u64 {
+        match self {
+            PoolEvent::Swap { block, .. } | PoolEvent::ModifyLiquidity { block, .. } => *block,
+        }
+    }
+}
+
+/// Fetches and decodes the events of a pool emitted between `from_block` and `to_block` inclusive, in order.
+pub async fn fetch(
+    manager: Address,
+    key: &PoolKey,
+    from_block: u64,
+    to_block: u64,
+    provider: AnvilProvider,
+) -> Result<Vec<PoolEvent>, ArenaError> {
+    if from_block > to_block {
+        return Ok(Vec::new());
+    }
+
+    let filter = Filter::new()
+        .address(manager)
+        .event_signature(vec![
+            IPoolManager::Swap::SIGNATURE_HASH,
+            IPoolManager::ModifyLiquidity::SIGNATURE_HASH,
+        ])
+        .topic1(pool::pool_id(key))
+        .from_block(from_block)
+        .to_block(to_block);
+
+    let mut events = Vec::new();
+
+    for log in provider.get_logs(&filter).await? {
+        let block = log.block_number.unwrap_or_default();
+
+        if let Ok(swap) = log.log_decode::<IPoolManager::Swap>() {
+            let swap = swap.inner.data;
+            events.push(PoolEvent::Swap {
+                block,
+                sender: swap.sender,
+                amount0: swap.amount0,
+                amount1: swap.amount1,
+                sqrt_price_x96: U256::from(swap.sqrtPriceX96),
+                liquidity: swap.liquidity,
+                tick: swap.tick.as_i32(),
+                fee: swap.fee.to::<u32>(),
+            });
+        } else if let Ok(modification) = log.log_decode::<IPoolManager::ModifyLiquidity>() {
+            let modification = modification.inner.data;
+            events.push(PoolEvent::ModifyLiquidity {
+                block,
+                sender: modification.sender,
+                tick_lower: modification.tickLower.as_i32(),
+                tick_upper: modification.tickUpper.as_i32(),
+                liquidity_delta: modification.liquidityDelta,
+            });
+        }
+    }
+
+    Ok(events)
+}
+
+/// An event emitted by one of the contracts of a simulation, decoded from its log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContractEvent {
+    /// Initialization of a pool by the `PoolManager`.
+    Initialize {
+        /// Id of the pool.
+        id: B256,
+
+        /// Lower currency of the pool.
+        currency0: Address,
+
+        /// Higher currency of the pool.
+        currency1: Address,
+
+        /// Fee of the pool, in hundredths of a bip.
+        fee: u32,
+
+        /// Tick spacing of the pool.
+        tick_spacing: i32,
+
+        /// Hooks contract of the pool.
+        hooks: Address,
+
+        /// Initial price of the pool.
+        sqrt_price_x96: U256,
+
+        /// Initial tick of the pool.
+        tick: i32,
+    },
+
+    /// A swap against a pool of the `PoolManager`.
+    Swap {
+        /// Id of the pool.
+        id: B256,
+
+        /// Address that initiated the swap through the `PoolManager`.
+        sender: Address,
+
+        /// Change in token0 from the swapper's perspective.
+        amount0: i128,
+
+        /// Change in token1 from the swapper's perspective.
+        amount1: i128,
+
+        /// Price of the pool after the swap.
+        sqrt_price_x96: U256,
+
+        /// In-range liquidity of the pool after the swap.
+        liquidity: u128,
+
+        /// Tick of the pool after the swap.
+        tick: i32,
+
+        /// Fee charged on the swap, in hundredths of a bip.
+        fee: u32,
+    },
+
+    /// A change to a liquidity position of a pool of the `PoolManager`.
+    ModifyLiquidity {
+        /// Id of the pool.
+        id: B256,
+
+        /// Address that modified the position through the `PoolManager`.
+        sender: Address,
+
+        /// Lower tick of the position.
+        tick_lower: i32,
+
+        /// Upper tick of the position.
+        tick_upper: i32,
+
+        /// Liquidity added to, or removed from, the position.
+        liquidity_delta: I256,
+    },
+
+    /// A transfer of an ERC-20 token.
+    Transfer {
+        /// Sender of the tokens, or the zero address for a mint.
+        from: Address,
+
+        /// Receiver of the tokens.
+        to: Address,
+
+        /// Raw amount transferred.
+        amount: U256,
+    },
+}
+
+/// A [`ContractEvent`] with the log it was decoded from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractLog {
+    /// Block the log was emitted in.
+    pub block: u64,
+
+    /// Hash of the transaction that emitted the log.
+    pub transaction: Option<B256>,
+
+    /// Index of the log in its block.
+    pub log_index: Option<u64>,
+
+    /// Contract that emitted the log.
+    pub address: Address,
+
+    /// The decoded event.
+    pub event: ContractEvent,
+}
+
+/// Returns the filter matching the `Initialize`, `Swap`, `ModifyLiquidity` and `Transfer` events emitted by
+/// `addresses` between `from_block` and `to_block` inclusive.
+pub fn log_filter(from_block: u64, to_block: u64, addresses: &[Address]) -> Filter {
+    Filter::new()
+        .address(addresses.to_vec())
+        .event_signature(vec![
+            IPoolManager::Initialize::SIGNATURE_HASH,
+            IPoolManager::Swap::SIGNATURE_HASH,
+            IPoolManager::ModifyLiquidity::SIGNATURE_HASH,
+            ArenaToken::Transfer::SIGNATURE_HASH,
+        ])
+        .from_block(from_block)
+        .to_block(to_block)
+}
+
+/// Fetches and decodes the `Initialize`, `Swap`, `ModifyLiquidity` and `Transfer` events emitted by `addresses`
+/// between `from_block` and `to_block` inclusive, in order. Logs of other events are skipped.
+pub async fn fetch_logs(
+    from_block: u64,
+    to_block: u64,
+    addresses: &[Address],
+    provider: AnvilProvider,
+) -> Result<Vec<ContractLog>, ArenaError> {
+    if from_block > to_block || addresses.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let filter = log_filter(from_block, to_block, addresses);
+    let mut logs = Vec::new();
+
+    for log in provider.get_logs(&filter).await? {
+        let event = if let Ok(initialize) = log.log_decode::<IPoolManager::Initialize>() {
+            let initialize = initialize.inner.data;
+            ContractEvent::Initialize {
+                id: initialize.id,
+                currency0: initialize.currency0,
+                currency1: initialize.currency1,
+                fee: initialize.fee.to::<u32>(),
+                tick_spacing: initialize.tickSpacing.as_i32(),
+                hooks: initialize.hooks,
+                sqrt_price_x96: U256::from(initialize.sqrtPriceX96),
+                tick: initialize.tick.as_i32(),
+            }
+        } else if let Ok(swap) = log.log_decode::<IPoolManager::Swap>() {
+            let swap = swap.inner.data;
+            ContractEvent::Swap {
+                id: swap.id,
+                sender: swap.sender,
+                amount0: swap.amount0,
+                amount1: swap.amount1,
+                sqrt_price_x96: U256::from(swap.sqrtPriceX96),
+                liquidity: swap.liquidity,
+                tick: swap.tick.as_i32(),
+                fee: swap.fee.to::<u32>(),
+            }
+        } else if let Ok(modification) = log.log_decode::<IPoolManager::ModifyLiquidity>() {
+            let modification = modification.inner.data;
+            ContractEvent::ModifyLiquidity {
+                id: modification.id,
+                sender: modification.sender,
+                tick_lower: modification.tickLower.as_i32(),
+                tick_upper: modification.tickUpper.as_i32(),
+                liquidity_delta: modification.liquidityDelta,
+            }
+        } else if let Ok(transfer) = log.log_decode::<ArenaToken::Transfer>() {
+            let transfer = transfer.inner.data;
+            ContractEvent::Transfer {
+                from: transfer.from,
+                to: transfer.to,
+                amount: transfer.amount,
+            }
+        } else {
+            continue;
+        };
+
+        logs.push(ContractLog {
+            block: log.block_number.unwrap_or_default(),
+            transaction: log.transaction_hash,
+            log_index: log.log_index,
+            address: log.address(),
+            event,
+        });
+    }
+
+    Ok(logs)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::rpc::types::FilterSet;
+
+    use super::*;
+
+    #[test]
+    fn test_log_filter() {
+        let (manager, token) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let filter = log_filter(7, 9, &[manager, token]);
+
+        assert_eq!(filter.get_from_block(), Some(7));
+        assert_eq!(filter.get_to_block(), Some(9));
+        assert_eq!(filter.address, FilterSet::from(vec![manager, token]));
+        assert!(filter.topics[0]
+            .iter()
+            .any(|topic| *topic == IPoolManager::Swap::SIGNATURE_HASH));
+    }
+}
@@ -0,0 +1,323 @@
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    inspector::{Inspector, SaveData},
+    pnl::{AgentMark, RangeMark},
+    position::PositionRecord,
+};
+use crate::error::ArenaError;
+
+/// Fees earned by a position of a strategy or agent up to a step.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RangeFees {
+    /// Step of the mark, or `None` for the mark taken after initialization.
+    pub step: Option<usize>,
+
+    /// Lower tick of the range.
+    pub tick_lower: i32,
+
+    /// Upper tick of the range.
+    pub tick_upper: i32,
+
+    /// Liquidity held over the range at the mark.
+    pub liquidity: u128,
+
+    /// Whole amounts of token0 and token1 earned in fees over the range so far.
+    pub earned: (f64, f64),
+
+    /// Value of the fees earned over the range so far, each at the spot price of the step it was earned at, in whole
+    /// units of token1.
+    pub income: f64,
+}
+
+/// Returns of the positions of a strategy or agent up to a step, split into fee income and price movement, in whole
+/// units of token1.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FeePoint {
+    /// Step of the mark, or `None` for the mark taken after initialization.
+    pub step: Option<usize>,
+
+    /// Whole amounts of token0 and token1 earned in fees over every range so far.
+    pub earned: (f64, f64),
+
+    /// Value of the fees earned so far, each at the spot price of the step it was earned at.
+    pub fee_income: f64,
+
+    /// Change in value of the tokens backing the positions against holding the tokens deposited into them instead, at
+    /// the spot price and excluding fees.
+    pub price_movement: f64,
+}
+
+impl FeePoint {
+    /// Returns the returns of the positions, fee income and price movement combined.
+    pub fn net(&self) -> f64 {
+        self.fee_income + self.price_movement
+    }
+}
+
+/// Implementation of an [`Inspector`] attributing the fees earned by the pool to the positions of every strategy and
+/// agent, to decompose the returns of liquidity provision into fee income and losses to price movement.
+///
+/// Every step, the fees earned by a position are read from the growth of the fees inside its range since the previous
+/// mark, times the liquidity it held then on the pool, and valued at the spot price. Each wallet holds its positions
+/// under a salt of its own, see [`super::pool::position_salt`], so wallets providing over the same range are attributed
+/// the fees of their own liquidity only. A range first marked is credited the fees it
+/// had earned since it was opened. Liquidity added to or removed from a range within a step earns at the liquidity of
+/// the previous mark, so fees of the step it changed in are approximate. Strategies and agents only appear once they
+/// deposit into a position.
+///
+/// On [`Inspector::save`], the points of every strategy and agent, and of each of their ranges, are written to a CSV
+/// file in long format if a destination was set with [`FeeInspector::with_save_data`]. Rows of a whole strategy or
+/// agent leave the ticks empty, and rows of a range leave the price movement empty. The inspector observes marks only,
+/// so logged values are dropped and cannot be inspected.
+#[derive(Debug, Clone)]
+pub struct FeeInspector<V = f64> {
+    /// Destination of the file written on save.
+    pub save_data: Option<SaveData>,
+
+    series: BTreeMap<String, Vec<FeePoint>>,
+    ranges: BTreeMap<String, Vec<RangeFees>>,
+    // open ranges of every strategy or agent as of its latest mark
+    previous: BTreeMap<String, Vec<RangeMark>>,
+    _value: PhantomData<fn(V)>,
+}
+
+impl<V> Default for FeeInspector<V> {
+    fn default() -> Self {
+        Self {
+            save_data: None,
+            series: BTreeMap::new(),
+            ranges: BTreeMap::new(),
+            previous: BTreeMap::new(),
+            _value: PhantomData,
+        }
+    }
+}
+
+impl<V> FeeInspector<V> {
+    /// Public constructor function for a new, empty [`FeeInspector`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write the points to `save_data` on save.
+    pub fn with_save_data(mut self, save_data: SaveData) -> Self {
+        self.save_data = Some(save_data);
+        self
+    }
+
+    /// Returns the points of the strategy or agent `name`, oldest first.
+    pub fn series(&self, name: &str) -> Option<&[FeePoint]> {
+        self.series.get(name).map(Vec::as_slice)
+    }
+
+    /// Returns the points of every range of the strategy or agent `name`, oldest first.
+    pub fn ranges(&self, name: &str) -> Option<&[RangeFees]> {
+        self.ranges.get(name).map(Vec::as_slice)
+    }
+
+    /// Record `mark`, taken at `step`.
+    pub fn record(&mut self, step: Option<usize>, mark: &AgentMark) {
+        let previous = self.previous.remove(&mark.name).unwrap_or_default();
+        if previous.is_empty() && mark.ranges.is_empty() && !self.series.contains_key(&mark.name) {
+            return;
+        }
+
+        let ranges = self.ranges.entry(mark.name.clone()).or_default();
+        let scale = |(amount0, amount1): (f64, f64)| {
+            (
+                amount0 * 10f64.powi(-(mark.decimals.0 as i32)),
+                amount1 * 10f64.powi(-(mark.decimals.1 as i32)),
+            )
+        };
+
+        let mut step_earned = (0.0, 0.0);
+        for range in &mark.ranges {
+            let (tick_lower, tick_upper) = (range.position.tick_lower, range.position.tick_upper);
+            let same = |other: &RangeMark| {
+                other.salt == range.salt
+                    && other.position.tick_lower == tick_lower
+                    && other.position.tick_upper == tick_upper
+            };
+
+            let earned = match previous.iter().find(|other| same(other)) {
+                Some(other) => scale(
+                    PositionRecord {
+                        position: other.position,
+                        fee_growth_inside: other.fee_growth_inside,
+                    }
+                    .fees_owed(range.fee_growth_inside),
+                ),
+                None => range.fees_owed,
+            };
+            step_earned = (step_earned.0 + earned.0, step_earned.1 + earned.1);
+
+            let (total, income) = ranges
+                .iter()
+                .rev()
+                .find(|fees| fees.tick_lower == tick_lower && fees.tick_upper == tick_upper)
+                .map_or(((0.0, 0.0), 0.0), |fees| (fees.earned, fees.income));
+            ranges.push(RangeFees {
+                step,
+                tick_lower,
+                tick_upper,
+                liquidity: range.position.liquidity,
+                earned: (total.0 + earned.0, total.1 + earned.1),
+                income: income + mark.value_of(earned),
+            });
+        }
+
+        let series = self.series.entry(mark.name.clone()).or_default();
+        let (earned, fee_income) = series
+            .last()
+            .map_or(((0.0, 0.0), 0.0), |last| (last.earned, last.fee_income));
+        series.push(FeePoint {
+            step,
+            earned: (earned.0 + step_earned.0, earned.1 + step_earned.1),
+            fee_income: fee_income + mark.value_of(step_earned),
+            price_movement: mark.value_of(mark.positions) - mark.value_of(mark.deposited),
+        });
+
+        self.previous.insert(mark.name.clone(), mark.ranges.clone());
+    }
+}
+
+impl<V: Send> Inspector<V> for FeeInspector<V> {
+    fn log(&mut self, _value: V) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    fn inspect(&self, _step: usize) -> Option<V> {
+        None
+    }
+
+    fn save(&self) -> Result<(), ArenaError> {
+        let Some(save_data) = &self.save_data else {
+            return Ok(());
+        };
+
+        let mut writer = csv::Writer::from_path(save_data.file_path("csv")?)?;
+        writer.write_record([
+            "step",
+            "agent",
+            "tick_lower",
+            "tick_upper",
+            "fees0",
+            "fees1",
+            "fee_income",
+            "price_movement",
+        ])?;
+        for (name, series) in &self.series {
+            for point in series {
+                writer.serialize((
+                    point.step,
+                    name,
+                    None::<i32>,
+                    None::<i32>,
+                    point.earned.0,
+                    point.earned.1,
+                    point.fee_income,
+                    Some(point.price_movement),
+                ))?;
+            }
+        }
+        for (name, ranges) in &self.ranges {
+            for fees in ranges {
+                writer.serialize((
+                    fees.step,
+                    name,
+                    Some(fees.tick_lower),
+                    Some(fees.tick_upper),
+                    fees.earned.0,
+                    fees.earned.1,
+                    fees.income,
+                    None::<f64>,
+                ))?;
+            }
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    fn marks_agents(&self) -> bool {
+        true
+    }
+
+    fn on_marks(&mut self, step: Option<usize>, marks: &[AgentMark]) -> Result<(), ArenaError> {
+        for mark in marks {
+            self.record(step, mark);
+        }
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), ArenaError> {
+        self.series.clear();
+        self.ranges.clear();
+        self.previous.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, B256, U256};
+
+    use super::*;
+    use crate::engine::position::Position;
+
+    #[test]
+    fn test_fee_inspector() {
+        let q128 = U256::from(1) << 128;
+        // a position of 1e18 liquidity, with fee growth inside its range in whole token1 per unit of liquidity
+        let range = |growth: u64, owed| RangeMark {
+            position: Position::new(-60, 60, 10u128.pow(18)),
+            salt: B256::ZERO,
+            fee_growth_inside: (U256::ZERO, q128 * U256::from(growth)),
+            fees_owed: (0.0, owed),
+        };
+        let mark = |price, positions, ranges| AgentMark {
+            name: "strategy 0".to_string(),
+            owner: Address::ZERO,
+            price,
+            balances: (0.0, 0.0),
+            positions,
+            fees_collected: (0.0, 0.0),
+            fees_owed: (0.0, 0.0),
+            deposited: (1.0, 1.0),
+            ranges,
+            decimals: (18, 18),
+        };
+        let mut inspector = FeeInspector::<f64>::new();
+
+        inspector.record(None, &mark(1.0, (1.0, 1.0), vec![range(5, 0.0)]));
+        inspector.record(Some(0), &mark(1.0, (1.0, 1.0), vec![range(7, 2.0)]));
+        // the price doubles and the range earns another token1
+        inspector.record(Some(1), &mark(2.0, (0.7, 1.4), vec![range(8, 3.0)]));
+        // the position is closed, realizing its fees
+        inspector.record(Some(2), &mark(2.0, (0.0, 0.0), Vec::new()));
+        inspector.record(
+            Some(2),
+            &AgentMark {
+                name: "agent 0".to_string(),
+                ..mark(2.0, (0.0, 0.0), Vec::new())
+            },
+        );
+
+        let series = inspector.series("strategy 0").unwrap();
+        assert_eq!(series[0].fee_income, 0.0);
+        assert_eq!(series[1].fee_income, 2.0);
+        assert_eq!(series[2].earned, (0.0, 3.0));
+        assert!((series[2].price_movement + 0.2).abs() < 1e-9);
+        assert!((series[2].net() - 2.8).abs() < 1e-9);
+        assert_eq!(series[3].fee_income, 3.0);
+
+        let ranges = inspector.ranges("strategy 0").unwrap();
+        assert_eq!(ranges.len(), 3);
+        assert_eq!(ranges[2].earned, (0.0, 3.0));
+        assert!(inspector.series("agent 0").is_none());
+    }
+}
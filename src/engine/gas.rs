@@ -0,0 +1,143 @@
+use std::cmp::Ordering;
+
+use rand::rngs::StdRng;
+
+use crate::feed::Feed;
+
+/// Bound on the relative change of the base fee between two blocks is one over this, as set by EIP-1559.
+pub const BASE_FEE_MAX_CHANGE_DENOMINATOR: u128 = 8;
+
+/// Source of the base fee of the chain, updated at the start of every step, see [`crate::Config::gas_market`].
+#[derive(Default)]
+pub enum GasMarket {
+    /// Leave the base fee to the node, which applies EIP-1559 to every block it mines.
+    #[default]
+    Node,
+
+    /// Apply the EIP-1559 update rule once per step, to the gas used by every block of the previous step.
+    Eip1559(Eip1559),
+
+    /// Set the base fee to the value of a feed, in gwei, advanced once per step.
+    Feed(Box<dyn Feed>),
+}
+
+impl GasMarket {
+    /// Returns the base fee of the next step, in wei, given the gas used by the previous step, or `None` if the node
+    /// sets it.
+    ///
+    /// `gas_used` is `None` at the first step of a run, which starts from the initial base fee.
+    pub fn next_base_fee(&mut self, gas_used: Option<u128>, rng: &mut StdRng) -> Option<u128> {
+        match self {
+            GasMarket::Node => None,
+            GasMarket::Eip1559(market) => {
+                if let Some(gas_used) = gas_used {
+                    market.update(gas_used);
+                }
+                Some(market.base_fee)
+            }
+            GasMarket::Feed(feed) => {
+                let gwei = match gas_used {
+                    Some(_) => feed.step(rng),
+                    None => feed.current_value(),
+                };
+                Some((gwei.max(0.0) * 1e9) as u128)
+            }
+        }
+    }
+
+    /// Returns a short description of the market.
+    pub fn describe(&self) -> String {
+        match self {
+            GasMarket::Node => "node".to_string(),
+            GasMarket::Eip1559(market) => format!(
+                "eip1559 base fee {} target gas {}",
+                market.base_fee, market.target_gas
+            ),
+            GasMarket::Feed(_) => "feed".to_string(),
+        }
+    }
+}
+
+/// EIP-1559 base fee dynamics at the granularity of a simulation step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Eip1559 {
+    /// Current base fee, in wei.
+    pub base_fee: u128,
+
+    /// Gas used per step at which the base fee stays constant.
+    pub target_gas: u128,
+
+    /// Floor of the base fee, in wei.
+    pub min_base_fee: u128,
+}
+
+impl Eip1559 {
+    /// Public constructor function for a new [`Eip1559`] market starting at `base_fee`, targeting `target_gas` per
+    /// step.
+    pub fn new(base_fee: u128, target_gas: u128) -> Self {
+        Self {
+            base_fee,
+            target_gas: target_gas.max(1),
+            min_base_fee: 0,
+        }
+    }
+
+    /// Set the floor of the base fee.
+    pub fn with_min_base_fee(mut self, min_base_fee: u128) -> Self {
+        self.min_base_fee = min_base_fee;
+        self
+    }
+
+    /// Move the base fee by the relative excess of `gas_used` over the target, bounded by
+    /// [`BASE_FEE_MAX_CHANGE_DENOMINATOR`], and return it.
+    pub fn update(&mut self, gas_used: u128) -> u128 {
+        // gas used beyond twice the target cannot fit in a block, and moves the fee by the maximum
+        let gas_used = gas_used.min(2 * self.target_gas);
+
+        self.base_fee = match gas_used.cmp(&self.target_gas) {
+            Ordering::Equal => self.base_fee,
+            Ordering::Greater => {
+                let delta = self.base_fee * (gas_used - self.target_gas)
+                    / self.target_gas
+                    / BASE_FEE_MAX_CHANGE_DENOMINATOR;
+                self.base_fee + delta.max(1)
+            }
+            Ordering::Less => {
+                let delta = self.base_fee * (self.target_gas - gas_used)
+                    / self.target_gas
+                    / BASE_FEE_MAX_CHANGE_DENOMINATOR;
+                self.base_fee - delta
+            }
+        }
+        .max(self.min_base_fee);
+
+        self.base_fee
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::feed::deterministic::ConstantFeed;
+
+    #[test]
+    fn test_eip1559() {
+        let mut market = Eip1559::new(800, 100);
+
+        assert_eq!(market.update(100), 800);
+        assert_eq!(market.update(1000), 900);
+        assert_eq!(market.update(0), 788);
+        assert_eq!(market.with_min_base_fee(780).update(0), 780);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut market = GasMarket::Eip1559(Eip1559::new(800, 100));
+        assert_eq!(market.next_base_fee(None, &mut rng), Some(800));
+        assert_eq!(market.next_base_fee(Some(200), &mut rng), Some(900));
+
+        let mut market = GasMarket::Feed(Box::new(ConstantFeed::new(1.5)));
+        assert_eq!(market.next_base_fee(Some(0), &mut rng), Some(1_500_000_000));
+        assert_eq!(GasMarket::Node.next_base_fee(None, &mut rng), None);
+    }
+}
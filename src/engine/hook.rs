@@ -0,0 +1,175 @@
+use alloy::{
+    dyn_abi::DynSolValue,
+    hex,
+    primitives::{address, keccak256, Address, Bytes, B256, U256},
+    rpc::types::TransactionRequest,
+};
+
+use super::*;
+use crate::{backend::Backend, config::HookConfig};
+
+/// Deterministic deployment proxy predeployed by Anvil, through which hooks, and every contract of a deployment at
+/// deterministic addresses, are deployed with `CREATE2`.
+const CREATE2_FACTORY: Address = address!("4e59b44847b379578588920ca78fbf26c0b4956c");
+
+/// Runtime code of the deterministic deployment proxy, installed if the node was started without it.
+const CREATE2_FACTORY_CODE: &str = "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe03601600081602082378035828234f58015156039578182fd5b8082525050506014600cf3";
+
+/// Mask of the low address bits the `PoolManager` reads hook permissions from.
+pub const HOOK_FLAGS_MASK: u16 = (1 << 14) - 1;
+
+/// Deploy the hook described by `hook` for the pools of `manager`, at an address encoding its permission flags.
+///
+/// Returns the address of the deployed hook.
+pub(crate) async fn deploy(
+    hook: &HookConfig,
+    manager: Address,
+    provider: AnvilProvider,
+) -> Result<Address, ArenaError> {
+    let init_code = hook.init_code(manager)?;
+
+    // skip addresses already taken, e.g. by a hook deployed with the same arguments in an earlier run
+    let mut from = 0;
+    let salt = loop {
+        let (salt, hook_address) = mine(hook.flags, &init_code, from);
+        if provider.get_code_at(hook_address).await?.is_empty() {
+            break salt;
+        }
+        from = U256::from_be_bytes(salt.0).to::<u64>() + 1;
+    };
+
+    create2(salt, &init_code, provider)
+        .await
+        .map_err(|e| match e {
+            ArenaError::Custom(_) => ArenaError::Custom(format!(
+                "hook {} reverted on deployment",
+                hook.artifact.display()
+            )),
+            e => e,
+        })
+}
+
+/// Deploy `init_code` through the deployment proxy with `salt`, installing the proxy if the node was started without
+/// it.
+///
+/// The address of the contract only depends on `salt` and `init_code`, not on the nonce of the deploying wallet.
+/// Returns the address of the deployed contract, or an error if its creation reverted or the address is taken.
+pub(crate) async fn create2(
+    salt: B256,
+    init_code: &[u8],
+    provider: AnvilProvider,
+) -> Result<Address, ArenaError> {
+    if provider.get_code_at(CREATE2_FACTORY).await?.is_empty() {
+        provider
+            .set_code(
+                CREATE2_FACTORY,
+                hex::decode(CREATE2_FACTORY_CODE).unwrap().into(),
+            )
+            .await?;
+    }
+
+    let address = create2_address(salt, init_code);
+    if !provider.get_code_at(address).await?.is_empty() {
+        return Err(ArenaError::Custom(format!("address {address} is taken")));
+    }
+
+    provider
+        .send_transaction(
+            TransactionRequest::default()
+                .to(CREATE2_FACTORY)
+                .input(Bytes::from([salt.as_slice(), init_code].concat()).into())
+                .nonce(
+                    provider
+                        .get_transaction_count(provider.default_signer_address())
+                        .await?,
+                ),
+        )
+        .await?
+        .watch()
+        .await?;
+
+    if provider.get_code_at(address).await?.is_empty() {
+        return Err(ArenaError::Custom(format!(
+            "deployment at {address} reverted"
+        )));
+    }
+
+    Ok(address)
+}
+
+/// Returns the address the deployment proxy creates `init_code` at with `salt`.
+pub(crate) fn create2_address(salt: B256, init_code: &[u8]) -> Address {
+    CREATE2_FACTORY.create2(salt, keccak256(init_code))
+}
+
+/// Returns the first salt from `from` at which the deployment proxy creates `init_code` at an address whose low bits
+/// equal `flags`, along with that address.
+pub(crate) fn mine(flags: u16, init_code: &[u8], from: u64) -> (B256, Address) {
+    let init_code_hash = keccak256(init_code);
+
+    (from..)
+        .map(|salt| B256::from(U256::from(salt)))
+        .map(|salt| (salt, CREATE2_FACTORY.create2(salt, init_code_hash)))
+        .find(|(_, address)| {
+            u16::from_be_bytes([address[18], address[19]]) & HOOK_FLAGS_MASK == flags
+        })
+        .unwrap()
+}
+
+impl HookConfig {
+    /// Returns the creation code of the hook for the pools of `manager`, its constructor arguments appended.
+    pub(crate) fn init_code(&self, manager: Address) -> Result<Vec<u8>, ArenaError> {
+        let artifact: serde_json::Value = serde_json::from_slice(&std::fs::read(&self.artifact)?)?;
+
+        // foundry nests the bytecode under `object`, hardhat stores it directly
+        let bytecode = artifact
+            .pointer("/bytecode/object")
+            .or_else(|| artifact.get("bytecode"))
+            .and_then(|bytecode| bytecode.as_str())
+            .ok_or_else(|| {
+                ArenaError::Custom(format!(
+                    "no bytecode in hook artifact {}",
+                    self.artifact.display()
+                ))
+            })?;
+        let mut init_code = hex::decode(bytecode).map_err(|e| {
+            ArenaError::Custom(format!(
+                "invalid bytecode in hook artifact {}: {e}",
+                self.artifact.display()
+            ))
+        })?;
+
+        let mut arguments = vec![DynSolValue::Address(manager)];
+        arguments.extend(self.constructor_args.iter().cloned());
+        init_code.extend(DynSolValue::Tuple(arguments).abi_encode_params());
+
+        Ok(init_code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mine() {
+        let init_code = [0x60, 0x00, 0x60, 0x00, 0xf3];
+
+        for flags in [0, 1 << 7, HOOK_FLAGS_MASK] {
+            let (salt, address) = mine(flags, &init_code, 0);
+
+            assert_eq!(
+                u16::from_be_bytes([address[18], address[19]]) & HOOK_FLAGS_MASK,
+                flags
+            );
+            assert_eq!(address, CREATE2_FACTORY.create2(salt, keccak256(init_code)));
+
+            let (next, _) = mine(
+                flags,
+                &init_code,
+                U256::from_be_bytes(salt.0).to::<u64>() + 1,
+            );
+            assert!(next > salt);
+        }
+    }
+}
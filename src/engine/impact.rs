@@ -0,0 +1,331 @@
+use std::marker::PhantomData;
+
+use alloy::primitives::U256;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    events::PoolEvent,
+    inspector::{Inspector, SaveData},
+};
+use crate::{error::ArenaError, Signal};
+
+/// Execution of a single swap against the mid price of the pool before it, recorded by a [`PriceImpactInspector`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SwapImpact {
+    /// Step the swap was observed at.
+    pub step: usize,
+
+    /// Block the swap was mined in.
+    pub block: u64,
+
+    /// Whether the swapper bought token0.
+    pub buy: bool,
+
+    /// Size of the swap, in whole units of token1.
+    pub size: f64,
+
+    /// Price of the pool before the swap, in whole units of token1 per whole unit of token0.
+    pub mid: f64,
+
+    /// Price the swap executed at, fee included, in whole units of token1 per whole unit of token0.
+    pub execution: f64,
+
+    /// Fee charged on the swap, as a fraction.
+    pub fee: f64,
+}
+
+impl SwapImpact {
+    /// Returns the cost of the swap against the mid price, fee included, as a fraction of the mid price: positive when
+    /// the swapper paid more than the mid price to buy or received less to sell.
+    pub fn impact(&self) -> f64 {
+        if self.buy {
+            self.execution / self.mid - 1.0
+        } else {
+            1.0 - self.execution / self.mid
+        }
+    }
+}
+
+/// Price impact of the swaps within a range of sizes, a point of the curve reported by a [`PriceImpactInspector`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ImpactBucket {
+    /// Smallest size of the bucket, inclusive, in whole units of token1.
+    pub size_low: f64,
+
+    /// Largest size of the bucket, exclusive except for the last bucket, in whole units of token1.
+    pub size_high: f64,
+
+    /// Number of swaps in the bucket.
+    pub count: usize,
+
+    /// Mean size of the swaps in the bucket.
+    pub mean_size: f64,
+
+    /// Mean impact of the swaps in the bucket, see [`SwapImpact::impact`].
+    pub mean_impact: f64,
+
+    /// Median impact of the swaps in the bucket.
+    pub median_impact: f64,
+
+    /// Largest impact of the swaps in the bucket.
+    pub max_impact: f64,
+}
+
+/// Implementation of an [`Inspector`] recording the execution price of every swap on the pool against its mid price
+/// before the swap, reporting the price impact by swap size.
+///
+/// The mid price before a swap is the price the pool was left at by the previous swap, or at the end of the previous
+/// step, so swaps observed at the first step are not recorded. The execution price includes the fee, so impacts measure the
+/// effective spread paid by swappers.
+///
+/// On [`Inspector::save`], the impact curve is written to a CSV file if a destination was set with
+/// [`PriceImpactInspector::with_save_data`], one row per bucket: swaps are bucketed by size over `buckets` buckets
+/// spaced evenly in log size between the smallest and largest swap. The inspector observes swaps only, so logged values
+/// are dropped and cannot be inspected.
+#[derive(Debug, Clone)]
+pub struct PriceImpactInspector<V = f64> {
+    /// Number of size buckets of the impact curve.
+    pub buckets: usize,
+
+    /// Destination of the file written on save.
+    pub save_data: Option<SaveData>,
+
+    swaps: Vec<SwapImpact>,
+    // sqrt price x96 of the pool at the end of the previous step
+    mid: Option<U256>,
+    _value: PhantomData<fn(V)>,
+}
+
+impl<V> Default for PriceImpactInspector<V> {
+    fn default() -> Self {
+        Self {
+            buckets: 10,
+            save_data: None,
+            swaps: Vec::new(),
+            mid: None,
+            _value: PhantomData,
+        }
+    }
+}
+
+impl<V> PriceImpactInspector<V> {
+    /// Public constructor function for a new, empty [`PriceImpactInspector`] reporting an impact curve of 10 buckets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report an impact curve of `buckets` buckets.
+    pub fn with_buckets(mut self, buckets: usize) -> Self {
+        self.buckets = buckets.max(1);
+        self
+    }
+
+    /// Write the impact curve to `save_data` on save.
+    pub fn with_save_data(mut self, save_data: SaveData) -> Self {
+        self.save_data = Some(save_data);
+        self
+    }
+
+    /// Returns the swaps recorded so far, oldest first.
+    pub fn swaps(&self) -> &[SwapImpact] {
+        &self.swaps
+    }
+
+    /// Returns the impact curve of the swaps recorded so far, by increasing size, skipping empty buckets.
+    pub fn curve(&self) -> Vec<ImpactBucket> {
+        let sizes = self
+            .swaps
+            .iter()
+            .map(|swap| swap.size)
+            .filter(|size| *size > 0.0);
+        let (low, high) = sizes.fold((f64::INFINITY, 0.0f64), |(low, high), size| {
+            (low.min(size), high.max(size))
+        });
+        if low > high {
+            return Vec::new();
+        }
+
+        let width = (high / low).ln() / self.buckets as f64;
+        let edge = |idx: usize| low * (width * idx as f64).exp();
+        let mut buckets = vec![Vec::new(); self.buckets];
+        for swap in self.swaps.iter().filter(|swap| swap.size > 0.0) {
+            let idx = if width > 0.0 {
+                ((swap.size / low).ln() / width) as usize
+            } else {
+                0
+            };
+            buckets[idx.min(self.buckets - 1)].push(swap);
+        }
+
+        buckets
+            .into_iter()
+            .enumerate()
+            .filter(|(_, swaps)| !swaps.is_empty())
+            .map(|(idx, swaps)| {
+                let count = swaps.len();
+                let mut impacts: Vec<f64> = swaps.iter().map(|swap| swap.impact()).collect();
+                impacts.sort_by(f64::total_cmp);
+                let median = if count % 2 == 1 {
+                    impacts[count / 2]
+                } else {
+                    (impacts[count / 2 - 1] + impacts[count / 2]) / 2.0
+                };
+
+                ImpactBucket {
+                    size_low: edge(idx),
+                    size_high: if idx + 1 == self.buckets {
+                        high
+                    } else {
+                        edge(idx + 1)
+                    },
+                    count,
+                    mean_size: swaps.iter().map(|swap| swap.size).sum::<f64>() / count as f64,
+                    mean_impact: impacts.iter().sum::<f64>() / count as f64,
+                    median_impact: median,
+                    max_impact: impacts[count - 1],
+                }
+            })
+            .collect()
+    }
+
+    /// Record the swaps among `events`, observed at `step` on a pool with `decimals`, starting from a pool price of
+    /// `mid`, a sqrt price x96. Returns the pool price the events leave behind.
+    fn record(
+        &mut self,
+        step: usize,
+        events: &[PoolEvent],
+        decimals: (u8, u8),
+        mut mid: U256,
+    ) -> U256 {
+        let scale = |raw: f64, decimals: u8| raw * 10f64.powi(-(decimals as i32));
+        let price = |sqrt_price_x96: U256| {
+            (f64::from(sqrt_price_x96) / 2f64.powi(96)).powi(2)
+                * 10f64.powi(decimals.0 as i32 - decimals.1 as i32)
+        };
+
+        for event in events {
+            let PoolEvent::Swap {
+                block,
+                amount0,
+                amount1,
+                sqrt_price_x96,
+                fee,
+                ..
+            } = event
+            else {
+                continue;
+            };
+
+            let (amount0, amount1) = (
+                scale(*amount0 as f64, decimals.0),
+                scale(*amount1 as f64, decimals.1),
+            );
+            if amount0 != 0.0 && !mid.is_zero() {
+                self.swaps.push(SwapImpact {
+                    step,
+                    block: *block,
+                    buy: amount0 > 0.0,
+                    size: amount1.abs(),
+                    mid: price(mid),
+                    execution: (amount1 / amount0).abs(),
+                    fee: *fee as f64 / 1e6,
+                });
+            }
+            mid = *sqrt_price_x96;
+        }
+
+        mid
+    }
+}
+
+impl<V: Send> Inspector<V> for PriceImpactInspector<V> {
+    fn log(&mut self, _value: V) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    fn inspect(&self, _step: usize) -> Option<V> {
+        None
+    }
+
+    fn save(&self) -> Result<(), ArenaError> {
+        let Some(save_data) = &self.save_data else {
+            return Ok(());
+        };
+
+        let mut writer = csv::Writer::from_path(save_data.file_path("csv")?)?;
+        for bucket in self.curve() {
+            writer.serialize(bucket)?;
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    fn on_step(&mut self, signal: &Signal) -> Result<(), ArenaError> {
+        let Some(step) = signal.step else {
+            return Ok(());
+        };
+
+        if let Some(mid) = self.mid {
+            self.record(step, &signal.events, signal.decimals, mid);
+        }
+        self.mid = Some(U256::from(signal.sqrt_price_x96));
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), ArenaError> {
+        self.swaps.clear();
+        self.mid = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::Address;
+
+    use super::*;
+
+    #[test]
+    fn test_price_impact_inspector() {
+        let one = U256::from(1u128 << 96);
+        let swap = |amount0: i128, amount1: i128, sqrt_price_x96| PoolEvent::Swap {
+            block: 1,
+            sender: Address::ZERO,
+            amount0,
+            amount1,
+            sqrt_price_x96,
+            liquidity: 0,
+            tick: 0,
+            fee: 3000,
+        };
+        let mut inspector = PriceImpactInspector::<f64>::new().with_buckets(2);
+
+        // buying 1 token0 for 1.01 token1 at a mid of 1, then selling 100 token0 for 90 token1 at a mid of 1.21
+        let events = vec![
+            swap(
+                10i128.pow(18),
+                -(101 * 10i128.pow(16)),
+                one * U256::from(11) / U256::from(10),
+            ),
+            swap(-(100 * 10i128.pow(18)), 90 * 10i128.pow(18), one),
+        ];
+        let mid = inspector.record(0, &events, (18, 18), one);
+        assert_eq!(mid, one);
+
+        let swaps = inspector.swaps();
+        assert!(swaps[0].buy && !swaps[1].buy);
+        assert!((swaps[0].impact() - 0.01).abs() < 1e-9);
+        assert!((swaps[1].mid - 1.21).abs() < 1e-9);
+        assert!((swaps[1].impact() - (1.0 - 0.9 / 1.21)).abs() < 1e-9);
+        assert_eq!(swaps[1].fee, 0.003);
+
+        let curve = inspector.curve();
+        assert_eq!(curve.len(), 2);
+        assert!((curve[0].size_low - 1.01).abs() < 1e-9);
+        assert_eq!(curve[1].size_high, 90.0);
+        assert_eq!(curve[1].count, 1);
+        assert!(curve[1].max_impact > curve[0].max_impact);
+    }
+}
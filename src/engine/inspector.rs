@@ -1,17 +1,104 @@
-use std::{fs::OpenOptions, io::Seek};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{Seek, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex, PoisonError},
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
+use futures::executor::block_on;
 use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+use crate::{
+    engine::{
+        arbitrageur::ArbitrageFill, events::ContractLog, math::LiquidityProfile, pnl::AgentMark,
+    },
+    error::ArenaError,
+    Signal,
+};
 
 /// Trait allowing custom behavior to be defined for logging and inspecting values.
-pub trait Inspector<V> {
+pub trait Inspector<V>: Send {
     /// Log a value to state.
-    fn log(&mut self, value: V);
+    fn log(&mut self, value: V) -> Result<(), ArenaError>;
 
     /// Inspect a value at a given time step.
     fn inspect(&self, step: usize) -> Option<V>;
 
     /// Save the inspector state.
-    fn save(&self);
+    fn save(&self) -> Result<(), ArenaError>;
+
+    /// Observe the state of the pool at the end of a simulation step.
+    fn on_step(&mut self, _signal: &Signal) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    /// Observe a fill of the arbitrageur on the pool at index `pool`, the pool of the [`crate::Config`] being 0.
+    fn on_arbitrage(&mut self, _pool: usize, _fill: &ArbitrageFill) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    /// Returns whether the arena marks every participant's wallet for [`Inspector::on_marks`], which costs a few calls
+    /// to the node per wallet and step.
+    fn marks_agents(&self) -> bool {
+        false
+    }
+
+    /// Observe the holdings of every strategy, agent, the arbitrageur and every noise trader at the end of a step, or
+    /// after initialization if `step` is `None`. Only called if [`Inspector::marks_agents`] returns true.
+    fn on_marks(&mut self, _step: Option<usize>, _marks: &[AgentMark]) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    /// Returns the number of tick bitmap words on either side of the current tick the arena reads the liquidity of the
+    /// pool over for [`Inspector::on_liquidity`] at the end of `step`, or `None` to skip the step. Every word costs a
+    /// call to the node, plus one per initialized tick in it.
+    fn liquidity_words(&self, _step: usize) -> Option<i16> {
+        None
+    }
+
+    /// Observe the liquidity of the pool at the end of a step, read over the words returned by
+    /// [`Inspector::liquidity_words`]. Only called if it returns some.
+    fn on_liquidity(
+        &mut self,
+        _step: usize,
+        _profile: &LiquidityProfile,
+    ) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    /// Returns whether the arena captures the logs of the contracts for [`Inspector::on_logs`], which costs a call to
+    /// the node per step.
+    fn captures_logs(&self) -> bool {
+        false
+    }
+
+    /// Observe the logs the `PoolManager` and the pool tokens emitted since the previous capture, from the deployment
+    /// of the pools on, at the end of a step, or after initialization if `step` is `None`. Only called if
+    /// [`Inspector::captures_logs`] returns true.
+    fn on_logs(&mut self, _step: Option<usize>, _logs: &[ContractLog]) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    /// Record that a simulation component panicked, with the step at which it did.
+    fn on_failure(
+        &mut self,
+        _step: Option<usize>,
+        _component: &str,
+        _message: &str,
+    ) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    /// Forget the values logged and the observations made in previous runs, before a run reverts the chain to a
+    /// deployment reused across runs, see [`crate::arena::ArenaBuilder::with_deployment_reuse`].
+    fn reset(&mut self) -> Result<(), ArenaError> {
+        Ok(())
+    }
 }
 
 /// Type that allows for logging indexed values to files on disc.
@@ -72,7 +159,7 @@ impl Logger {
     }
 
     /// Append a log message to the appropriate file format.
-    fn append_to_file(&self, record: &LogMessage) -> Result<(), Box<dyn std::error::Error>> {
+    fn append_to_file(&self, record: &LogMessage) -> Result<(), ArenaError> {
         let mut file = OpenOptions::new()
             .append(true)
             .create(true)
@@ -101,21 +188,627 @@ impl Logger {
 }
 
 impl Inspector<LogMessage> for Logger {
-    fn log(&mut self, mut value: LogMessage) {
+    fn log(&mut self, mut value: LogMessage) -> Result<(), ArenaError> {
         value.id = self.counter;
         self.counter += 1;
         self.values.push(value.clone());
 
-        if let Err(e) = self.append_to_file(&value) {
-            eprintln!("Failed to append to file: {}", e);
-        }
+        self.append_to_file(&value)
     }
 
     fn inspect(&self, step: usize) -> Option<LogMessage> {
         self.values.get(step).cloned()
     }
 
-    fn save(&self) {}
+    fn save(&self) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), ArenaError> {
+        // messages already appended to the file are kept, as in any log, so their ids keep counting up
+        self.values.clear();
+        Ok(())
+    }
+}
+
+/// Destination of the file an inspector writes on [`Inspector::save`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SaveData {
+    /// Write to the file `name` in the directory `path`, replacing it if it exists.
+    ToFile {
+        /// Directory of the file, created if missing.
+        path: PathBuf,
+
+        /// Name of the file, without its extension.
+        name: String,
+    },
+
+    /// Write to a new file in the directory `path`, named `name` followed by the first numeric suffix not taken, so
+    /// the files of earlier saves are kept.
+    ToNewFile {
+        /// Directory of the file, created if missing.
+        path: PathBuf,
+
+        /// Name of the file, without its extension.
+        name: String,
+    },
+}
+
+impl SaveData {
+    /// Returns the path of the file to write, with the given extension, creating its directory if missing.
+    pub fn file_path(&self, extension: &str) -> Result<PathBuf, ArenaError> {
+        let (SaveData::ToFile { path, name } | SaveData::ToNewFile { path, name }) = self;
+        fs::create_dir_all(path)?;
+
+        let file_path = match self {
+            SaveData::ToFile { .. } => path.join(format!("{name}.{extension}")),
+            SaveData::ToNewFile { .. } => (0..)
+                .map(|idx| path.join(format!("{name}_{idx}.{extension}")))
+                .find(|file_path| !file_path.exists())
+                .unwrap(),
+        };
+
+        Ok(file_path)
+    }
+}
+
+/// Column holding the step of a row written by a [`CsvInspector`].
+#[derive(Serialize)]
+struct StepColumn {
+    step: usize,
+}
+
+/// Implementation of an [`Inspector`] keeping one row per step and writing them to a CSV file on
+/// [`Inspector::save`].
+///
+/// Rows are keyed by the step they were logged at, and a value logged again during the same step replaces the row of
+/// that step. `T` must serialize to a struct, whose fields name the columns after a leading `step` column.
+#[derive(Debug, Clone)]
+pub struct CsvInspector<T> {
+    /// Destination of the file written on save.
+    pub save_data: SaveData,
+
+    rows: Vec<(usize, T)>,
+    step: usize,
+}
+
+impl<T> CsvInspector<T> {
+    /// Public constructor function for a new [`CsvInspector`] writing to `save_data`.
+    pub fn new(save_data: SaveData) -> Self {
+        Self {
+            save_data,
+            rows: Vec::new(),
+            step: 0,
+        }
+    }
+
+    /// Returns the rows logged so far, with their steps.
+    pub fn rows(&self) -> &[(usize, T)] {
+        &self.rows
+    }
+}
+
+impl<T: Serialize + Clone + Send> Inspector<T> for CsvInspector<T> {
+    fn log(&mut self, value: T) -> Result<(), ArenaError> {
+        match self.rows.last_mut() {
+            Some((step, row)) if *step == self.step => *row = value,
+            _ => self.rows.push((self.step, value)),
+        }
+
+        Ok(())
+    }
+
+    fn inspect(&self, step: usize) -> Option<T> {
+        self.rows
+            .iter()
+            .find(|(row_step, _)| *row_step == step)
+            .map(|(_, row)| row.clone())
+    }
+
+    fn save(&self) -> Result<(), ArenaError> {
+        let mut writer = csv::Writer::from_path(self.save_data.file_path("csv")?)?;
+        for (step, row) in &self.rows {
+            writer.serialize((StepColumn { step: *step }, row))?;
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    fn on_step(&mut self, signal: &Signal) -> Result<(), ArenaError> {
+        // values logged after the end of a step belong to the next one
+        if let Some(step) = signal.step {
+            self.step = step + 1;
+        }
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), ArenaError> {
+        self.rows.clear();
+        self.step = 0;
+        Ok(())
+    }
+}
+
+/// Line written by an [`NdjsonInspector`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NdjsonLine<T> {
+    /// Step the value was logged at.
+    pub step: usize,
+
+    /// Time the value was logged at, in milliseconds since the Unix epoch.
+    pub timestamp: u128,
+
+    /// The logged value.
+    pub value: T,
+}
+
+/// Implementation of an [`Inspector`] writing logged values as newline-delimited JSON, one [`NdjsonLine`] per value,
+/// e.g. to be piped into `jq`.
+///
+/// Values are buffered and appended to the file every [`NdjsonInspector::flush_interval`] values and on
+/// [`Inspector::save`], so memory stays bounded over long runs. Only buffered values can be inspected.
+#[derive(Debug)]
+pub struct NdjsonInspector<T> {
+    /// Number of buffered values at which they are written to the file.
+    pub flush_interval: usize,
+
+    file: File,
+    pending: RefCell<Vec<NdjsonLine<T>>>,
+    step: usize,
+}
+
+impl<T> NdjsonInspector<T> {
+    /// Public constructor function for a new [`NdjsonInspector`] writing to `save_data`, flushing every 1000 values.
+    ///
+    /// The file is created right away, replacing any file at the same path.
+    pub fn new(save_data: &SaveData) -> Result<Self, ArenaError> {
+        Ok(Self {
+            flush_interval: 1000,
+            file: File::create(save_data.file_path("ndjson")?)?,
+            pending: RefCell::new(Vec::new()),
+            step: 0,
+        })
+    }
+
+    /// Write buffered values every `flush_interval` values.
+    pub fn with_flush_interval(mut self, flush_interval: usize) -> Self {
+        self.flush_interval = flush_interval.max(1);
+        self
+    }
+}
+
+impl<T: Serialize> NdjsonInspector<T> {
+    /// Append the buffered values to the file and clear the buffer.
+    fn flush(&self) -> Result<(), ArenaError> {
+        let mut buffer = Vec::new();
+        for line in self.pending.borrow_mut().drain(..) {
+            serde_json::to_writer(&mut buffer, &line)?;
+            buffer.push(b'\n');
+        }
+
+        (&self.file).write_all(&buffer)?;
+        Ok(())
+    }
+}
+
+impl<T: Serialize + Clone + Send> Inspector<T> for NdjsonInspector<T> {
+    fn log(&mut self, value: T) -> Result<(), ArenaError> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        self.pending.get_mut().push(NdjsonLine {
+            step: self.step,
+            timestamp,
+            value,
+        });
+
+        if self.pending.get_mut().len() >= self.flush_interval {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn inspect(&self, step: usize) -> Option<T> {
+        self.pending
+            .borrow()
+            .iter()
+            .rev()
+            .find(|line| line.step == step)
+            .map(|line| line.value.clone())
+    }
+
+    fn save(&self) -> Result<(), ArenaError> {
+        self.flush()
+    }
+
+    fn on_step(&mut self, signal: &Signal) -> Result<(), ArenaError> {
+        if let Some(step) = signal.step {
+            self.step = step + 1;
+        }
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), ArenaError> {
+        // the lines of previous runs are truncated away, so the file holds the latest run only
+        self.pending.get_mut().clear();
+        self.file.set_len(0)?;
+        self.file.rewind()?;
+        self.step = 0;
+        Ok(())
+    }
+}
+
+/// Value forwarded by a [`BroadcastInspector`], along with the step it was logged at.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Streamed<T> {
+    /// Step the value was logged at.
+    pub step: usize,
+
+    /// The logged value.
+    pub value: T,
+}
+
+/// Implementation of an [`Inspector`] forwarding every logged value over a [`broadcast`] channel, so dashboards, live
+/// plots or other processes can follow the output of a run while it is in progress.
+///
+/// Values logged while nobody is subscribed are dropped, and subscribers lagging behind by more than the capacity of
+/// the channel miss the oldest values, as with any [`broadcast`] channel. The latest value can be inspected.
+#[derive(Debug)]
+pub struct BroadcastInspector<T> {
+    sender: broadcast::Sender<Streamed<T>>,
+    latest: Option<Streamed<T>>,
+    step: usize,
+}
+
+impl<T: Clone> BroadcastInspector<T> {
+    /// Public constructor function for a new [`BroadcastInspector`] over a channel holding up to `capacity` values per
+    /// subscriber.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            sender: broadcast::channel(capacity.max(1)).0,
+            latest: None,
+            step: 0,
+        }
+    }
+
+    /// Returns a receiver of the values logged from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<Streamed<T>> {
+        self.sender.subscribe()
+    }
+
+    /// Returns the sending half of the channel, from which receivers can be created once the inspector is moved into an
+    /// arena.
+    pub fn sender(&self) -> broadcast::Sender<Streamed<T>> {
+        self.sender.clone()
+    }
+}
+
+impl<T: Clone + Send> Inspector<T> for BroadcastInspector<T> {
+    fn log(&mut self, value: T) -> Result<(), ArenaError> {
+        let streamed = Streamed {
+            step: self.step,
+            value,
+        };
+
+        // sending only fails without subscribers, whose values are not kept
+        let _ = self.sender.send(streamed.clone());
+        self.latest = Some(streamed);
+
+        Ok(())
+    }
+
+    fn inspect(&self, step: usize) -> Option<T> {
+        self.latest
+            .as_ref()
+            .filter(|latest| latest.step == step)
+            .map(|latest| latest.value.clone())
+    }
+
+    fn save(&self) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    fn on_step(&mut self, signal: &Signal) -> Result<(), ArenaError> {
+        if let Some(step) = signal.step {
+            self.step = step + 1;
+        }
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), ArenaError> {
+        self.latest = None;
+        self.step = 0;
+        Ok(())
+    }
+}
+
+/// What a [`BufferedInspector`] does with a logged value when its buffer is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Backpressure {
+    /// Wait for the background thread to make room, slowing the simulation down to the pace of the inspector.
+    #[default]
+    Block,
+
+    /// Drop the value, counted by [`BufferedInspector::dropped`].
+    Drop,
+}
+
+/// Call forwarded by a [`BufferedInspector`] to its background thread.
+enum Message<V> {
+    Log(V),
+    Step(Box<Signal>),
+    Arbitrage(usize, ArbitrageFill),
+    Marks(Option<usize>, Vec<AgentMark>),
+    Liquidity(usize, LiquidityProfile),
+    Logs(Option<usize>, Vec<ContractLog>),
+    Failure(Option<usize>, String, String),
+    Reset,
+    Inspect(usize, oneshot::Sender<Option<V>>),
+    Save(oneshot::Sender<Result<(), ArenaError>>),
+}
+
+/// Implementation of an [`Inspector`] running another inspector on a background thread behind a bounded buffer, so
+/// logging a value does not wait for the work the inspector does with it, such as writing to a file.
+///
+/// Every call is forwarded to the inner inspector in order. When the buffer is full, logged values are handled
+/// according to the [`Backpressure`] policy, while the other calls always wait for room so the inner inspector observes
+/// every step. Calls returning a value, such as [`Inspector::inspect`], wait for the calls buffered before them, and
+/// [`Inspector::save`] flushes the buffer before saving the inner inspector.
+///
+/// Like [`Inspector::marks_agents`] and [`Inspector::captures_logs`], which are read once at construction, the calls
+/// the arena makes every step avoid the round trip through the buffer: [`Inspector::liquidity_words`] only depends on
+/// the step and the configuration of the inner inspector, so it is answered directly, and [`Inspector::inspect`]
+/// answers are cached until the next call changes the inner inspector.
+///
+/// An error returned by the inner inspector is returned by the next call to the buffered inspector, and later errors
+/// are dropped until then. Dropping the buffered inspector waits for the buffered calls to be handled.
+pub struct BufferedInspector<V> {
+    /// Handling of logged values when the buffer is full.
+    pub backpressure: Backpressure,
+
+    sender: Option<mpsc::Sender<Message<V>>>,
+    worker: Option<thread::JoinHandle<()>>,
+    inner: Arc<Mutex<dyn Inspector<V>>>,
+    error: Arc<Mutex<Option<ArenaError>>>,
+    marks_agents: bool,
+    captures_logs: bool,
+    inspected: Mutex<HashMap<usize, Option<V>>>,
+    dropped: usize,
+}
+
+impl<V: Send + 'static> BufferedInspector<V> {
+    /// Public constructor function for a new [`BufferedInspector`] running `inner` behind a buffer of `capacity` calls,
+    /// blocking when it is full.
+    pub fn new(inner: impl Inspector<V> + 'static, capacity: usize) -> Self {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        let error = Arc::new(Mutex::new(None));
+        let (marks_agents, captures_logs) = (inner.marks_agents(), inner.captures_logs());
+        let inner: Arc<Mutex<dyn Inspector<V>>> = Arc::new(Mutex::new(inner));
+
+        let worker = {
+            let (inner, error) = (inner.clone(), error.clone());
+            thread::spawn(move || Self::work(inner, receiver, error))
+        };
+
+        Self {
+            backpressure: Backpressure::Block,
+            sender: Some(sender),
+            worker: Some(worker),
+            inner,
+            error,
+            marks_agents,
+            captures_logs,
+            inspected: Mutex::new(HashMap::new()),
+            dropped: 0,
+        }
+    }
+
+    /// Handle logged values with `backpressure` when the buffer is full.
+    pub fn with_backpressure(mut self, backpressure: Backpressure) -> Self {
+        self.backpressure = backpressure;
+        self
+    }
+
+    /// Returns the number of logged values dropped because the buffer was full.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Handle the calls received by the background thread until the buffered inspector is dropped.
+    fn work(
+        inner: Arc<Mutex<dyn Inspector<V>>>,
+        mut receiver: mpsc::Receiver<Message<V>>,
+        error: Arc<Mutex<Option<ArenaError>>>,
+    ) {
+        while let Some(message) = receiver.blocking_recv() {
+            let mut inner = inner.lock().unwrap_or_else(PoisonError::into_inner);
+            let result = match message {
+                Message::Log(value) => inner.log(value),
+                Message::Step(signal) => inner.on_step(&signal),
+                Message::Arbitrage(pool, fill) => inner.on_arbitrage(pool, &fill),
+                Message::Marks(step, marks) => inner.on_marks(step, &marks),
+                Message::Liquidity(step, profile) => inner.on_liquidity(step, &profile),
+                Message::Logs(step, logs) => inner.on_logs(step, &logs),
+                Message::Failure(step, component, message) => {
+                    inner.on_failure(step, &component, &message)
+                }
+                Message::Reset => inner.reset(),
+                Message::Inspect(step, reply) => {
+                    let _ = reply.send(inner.inspect(step));
+                    Ok(())
+                }
+                Message::Save(reply) => {
+                    let _ = reply.send(inner.save());
+                    Ok(())
+                }
+            };
+
+            if let Err(e) = result {
+                error
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .get_or_insert(e);
+            }
+        }
+    }
+
+    /// Returns the error of the inner inspector pending since the previous call, if any.
+    fn check(&self) -> Result<(), ArenaError> {
+        match self
+            .error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+        {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Forget the cached answers of [`Inspector::inspect`], before a call that changes the inner inspector.
+    fn invalidate(&self) {
+        self.inspected
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// Forward `message` to the background thread, waiting for room in the buffer.
+    fn send(&self, message: Message<V>) -> Result<(), ArenaError> {
+        self.check()?;
+        self.invalidate();
+        block_on(self.sender.as_ref().unwrap().send(message))
+            .map_err(|_| ArenaError::Custom("buffered inspector thread stopped".to_string()))
+    }
+
+    /// Forward a call returning a value to the background thread, and wait for its reply.
+    fn request<T>(&self, message: impl FnOnce(oneshot::Sender<T>) -> Message<V>) -> Option<T> {
+        let (reply, response) = oneshot::channel();
+        self.check().ok()?;
+        block_on(self.sender.as_ref().unwrap().send(message(reply))).ok()?;
+        block_on(response).ok()
+    }
+}
+
+impl<V: Clone + Send + 'static> Inspector<V> for BufferedInspector<V> {
+    fn log(&mut self, value: V) -> Result<(), ArenaError> {
+        if self.backpressure == Backpressure::Block {
+            return self.send(Message::Log(value));
+        }
+
+        self.check()?;
+        self.invalidate();
+        match self.sender.as_ref().unwrap().try_send(Message::Log(value)) {
+            Ok(()) => Ok(()),
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                self.dropped += 1;
+                Ok(())
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => Err(ArenaError::Custom(
+                "buffered inspector thread stopped".to_string(),
+            )),
+        }
+    }
+
+    fn inspect(&self, step: usize) -> Option<V> {
+        if let Some(value) = self
+            .inspected
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&step)
+        {
+            return value.clone();
+        }
+
+        let value = self
+            .request(|reply| Message::Inspect(step, reply))
+            .flatten();
+        self.inspected
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(step, value.clone());
+
+        value
+    }
+
+    fn save(&self) -> Result<(), ArenaError> {
+        self.request(Message::Save).unwrap_or_else(|| {
+            self.check()?;
+            Err(ArenaError::Custom(
+                "buffered inspector thread stopped".to_string(),
+            ))
+        })?;
+
+        // errors of calls handled before the save
+        self.check()
+    }
+
+    fn on_step(&mut self, signal: &Signal) -> Result<(), ArenaError> {
+        self.send(Message::Step(Box::new(signal.clone())))
+    }
+
+    fn on_arbitrage(&mut self, pool: usize, fill: &ArbitrageFill) -> Result<(), ArenaError> {
+        self.send(Message::Arbitrage(pool, *fill))
+    }
+
+    fn marks_agents(&self) -> bool {
+        self.marks_agents
+    }
+
+    fn on_marks(&mut self, step: Option<usize>, marks: &[AgentMark]) -> Result<(), ArenaError> {
+        self.send(Message::Marks(step, marks.to_vec()))
+    }
+
+    fn liquidity_words(&self, step: usize) -> Option<i16> {
+        self.inner
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .liquidity_words(step)
+    }
+
+    fn on_liquidity(&mut self, step: usize, profile: &LiquidityProfile) -> Result<(), ArenaError> {
+        self.send(Message::Liquidity(step, profile.clone()))
+    }
+
+    fn captures_logs(&self) -> bool {
+        self.captures_logs
+    }
+
+    fn on_logs(&mut self, step: Option<usize>, logs: &[ContractLog]) -> Result<(), ArenaError> {
+        self.send(Message::Logs(step, logs.to_vec()))
+    }
+
+    fn on_failure(
+        &mut self,
+        step: Option<usize>,
+        component: &str,
+        message: &str,
+    ) -> Result<(), ArenaError> {
+        self.send(Message::Failure(
+            step,
+            component.to_string(),
+            message.to_string(),
+        ))
+    }
+
+    fn reset(&mut self) -> Result<(), ArenaError> {
+        self.send(Message::Reset)
+    }
+}
+
+impl<V> Drop for BufferedInspector<V> {
+    fn drop(&mut self) {
+        // closing the buffer stops the thread once it handled the calls left
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
 }
 
 /// No-op implementation of an [`Inspector`] for custom use cases.
@@ -125,6 +818,234 @@ impl Inspector<f64> for EmptyInspector {
     fn inspect(&self, _step: usize) -> Option<f64> {
         None
     }
-    fn log(&mut self, _value: f64) {}
-    fn save(&self) {}
+    fn log(&mut self, _value: f64) -> Result<(), ArenaError> {
+        Ok(())
+    }
+    fn save(&self) -> Result<(), ArenaError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, Signed, Uint, U256};
+
+    use super::*;
+    use crate::types::controller::ArenaController::PoolKey;
+
+    #[derive(Debug, Clone, PartialEq, Serialize)]
+    struct Row {
+        price: f64,
+        trades: usize,
+    }
+
+    #[test]
+    fn test_csv_inspector() {
+        let end_of = |step| {
+            Signal::new(
+                U256::ZERO,
+                Some(step),
+                Signed::ZERO,
+                Uint::ZERO,
+                Address::ZERO,
+                PoolKey {
+                    currency0: Address::ZERO,
+                    currency1: Address::ZERO,
+                    fee: Uint::ZERO,
+                    tickSpacing: Signed::ZERO,
+                    hooks: Address::ZERO,
+                },
+                Address::ZERO,
+                0.0,
+                Address::ZERO,
+            )
+        };
+        let row = |price, trades| Row { price, trades };
+
+        let path = std::env::temp_dir().join(format!("arena-csv-{}", std::process::id()));
+        let mut inspector = CsvInspector::new(SaveData::ToNewFile {
+            path: path.clone(),
+            name: "run".to_string(),
+        });
+
+        inspector.log(row(1.0, 1)).unwrap();
+        inspector.log(row(1.5, 2)).unwrap();
+        inspector.on_step(&end_of(0)).unwrap();
+        inspector.on_step(&end_of(1)).unwrap();
+        inspector.log(row(2.0, 0)).unwrap();
+
+        assert_eq!(inspector.inspect(0), Some(row(1.5, 2)));
+        assert_eq!(inspector.inspect(1), None);
+        assert_eq!(inspector.inspect(2), Some(row(2.0, 0)));
+
+        inspector.save().unwrap();
+        inspector.save().unwrap();
+        let written = fs::read_to_string(path.join("run_0.csv")).unwrap();
+        assert_eq!(written, "step,price,trades\n0,1.5,2\n2,2.0,0\n");
+        assert!(path.join("run_1.csv").exists());
+
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn test_broadcast_inspector() {
+        let mut inspector = BroadcastInspector::new(2);
+        inspector.log(0.5).unwrap();
+
+        let mut receiver = inspector.subscribe();
+        let mut late = inspector.sender().subscribe();
+        for value in [1.0, 2.0, 3.0] {
+            inspector.log(value).unwrap();
+        }
+
+        // the channel only keeps the latest two values
+        assert!(matches!(
+            receiver.try_recv(),
+            Err(broadcast::error::TryRecvError::Lagged(1))
+        ));
+        assert_eq!(receiver.try_recv().unwrap().value, 2.0);
+        assert_eq!(receiver.try_recv().unwrap().value, 3.0);
+        assert!(receiver.try_recv().is_err());
+        assert!(late.try_recv().is_err());
+        assert_eq!(late.try_recv().unwrap().step, 0);
+        assert_eq!(inspector.inspect(0), Some(3.0));
+    }
+
+    #[test]
+    fn test_buffered_inspector() {
+        let path = std::env::temp_dir().join(format!("arena-buffered-{}", std::process::id()));
+        let save_data = SaveData::ToFile {
+            path: path.clone(),
+            name: "run".to_string(),
+        };
+        let file_path = save_data.file_path("ndjson").unwrap();
+
+        let inner = NdjsonInspector::new(&save_data).unwrap();
+        let mut inspector = BufferedInspector::new(inner, 4);
+        for value in 0..100 {
+            inspector.log(value as f64).unwrap();
+        }
+        // values are handled in order, before the inspection
+        assert_eq!(inspector.inspect(0), Some(99.0));
+
+        inspector.save().unwrap();
+        let lines = fs::read_to_string(&file_path).unwrap();
+        assert_eq!(lines.lines().count(), 100);
+        assert_eq!(inspector.dropped(), 0);
+
+        // a reset reaches the inner inspector in order, which starts its file over
+        inspector.reset().unwrap();
+        for value in 0..3 {
+            inspector.log(value as f64).unwrap();
+        }
+        inspector.save().unwrap();
+        let lines = fs::read_to_string(&file_path).unwrap();
+        assert_eq!(lines.lines().count(), 3);
+        assert!(lines.starts_with("{\"step\":0"));
+
+        // an inspector holding the thread until released, so the buffer of one value fills up
+        struct Held(std::sync::mpsc::Receiver<()>, usize);
+        impl Inspector<f64> for Held {
+            fn log(&mut self, _value: f64) -> Result<(), ArenaError> {
+                self.0.recv().unwrap();
+                self.1 += 1;
+                Ok(())
+            }
+            fn inspect(&self, _step: usize) -> Option<f64> {
+                Some(self.1 as f64)
+            }
+            fn save(&self) -> Result<(), ArenaError> {
+                Ok(())
+            }
+        }
+
+        let (release, held) = std::sync::mpsc::channel();
+        let mut dropping =
+            BufferedInspector::new(Held(held, 0), 1).with_backpressure(Backpressure::Drop);
+        for value in 0..3 {
+            dropping.log(value as f64).unwrap();
+        }
+        for _ in 0..3 {
+            release.send(()).unwrap();
+        }
+        dropping.save().unwrap();
+        assert!(dropping.dropped() > 0);
+        assert_eq!(dropping.inspect(0), Some((3 - dropping.dropped()) as f64));
+
+        fs::remove_dir_all(path).unwrap();
+    }
+
+    #[test]
+    fn test_buffered_inspector_cache() {
+        // an inspector counting the inspections it answers, sampling liquidity every other step
+        struct Counted(Arc<Mutex<usize>>, f64);
+        impl Inspector<f64> for Counted {
+            fn log(&mut self, value: f64) -> Result<(), ArenaError> {
+                self.1 = value;
+                Ok(())
+            }
+            fn inspect(&self, _step: usize) -> Option<f64> {
+                *self.0.lock().unwrap() += 1;
+                Some(self.1)
+            }
+            fn save(&self) -> Result<(), ArenaError> {
+                Ok(())
+            }
+            fn liquidity_words(&self, step: usize) -> Option<i16> {
+                step.is_multiple_of(2).then_some(3)
+            }
+        }
+
+        let inspections = Arc::new(Mutex::new(0));
+        let mut inspector = BufferedInspector::new(Counted(inspections.clone(), 0.0), 4);
+        assert_eq!(inspector.liquidity_words(0), Some(3));
+        assert_eq!(inspector.liquidity_words(1), None);
+
+        inspector.log(1.0).unwrap();
+        assert_eq!(inspector.inspect(0), Some(1.0));
+        assert_eq!(inspector.inspect(0), Some(1.0));
+        assert_eq!(*inspections.lock().unwrap(), 1);
+
+        // a later call invalidates the cached answers
+        inspector.log(2.0).unwrap();
+        assert_eq!(inspector.inspect(0), Some(2.0));
+        assert_eq!(*inspections.lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_ndjson_inspector() {
+        let path = std::env::temp_dir().join(format!("arena-ndjson-{}", std::process::id()));
+        let save_data = SaveData::ToFile {
+            path: path.clone(),
+            name: "run".to_string(),
+        };
+        let file_path = save_data.file_path("ndjson").unwrap();
+        let mut inspector = NdjsonInspector::new(&save_data)
+            .unwrap()
+            .with_flush_interval(2);
+        let lines = || {
+            fs::read_to_string(&file_path)
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect::<Vec<NdjsonLine<f64>>>()
+        };
+
+        inspector.log(1.0).unwrap();
+        assert!(lines().is_empty());
+        assert_eq!(inspector.inspect(0), Some(1.0));
+
+        inspector.log(2.0).unwrap();
+        assert_eq!(lines().len(), 2);
+        assert_eq!(inspector.inspect(0), None);
+
+        inspector.log(3.0).unwrap();
+        inspector.save().unwrap();
+        inspector.save().unwrap();
+        let values: Vec<f64> = lines().iter().map(|line| line.value).collect();
+        assert_eq!(values, vec![1.0, 2.0, 3.0]);
+        assert!(lines()[0].timestamp > 0);
+
+        fs::remove_dir_all(path).unwrap();
+    }
 }
@@ -0,0 +1,244 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use alloy::{primitives::Bytes, providers::WalletProvider};
+use async_trait::async_trait;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use super::{arbitrageur::pool_fee, *};
+use crate::{agent::Agent, Signal};
+
+/// Outcome of the liquidity provided by a [`JitLiquidity`] agent over a run, in whole units of token1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct JitReport {
+    /// Number of swaps liquidity was provided around.
+    pub swaps: usize,
+
+    /// Fees earned by the liquidity.
+    pub fees: f64,
+
+    /// Change in value of the tokens backing the liquidity between mint and burn, marked at the feed value after each
+    /// swap.
+    pub inventory_pnl: f64,
+
+    /// Fees paid by the swaps liquidity was provided around, to every liquidity provider in range.
+    pub swap_fees: f64,
+}
+
+impl JitReport {
+    /// Returns the profit of the agent, its fees net of the change in value of its liquidity, excluding gas.
+    pub fn profit(&self) -> f64 {
+        self.fees + self.inventory_pnl
+    }
+
+    /// Returns the share of the fees of the swaps it provided around that the agent took from passive liquidity
+    /// providers, who would otherwise have earned them.
+    pub fn fee_share(&self) -> f64 {
+        if self.swap_fees > 0.0 {
+            self.fees / self.swap_fees
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Just-in-time liquidity provider, minting a tight range around the current price right before a large swap and
+/// burning it right after, to capture most of the swap's fees.
+///
+/// The provider is an [`Agent`] watching the swaps of every other participant, see [`Agent::watches_swaps`]: it sees
+/// each of them before it executes, as if read from the mempool, and acts from its own agent wallet. Its positions are
+/// recorded in the [`Holdings`] of that wallet, and its outcome in [`crate::summary::RunSummary::jit`] when added with
+/// [`crate::arena::ArenaBuilder::with_jit_liquidity`]. Clones share the same report.
+#[derive(Debug, Clone, Default)]
+pub struct JitLiquidity {
+    /// Notional, in whole units of token1 at the feed value, above which a swap is provided around.
+    pub min_notional: f64,
+
+    /// Liquidity minted, as a multiple of the in-range liquidity of the pool.
+    pub multiple: f64,
+
+    /// Ticks the range spans either side of the current tick, widened to the nearest usable ticks.
+    pub width: i32,
+
+    /// Outcome of the run so far.
+    report: Arc<Mutex<JitReport>>,
+
+    /// Position minted around the swap being executed, with the holdings of the wallet before the mint.
+    minted: Option<(Position, Holdings)>,
+}
+
+impl JitLiquidity {
+    /// Public constructor function for a new [`JitLiquidity`] agent, providing around swaps of at least `min_notional`
+    /// whole units of token1 with `multiple` times the in-range liquidity, over a single tick spacing.
+    pub fn new(min_notional: f64, multiple: f64) -> Self {
+        Self {
+            min_notional,
+            multiple,
+            ..Self::default()
+        }
+    }
+
+    /// Span `width` ticks either side of the current tick.
+    pub fn with_width(mut self, width: i32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Returns the outcome of the run so far.
+    pub fn report(&self) -> JitReport {
+        *self.report.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Forget the outcome of previous runs.
+    pub(crate) fn reset(&self) {
+        *self.report.lock().unwrap_or_else(PoisonError::into_inner) = JitReport::default();
+    }
+
+    /// Returns the notional of `swap` on a pool of tokens with `decimals`, in whole units of token1, with token0 worth
+    /// `price` whole units of token1: the input of an exact-input swap, or the output of an exact-output swap.
+    pub fn notional(swap: &DeferredSwap, decimals: (u8, u8), price: f64) -> f64 {
+        let exact_input = swap.amount_specified.is_negative();
+        let amount = f64::from(swap.amount_specified.unsigned_abs());
+
+        if exact_input == swap.zero_for_one {
+            amount * 10f64.powi(-(decimals.0 as i32)) * price
+        } else {
+            amount * 10f64.powi(-(decimals.1 as i32))
+        }
+    }
+
+    /// Returns whether `swap` is large enough to provide around, see [`JitLiquidity::notional`].
+    pub fn is_large(&self, swap: &DeferredSwap, decimals: (u8, u8), price: f64) -> bool {
+        Self::notional(swap, decimals, price) >= self.min_notional
+    }
+}
+
+#[async_trait]
+impl Agent for JitLiquidity {
+    async fn act(
+        &mut self,
+        _signal: &Signal,
+        _provider: AnvilProvider,
+        _engine: Engine,
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    fn watches_swaps(&self) -> bool {
+        true
+    }
+
+    /// Mint liquidity around the current price if `swap` is large.
+    async fn before_swap(
+        &mut self,
+        swap: &DeferredSwap,
+        price: f64,
+        provider: AnvilProvider,
+        engine: Engine,
+    ) -> Result<(), ArenaError> {
+        if !self.is_large(swap, engine.decimals, price) {
+            return Ok(());
+        }
+
+        let signal = engine.signal(None, price, provider.clone()).await?;
+        let liquidity = (signal.liquidity as f64 * self.multiple) as u128;
+        if liquidity == 0 {
+            return Ok(());
+        }
+
+        let tick_spacing = signal.pool.tickSpacing.as_i32();
+        let position = Position::around(signal.tick.as_i32(), self.width, tick_spacing, liquidity);
+        let before = engine.holdings(provider.default_signer_address());
+
+        engine
+            .open_position(&position, Bytes::new(), provider)
+            .await?;
+        self.minted = Some((position, before));
+
+        let mut report = self.report.lock().unwrap_or_else(PoisonError::into_inner);
+        report.swaps += 1;
+        report.swap_fees += Self::notional(swap, engine.decimals, price) * pool_fee(&signal.pool);
+
+        Ok(())
+    }
+
+    /// Burn the liquidity minted before the swap, if any, and record its outcome.
+    async fn after_swap(
+        &mut self,
+        _swap: &DeferredSwap,
+        price: f64,
+        provider: AnvilProvider,
+        engine: Engine,
+    ) -> Result<(), ArenaError> {
+        let Some((position, before)) = self.minted.take() else {
+            return Ok(());
+        };
+
+        let owner = provider.default_signer_address();
+        engine
+            .close_position(&position, Bytes::new(), provider)
+            .await?;
+        let after = engine.holdings(owner);
+
+        // raw amounts are valued in whole units of token1
+        let (scale0, scale1) = (
+            10f64.powi(-(engine.decimals.0 as i32)),
+            10f64.powi(-(engine.decimals.1 as i32)),
+        );
+        let value = |amounts: (f64, f64)| amounts.0 * scale0 * price + amounts.1 * scale1;
+
+        let mut report = self.report.lock().unwrap_or_else(PoisonError::into_inner);
+        report.fees += value((after.fees.0 - before.fees.0, after.fees.1 - before.fees.1));
+        // the net deposit of a closed position is what it lost to the swap
+        report.inventory_pnl -= value((
+            after.deposited.0 - before.deposited.0,
+            after.deposited.1 - before.deposited.1,
+        ));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jit_report() {
+        let jit = JitLiquidity::new(1000.0, 9.0).with_width(10);
+        let swap = |zero_for_one, amount: i128| DeferredSwap {
+            zero_for_one,
+            amount_specified: I256::try_from(amount).unwrap(),
+            sqrt_price_limit_x96: None,
+        };
+        let e18 = 10i128.pow(18);
+
+        // selling one token0 for token1, or buying one token1 with token0, is worth the price of token0
+        assert!(jit.is_large(&swap(true, -e18), (18, 18), 1000.0));
+        assert!(!jit.is_large(&swap(true, -e18), (18, 18), 999.0));
+        assert!(!jit.is_large(&swap(false, -999 * e18), (18, 18), 1.0));
+        // exact outputs are valued by the token bought
+        assert_eq!(JitLiquidity::notional(&swap(false, e18), (18, 6), 2.0), 2.0);
+        assert_eq!(
+            JitLiquidity::notional(&swap(true, 10i128.pow(6)), (18, 6), 2.0),
+            1.0
+        );
+
+        let report = JitReport {
+            swaps: 2,
+            fees: 2.7,
+            inventory_pnl: -0.5,
+            swap_fees: 3.0,
+        };
+        assert!((report.profit() - 2.2).abs() < 1e-12);
+        assert!((report.fee_share() - 0.9).abs() < 1e-12);
+        assert_eq!(JitReport::default().fee_share(), 0.0);
+
+        // clones share the report, which resets between runs
+        jit.clone().report.lock().unwrap().swaps = 3;
+        assert_eq!(jit.report().swaps, 3);
+        jit.reset();
+        assert_eq!(jit.report(), JitReport::default());
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::{HashMap, VecDeque};
+
+use alloy::primitives::B256;
+use async_trait::async_trait;
+use rand::rngs::StdRng;
+
+use super::{arbitrageur::Arbitrageur, *};
+use crate::{agent::Agent, AnvilProvider, Signal};
+
+/// Wrapper delaying an [`Agent`] or [`Arbitrageur`] by a number of calls between deciding on a swap and sending it, so
+/// its swaps land against a market that moved on since.
+///
+/// The wrapped component decides on the signal of every call through an engine holding its swaps back, see
+/// [`Engine::deferring`], and the swaps it decided on are sent `delay` calls later exactly as decided, in direction,
+/// amount and price limit. Agents are called once per step, so the delay of a delayed agent counts steps, while
+/// arbitrageurs are called on every feed update, so the delay of a delayed arbitrageur counts sub-ticks. Anything else
+/// the wrapped component does, such as providing liquidity, executes right away. Decisions are queued per pool.
+pub struct Delayed<T> {
+    /// The wrapped agent or arbitrageur.
+    pub inner: T,
+
+    /// Number of calls between deciding on a swap and sending it.
+    pub delay: usize,
+
+    /// Swaps decided but not sent yet, one batch per call, oldest first, keyed by the id of their pool.
+    queues: HashMap<B256, VecDeque<Vec<DeferredSwap>>>,
+}
+
+impl<T> Delayed<T> {
+    /// Public constructor function for a new [`Delayed`] component sending its swaps `delay` calls after deciding on
+    /// them.
+    pub fn new(inner: T, delay: usize) -> Self {
+        Self {
+            inner,
+            delay,
+            queues: HashMap::new(),
+        }
+    }
+
+    /// Queue the swaps decided on the pool of `signal` and return those decided `delay` calls ago on the same pool.
+    pub fn queue(&mut self, signal: &Signal, swaps: Vec<DeferredSwap>) -> Vec<DeferredSwap> {
+        let queue = self.queues.entry(pool::pool_id(&signal.pool)).or_default();
+        queue.push_back(swaps);
+
+        if queue.len() > self.delay {
+            queue.pop_front().unwrap_or_default()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Queue the swaps `deferring` held back and send those that are due through `engine`.
+    async fn send_due(
+        &mut self,
+        signal: &Signal,
+        deferring: &Engine,
+        engine: &Engine,
+        provider: AnvilProvider,
+    ) -> Result<(), ArenaError> {
+        for swap in self.queue(signal, deferring.take_deferred()) {
+            swap.send(engine, provider.clone()).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T: Agent> Agent for Delayed<T> {
+    async fn init(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        self.inner.init(signal, provider, engine, rng).await
+    }
+
+    async fn bid(&mut self, signal: &Signal, rng: &mut StdRng) -> Result<u128, ArenaError> {
+        self.inner.bid(signal, rng).await
+    }
+
+    async fn act(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        let deferring = engine.deferring();
+        self.inner
+            .act(signal, provider.clone(), deferring.clone(), rng)
+            .await?;
+
+        self.send_due(signal, &deferring, &engine, provider).await
+    }
+
+    fn watches_swaps(&self) -> bool {
+        self.inner.watches_swaps()
+    }
+
+    async fn before_swap(
+        &mut self,
+        swap: &DeferredSwap,
+        price: f64,
+        provider: AnvilProvider,
+        engine: Engine,
+    ) -> Result<(), ArenaError> {
+        self.inner.before_swap(swap, price, provider, engine).await
+    }
+
+    async fn after_swap(
+        &mut self,
+        swap: &DeferredSwap,
+        price: f64,
+        provider: AnvilProvider,
+        engine: Engine,
+    ) -> Result<(), ArenaError> {
+        self.inner.after_swap(swap, price, provider, engine).await
+    }
+}
+
+#[async_trait]
+impl<T: Arbitrageur> Arbitrageur for Delayed<T> {
+    async fn init(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+    ) -> Result<(), ArenaError> {
+        self.inner.init(signal, provider, engine).await
+    }
+
+    fn reseed(&mut self, rng: StdRng) {
+        self.inner.reseed(rng);
+    }
+
+    async fn arbitrage(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+    ) -> Result<(), ArenaError> {
+        let deferring = engine.deferring();
+        self.inner
+            .arbitrage(signal, provider.clone(), deferring.clone())
+            .await?;
+
+        self.send_due(signal, &deferring, &engine, provider).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        network::EthereumWallet,
+        primitives::{Address, Signed, Uint, I256, U256},
+        providers::ProviderBuilder,
+        rpc::client::ClientBuilder,
+        signers::local::PrivateKeySigner,
+    };
+
+    use super::*;
+    use crate::{
+        engine::arbitrageur::FixedArbitrageur, types::controller::ArenaController::PoolKey,
+    };
+
+    /// Returns a provider for a node that is never contacted, since deferred swaps are not sent.
+    fn provider() -> AnvilProvider {
+        ProviderBuilder::new()
+            .with_recommended_fillers()
+            .wallet(EthereumWallet::from(PrivateKeySigner::random()))
+            .on_client(
+                ClientBuilder::default()
+                    .http("http://127.0.0.1:1".parse().unwrap())
+                    .boxed(),
+            )
+    }
+
+    fn signal(step: usize, fee: u32, divergence: f64) -> Signal {
+        let mut signal = Signal::new(
+            U256::from(10).pow(U256::from(18)),
+            Some(step),
+            Signed::ZERO,
+            Uint::ZERO,
+            Address::ZERO,
+            PoolKey {
+                currency0: Address::ZERO,
+                currency1: Address::repeat_byte(1),
+                fee: Uint::from(fee),
+                tickSpacing: Signed::try_from(60).unwrap(),
+                hooks: Address::ZERO,
+            },
+            Address::ZERO,
+            0.0,
+            Address::ZERO,
+        );
+        signal.divergence = divergence;
+        signal
+    }
+
+    fn swap(amount: i64) -> DeferredSwap {
+        DeferredSwap {
+            zero_for_one: true,
+            amount_specified: I256::try_from(amount).unwrap(),
+            sqrt_price_limit_x96: None,
+        }
+    }
+
+    #[test]
+    fn test_delayed() {
+        let mut delayed = Delayed::new(FixedArbitrageur::default(), 2);
+        assert!(delayed
+            .queue(&signal(0, 3000, 0.0), vec![swap(1)])
+            .is_empty());
+        assert!(delayed.queue(&signal(1, 3000, 0.0), vec![]).is_empty());
+        assert!(delayed
+            .queue(&signal(0, 500, 0.0), vec![swap(7)])
+            .is_empty());
+        assert_eq!(
+            delayed.queue(&signal(2, 3000, 0.0), vec![swap(3)]),
+            vec![swap(1)]
+        );
+        assert!(delayed.queue(&signal(3, 3000, 0.0), vec![]).is_empty());
+        assert_eq!(delayed.queue(&signal(4, 3000, 0.0), vec![]), vec![swap(3)]);
+
+        let mut immediate = Delayed::new(FixedArbitrageur::default(), 0);
+        assert_eq!(
+            immediate.queue(&signal(5, 3000, 0.0), vec![swap(5)]),
+            vec![swap(5)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delayed_decisions() {
+        let depth = I256::try_from(-1000).unwrap();
+        let mut delayed = Delayed::new(FixedArbitrageur { depth }, 1);
+        let engine = Engine::new(Address::ZERO);
+
+        // the swap decided on the pool priced above the liquid exchange is held back, not sent
+        delayed
+            .arbitrage(&signal(0, 3000, 0.01), provider(), engine.clone())
+            .await
+            .unwrap();
+
+        let queued = &delayed.queues[&pool::pool_id(&signal(0, 3000, 0.0).pool)];
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].len(), 1);
+        assert!(queued[0][0].zero_for_one);
+        assert_eq!(queued[0][0].amount_specified, depth);
+        assert!(queued[0][0].sqrt_price_limit_x96.is_some());
+
+        // a later call sends it as decided, against a node that cannot be reached here
+        assert!(delayed
+            .arbitrage(&signal(1, 3000, -0.01), provider(), engine)
+            .await
+            .is_err());
+    }
+}
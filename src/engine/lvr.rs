@@ -0,0 +1,261 @@
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    inspector::{Inspector, SaveData},
+    pnl::AgentMark,
+};
+use crate::{error::ArenaError, Signal};
+
+/// Performance of the liquidity positions of a strategy or agent at a step against its benchmarks, in whole units of
+/// token1 at the feed value.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LpPoint {
+    /// Step of the mark, or `None` for the mark taken after initialization.
+    pub step: Option<usize>,
+
+    /// Feed value the point was marked at, in whole units of token1 per whole unit of token0.
+    pub price: f64,
+
+    /// Value of the tokens backing the open positions, excluding fees.
+    pub position_value: f64,
+
+    /// Value of the tokens deposited into the positions, had they been held instead.
+    pub hodl_value: f64,
+
+    /// Value of the fees earned by the positions so far, collected or not.
+    pub fees: f64,
+
+    /// Loss versus rebalancing accrued since the first mark: what a portfolio holding the same amount of token0 as the
+    /// positions, rebalanced every step at the feed value, earned over the positions, excluding fees.
+    pub lvr: f64,
+}
+
+impl LpPoint {
+    /// Returns the impermanent loss of the positions, their shortfall against holding the tokens deposited.
+    pub fn impermanent_loss(&self) -> f64 {
+        self.hodl_value - self.position_value
+    }
+
+    /// Returns the value of the rebalancing benchmark, the positions plus their loss versus rebalancing.
+    pub fn rebalancing_value(&self) -> f64 {
+        self.position_value + self.lvr
+    }
+}
+
+/// Implementation of an [`Inspector`] measuring the liquidity positions of every strategy and agent against holding
+/// the tokens deposited into them and against the rebalancing benchmark, every step.
+///
+/// Positions are valued at the feed value, the price of the external market arbitrageurs trade the pool towards. Loss
+/// versus rebalancing accrues every step as the gain the token0 backing the positions at the previous mark made on the
+/// move of the feed value, less the change in value of the positions net of liquidity added or removed since. Strategies
+/// and agents only appear once they deposit into a position.
+///
+/// On [`Inspector::save`], the series of every strategy and agent is written to a CSV file if a destination was set
+/// with [`LvrInspector::with_save_data`]. The inspector observes marks only, so logged values are dropped and cannot be
+/// inspected.
+#[derive(Debug, Clone)]
+pub struct LvrInspector<V = f64> {
+    /// Destination of the file written on save.
+    pub save_data: Option<SaveData>,
+
+    series: BTreeMap<String, Vec<LpPoint>>,
+    // previous mark of every strategy or agent with positions
+    previous: BTreeMap<String, AgentMark>,
+    price: f64,
+    _value: PhantomData<fn(V)>,
+}
+
+impl<V> Default for LvrInspector<V> {
+    fn default() -> Self {
+        Self {
+            save_data: None,
+            series: BTreeMap::new(),
+            previous: BTreeMap::new(),
+            price: 0.0,
+            _value: PhantomData,
+        }
+    }
+}
+
+impl<V> LvrInspector<V> {
+    /// Public constructor function for a new, empty [`LvrInspector`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write the series to `save_data` on save.
+    pub fn with_save_data(mut self, save_data: SaveData) -> Self {
+        self.save_data = Some(save_data);
+        self
+    }
+
+    /// Returns the series of the strategy or agent `name`, oldest first.
+    pub fn series(&self, name: &str) -> Option<&[LpPoint]> {
+        self.series.get(name).map(Vec::as_slice)
+    }
+
+    /// Record `mark`, taken at `step` with the feed value at `price`.
+    pub fn record(&mut self, step: Option<usize>, price: f64, mark: &AgentMark) {
+        let value = |amounts: (f64, f64)| amounts.0 * price + amounts.1;
+        let position_value = value(mark.positions);
+
+        let lvr = match (self.previous.get(&mark.name), self.series.get(&mark.name)) {
+            (Some(previous), Some(series)) => {
+                let last = series.last().unwrap();
+                // liquidity added or removed since the previous mark moves value in or out of the positions
+                let flow = value((
+                    mark.deposited.0 - previous.deposited.0,
+                    mark.deposited.1 - previous.deposited.1,
+                ));
+                let rebalanced = previous.positions.0 * (price - last.price);
+
+                last.lvr + rebalanced - (position_value - last.position_value - flow)
+            }
+            _ if mark.deposited == (0.0, 0.0) && mark.positions == (0.0, 0.0) => return,
+            _ => 0.0,
+        };
+
+        self.series
+            .entry(mark.name.clone())
+            .or_default()
+            .push(LpPoint {
+                step,
+                price,
+                position_value,
+                hodl_value: value(mark.deposited),
+                fees: value(mark.fees_collected) + value(mark.fees_owed),
+                lvr,
+            });
+        self.previous.insert(mark.name.clone(), mark.clone());
+    }
+}
+
+impl<V: Send> Inspector<V> for LvrInspector<V> {
+    fn log(&mut self, _value: V) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    fn inspect(&self, _step: usize) -> Option<V> {
+        None
+    }
+
+    fn save(&self) -> Result<(), ArenaError> {
+        let Some(save_data) = &self.save_data else {
+            return Ok(());
+        };
+
+        let mut writer = csv::Writer::from_path(save_data.file_path("csv")?)?;
+        writer.write_record([
+            "step",
+            "agent",
+            "price",
+            "position_value",
+            "hodl_value",
+            "impermanent_loss",
+            "fees",
+            "lvr",
+            "rebalancing_value",
+        ])?;
+        for (name, series) in &self.series {
+            for point in series {
+                writer.serialize((
+                    point.step,
+                    name,
+                    point.price,
+                    point.position_value,
+                    point.hodl_value,
+                    point.impermanent_loss(),
+                    point.fees,
+                    point.lvr,
+                    point.rebalancing_value(),
+                ))?;
+            }
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    fn on_step(&mut self, signal: &Signal) -> Result<(), ArenaError> {
+        self.price = signal.current_value;
+        Ok(())
+    }
+
+    fn marks_agents(&self) -> bool {
+        true
+    }
+
+    fn on_marks(&mut self, step: Option<usize>, marks: &[AgentMark]) -> Result<(), ArenaError> {
+        for mark in marks {
+            // the mark after initialization precedes any step, and is taken at the pool price
+            let price = if step.is_some() {
+                self.price
+            } else {
+                mark.price
+            };
+            self.record(step, price, mark);
+        }
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), ArenaError> {
+        self.series.clear();
+        self.previous.clear();
+        self.price = 0.0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::Address;
+
+    use super::*;
+
+    #[test]
+    fn test_lvr_inspector() {
+        // a full-range position of liquidity 100, backed by 100 / sqrt(p) token0 and 100 sqrt(p) token1
+        let mark = |price: f64, deposited| AgentMark {
+            name: "strategy 0".to_string(),
+            owner: Address::ZERO,
+            price,
+            balances: (0.0, 0.0),
+            positions: (100.0 / price.sqrt(), 100.0 * price.sqrt()),
+            fees_collected: (0.0, 0.5),
+            fees_owed: (0.0, 0.0),
+            deposited,
+            ranges: Vec::new(),
+            decimals: (18, 18),
+        };
+        let mut inspector = LvrInspector::<f64>::new();
+
+        inspector.record(None, 100.0, &mark(100.0, (10.0, 1000.0)));
+        inspector.record(Some(0), 121.0, &mark(121.0, (10.0, 1000.0)));
+        let empty = AgentMark {
+            name: "agent 0".to_string(),
+            ..mark(1.0, (0.0, 0.0))
+        };
+        inspector.record(
+            Some(0),
+            121.0,
+            &AgentMark {
+                positions: (0.0, 0.0),
+                ..empty
+            },
+        );
+
+        let series = inspector.series("strategy 0").unwrap();
+        assert_eq!(series[0].lvr, 0.0);
+        assert_eq!(series[0].impermanent_loss(), 0.0);
+
+        // the rebalancing portfolio gains 10 * 21 to 2210, the position 2 * 100 * (11 - 10) to 2200
+        assert!((series[1].lvr - 10.0).abs() < 1e-9);
+        assert!((series[1].rebalancing_value() - 2210.0).abs() < 1e-9);
+        assert!((series[1].impermanent_loss() - 10.0).abs() < 1e-9);
+        assert_eq!(series[1].fees, 0.5);
+        assert!(inspector.series("agent 0").is_none());
+    }
+}
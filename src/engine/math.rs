@@ -0,0 +1,349 @@
+/// Returns the sqrt price, in raw units, at a tick.
+pub fn tick_sqrt_price(tick: i32) -> f64 {
+    1.0001f64.powf(tick as f64 / 2.0)
+}
+
+/// Liquidity of a pool around its current price, as needed to size swaps across initialized ticks.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LiquidityProfile {
+    /// Current sqrt price, in raw units.
+    pub sqrt_price: f64,
+
+    /// Current tick.
+    pub tick: i32,
+
+    /// In-range liquidity.
+    pub liquidity: u128,
+
+    /// Initialized ticks and their net liquidity, added when crossing the tick upwards, sorted by tick.
+    ///
+    /// Ticks missing from the profile are treated as uninitialized, so the profile only needs to cover the ticks a swap
+    /// may cross.
+    pub ticks: Vec<(i32, i128)>,
+}
+
+/// Swap sized by [`optimal_swap`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptimalSwap {
+    /// Whether token0 is sold for token1.
+    pub zero_for_one: bool,
+
+    /// Exact input amount, fee included, in raw units of the input token.
+    pub amount_in: f64,
+
+    /// Output amount, in raw units of the output token.
+    pub amount_out: f64,
+
+    /// Sqrt price of the pool after the swap, in raw units.
+    pub sqrt_price_after: f64,
+}
+
+/// Returns the swap moving the pool to the edge of its no-arbitrage band around `external_price`, walking across the
+/// initialized ticks of `profile`, or `None` if the pool is already within the band.
+///
+/// `external_price` is in raw units of token1 per raw unit of token0, and `fee` is the pool fee as a fraction. The
+/// band is where the marginal trade, net of the fee, no longer profits against the external price: the swap moves the
+/// pool price to `external_price / (1 - fee)` when selling token0 and to `external_price * (1 - fee)` when buying it,
+/// which is exactly `external_price` for a pool without fees.
+///
+/// If liquidity runs out before the target, the swap stops at the last initialized tick with liquidity.
+pub fn optimal_swap(
+    profile: &LiquidityProfile,
+    external_price: f64,
+    fee: f64,
+) -> Option<OptimalSwap> {
+    if external_price <= 0.0 || !(0.0..1.0).contains(&fee) {
+        return None;
+    }
+
+    let zero_for_one = profile.sqrt_price.powi(2) * (1.0 - fee) > external_price;
+    let target = if zero_for_one {
+        (external_price / (1.0 - fee)).sqrt()
+    } else {
+        (external_price * (1.0 - fee)).sqrt()
+    };
+    if !zero_for_one && target <= profile.sqrt_price {
+        return None;
+    }
+
+    swap_to(profile, target, fee)
+}
+
+/// Returns the swap moving the pool to `target`, a sqrt price in raw units, walking across the initialized ticks of
+/// `profile`, or `None` if the pool is already there.
+///
+/// `fee` is the pool fee as a fraction, charged on the input. If liquidity runs out before the target, the swap stops
+/// at the last initialized tick with liquidity.
+pub fn swap_to(profile: &LiquidityProfile, target: f64, fee: f64) -> Option<OptimalSwap> {
+    let sqrt_price = profile.sqrt_price;
+    if target == sqrt_price || !(0.0..1.0).contains(&fee) {
+        return None;
+    }
+    let zero_for_one = target < sqrt_price;
+
+    let mut liquidity = profile.liquidity as f64;
+    let mut current = sqrt_price;
+    let (mut amount_in, mut amount_out) = (0.0, 0.0);
+
+    let mut step = |liquidity: f64, next: f64| {
+        if zero_for_one {
+            amount_in += liquidity * (1.0 / next - 1.0 / current);
+            amount_out += liquidity * (current - next);
+        } else {
+            amount_in += liquidity * (next - current);
+            amount_out += liquidity * (1.0 / current - 1.0 / next);
+        }
+        current = next;
+    };
+
+    let mut reached = false;
+    for &(tick, liquidity_net) in crossings(profile, zero_for_one) {
+        let boundary = tick_sqrt_price(tick);
+        let next = if zero_for_one {
+            boundary.max(target)
+        } else {
+            boundary.min(target)
+        };
+        step(liquidity, next);
+
+        if next == target {
+            reached = true;
+            break;
+        }
+
+        liquidity = cross(liquidity, liquidity_net, zero_for_one);
+    }
+    if !reached && liquidity > 0.0 {
+        step(liquidity, target);
+    }
+
+    (amount_in > 0.0).then_some(OptimalSwap {
+        zero_for_one,
+        amount_in: amount_in / (1.0 - fee),
+        amount_out,
+        sqrt_price_after: current,
+    })
+}
+
+/// Returns the initialized ticks of `profile` a swap in the given direction crosses, in the order it crosses them.
+fn crossings(
+    profile: &LiquidityProfile,
+    zero_for_one: bool,
+) -> Box<dyn Iterator<Item = &(i32, i128)> + '_> {
+    if zero_for_one {
+        Box::new(
+            profile
+                .ticks
+                .iter()
+                .rev()
+                .filter(|(tick, _)| *tick <= profile.tick),
+        )
+    } else {
+        Box::new(
+            profile
+                .ticks
+                .iter()
+                .filter(|(tick, _)| *tick > profile.tick),
+        )
+    }
+}
+
+/// Returns the liquidity after crossing a tick of net liquidity `liquidity_net`, which crossing downwards removes and
+/// crossing upwards adds.
+fn cross(liquidity: f64, liquidity_net: i128, zero_for_one: bool) -> f64 {
+    let net = liquidity_net as f64;
+
+    if zero_for_one {
+        liquidity - net
+    } else {
+        liquidity + net
+    }
+    .max(0.0)
+}
+
+/// Returns `swap`, from the pool state of `profile`, shrunk to take at most `max_in` of the input token and give at most
+/// `max_out` of the output token, in raw units, or `None` if less than one unit can be swapped within the limits.
+pub fn cap_swap(
+    profile: &LiquidityProfile,
+    swap: OptimalSwap,
+    fee: f64,
+    max_in: f64,
+    max_out: f64,
+) -> Option<OptimalSwap> {
+    let fits = |swap: &OptimalSwap| swap.amount_in <= max_in && swap.amount_out <= max_out;
+    if fits(&swap) {
+        return Some(swap);
+    }
+
+    // both amounts grow with the distance the price moves, so bisect on the final sqrt price
+    let (mut near, mut far) = (profile.sqrt_price, swap.sqrt_price_after);
+    let mut best = None;
+    for _ in 0..64 {
+        let mid = (near + far) / 2.0;
+        match swap_to(profile, mid, fee) {
+            Some(swap) if !fits(&swap) => far = mid,
+            swap => {
+                best = swap.or(best);
+                near = mid;
+            }
+        }
+    }
+
+    best.filter(|swap| swap.amount_in >= 1.0)
+}
+
+/// Returns the swaps equalizing the prices of two pools of the same pair net of their fees, selling token0 into the pool
+/// quoting it higher and buying the same amount back from the other, as the swaps against `a` and `b`.
+///
+/// `fee_a` and `fee_b` are the pool fees as fractions. Returns `None` if each pool is within the no-arbitrage band of
+/// the other, where buying from one and selling into the other no longer profits at the margin.
+pub fn cross_pool_swap(
+    a: &LiquidityProfile,
+    fee_a: f64,
+    b: &LiquidityProfile,
+    fee_b: f64,
+) -> Option<(OptimalSwap, OptimalSwap)> {
+    if !(0.0..1.0).contains(&fee_a) || !(0.0..1.0).contains(&fee_b) {
+        return None;
+    }
+
+    let (price_a, price_b) = (a.sqrt_price.powi(2), b.sqrt_price.powi(2));
+    if price_a * (1.0 - fee_a) > price_b / (1.0 - fee_b) {
+        equalize(a, fee_a, b, fee_b)
+    } else if price_b * (1.0 - fee_b) > price_a / (1.0 - fee_a) {
+        equalize(b, fee_b, a, fee_a).map(|(sell, buy)| (buy, sell))
+    } else {
+        None
+    }
+}
+
+/// Returns the swaps selling token0 into `rich` and buying it from `cheap` until the marginal price received for token0
+/// in one meets the marginal price paid in the other, net of fees.
+fn equalize(
+    rich: &LiquidityProfile,
+    fee_rich: f64,
+    cheap: &LiquidityProfile,
+    fee_cheap: f64,
+) -> Option<(OptimalSwap, OptimalSwap)> {
+    // the amount sold falls and the amount bought grows with the meeting price, so bisect on it
+    let legs = |price: f64| {
+        (
+            swap_to(rich, (price / (1.0 - fee_rich)).sqrt(), fee_rich),
+            swap_to(cheap, (price * (1.0 - fee_cheap)).sqrt(), fee_cheap),
+        )
+    };
+    let (mut low, mut high) = (
+        cheap.sqrt_price.powi(2) / (1.0 - fee_cheap),
+        rich.sqrt_price.powi(2) * (1.0 - fee_rich),
+    );
+    for _ in 0..64 {
+        let mid = (low * high).sqrt();
+        let (sell, buy) = legs(mid);
+        if sell.map_or(0.0, |swap| swap.amount_in) > buy.map_or(0.0, |swap| swap.amount_out) {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    match legs((low * high).sqrt()) {
+        (Some(sell), Some(buy)) if sell.amount_in >= 1.0 && buy.amount_in >= 1.0 => {
+            Some((sell, buy))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_optimal_swap() {
+        // 1000 liquidity in [-200, 200) and 500 more in [0, 200)
+        let profile = LiquidityProfile {
+            sqrt_price: tick_sqrt_price(100),
+            tick: 100,
+            liquidity: 1500,
+            ticks: vec![(-200, 1000), (0, 500), (200, -1500)],
+        };
+
+        assert!(optimal_swap(&profile, tick_sqrt_price(100).powi(2), 0.0).is_none());
+
+        // within the range: exactly the single-range amount
+        let target = tick_sqrt_price(150);
+        let swap = optimal_swap(&profile, target.powi(2), 0.0).unwrap();
+        assert!(!swap.zero_for_one);
+        assert!((swap.amount_in - 1500.0 * (target - tick_sqrt_price(100))).abs() < 1e-9);
+        assert!((swap.sqrt_price_after - target).abs() < 1e-12);
+
+        // crossing tick 0 downwards leaves 1000 of liquidity
+        let target = tick_sqrt_price(-100);
+        let swap = optimal_swap(&profile, target.powi(2), 0.0).unwrap();
+        let expected = 1500.0 * (1.0 / tick_sqrt_price(0) - 1.0 / tick_sqrt_price(100))
+            + 1000.0 * (1.0 / target - 1.0 / tick_sqrt_price(0));
+        assert!(swap.zero_for_one);
+        assert!((swap.amount_in - expected).abs() < 1e-9);
+
+        // liquidity runs out at tick 200
+        let swap = optimal_swap(&profile, tick_sqrt_price(400).powi(2), 0.0).unwrap();
+        assert!((swap.sqrt_price_after - tick_sqrt_price(200)).abs() < 1e-12);
+
+        // the fee narrows the move and grosses up the input
+        let target = tick_sqrt_price(150);
+        let with_fee = optimal_swap(&profile, target.powi(2), 0.003).unwrap();
+        let net = 1500.0 * ((target.powi(2) * 0.997).sqrt() - tick_sqrt_price(100));
+        assert!((with_fee.amount_in - net / 0.997).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cross_pool_swap() {
+        let profile = |sqrt_price: f64| LiquidityProfile {
+            sqrt_price,
+            tick: (sqrt_price.powi(2).ln() / 1.0001f64.ln()).floor() as i32,
+            liquidity: 1_000_000,
+            ticks: Vec::new(),
+        };
+        let (a, b) = (profile(1.1), profile(1.0));
+
+        // with equal liquidity and no fees, both pools meet where as much token0 is sold as bought
+        let (sell, buy) = cross_pool_swap(&a, 0.0, &b, 0.0).unwrap();
+        let meet = 2.0 / (1.0 / 1.1 + 1.0);
+        assert!(sell.zero_for_one && !buy.zero_for_one);
+        assert!((sell.sqrt_price_after - meet).abs() < 1e-9);
+        assert!((buy.sqrt_price_after - meet).abs() < 1e-9);
+        assert!((sell.amount_in - buy.amount_out).abs() < 1e-3);
+        assert!(sell.amount_out > buy.amount_in);
+
+        let (buy, sell) = cross_pool_swap(&b, 0.0, &a, 0.0).unwrap();
+        assert!(sell.zero_for_one && !buy.zero_for_one);
+
+        // fees wider than the discrepancy leave both pools alone
+        assert!(cross_pool_swap(&a, 0.1, &b, 0.1).is_none());
+        assert!(cross_pool_swap(&a, 0.0, &a, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_cap_swap() {
+        let profile = LiquidityProfile {
+            sqrt_price: 1.0,
+            tick: 0,
+            liquidity: 1_000_000,
+            ticks: Vec::new(),
+        };
+        let swap = optimal_swap(&profile, 1.21, 0.0).unwrap();
+
+        assert_eq!(
+            cap_swap(&profile, swap, 0.0, f64::INFINITY, f64::INFINITY),
+            Some(swap)
+        );
+        assert!(cap_swap(&profile, swap, 0.0, 0.5, f64::INFINITY).is_none());
+
+        let capped = cap_swap(&profile, swap, 0.0, 50_000.0, f64::INFINITY).unwrap();
+        assert!((capped.amount_in - 50_000.0).abs() < 1e-3);
+        assert!((capped.sqrt_price_after - 1.05).abs() < 1e-9);
+
+        let capped = cap_swap(&profile, swap, 0.0, f64::INFINITY, 10_000.0).unwrap();
+        assert!((capped.amount_out - 10_000.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    engine::{
+        arbitrageur::ArbitrageFill,
+        inspector::{Inspector, SaveData},
+    },
+    error::ArenaError,
+    Signal,
+};
+
+/// Name of the [`Metric`]s reporting the PnL of strategies, shown by the inspectors charting a run.
+pub const PNL_METRIC: &str = "pnl";
+
+/// Value of a named metric, logged to a [`MetricsInspector`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Metric {
+    /// Name of the series the value belongs to.
+    pub name: String,
+
+    /// The recorded value.
+    pub value: f64,
+
+    /// Unit of the value, e.g. `token1` or `wei`.
+    pub unit: Option<String>,
+
+    /// Identifier of the strategy or agent the value was recorded for, or `None` for values of the whole run.
+    pub agent: Option<usize>,
+}
+
+impl Metric {
+    /// Public constructor function for a new [`Metric`] of the series `name`, without unit or agent.
+    pub fn new(name: impl Into<String>, value: f64) -> Self {
+        Self {
+            name: name.into(),
+            value,
+            unit: None,
+            agent: None,
+        }
+    }
+
+    /// Tag the value with `unit`.
+    pub fn with_unit(mut self, unit: impl Into<String>) -> Self {
+        self.unit = Some(unit.into());
+        self
+    }
+
+    /// Record the value for the strategy or agent `agent`.
+    pub fn for_agent(mut self, agent: usize) -> Self {
+        self.agent = Some(agent);
+        self
+    }
+}
+
+/// Values of a metric recorded over a run, for a single agent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Series {
+    /// Name of the metric.
+    pub name: String,
+
+    /// Unit of the values, taken from the first value recorded with one.
+    pub unit: Option<String>,
+
+    /// Identifier of the strategy or agent the values were recorded for, or `None` for values of the whole run.
+    pub agent: Option<usize>,
+
+    /// Recorded values and the steps they were recorded at, oldest first.
+    pub points: Vec<(usize, f64)>,
+}
+
+impl Series {
+    /// Returns the latest recorded value.
+    pub fn last(&self) -> Option<f64> {
+        self.points.last().map(|(_, value)| *value)
+    }
+
+    /// Returns the sum of the recorded values.
+    pub fn total(&self) -> f64 {
+        self.points.iter().map(|(_, value)| value).sum()
+    }
+}
+
+/// Implementation of an [`Inspector`] recording any number of named series, each tagged with a unit and the agent it
+/// was recorded for, rather than a single type of value.
+///
+/// Strategies log [`Metric`]s through the inspector, and the engine records the feed value, the divergence of the pool
+/// and the in-range liquidity at the end of every step, and the profit of every arbitrage fill, as the series
+/// `feed_value`, `divergence`, `liquidity` and `arbitrage_profit`.
+///
+/// On [`Inspector::save`], every value is written to a CSV file in long format, one row per value, if a destination
+/// was set with [`MetricsInspector::with_save_data`].
+#[derive(Debug, Clone, Default)]
+pub struct MetricsInspector {
+    /// Destination of the file written on save.
+    pub save_data: Option<SaveData>,
+
+    series: Vec<Series>,
+    index: HashMap<(String, Option<usize>), usize>,
+    // series and point of every recorded value, in the order they were recorded
+    recorded: Vec<(usize, usize)>,
+    step: usize,
+}
+
+impl MetricsInspector {
+    /// Public constructor function for a new, empty [`MetricsInspector`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write the recorded values to `save_data` on save.
+    pub fn with_save_data(mut self, save_data: SaveData) -> Self {
+        self.save_data = Some(save_data);
+        self
+    }
+
+    /// Record `value` in the series `name` of the whole run, at the current step.
+    pub fn record(&mut self, name: impl Into<String>, value: f64) {
+        self.record_metric(Metric::new(name, value));
+    }
+
+    /// Record `metric` at the current step.
+    pub fn record_metric(&mut self, metric: Metric) {
+        let key = (metric.name, metric.agent);
+        let idx = match self.index.get(&key) {
+            Some(&idx) => idx,
+            None => {
+                self.series.push(Series {
+                    name: key.0.clone(),
+                    unit: None,
+                    agent: key.1,
+                    points: Vec::new(),
+                });
+                self.index.insert(key, self.series.len() - 1);
+                self.series.len() - 1
+            }
+        };
+
+        let series = &mut self.series[idx];
+        if series.unit.is_none() {
+            series.unit = metric.unit;
+        }
+        series.points.push((self.step, metric.value));
+        self.recorded.push((idx, series.points.len() - 1));
+    }
+
+    /// Returns the series `name` recorded for `agent`, or for the whole run if `agent` is `None`.
+    pub fn series(&self, name: &str, agent: Option<usize>) -> Option<&Series> {
+        self.index
+            .get(&(name.to_string(), agent))
+            .map(|&idx| &self.series[idx])
+    }
+
+    /// Returns every recorded series, in the order they were first recorded.
+    pub fn all_series(&self) -> &[Series] {
+        &self.series
+    }
+}
+
+impl Inspector<Metric> for MetricsInspector {
+    fn log(&mut self, value: Metric) -> Result<(), ArenaError> {
+        self.record_metric(value);
+        Ok(())
+    }
+
+    fn inspect(&self, step: usize) -> Option<Metric> {
+        // the latest value recorded at the step, across series
+        self.recorded
+            .iter()
+            .rev()
+            .map(|&(idx, point)| (&self.series[idx], self.series[idx].points[point]))
+            .find(|(_, (at, _))| *at == step)
+            .map(|(series, (_, value))| Metric {
+                name: series.name.clone(),
+                value,
+                unit: series.unit.clone(),
+                agent: series.agent,
+            })
+    }
+
+    fn save(&self) -> Result<(), ArenaError> {
+        let Some(save_data) = &self.save_data else {
+            return Ok(());
+        };
+
+        let mut writer = csv::Writer::from_path(save_data.file_path("csv")?)?;
+        writer.write_record(["step", "name", "agent", "unit", "value"])?;
+        for series in &self.series {
+            for (step, value) in &series.points {
+                writer.serialize((step, &series.name, series.agent, &series.unit, value))?;
+            }
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    fn on_step(&mut self, signal: &Signal) -> Result<(), ArenaError> {
+        let Some(step) = signal.step else {
+            return Ok(());
+        };
+        self.step = step;
+
+        self.record_metric(Metric::new("feed_value", signal.current_value).with_unit("token1"));
+        self.record_metric(Metric::new("divergence", signal.divergence).with_unit("fraction"));
+        self.record_metric(Metric::new("liquidity", signal.liquidity as f64));
+
+        // values recorded after the end of a step belong to the next one
+        self.step = step + 1;
+        Ok(())
+    }
+
+    fn on_arbitrage(&mut self, _pool: usize, fill: &ArbitrageFill) -> Result<(), ArenaError> {
+        self.record_metric(Metric::new("arbitrage_profit", fill.profit()).with_unit("token1"));
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), ArenaError> {
+        self.series.clear();
+        self.index.clear();
+        self.recorded.clear();
+        self.step = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics_inspector() {
+        let mut inspector = MetricsInspector::new();
+
+        inspector.record("fee_income", 1.5);
+        inspector
+            .log(
+                Metric::new("fee_income", 2.0)
+                    .with_unit("token1")
+                    .for_agent(0),
+            )
+            .unwrap();
+        inspector
+            .log(Metric::new("fee_income", 0.5).for_agent(0))
+            .unwrap();
+        inspector.record("gas", 21000.0);
+
+        let run = inspector.series("fee_income", None).unwrap();
+        assert_eq!(run.points, vec![(0, 1.5)]);
+        assert_eq!(run.unit, None);
+
+        let agent = inspector.series("fee_income", Some(0)).unwrap();
+        assert_eq!(agent.total(), 2.5);
+        assert_eq!(agent.last(), Some(0.5));
+        assert_eq!(agent.unit.as_deref(), Some("token1"));
+
+        assert_eq!(inspector.all_series().len(), 3);
+        assert!(inspector.series("fee_income", Some(1)).is_none());
+        assert_eq!(inspector.inspect(0).unwrap().name, "gas");
+        assert!(inspector.inspect(1).is_none());
+    }
+}
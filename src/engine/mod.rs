@@ -1,25 +1,410 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+};
+
 use alloy::{
-    primitives::{Address, Signed, I256},
+    eips::BlockNumberOrTag,
+    primitives::utils::parse_ether,
+    primitives::{Address, Signed, B256, I256, U256},
     providers::{Provider, WalletProvider},
+    rpc::types::TransactionRequest,
 };
 
 use super::*;
-use crate::{error::ArenaError, types::controller::ArenaController};
+use crate::{
+    backend::{self, Backend},
+    engine::{
+        arbitrageur::{ArbitrageFill, ArbitrageLedger, Inventory},
+        pool::PoolState,
+        position::{Holdings, Position},
+    },
+    error::ArenaError,
+    types::{
+        controller::ArenaController::{self, PoolKey},
+        exchange::LiquidExchange,
+        router::{
+            ModifyLiquidityParams, PoolModifyLiquidityTest, PoolSwapTest, SwapParams, TestSettings,
+        },
+        token::ArenaToken,
+    },
+};
+
 /// Defines a trait for custom arbitrage strategies.
 pub mod arbitrageur;
 
 /// Defines a trait that allows custom strategy logging and telemetry.
 pub mod inspector;
 
+/// Columnar Parquet output for inspectors.
+#[cfg(feature = "arrow")]
+pub mod columnar;
+
+/// Inspector recording named series of metrics.
+pub mod metrics;
+
+/// Realized and unrealized PnL of strategies and agents.
+pub mod pnl;
+
+/// Impermanent loss and loss versus rebalancing of liquidity positions.
+pub mod lvr;
+
+/// Attribution of fee income to liquidity positions.
+pub mod fees;
+
+/// Snapshots of the liquidity of the pool by tick.
+pub mod distribution;
+
+/// Price impact of the swaps on the pool by size.
+pub mod impact;
+
+/// Capture of the events of every contract of a simulation.
+pub mod eventlog;
+
+/// Inspector rendering charts of a run.
+#[cfg(feature = "plot")]
+pub mod plot;
+
+/// Live terminal dashboard of a run.
+#[cfg(feature = "tui")]
+pub mod dashboard;
+
+/// Seeded random number management for reproducible and variance-reduced simulations.
+pub mod rng;
+
+/// Defines an agent submitting uninformed order flow.
+pub mod noise;
+
+/// Helpers for reading pool state from the `PoolManager`.
+pub mod pool;
+
+/// Decoded events emitted by the `PoolManager`.
+pub mod events;
+
+/// Defines liquidity positions and helpers for opening and closing them.
+pub mod position;
+
+/// Configuration of the tokens deployed with each pool.
+pub(crate) mod token;
+
+/// Deployment of user-supplied hooks at addresses encoding their permissions.
+pub mod hook;
+
+/// Snapshotting and rewinding the chain, for strategies to evaluate actions before committing to them.
+pub mod snapshots;
+
+/// Balance and position tracking for strategy wallets.
+pub mod portfolio;
+
+/// Declarative operations executed by the engine on behalf of strategies.
+pub mod action;
+
+/// Base fee dynamics of the simulated chain.
+pub mod gas;
+
+/// Closed-form swap sizing against concentrated liquidity.
+pub mod math;
+
+/// Defines an agent providing just-in-time liquidity around large swaps.
+pub mod jit;
+
+/// Delays between agents observing the market and their transactions landing.
+pub mod latency;
+
+/// Ordering of agents by the bids of an auction for their position in a block.
+pub mod auction;
+
+/// Per-strategy performance metrics computed over a run.
+pub mod score;
+
+/// Gas limit a participant's transaction is sent with when its gas cannot be estimated, as for a call that reverts.
+///
+/// Sending the transaction with an explicit limit rather than failing at estimation mines its revert, so the revert
+/// has a receipt and can be traced, see [`crate::trace::TraceSelection::reverts`].
+pub const REVERT_GAS_LIMIT: u128 = 1_000_000;
+
+/// Send a transaction of a participant from the wallet behind `provider` and wait for it to be mined, with
+/// [`REVERT_GAS_LIMIT`] if its gas cannot be estimated. Returns an error if the transaction reverted.
+pub(crate) async fn submit(
+    tx: TransactionRequest,
+    provider: &AnvilProvider,
+) -> Result<(), ArenaError> {
+    let tx = tx.from(provider.default_signer_address());
+    let (gas_limit, estimate_error) = match provider.estimate_gas(&tx).await {
+        Ok(gas_limit) => (gas_limit, None),
+        Err(e) => (REVERT_GAS_LIMIT, Some(e)),
+    };
+
+    let receipt = provider
+        .send_transaction(tx.gas_limit(gas_limit))
+        .await?
+        .get_receipt()
+        .await
+        .map_err(ArenaError::PendingTransactionError)?;
+
+    if !receipt.status() {
+        return Err(ArenaError::Custom(format!(
+            "transaction {} reverted{}",
+            receipt.transaction_hash,
+            estimate_error.map_or_else(String::new, |e| format!(": {e}"))
+        )));
+    }
+    Ok(())
+}
+
 /// Abstraction to allow strategies to call state changing functions on the PoolManager without having to worry about callbacks.
 #[derive(Debug, Clone)]
 pub struct Engine {
     pub(crate) controller: Address,
+
+    /// Key of the pool the engine acts on, if not the pool of the controller but another pool of its `PoolManager`.
+    pub(crate) key: Option<PoolKey>,
+
+    /// Liquid exchange the pool is arbitraged against, if not the one of the controller.
+    pub(crate) exchange: Option<Address>,
+
+    /// Positions of every wallet, shared between all clones of the engine.
+    pub(crate) ledger: Arc<Mutex<HashMap<Address, Holdings>>>,
+
+    /// Decimals of token0 and token1.
+    pub(crate) decimals: (u8, u8),
+
+    /// Fills of the arbitrageur on the pool, shared between all clones of the engine.
+    pub(crate) arbitrage: Arc<Mutex<ArbitrageLedger>>,
+
+    /// Swaps held back rather than sent, if the engine defers them, see [`Engine::deferring`].
+    pub(crate) deferred: Option<Arc<Mutex<Vec<DeferredSwap>>>>,
+}
+
+/// A swap decided through [`Engine::swap`] on an engine deferring swaps, sent later exactly as decided with
+/// [`DeferredSwap::send`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeferredSwap {
+    /// Whether token0 is sold for token1.
+    pub zero_for_one: bool,
+
+    /// Amount swapped, negative for an exact input and positive for an exact output.
+    pub amount_specified: I256,
+
+    /// Sqrt price the swap stops at, if any.
+    pub sqrt_price_limit_x96: Option<Uint<160, 3>>,
+}
+
+impl DeferredSwap {
+    /// Send the swap through `engine` from the wallet behind `provider`.
+    pub async fn send(&self, engine: &Engine, provider: AnvilProvider) -> Result<(), ArenaError> {
+        engine
+            .swap(
+                self.zero_for_one,
+                self.amount_specified,
+                self.sqrt_price_limit_x96,
+                provider,
+            )
+            .await
+    }
 }
 
 #[allow(clippy::redundant_closure)]
 impl Engine {
-    /// Modify pool liquidity.
+    /// Public constructor function for a new [`Engine`] acting through a deployed controller.
+    pub(crate) fn new(controller: Address) -> Self {
+        Self {
+            controller,
+            key: None,
+            exchange: None,
+            ledger: Arc::new(Mutex::new(HashMap::new())),
+            decimals: (18, 18),
+            arbitrage: Arc::new(Mutex::new(ArbitrageLedger::default())),
+            deferred: None,
+        }
+    }
+
+    /// Public constructor function for a new [`Engine`] acting on the pool of `key` on the `PoolManager` of a deployed
+    /// controller rather than on the pool of the controller, arbitraged against the liquid exchange at `exchange`.
+    pub(crate) fn shared(controller: Address, key: PoolKey, exchange: Address) -> Self {
+        Self {
+            key: Some(key),
+            exchange: Some(exchange),
+            ..Self::new(controller)
+        }
+    }
+
+    /// Read the state of the pool the engine acts on through the controller, replacing the pool, its price and the
+    /// liquid exchange price by those of the pool of the engine if it is not the pool of the controller.
+    async fn state(&self, provider: AnvilProvider) -> Result<ArenaController::Signal, ArenaError> {
+        let mut state = backend::read(
+            &provider,
+            self.controller,
+            ArenaController::constructSignalCall {},
+        )
+        .await?
+        ._0;
+
+        if let Some(key) = &self.key {
+            (state.sqrtPriceX96, state.currentTick) =
+                pool::slot0(state.manager, key, provider.clone()).await?;
+            state.lexPrice = backend::read(
+                &provider,
+                self.liquid_exchange(),
+                LiquidExchange::priceCall {},
+            )
+            .await?
+            ._0;
+            state.pool = key.clone();
+        }
+
+        Ok(state)
+    }
+
+    /// Returns a clone of the engine holding back every swap sent through [`Engine::swap`], to be taken with
+    /// [`Engine::take_deferred`] and sent later, while other operations still execute right away.
+    pub fn deferring(&self) -> Self {
+        Self {
+            deferred: Some(Arc::new(Mutex::new(Vec::new()))),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the swaps held back so far by an engine returned by [`Engine::deferring`], oldest first, and forgets
+    /// them.
+    pub fn take_deferred(&self) -> Vec<DeferredSwap> {
+        self.deferred.as_ref().map_or_else(Vec::new, |deferred| {
+            std::mem::take(&mut *deferred.lock().unwrap_or_else(PoisonError::into_inner))
+        })
+    }
+
+    /// Read the current state of the pool into a [`Signal`].
+    pub async fn signal(
+        &self,
+        step: Option<usize>,
+        current_value: f64,
+        provider: AnvilProvider,
+    ) -> Result<Signal, ArenaError> {
+        let state = self.state(provider.clone()).await?;
+
+        let mut signal = Signal::new(
+            state.lexPrice,
+            step,
+            state.currentTick,
+            state.sqrtPriceX96,
+            state.manager,
+            state.pool,
+            state.fetcher,
+            current_value,
+            self.controller,
+        );
+        signal.decimals = self.decimals;
+        signal.liquidity = pool::liquidity(signal.manager, &signal.pool, provider.clone()).await?;
+        signal.pool_state =
+            PoolState::fetch(signal.manager, &signal.pool, provider.clone()).await?;
+
+        if let Some(block) = provider
+            .get_block_by_number(BlockNumberOrTag::Latest, false)
+            .await?
+        {
+            signal.block_number = block.header.number;
+            signal.timestamp = block.header.timestamp;
+            signal.base_fee = block.header.base_fee_per_gas;
+        }
+        signal.gas_price = provider.get_gas_price().await?;
+
+        Ok(signal)
+    }
+
+    /// Set the price of the liquid exchange the pool is arbitraged against.
+    ///
+    /// `price` is the price of token0 in terms of token1 in whole tokens, while the liquid exchange quotes raw units.
+    pub(crate) async fn set_price(
+        &self,
+        price: f64,
+        provider: AnvilProvider,
+    ) -> Result<(), ArenaError> {
+        let price = price * 10f64.powi(self.decimals.1 as i32 - self.decimals.0 as i32);
+        let price = parse_ether(&format!("{price:.18}"))?;
+        let nonce = provider
+            .get_transaction_count(provider.default_signer_address())
+            .await?;
+
+        // the controller only forwards its price to its liquid exchange, which anyone may set
+        LiquidExchange::new(self.liquid_exchange(), provider.clone())
+            .setPrice(price)
+            .nonce(nonce)
+            .send()
+            .await
+            .map_err(ArenaError::ContractError)?
+            .watch()
+            .await
+            .map_err(|e| ArenaError::PendingTransactionError(e))?;
+
+        Ok(())
+    }
+
+    /// Advance the chain clock by `seconds`, taking effect at the next mined block.
+    pub async fn increase_time(
+        &self,
+        seconds: u64,
+        provider: AnvilProvider,
+    ) -> Result<(), ArenaError> {
+        provider.increase_time(seconds).await
+    }
+
+    /// Set the timestamp of the next mined block, which must be later than the latest block.
+    pub async fn set_next_block_timestamp(
+        &self,
+        timestamp: u64,
+        provider: AnvilProvider,
+    ) -> Result<(), ArenaError> {
+        provider.set_next_block_timestamp(timestamp).await
+    }
+
+    /// Mine `blocks` blocks, the first of which includes any pending transactions.
+    pub async fn mine(&self, blocks: u64, provider: AnvilProvider) -> Result<(), ArenaError> {
+        Backend::mine(&provider, blocks).await
+    }
+
+    /// Read the [`Inventory`] of `owner` in the tokens of the pool of `key`.
+    pub async fn inventory(
+        &self,
+        owner: Address,
+        key: &PoolKey,
+        provider: AnvilProvider,
+    ) -> Result<Inventory, ArenaError> {
+        Inventory::fetch(owner, key, provider).await
+    }
+
+    /// Returns the fills of the arbitrageur on the pool and the feed value of every step to mark them at.
+    pub fn arbitrage_ledger(&self) -> ArbitrageLedger {
+        self.arbitrage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Record a fill of the arbitrageur.
+    pub(crate) fn record_fill(&self, fill: ArbitrageFill) {
+        self.arbitrage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .fills
+            .push(fill);
+    }
+
+    /// Record the feed value at the end of `step`.
+    pub(crate) fn record_mark(&self, step: usize, price: f64) {
+        self.arbitrage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .marks
+            .insert(step, price);
+    }
+
+    /// Modify pool liquidity from the wallet behind `provider`, through the liquidity router of the controller.
+    ///
+    /// The router settles with the wallet, which pays the tokens backing added liquidity and receives those backing
+    /// removed liquidity, along with the fees of the position. The wallet approves the router to spend both tokens
+    /// before its first modification. The position is salted with the wallet's address, see [`pool::position_salt`], so
+    /// wallets providing over the same range hold distinct positions. The modification is recorded in the [`Holdings`]
+    /// of the wallet.
     pub async fn modify_liquidity(
         &self,
         liquidity_delta: I256,
@@ -28,23 +413,229 @@ impl Engine {
         hook_data: Bytes,
         provider: AnvilProvider,
     ) -> Result<(), ArenaError> {
-        let controller = ArenaController::new(self.controller, provider.clone());
+        let owner = provider.default_signer_address();
+
+        let signal = self.state(provider.clone()).await?;
+        let router = backend::read(
+            &provider,
+            self.controller,
+            ArenaController::getRouterCall {},
+        )
+        .await?
+        ._0;
+        let (tick, lower, upper) = (
+            signal.currentTick.as_i32(),
+            tick_lower.as_i32(),
+            tick_upper.as_i32(),
+        );
+        let delta = i128::try_from(liquidity_delta)
+            .map_err(|_| ArenaError::Custom("liquidity delta overflows int128".to_string()))?;
+
+        for currency in [signal.pool.currency0, signal.pool.currency1] {
+            token::approve(currency, router, provider.clone()).await?;
+        }
+
+        let before = pool::fee_growth_inside(
+            signal.manager,
+            &signal.pool,
+            lower,
+            upper,
+            tick,
+            provider.clone(),
+        )
+        .await?;
+
+        let modification = PoolModifyLiquidityTest::new(router, provider.clone())
+            .modifyLiquidity(
+                pool::router_key(&signal.pool),
+                ModifyLiquidityParams {
+                    tickLower: tick_lower,
+                    tickUpper: tick_upper,
+                    liquidityDelta: liquidity_delta,
+                    salt: pool::position_salt(owner),
+                },
+                hook_data,
+            )
+            .nonce(provider.get_transaction_count(owner).await?)
+            .into_transaction_request();
+        submit(modification, &provider).await?;
+
+        let after = pool::fee_growth_inside(
+            signal.manager,
+            &signal.pool,
+            lower,
+            upper,
+            tick,
+            provider.clone(),
+        )
+        .await?;
+
+        let amounts = Position::new(lower, upper, delta.unsigned_abs())
+            .amounts(f64::from(signal.sqrtPriceX96) / 2f64.powi(96));
+
+        self.ledger
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(owner)
+            .or_default()
+            .record(lower, upper, delta, amounts, before, after);
+
+        Ok(())
+    }
+
+    /// Returns the key of the pool the engine acts on.
+    pub async fn pool_key(&self, provider: AnvilProvider) -> Result<PoolKey, ArenaError> {
+        if let Some(key) = &self.key {
+            return Ok(key.clone());
+        }
+
+        Ok(backend::read(
+            &provider,
+            self.controller,
+            ArenaController::constructSignalCall {},
+        )
+        .await?
+        ._0
+        .pool)
+    }
+
+    /// Returns the liquidity router liquidity is modified through, the second contract the controller deploys after the
+    /// `PoolManager`, which owns the positions of every wallet in the `PoolManager`.
+    pub fn liquidity_router(&self) -> Address {
+        self.controller.create(2)
+    }
+
+    /// Returns the `PoolSwapTest` router swaps are sent through, the third contract the controller deploys after the
+    /// `PoolManager` and the liquidity router.
+    pub fn swap_router(&self) -> Address {
+        self.controller.create(3)
+    }
+
+    /// Returns the liquid exchange the pool is arbitraged against, by default the one the controller deploys after its
+    /// pool tokens, the seventh contract it creates.
+    pub fn liquid_exchange(&self) -> Address {
+        self.exchange.unwrap_or_else(|| self.controller.create(7))
+    }
+
+    /// Sell `amount_in` of token0, or of token1 if not `zero_for_one`, on the liquid exchange from the wallet behind
+    /// `provider`, at the exchange price.
+    ///
+    /// The exchange pays out of its own balances, so it is minted whatever it lacks of the output token beforehand.
+    pub async fn swap_on_exchange(
+        &self,
+        zero_for_one: bool,
+        amount_in: U256,
+        provider: AnvilProvider,
+    ) -> Result<(), ArenaError> {
+        if amount_in.is_zero() {
+            return Ok(());
+        }
+
+        let key = self.pool_key(provider.clone()).await?;
+        let exchange = LiquidExchange::new(self.liquid_exchange(), provider.clone());
+        let owner = provider.default_signer_address();
+
+        let (input, output) = if zero_for_one {
+            (key.currency0, key.currency1)
+        } else {
+            (key.currency1, key.currency0)
+        };
+
+        // the exchange quotes raw units of token1 per raw unit of token0, scaled by 1e18
+        let price = backend::read(&provider, *exchange.address(), LiquidExchange::priceCall {})
+            .await?
+            ._0;
+        let wad = U256::from(10).pow(U256::from(18));
+        let amount_out = if zero_for_one {
+            amount_in * price / wad
+        } else if price.is_zero() {
+            return Err(ArenaError::Custom(
+                "cannot sell token1 on the liquid exchange at a zero price".to_string(),
+            ));
+        } else {
+            amount_in * wad / price
+        };
+
+        let reserve = pool::balance_of(output, *exchange.address(), provider.clone()).await?;
+        if reserve < amount_out {
+            ArenaToken::new(output, provider.clone())
+                .mint(*exchange.address(), amount_out - reserve)
+                .nonce(provider.get_transaction_count(owner).await?)
+                .send()
+                .await
+                .map_err(ArenaError::ContractError)?
+                .watch()
+                .await
+                .map_err(ArenaError::PendingTransactionError)?;
+        }
+
+        token::approve(input, *exchange.address(), provider.clone()).await?;
+        let swap = exchange
+            .swap(input, amount_in)
+            .nonce(provider.get_transaction_count(owner).await?)
+            .into_transaction_request();
+        submit(swap, &provider).await?;
+
+        Ok(())
+    }
+
+    /// Swap against the pool from the wallet behind `provider`, in a single call to the swap router.
+    ///
+    /// A negative `amount_specified` is an exact-input swap and a positive one is exact-output. The swap stops once the
+    /// pool reaches `sqrt_price_limit_x96`, or fills entirely if it is `None`. The router settles the swap with the
+    /// wallet, which approves it to spend its input token before its first swap. An engine returned by
+    /// [`Engine::deferring`] holds the swap back instead.
+    pub async fn swap(
+        &self,
+        zero_for_one: bool,
+        amount_specified: I256,
+        sqrt_price_limit_x96: Option<Uint<160, 3>>,
+        provider: AnvilProvider,
+    ) -> Result<(), ArenaError> {
+        if let Some(deferred) = &self.deferred {
+            deferred
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(DeferredSwap {
+                    zero_for_one,
+                    amount_specified,
+                    sqrt_price_limit_x96,
+                });
+            return Ok(());
+        }
+
+        let key = self.pool_key(provider.clone()).await?;
+        let router = self.swap_router();
 
-        controller
-            .addLiquidity(liquidity_delta, tick_lower, tick_upper, hook_data)
+        let input = if zero_for_one {
+            key.currency0
+        } else {
+            key.currency1
+        };
+        token::approve(input, router, provider.clone()).await?;
+
+        let swap = PoolSwapTest::new(router, provider.clone())
+            .swap(
+                pool::router_key(&key),
+                SwapParams {
+                    zeroForOne: zero_for_one,
+                    amountSpecified: amount_specified,
+                    sqrtPriceLimitX96: sqrt_price_limit_x96
+                        .unwrap_or_else(|| pool::price_limit(zero_for_one)),
+                },
+                TestSettings {
+                    takeClaims: false,
+                    settleUsingBurn: false,
+                },
+                Bytes::new(),
+            )
             .nonce(
                 provider
                     .get_transaction_count(provider.default_signer_address())
-                    .await
-                    .unwrap(),
+                    .await?,
             )
-            .send()
-            .await
-            .map_err(ArenaError::ContractError)?
-            .watch()
-            .await
-            .map_err(|e| ArenaError::PendingTransactionError(e))?;
+            .into_transaction_request();
 
-        Ok(())
+        submit(swap, &provider).await
     }
 }
@@ -0,0 +1,57 @@
+use alloy::primitives::utils::parse_units;
+use rand::rngs::StdRng;
+
+use super::*;
+use crate::feed::orderflow::{Order, OrderFlow};
+
+/// Agent that submits uninformed market orders drawn from an [`OrderFlow`] process.
+///
+/// Orders are swapped from the wallet of the trader, through [`Engine::swap`].
+pub struct NoiseTrader {
+    /// The process generating this trader's orders.
+    pub flow: Box<dyn OrderFlow>,
+}
+
+impl NoiseTrader {
+    /// Public constructor function for a new [`NoiseTrader`].
+    pub fn new(flow: Box<dyn OrderFlow>) -> Self {
+        Self { flow }
+    }
+
+    /// Execute the orders arriving during one step as exact-input swaps against the pool, from the wallet behind
+    /// `provider`.
+    ///
+    /// Order amounts are in whole units of the input token.
+    pub async fn trade(
+        &mut self,
+        engine: &Engine,
+        provider: AnvilProvider,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        for order in self.flow.orders(rng)? {
+            Self::execute(&order, engine, provider.clone()).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Execute a single order as an exact-input swap against the pool, from the wallet behind `provider`.
+    pub async fn execute(
+        order: &Order,
+        engine: &Engine,
+        provider: AnvilProvider,
+    ) -> Result<(), ArenaError> {
+        let decimals = if order.zero_for_one {
+            engine.decimals.0
+        } else {
+            engine.decimals.1
+        };
+        let amount = parse_units(&format!("{:.*}", decimals as usize, order.amount), decimals)?
+            .get_absolute();
+        let amount = I256::try_from(amount).unwrap_or(I256::MAX);
+
+        engine
+            .swap(order.zero_for_one, -amount, None, provider)
+            .await
+    }
+}
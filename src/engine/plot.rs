@@ -0,0 +1,285 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use plotters::{coord::Shift, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    engine::{
+        inspector::{Inspector, SaveData},
+        metrics::{Metric, PNL_METRIC},
+    },
+    error::ArenaError,
+    Signal,
+};
+
+/// Returns an [`ArenaError`] for a failure to render a chart.
+fn plot_error(e: impl Display) -> ArenaError {
+    ArenaError::PlotError(e.to_string())
+}
+
+/// Image format written by a [`PlotInspector`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlotFormat {
+    /// Raster PNG image.
+    #[default]
+    Png,
+
+    /// Vector SVG image.
+    Svg,
+}
+
+impl PlotFormat {
+    /// Returns the file extension of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PlotFormat::Png => "png",
+            PlotFormat::Svg => "svg",
+        }
+    }
+}
+
+/// Line of a chart, with its label and points.
+type Line<'a> = (String, &'a [(usize, f64)]);
+
+/// Implementation of an [`Inspector`] rendering the charts of a run to an image on [`Inspector::save`], so quick
+/// experiments produce visual output without a separate notebook step.
+///
+/// The image holds four charts over the steps of the run: the feed value, the pool price against the feed value, the
+/// in-range liquidity of the pool and the PnL of strategies. Strategies report their PnL by logging [`Metric`]s named
+/// [`PNL_METRIC`], one line per agent, and other metrics are ignored.
+#[derive(Debug, Clone)]
+pub struct PlotInspector {
+    /// Destination of the image.
+    pub save_data: SaveData,
+
+    /// Format of the image.
+    pub format: PlotFormat,
+
+    /// Width and height of the image, in pixels.
+    pub size: (u32, u32),
+
+    feed: Vec<(usize, f64)>,
+    pool: Vec<(usize, f64)>,
+    liquidity: Vec<(usize, f64)>,
+    pnl: BTreeMap<Option<usize>, Vec<(usize, f64)>>,
+    step: usize,
+}
+
+impl PlotInspector {
+    /// Public constructor function for a new [`PlotInspector`] rendering a 1600 by 1000 PNG image to `save_data`.
+    pub fn new(save_data: SaveData) -> Self {
+        Self {
+            save_data,
+            format: PlotFormat::Png,
+            size: (1600, 1000),
+            feed: Vec::new(),
+            pool: Vec::new(),
+            liquidity: Vec::new(),
+            pnl: BTreeMap::new(),
+            step: 0,
+        }
+    }
+
+    /// Render the image in `format`.
+    pub fn with_format(mut self, format: PlotFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Render an image of `width` by `height` pixels.
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.size = (width, height);
+        self
+    }
+
+    /// Draw the four charts on `root`.
+    fn render<DB: DrawingBackend>(&self, root: &DrawingArea<DB, Shift>) -> Result<(), ArenaError> {
+        root.fill(&WHITE).map_err(plot_error)?;
+        let panels = root.split_evenly((2, 2));
+
+        let pnl: Vec<Line> = self
+            .pnl
+            .iter()
+            .map(|(agent, points)| {
+                let label = agent.map_or("run".to_string(), |agent| format!("strategy {agent}"));
+                (label, points.as_slice())
+            })
+            .collect();
+
+        draw_chart(
+            &panels[0],
+            "Feed value",
+            &[("feed".to_string(), &self.feed)],
+        )?;
+        draw_chart(
+            &panels[1],
+            "Pool price and feed value",
+            &[
+                ("pool".to_string(), &self.pool),
+                ("feed".to_string(), &self.feed),
+            ],
+        )?;
+        draw_chart(
+            &panels[2],
+            "In-range liquidity",
+            &[("liquidity".to_string(), &self.liquidity)],
+        )?;
+        draw_chart(&panels[3], "Strategy PnL", &pnl)?;
+
+        root.present().map_err(plot_error)
+    }
+}
+
+/// Draw a line chart of `lines` over the steps, with a legend if there are several.
+fn draw_chart<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    caption: &str,
+    lines: &[Line],
+) -> Result<(), ArenaError> {
+    let points = || lines.iter().flat_map(|(_, points)| points.iter());
+    let steps = points().map(|(step, _)| *step).max().unwrap_or(0).max(1) as f64;
+    let (mut low, mut high) = points().fold(
+        (f64::INFINITY, f64::NEG_INFINITY),
+        |(low, high), (_, value)| (low.min(*value), high.max(*value)),
+    );
+    if low > high {
+        (low, high) = (0.0, 1.0);
+    }
+    // pad the range so flat lines stay visible
+    let pad = ((high - low) * 0.05).max(high.abs() * 1e-6).max(1e-12);
+
+    let mut chart = ChartBuilder::on(area)
+        .caption(caption, ("sans-serif", 20))
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(70)
+        .build_cartesian_2d(0.0..steps, (low - pad)..(high + pad))
+        .map_err(plot_error)?;
+    chart
+        .configure_mesh()
+        .x_desc("step")
+        .draw()
+        .map_err(plot_error)?;
+
+    for (idx, (label, points)) in lines.iter().enumerate() {
+        let color = Palette99::pick(idx).to_rgba();
+        chart
+            .draw_series(LineSeries::new(
+                points.iter().map(|(step, value)| (*step as f64, *value)),
+                color,
+            ))
+            .map_err(plot_error)?
+            .label(label.as_str())
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+    }
+
+    if lines.len() > 1 {
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()
+            .map_err(plot_error)?;
+    }
+
+    Ok(())
+}
+
+impl Inspector<Metric> for PlotInspector {
+    fn log(&mut self, value: Metric) -> Result<(), ArenaError> {
+        if value.name == PNL_METRIC {
+            self.pnl
+                .entry(value.agent)
+                .or_default()
+                .push((self.step, value.value));
+        }
+
+        Ok(())
+    }
+
+    fn inspect(&self, step: usize) -> Option<Metric> {
+        self.pnl.iter().find_map(|(agent, points)| {
+            let (_, value) = points.iter().rev().find(|(at, _)| *at == step)?;
+            Some(Metric {
+                name: PNL_METRIC.to_string(),
+                value: *value,
+                unit: None,
+                agent: *agent,
+            })
+        })
+    }
+
+    fn save(&self) -> Result<(), ArenaError> {
+        let path = self.save_data.file_path(self.format.extension())?;
+
+        match self.format {
+            PlotFormat::Png => {
+                self.render(&BitMapBackend::new(&path, self.size).into_drawing_area())
+            }
+            PlotFormat::Svg => self.render(&SVGBackend::new(&path, self.size).into_drawing_area()),
+        }
+    }
+
+    fn on_step(&mut self, signal: &Signal) -> Result<(), ArenaError> {
+        let Some(step) = signal.step else {
+            return Ok(());
+        };
+
+        self.feed.push((step, signal.current_value));
+        self.pool.push((step, signal.price()));
+        self.liquidity.push((step, signal.liquidity as f64));
+
+        // values logged after the end of a step belong to the next one
+        self.step = step + 1;
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), ArenaError> {
+        self.feed.clear();
+        self.pool.clear();
+        self.liquidity.clear();
+        self.pnl.clear();
+        self.step = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_plot_inspector() {
+        let path = std::env::temp_dir().join(format!("arena-plot-{}", std::process::id()));
+        let save_data = SaveData::ToFile {
+            path: path.clone(),
+            name: "run".to_string(),
+        };
+
+        let mut inspector = PlotInspector::new(save_data.clone())
+            .with_format(PlotFormat::Svg)
+            .with_size(800, 500);
+        for step in 0..10 {
+            inspector.feed.push((step, 1.0 + step as f64 * 0.1));
+            inspector.pool.push((step, 1.0));
+            inspector
+                .log(Metric::new(PNL_METRIC, step as f64).for_agent(0))
+                .unwrap();
+            inspector.log(Metric::new("ignored", 0.0)).unwrap();
+            inspector.step += 1;
+        }
+        assert_eq!(inspector.inspect(3).unwrap().value, 3.0);
+        assert_eq!(inspector.pnl.len(), 1);
+
+        inspector.save().unwrap();
+        let svg = fs::read_to_string(save_data.file_path("svg").unwrap()).unwrap();
+        assert!(svg.starts_with("<svg") && svg.contains("Strategy PnL"));
+
+        inspector.with_format(PlotFormat::Png).save().unwrap();
+        assert!(save_data.file_path("png").unwrap().exists());
+
+        fs::remove_dir_all(path).unwrap();
+    }
+}
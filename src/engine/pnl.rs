@@ -0,0 +1,396 @@
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use alloy::{
+    primitives::{Address, B256, U256},
+    providers::WalletProvider,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    inspector::{Inspector, SaveData},
+    portfolio::Portfolio,
+    position::{Position, PositionRecord},
+    *,
+};
+use crate::Signal;
+
+/// Open position of a strategy or agent wallet at the end of a step, part of an [`AgentMark`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RangeMark {
+    /// The position, with the liquidity held on the pool under the salt of the wallet.
+    pub position: Position,
+
+    /// Salt of the position, see [`pool::position_salt`].
+    pub salt: B256,
+
+    /// Fee growth of token0 and token1 inside the range of the position, as Q128.128 raw fees per unit of liquidity.
+    pub fee_growth_inside: (U256, U256),
+
+    /// Fees of token0 and token1 earned by the position and not collected yet, in whole tokens.
+    pub fees_owed: (f64, f64),
+}
+
+/// Holdings of a participant's wallet at the end of a step, in whole tokens, passed to [`Inspector::on_marks`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentMark {
+    /// Name of the participant, e.g. `strategy 0`, `agent 1`, `arbitrageur` or `noise trader 0`.
+    pub name: String,
+
+    /// Address of the wallet.
+    pub owner: Address,
+
+    /// Spot price of the pool, in whole units of token1 per whole unit of token0.
+    pub price: f64,
+
+    /// Balances of token0 and token1 held by the wallet.
+    pub balances: (f64, f64),
+
+    /// Amounts of token0 and token1 backing the open positions of the wallet at the spot price.
+    pub positions: (f64, f64),
+
+    /// Fees of token0 and token1 collected so far into the balances, by modifying positions.
+    pub fees_collected: (f64, f64),
+
+    /// Fees of token0 and token1 earned by the open positions and not collected yet.
+    pub fees_owed: (f64, f64),
+
+    /// Net amounts of token0 and token1 deposited into positions.
+    pub deposited: (f64, f64),
+
+    /// Open positions of the wallet, one per tick range.
+    pub ranges: Vec<RangeMark>,
+
+    /// Decimals of token0 and token1.
+    pub decimals: (u8, u8),
+}
+
+impl AgentMark {
+    /// Mark the wallet behind `provider` at the spot price of the pool of `signal`, including the positions and fees
+    /// recorded by the engine.
+    pub async fn fetch(
+        name: impl Into<String>,
+        engine: &Engine,
+        signal: &Signal,
+        provider: AnvilProvider,
+    ) -> Result<Self, ArenaError> {
+        let owner = provider.default_signer_address();
+        let holdings = engine.holdings(owner);
+        let portfolio =
+            Portfolio::fetch(provider.clone(), signal, &holdings.open_positions()).await?;
+
+        let scale = |(amount0, amount1): (f64, f64)| {
+            (
+                amount0 * 10f64.powi(-(portfolio.decimals.0 as i32)),
+                amount1 * 10f64.powi(-(portfolio.decimals.1 as i32)),
+            )
+        };
+
+        // fees are attributed to the liquidity the wallet holds on the pool under its own salt
+        let salt = pool::position_salt(owner);
+        let mut owed = (0.0, 0.0);
+        let mut ranges = Vec::with_capacity(holdings.positions.len());
+        for record in &holdings.positions {
+            let (tick_lower, tick_upper) = (record.position.tick_lower, record.position.tick_upper);
+            let liquidity = pool::position_liquidity(
+                signal.manager,
+                &signal.pool,
+                engine.liquidity_router(),
+                (tick_lower, tick_upper),
+                salt,
+                provider.clone(),
+            )
+            .await?;
+            let record = PositionRecord {
+                position: Position {
+                    liquidity,
+                    ..record.position
+                },
+                fee_growth_inside: record.fee_growth_inside,
+            };
+
+            let fee_growth_inside = pool::fee_growth_inside(
+                signal.manager,
+                &signal.pool,
+                tick_lower,
+                tick_upper,
+                signal.tick.as_i32(),
+                provider.clone(),
+            )
+            .await?;
+            let fees = record.fees_owed(fee_growth_inside);
+            owed = (owed.0 + fees.0, owed.1 + fees.1);
+
+            ranges.push(RangeMark {
+                position: record.position,
+                salt,
+                fee_growth_inside,
+                fees_owed: scale(fees),
+            });
+        }
+
+        Ok(Self {
+            name: name.into(),
+            owner,
+            price: signal.price(),
+            balances: portfolio.balances(),
+            positions: portfolio.position_amounts(),
+            fees_collected: scale(holdings.fees),
+            fees_owed: scale(owed),
+            deposited: scale(holdings.deposited),
+            ranges,
+            decimals: portfolio.decimals,
+        })
+    }
+
+    /// Returns the value of `amounts` at the spot price, in whole units of token1.
+    pub fn value_of(&self, amounts: (f64, f64)) -> f64 {
+        amounts.0 * self.price + amounts.1
+    }
+
+    /// Returns the value of the balances, open positions and fees owed of the wallet, in whole units of token1.
+    ///
+    /// Positions are paid for by the wallet and pay their fees back to it, so deposits are already taken out of the
+    /// balances, and collected fees already added to them.
+    pub fn value(&self) -> f64 {
+        self.value_of(self.balances) + self.value_of(self.positions) + self.value_of(self.fees_owed)
+    }
+
+    /// Returns the amount of token0 held by the wallet in balances, positions and fees owed.
+    pub fn inventory(&self) -> f64 {
+        self.balances.0 + self.positions.0 + self.fees_owed.0
+    }
+}
+
+/// PnL of a strategy or agent at a step, in whole units of token1.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PnlPoint {
+    /// Step of the mark, or `None` for the mark taken after initialization.
+    pub step: Option<usize>,
+
+    /// Value of the wallet, see [`AgentMark::value`].
+    pub value: f64,
+
+    /// PnL locked in by reducing the token0 inventory of the wallet, and earned in token1.
+    pub realized: f64,
+
+    /// PnL of the token0 inventory of the wallet against its average cost.
+    pub unrealized: f64,
+}
+
+impl PnlPoint {
+    /// Returns the change in value of the wallet since its first mark.
+    pub fn total(&self) -> f64 {
+        self.realized + self.unrealized
+    }
+}
+
+/// Implementation of an [`Inspector`] marking the wallet and positions of every strategy and agent to market at the
+/// spot price of the pool every step, tracking their realized and unrealized PnL.
+///
+/// PnL is measured from the mark taken after initialization, and split with the average cost method, treating token0 as
+/// the traded asset and token1 as cash: token0 held in balances, positions and fees is inventory, whose cost is averaged
+/// over the spot prices of the steps it grew at. Unrealized PnL is the gain of the inventory over its average cost, and
+/// realized PnL the rest of the change in value, locked in by selling inventory or earned in token1.
+///
+/// On [`Inspector::save`], the series of every strategy and agent is written to a CSV file if a destination was set
+/// with [`PnlInspector::with_save_data`].
+///
+/// The inspector observes marks only, so logged values are dropped and cannot be inspected.
+#[derive(Debug, Clone)]
+pub struct PnlInspector<V = f64> {
+    /// Destination of the file written on save.
+    pub save_data: Option<SaveData>,
+
+    series: BTreeMap<String, Vec<PnlPoint>>,
+    // inventory and its average cost per strategy or agent, as of its latest mark
+    books: BTreeMap<String, (f64, f64)>,
+    _value: PhantomData<fn(V)>,
+}
+
+impl<V> Default for PnlInspector<V> {
+    fn default() -> Self {
+        Self {
+            save_data: None,
+            series: BTreeMap::new(),
+            books: BTreeMap::new(),
+            _value: PhantomData,
+        }
+    }
+}
+
+impl<V> PnlInspector<V> {
+    /// Public constructor function for a new, empty [`PnlInspector`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write the PnL series to `save_data` on save.
+    pub fn with_save_data(mut self, save_data: SaveData) -> Self {
+        self.save_data = Some(save_data);
+        self
+    }
+
+    /// Returns the PnL series of the strategy or agent `name`, oldest first.
+    pub fn series(&self, name: &str) -> Option<&[PnlPoint]> {
+        self.series.get(name).map(Vec::as_slice)
+    }
+
+    /// Record `mark`, taken at `step`.
+    pub fn record(&mut self, step: Option<usize>, mark: &AgentMark) {
+        let (inventory, cost) = self
+            .books
+            .get(&mark.name)
+            .copied()
+            .unwrap_or((0.0, mark.price));
+        let held = mark.inventory();
+
+        // inventory bought since the previous mark is taken at the spot price
+        let cost = if held > inventory && held > 0.0 {
+            (cost * inventory.max(0.0) + mark.price * (held - inventory.max(0.0))) / held
+        } else {
+            cost
+        };
+        self.books.insert(mark.name.clone(), (held, cost));
+
+        let series = self.series.entry(mark.name.clone()).or_default();
+        let value = mark.value();
+        let total = series.first().map_or(0.0, |first| value - first.value);
+        let unrealized = (mark.price - cost) * held;
+
+        series.push(PnlPoint {
+            step,
+            value,
+            realized: total - unrealized,
+            unrealized,
+        });
+    }
+}
+
+impl<V: Send> Inspector<V> for PnlInspector<V> {
+    fn log(&mut self, _value: V) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    fn inspect(&self, _step: usize) -> Option<V> {
+        None
+    }
+
+    fn save(&self) -> Result<(), ArenaError> {
+        let Some(save_data) = &self.save_data else {
+            return Ok(());
+        };
+
+        let mut writer = csv::Writer::from_path(save_data.file_path("csv")?)?;
+        writer.write_record(["step", "agent", "value", "pnl", "realized", "unrealized"])?;
+        for (name, series) in &self.series {
+            for point in series {
+                writer.serialize((
+                    point.step,
+                    name,
+                    point.value,
+                    point.total(),
+                    point.realized,
+                    point.unrealized,
+                ))?;
+            }
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    fn marks_agents(&self) -> bool {
+        true
+    }
+
+    fn on_marks(&mut self, step: Option<usize>, marks: &[AgentMark]) -> Result<(), ArenaError> {
+        for mark in marks {
+            self.record(step, mark);
+        }
+
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), ArenaError> {
+        self.series.clear();
+        self.books.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pnl_inspector() {
+        let mark = |price, balances, positions, deposited| AgentMark {
+            name: "strategy 0".to_string(),
+            owner: Address::ZERO,
+            price,
+            balances,
+            positions,
+            fees_collected: (0.0, 0.0),
+            fees_owed: (0.0, 1.0),
+            deposited,
+            ranges: Vec::new(),
+            decimals: (18, 18),
+        };
+        let mut inspector = PnlInspector::<f64>::new();
+
+        // 1 token0 and 100 token1 deposited, worth 200 at a price of 100, with 1 token1 of fees
+        inspector.record(None, &mark(100.0, (0.0, 0.0), (1.0, 100.0), (1.0, 100.0)));
+        // the price rises and the position sells half its token0
+        inspector.record(
+            Some(0),
+            &mark(120.0, (0.0, 0.0), (0.5, 160.0), (1.0, 100.0)),
+        );
+        // the price rises again with the position closed into the wallet
+        inspector.record(Some(1), &mark(140.0, (0.5, 160.0), (0.0, 0.0), (0.0, 0.0)));
+        // the price falls back and the wallet buys one token0
+        inspector.record(Some(2), &mark(100.0, (1.5, 60.0), (0.0, 0.0), (0.0, 0.0)));
+
+        let series = inspector.series("strategy 0").unwrap();
+        assert_eq!(series[0].total(), 0.0);
+        assert_eq!(series[0].unrealized, 0.0);
+
+        // the half still held gained 10 and the half sold 10
+        assert!((series[1].total() - 20.0).abs() < 1e-9);
+        assert!((series[1].unrealized - 10.0).abs() < 1e-9);
+        assert!((series[1].realized - 10.0).abs() < 1e-9);
+
+        assert!((series[2].total() - 30.0).abs() < 1e-9);
+        assert!((series[2].unrealized - 20.0).abs() < 1e-9);
+
+        // the gain of the half held is gone, while that of the half sold stays
+        assert!((series[3].total() - 10.0).abs() < 1e-9);
+        assert_eq!(series[3].unrealized, 0.0);
+        assert!((series[3].realized - 10.0).abs() < 1e-9);
+        assert!(inspector.series("strategy 1").is_none());
+    }
+
+    #[test]
+    fn test_mint_burn_pnl() {
+        let mark = |balances, positions, deposited| AgentMark {
+            name: "agent 0".to_string(),
+            owner: Address::ZERO,
+            price: 100.0,
+            balances,
+            positions,
+            fees_collected: (0.0, 0.0),
+            fees_owed: (0.0, 0.0),
+            deposited,
+            ranges: Vec::new(),
+            decimals: (18, 18),
+        };
+        let mut inspector = PnlInspector::<f64>::new();
+
+        // the wallet pays for the position it mints, and is paid back when it burns it at the same price
+        inspector.record(None, &mark((10.0, 1000.0), (0.0, 0.0), (0.0, 0.0)));
+        inspector.record(Some(0), &mark((9.0, 900.0), (1.0, 100.0), (1.0, 100.0)));
+        inspector.record(Some(1), &mark((10.0, 1000.0), (0.0, 0.0), (0.0, 0.0)));
+
+        let series = inspector.series("agent 0").unwrap();
+        assert!(series.iter().all(|point| point.total().abs() < 1e-9));
+    }
+}
@@ -0,0 +1,519 @@
+use alloy::{
+    primitives::{keccak256, Address, B256, I256, U256},
+    sol_types::SolValue,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{math::LiquidityProfile, *};
+use crate::{
+    backend,
+    types::{
+        controller::ArenaController::PoolKey,
+        manager::{self, IExtsload},
+        router,
+        token::ArenaToken,
+    },
+};
+
+/// Storage slot of the `pools` mapping in the `PoolManager`.
+const POOLS_SLOT: u64 = 6;
+
+/// Offset of `feeGrowthGlobal0X128` within `Pool.State`, directly followed by `feeGrowthGlobal1X128`.
+const FEE_GROWTH_GLOBAL_OFFSET: u64 = 1;
+
+/// Offset of `liquidity` within `Pool.State`.
+const LIQUIDITY_OFFSET: u64 = 3;
+
+/// Offset of the `ticks` mapping within `Pool.State`.
+const TICKS_OFFSET: u64 = 4;
+
+/// Offset of the `tickBitmap` mapping within `Pool.State`.
+const TICK_BITMAP_OFFSET: u64 = 5;
+
+/// Offset of the `positions` mapping within `Pool.State`.
+const POSITIONS_OFFSET: u64 = 6;
+
+/// Offset of `feeGrowthOutside0X128` within `Pool.TickInfo`, directly followed by `feeGrowthOutside1X128`.
+const FEE_GROWTH_OUTSIDE_OFFSET: u64 = 1;
+
+/// Lowest sqrt price limit of a swap, one above `TickMath.MIN_SQRT_PRICE`.
+pub const MIN_PRICE_LIMIT: Uint<160, 3> = Uint::from_limbs([4295128740, 0, 0]);
+
+/// Highest sqrt price limit of a swap, one below `TickMath.MAX_SQRT_PRICE`.
+pub const MAX_PRICE_LIMIT: Uint<160, 3> =
+    Uint::from_limbs([0x5d951d5263988d25, 0xefd1fc6a50648849, 0xfffd8963]);
+
+/// Fee growth and token balances of a pool, read from the `PoolManager` each step.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolState {
+    /// Global fee growth of token0, as Q128.128 fees per unit of liquidity.
+    pub fee_growth_global0_x128: U256,
+
+    /// Global fee growth of token1, as Q128.128 fees per unit of liquidity.
+    pub fee_growth_global1_x128: U256,
+
+    /// Balance of token0 held by the `PoolManager`.
+    ///
+    /// The `PoolManager` holds the reserves of every pool it manages, so this is the pool's balance only as long as
+    /// no other pool shares the token.
+    pub balance0: U256,
+
+    /// Balance of token1 held by the `PoolManager`, see [`PoolState::balance0`].
+    pub balance1: U256,
+}
+
+impl PoolState {
+    /// Read the state of a pool from the `PoolManager`.
+    pub async fn fetch(
+        manager: Address,
+        key: &PoolKey,
+        provider: AnvilProvider,
+    ) -> Result<Self, ArenaError> {
+        let (fee_growth_global0_x128, fee_growth_global1_x128) =
+            fee_growth_global(manager, key, provider.clone()).await?;
+
+        Ok(Self {
+            fee_growth_global0_x128,
+            fee_growth_global1_x128,
+            balance0: balance_of(key.currency0, manager, provider.clone()).await?,
+            balance1: balance_of(key.currency1, manager, provider).await?,
+        })
+    }
+}
+
+/// Reads the balance of `owner` in `currency`, where the zero address is the native currency.
+pub(crate) async fn balance_of(
+    currency: Address,
+    owner: Address,
+    provider: AnvilProvider,
+) -> Result<U256, ArenaError> {
+    if currency.is_zero() {
+        return Ok(provider.get_balance(owner).await?);
+    }
+
+    Ok(
+        backend::read(&provider, currency, ArenaToken::balanceOfCall { _0: owner })
+            .await?
+            ._0,
+    )
+}
+
+/// Returns the relative deviation of the pool price from the `LiquidExchange` price, `pool / lex - 1`.
+///
+/// `lex_price` is a WAD, as returned by the controller. Returns `0.0` while the `LiquidExchange` has no price.
+pub fn divergence(sqrt_price_x96: U256, lex_price: U256) -> f64 {
+    let reference = f64::from(lex_price) / 1e18;
+
+    if reference == 0.0 {
+        return 0.0;
+    }
+
+    (f64::from(sqrt_price_x96) / 2f64.powi(96)).powi(2) / reference - 1.0
+}
+
+/// Returns the sqrt price x96 at which token0 is worth `price` token1, in whole tokens, given the decimals of token0
+/// and token1.
+///
+/// Returns `None` if the price is not positive or does not fit a sqrt price.
+pub fn sqrt_price_x96(price: f64, decimals: (u8, u8)) -> Option<Uint<160, 3>> {
+    let raw = price * 10f64.powi(decimals.1 as i32 - decimals.0 as i32);
+    if raw.is_nan() || raw <= 0.0 {
+        return None;
+    }
+
+    Uint::try_from(raw.sqrt() * 2f64.powi(96)).ok()
+}
+
+/// Returns the sqrt price limit letting a swap in the direction of `zero_for_one` move the price as far as it takes.
+pub fn price_limit(zero_for_one: bool) -> Uint<160, 3> {
+    if zero_for_one {
+        MIN_PRICE_LIMIT
+    } else {
+        MAX_PRICE_LIMIT
+    }
+}
+
+/// Returns `key` as encoded in calls to the routers of the controller.
+pub(crate) fn router_key(key: &PoolKey) -> router::PoolKey {
+    router::PoolKey {
+        currency0: key.currency0,
+        currency1: key.currency1,
+        fee: key.fee,
+        tickSpacing: key.tickSpacing,
+        hooks: key.hooks,
+    }
+}
+
+/// Returns `key` as encoded in calls to the `PoolManager`.
+pub(crate) fn manager_key(key: &PoolKey) -> manager::PoolKey {
+    manager::PoolKey {
+        currency0: key.currency0,
+        currency1: key.currency1,
+        fee: key.fee,
+        tickSpacing: key.tickSpacing,
+        hooks: key.hooks,
+    }
+}
+
+/// Returns the salt of the positions of `owner`, its address left-padded to 32 bytes.
+///
+/// Every wallet modifies liquidity through the same router, which owns the positions in the `PoolManager`, so each
+/// wallet salts its positions to keep them, and the fees they accrue, apart from those of every other wallet.
+pub fn position_salt(owner: Address) -> B256 {
+    owner.into_word()
+}
+
+/// Returns the `PoolId` of a [`PoolKey`].
+pub fn pool_id(key: &PoolKey) -> B256 {
+    keccak256(key.abi_encode())
+}
+
+/// Returns the storage slot of a pool's `Pool.State` in the `PoolManager`.
+pub(crate) fn pool_state_slot(key: &PoolKey) -> U256 {
+    let mut preimage = [0u8; 64];
+    preimage[..32].copy_from_slice(pool_id(key).as_slice());
+    preimage[32..].copy_from_slice(&U256::from(POOLS_SLOT).to_be_bytes::<32>());
+
+    U256::from_be_bytes(keccak256(preimage).0)
+}
+
+/// Returns the storage slot of a tick's `Pool.TickInfo` in the `PoolManager`.
+pub(crate) fn tick_info_slot(key: &PoolKey, tick: i32) -> U256 {
+    let mut preimage = [0u8; 64];
+    preimage[..32].copy_from_slice(&I256::unchecked_from(tick).to_be_bytes::<32>());
+    preimage[32..]
+        .copy_from_slice(&(pool_state_slot(key) + U256::from(TICKS_OFFSET)).to_be_bytes::<32>());
+
+    U256::from_be_bytes(keccak256(preimage).0)
+}
+
+/// Returns the storage slot of a word of a pool's tick bitmap in the `PoolManager`.
+pub(crate) fn tick_bitmap_slot(key: &PoolKey, word: i16) -> U256 {
+    let mut preimage = [0u8; 64];
+    preimage[..32].copy_from_slice(&I256::unchecked_from(word).to_be_bytes::<32>());
+    preimage[32..].copy_from_slice(
+        &(pool_state_slot(key) + U256::from(TICK_BITMAP_OFFSET)).to_be_bytes::<32>(),
+    );
+
+    U256::from_be_bytes(keccak256(preimage).0)
+}
+
+/// Returns the storage slot of the `Position.State` of the position `owner` holds over a tick range with `salt`, in the
+/// `PoolManager`.
+pub(crate) fn position_slot(
+    key: &PoolKey,
+    owner: Address,
+    tick_lower: i32,
+    tick_upper: i32,
+    salt: B256,
+) -> U256 {
+    // positions are keyed by keccak256(abi.encodePacked(owner, tickLower, tickUpper, salt)), ticks packed as int24
+    let mut packed = Vec::with_capacity(58);
+    packed.extend_from_slice(owner.as_slice());
+    packed.extend_from_slice(&tick_lower.to_be_bytes()[1..]);
+    packed.extend_from_slice(&tick_upper.to_be_bytes()[1..]);
+    packed.extend_from_slice(salt.as_slice());
+
+    let mut preimage = [0u8; 64];
+    preimage[..32].copy_from_slice(keccak256(packed).as_slice());
+    preimage[32..].copy_from_slice(
+        &(pool_state_slot(key) + U256::from(POSITIONS_OFFSET)).to_be_bytes::<32>(),
+    );
+
+    U256::from_be_bytes(keccak256(preimage).0)
+}
+
+/// Reads `n` consecutive storage slots of the `PoolManager`, starting at `slot`.
+async fn read_slots(
+    manager: Address,
+    slot: U256,
+    n: u64,
+    provider: AnvilProvider,
+) -> Result<Vec<U256>, ArenaError> {
+    let values = backend::read(
+        &provider,
+        manager,
+        IExtsload::extsload_1Call {
+            startSlot: B256::from(slot),
+            nSlots: U256::from(n),
+        },
+    )
+    .await?
+    .values;
+
+    Ok(values
+        .into_iter()
+        .map(|value| U256::from_be_bytes(value.0))
+        .collect())
+}
+
+/// Reads the global fee growth of token0 and token1 of a pool, as Q128.128 fees per unit of liquidity.
+pub async fn fee_growth_global(
+    manager: Address,
+    key: &PoolKey,
+    provider: AnvilProvider,
+) -> Result<(U256, U256), ArenaError> {
+    let values = read_slots(
+        manager,
+        pool_state_slot(key) + U256::from(FEE_GROWTH_GLOBAL_OFFSET),
+        2,
+        provider,
+    )
+    .await?;
+
+    Ok((values[0], values[1]))
+}
+
+/// Reads the fee growth of token0 and token1 on the other side of a tick, relative to the current tick.
+pub async fn fee_growth_outside(
+    manager: Address,
+    key: &PoolKey,
+    tick: i32,
+    provider: AnvilProvider,
+) -> Result<(U256, U256), ArenaError> {
+    let values = read_slots(
+        manager,
+        tick_info_slot(key, tick) + U256::from(FEE_GROWTH_OUTSIDE_OFFSET),
+        2,
+        provider,
+    )
+    .await?;
+
+    Ok((values[0], values[1]))
+}
+
+/// Reads the liquidity of the position `owner` holds over a tick range with `salt`, zero if it holds none.
+pub async fn position_liquidity(
+    manager: Address,
+    key: &PoolKey,
+    owner: Address,
+    (tick_lower, tick_upper): (i32, i32),
+    salt: B256,
+    provider: AnvilProvider,
+) -> Result<u128, ArenaError> {
+    let slot = position_slot(key, owner, tick_lower, tick_upper, salt);
+    let values = read_slots(manager, slot, 1, provider).await?;
+
+    // liquidity is the uint128 in the lowest bits of the first slot of the state
+    Ok((values[0] & U256::from(u128::MAX)).to::<u128>())
+}
+
+/// Reads the fee growth of token0 and token1 inside a tick range, as Q128.128 fees per unit of liquidity.
+///
+/// Only differences between readings of the same range are meaningful.
+pub async fn fee_growth_inside(
+    manager: Address,
+    key: &PoolKey,
+    tick_lower: i32,
+    tick_upper: i32,
+    current_tick: i32,
+    provider: AnvilProvider,
+) -> Result<(U256, U256), ArenaError> {
+    let global = fee_growth_global(manager, key, provider.clone()).await?;
+    let lower = fee_growth_outside(manager, key, tick_lower, provider.clone()).await?;
+    let upper = fee_growth_outside(manager, key, tick_upper, provider).await?;
+
+    let inside = |global: U256, lower: U256, upper: U256| {
+        if current_tick < tick_lower {
+            lower.wrapping_sub(upper)
+        } else if current_tick >= tick_upper {
+            upper.wrapping_sub(lower)
+        } else {
+            global.wrapping_sub(lower).wrapping_sub(upper)
+        }
+    };
+
+    Ok((
+        inside(global.0, lower.0, upper.0),
+        inside(global.1, lower.1, upper.1),
+    ))
+}
+
+/// Reads the sqrt price and the current tick of a pool from the `Slot0` of its state in the `PoolManager`.
+pub async fn slot0(
+    manager: Address,
+    key: &PoolKey,
+    provider: AnvilProvider,
+) -> Result<(Uint<160, 3>, Signed<24, 1>), ArenaError> {
+    let value = backend::read(
+        &provider,
+        manager,
+        IExtsload::extsload_0Call {
+            slot: B256::from(pool_state_slot(key)),
+        },
+    )
+    .await?
+    .value;
+
+    Ok(decode_slot0(U256::from_be_bytes(value.0)))
+}
+
+/// Returns the sqrt price and the current tick packed into the lowest 184 bits of a `Slot0`.
+fn decode_slot0(slot0: U256) -> (Uint<160, 3>, Signed<24, 1>) {
+    let sqrt_price_x96 = Uint::<160, 3>::from(slot0 & ((U256::from(1) << 160) - U256::from(1)));
+    let tick = ((slot0 >> 160usize) & U256::from(0xff_ffff)).to::<u32>();
+    // sign extend the 24-bit tick
+    let tick = ((tick << 8) as i32) >> 8;
+
+    (sqrt_price_x96, Signed::try_from(tick).unwrap_or_default())
+}
+
+/// Reads the in-range liquidity of a pool from the `PoolManager`.
+pub async fn liquidity(
+    manager: Address,
+    key: &PoolKey,
+    provider: AnvilProvider,
+) -> Result<u128, ArenaError> {
+    let slot = pool_state_slot(key) + U256::from(LIQUIDITY_OFFSET);
+
+    let value = backend::read(
+        &provider,
+        manager,
+        IExtsload::extsload_0Call {
+            slot: B256::from(slot),
+        },
+    )
+    .await?
+    .value;
+
+    Ok(U256::from_be_bytes(value.0).to::<u128>())
+}
+
+/// Reads the liquidity of a pool around its current tick, with the initialized ticks of the `words` tick bitmap words on
+/// either side of the current one.
+///
+/// A bitmap word covers 256 tick spacings, so a couple of words cover most swaps.
+pub async fn liquidity_profile(
+    manager: Address,
+    key: &PoolKey,
+    sqrt_price_x96: U256,
+    tick: i32,
+    words: i16,
+    provider: AnvilProvider,
+) -> Result<LiquidityProfile, ArenaError> {
+    let spacing = key.tickSpacing.as_i32().max(1);
+    let current = (tick.div_euclid(spacing) >> 8) as i16;
+
+    let mut ticks = Vec::new();
+    for word in current.saturating_sub(words)..=current.saturating_add(words) {
+        let bitmap =
+            read_slots(manager, tick_bitmap_slot(key, word), 1, provider.clone()).await?[0];
+
+        for bit in (0..256).filter(|bit| bitmap.bit(*bit)) {
+            let initialized = (word as i32 * 256 + bit as i32) * spacing;
+            let info = read_slots(
+                manager,
+                tick_info_slot(key, initialized),
+                1,
+                provider.clone(),
+            )
+            .await?[0];
+
+            // `liquidityNet` is packed in the upper half of the slot, after `liquidityGross`
+            ticks.push((initialized, (info >> 128usize).to::<u128>() as i128));
+        }
+    }
+
+    Ok(LiquidityProfile {
+        sqrt_price: f64::from(sqrt_price_x96) / 2f64.powi(96),
+        tick,
+        liquidity: liquidity(manager, key, provider).await?,
+        ticks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_divergence() {
+        let one = U256::from(1u128 << 96);
+        let wad = U256::from(10u128.pow(18));
+
+        assert_eq!(divergence(one, wad), 0.0);
+        assert!((divergence(one, wad * U256::from(2)) + 0.5).abs() < 1e-12);
+        assert!((divergence(one * U256::from(2), wad) - 3.0).abs() < 1e-12);
+        assert_eq!(divergence(one, U256::ZERO), 0.0);
+    }
+
+    #[test]
+    fn test_sqrt_price_x96() {
+        assert_eq!(sqrt_price_x96(1.0, (18, 18)), Some(Uint::from(1u128 << 96)));
+        assert_eq!(sqrt_price_x96(4.0, (18, 18)), Some(Uint::from(2u128 << 96)));
+        // 2000 USDC per ether is 2000e6 raw units of token1 per 1e18 of token0
+        let usdc = f64::from(sqrt_price_x96(2000.0, (18, 6)).unwrap()) / 2f64.powi(96);
+        assert!((usdc.powi(2) / 2000e-12 - 1.0).abs() < 1e-12);
+        assert_eq!(sqrt_price_x96(0.0, (18, 18)), None);
+    }
+
+    #[test]
+    fn test_position_slot() {
+        let key = PoolKey {
+            currency0: Address::repeat_byte(1),
+            currency1: Address::repeat_byte(2),
+            fee: Uint::from(3000),
+            tickSpacing: Signed::try_from(60).unwrap(),
+            hooks: Address::ZERO,
+        };
+        let router = Address::repeat_byte(9);
+        let slot =
+            |owner: Address, lower| position_slot(&key, router, lower, 600, position_salt(owner));
+
+        // every wallet holds its own position over the same range through the same router
+        let (alice, bob) = (Address::repeat_byte(3), Address::repeat_byte(4));
+        assert_ne!(slot(alice, -600), slot(bob, -600));
+        assert_ne!(slot(alice, -600), slot(alice, -60));
+        assert_eq!(slot(alice, -600), slot(alice, -600));
+    }
+
+    #[test]
+    fn test_position_salt() {
+        let owner = Address::repeat_byte(7);
+        let salt = position_salt(owner);
+
+        assert_eq!(&salt[12..], owner.as_slice());
+        assert!(salt[..12].iter().all(|byte| *byte == 0));
+        assert_ne!(salt, position_salt(Address::repeat_byte(8)));
+    }
+
+    #[test]
+    fn test_price_limits() {
+        assert_eq!(
+            price_limit(true),
+            "4295128740".parse::<Uint<160, 3>>().unwrap()
+        );
+        assert_eq!(
+            price_limit(false),
+            "1461446703485210103287273052203988822378723970341"
+                .parse::<Uint<160, 3>>()
+                .unwrap()
+        );
+
+        let key = PoolKey {
+            currency0: Address::repeat_byte(1),
+            currency1: Address::repeat_byte(2),
+            fee: Uint::from(3000),
+            tickSpacing: alloy::primitives::Signed::try_from(60).unwrap(),
+            hooks: Address::repeat_byte(3),
+        };
+        // the routers and the manager encode the key exactly like the controller, so all name the same pool
+        assert_eq!(router_key(&key).abi_encode(), key.abi_encode());
+        assert_eq!(manager_key(&key).abi_encode(), key.abi_encode());
+    }
+
+    #[test]
+    fn test_decode_slot0() {
+        let sqrt_price_x96 = U256::from(1u128 << 96);
+        // a tick of -120 with a protocol fee and an lp fee packed above it
+        let tick = U256::from(0xff_ff88u64) << 160;
+        let fees = (U256::from(3000) << 208) | (U256::from(5) << 184);
+
+        let (price, tick) = decode_slot0(sqrt_price_x96 | tick | fees);
+        assert_eq!(U256::from(price), sqrt_price_x96);
+        assert_eq!(tick.as_i32(), -120);
+
+        let (_, tick) = decode_slot0(U256::from(60u64) << 160);
+        assert_eq!(tick.as_i32(), 60);
+    }
+}
@@ -0,0 +1,120 @@
+use alloy::{
+    primitives::{Address, U256},
+    providers::WalletProvider,
+};
+
+use super::{position::Position, *};
+use crate::{backend, types::token::ArenaToken, Signal};
+
+/// Snapshot of a strategy wallet's holdings, marked to market in terms of token1.
+#[derive(Debug, Clone)]
+pub struct Portfolio {
+    /// Address of the wallet.
+    pub owner: Address,
+
+    /// Raw balance of token0 held by the wallet.
+    pub balance0: U256,
+
+    /// Raw balance of token1 held by the wallet.
+    pub balance1: U256,
+
+    /// Decimals of token0 and token1.
+    pub decimals: (u8, u8),
+
+    /// Liquidity positions held by the strategy.
+    pub positions: Vec<Position>,
+
+    /// Square root of the pool price, as a float.
+    pub sqrt_price: f64,
+
+    /// Price of token0 in terms of token1 used to mark the portfolio, taken from the liquid exchange.
+    pub mark_price: f64,
+}
+
+impl Portfolio {
+    /// Read the balances of the wallet behind `provider` and combine them with the positions held by the strategy.
+    ///
+    /// Positions are held by the liquidity router on behalf of the wallet, so strategies pass in the positions they
+    /// opened.
+    pub async fn fetch(
+        provider: AnvilProvider,
+        signal: &Signal,
+        positions: &[Position],
+    ) -> Result<Self, ArenaError> {
+        let owner = provider.default_signer_address();
+
+        let (token0, token1) = (signal.pool.currency0, signal.pool.currency1);
+
+        let decimals = (
+            backend::read(&provider, token0, ArenaToken::decimalsCall {})
+                .await?
+                ._0,
+            backend::read(&provider, token1, ArenaToken::decimalsCall {})
+                .await?
+                ._0,
+        );
+
+        Ok(Self {
+            owner,
+            balance0: backend::read(&provider, token0, ArenaToken::balanceOfCall { _0: owner })
+                .await?
+                ._0,
+            balance1: backend::read(&provider, token1, ArenaToken::balanceOfCall { _0: owner })
+                .await?
+                ._0,
+            decimals,
+            positions: positions.to_vec(),
+            sqrt_price: f64::from(signal.sqrt_price_x96) / 2f64.powi(96),
+            // the liquid exchange quotes raw units of token1 per raw unit of token0
+            mark_price: f64::from(signal.lex_price) / 1e18
+                * 10f64.powi(decimals.0 as i32 - decimals.1 as i32),
+        })
+    }
+
+    /// Wallet balances of token0 and token1, in whole tokens.
+    pub fn balances(&self) -> (f64, f64) {
+        (
+            self.scale(f64::from(self.balance0), self.decimals.0),
+            self.scale(f64::from(self.balance1), self.decimals.1),
+        )
+    }
+
+    /// Amounts of token0 and token1 backing all positions at the pool price, in whole tokens.
+    pub fn position_amounts(&self) -> (f64, f64) {
+        let (amount0, amount1) = self
+            .positions
+            .iter()
+            .map(|position| position.amounts(self.sqrt_price))
+            .fold((0.0, 0.0), |acc, amounts| {
+                (acc.0 + amounts.0, acc.1 + amounts.1)
+            });
+
+        (
+            self.scale(amount0, self.decimals.0),
+            self.scale(amount1, self.decimals.1),
+        )
+    }
+
+    /// Total value of balances and positions in terms of token1, at the mark price.
+    pub fn value(&self) -> f64 {
+        self.value_at(self.mark_price)
+    }
+
+    /// Total value of balances and positions in terms of token1, at a given price of token0.
+    pub fn value_at(&self, price: f64) -> f64 {
+        let (balance0, balance1) = self.balances();
+        let (amount0, amount1) = self.position_amounts();
+
+        (balance0 + amount0) * price + balance1 + amount1
+    }
+
+    /// Value of raw amounts of token0 and token1 in terms of token1, at the mark price.
+    pub fn value_of(&self, amounts: (f64, f64)) -> f64 {
+        self.scale(amounts.0, self.decimals.0) * self.mark_price
+            + self.scale(amounts.1, self.decimals.1)
+    }
+
+    fn scale(&self, amount: f64, decimals: u8) -> f64 {
+        amount / 10f64.powi(decimals as i32)
+    }
+}
@@ -0,0 +1,299 @@
+use alloy::primitives::{Bytes, Signed, I256, U256};
+use serde::{Deserialize, Serialize};
+
+use super::{action::ActionRecord, *};
+
+/// Lowest tick representable by a Uniswap pool.
+pub const MIN_TICK: i32 = -887272;
+
+/// Highest tick representable by a Uniswap pool.
+pub const MAX_TICK: i32 = 887272;
+
+/// A concentrated liquidity position over a tick range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    /// Lower tick of the range.
+    pub tick_lower: i32,
+
+    /// Upper tick of the range.
+    pub tick_upper: i32,
+
+    /// Liquidity provided over the range.
+    pub liquidity: u128,
+}
+
+impl Position {
+    /// Public constructor function for a new [`Position`].
+    pub fn new(tick_lower: i32, tick_upper: i32, liquidity: u128) -> Self {
+        Self {
+            tick_lower,
+            tick_upper,
+            liquidity,
+        }
+    }
+
+    /// Position spanning every usable tick for a given tick spacing.
+    pub fn full_range(tick_spacing: i32, liquidity: u128) -> Self {
+        let max = MAX_TICK / tick_spacing * tick_spacing;
+
+        Self::new(-max, max, liquidity)
+    }
+
+    /// Position spanning `width` ticks either side of `tick`, widened outwards to the nearest usable ticks.
+    pub fn around(tick: i32, width: i32, tick_spacing: i32, liquidity: u128) -> Self {
+        let max = MAX_TICK / tick_spacing * tick_spacing;
+
+        let tick_lower = (tick - width).div_euclid(tick_spacing) * tick_spacing;
+        let tick_upper = -((-(tick + width)).div_euclid(tick_spacing) * tick_spacing);
+
+        Self::new(
+            tick_lower.max(-max),
+            tick_upper.min(max).max(tick_lower + tick_spacing),
+            liquidity,
+        )
+    }
+
+    /// Returns whether the position is in range at `tick`.
+    pub fn contains(&self, tick: i32) -> bool {
+        self.tick_lower <= tick && tick < self.tick_upper
+    }
+
+    /// Amounts of token0 and token1 backing the position at a pool price of `sqrt_price`, in raw token units.
+    ///
+    /// `sqrt_price` is the square root of the price of token0 in terms of token1, i.e. `sqrtPriceX96 / 2^96`.
+    pub fn amounts(&self, sqrt_price: f64) -> (f64, f64) {
+        let liquidity = self.liquidity as f64;
+        let sqrt_lower = 1.0001f64.powf(self.tick_lower as f64 / 2.0);
+        let sqrt_upper = 1.0001f64.powf(self.tick_upper as f64 / 2.0);
+        let sqrt_price = sqrt_price.clamp(sqrt_lower, sqrt_upper);
+
+        (
+            liquidity * (1.0 / sqrt_price - 1.0 / sqrt_upper),
+            liquidity * (sqrt_price - sqrt_lower),
+        )
+    }
+
+    fn ticks(&self) -> Result<(Signed<24, 1>, Signed<24, 1>), ArenaError> {
+        let convert = |tick: i32| {
+            Signed::try_from(tick)
+                .map_err(|_| ArenaError::Custom(format!("tick {tick} is out of range")))
+        };
+
+        Ok((convert(self.tick_lower)?, convert(self.tick_upper)?))
+    }
+}
+
+/// Liquidity a wallet holds over a tick range, with the fee growth inside the range when its fees were last accounted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionRecord {
+    /// The position.
+    pub position: Position,
+
+    /// Fee growth of token0 and token1 inside the range at the last checkpoint.
+    pub fee_growth_inside: (U256, U256),
+}
+
+impl PositionRecord {
+    /// Raw amounts of token0 and token1 earned in fees since the last checkpoint, given the current fee growth inside
+    /// the range.
+    pub fn fees_owed(&self, fee_growth_inside: (U256, U256)) -> (f64, f64) {
+        let owed = |now: U256, last: U256| {
+            f64::from(now.wrapping_sub(last)) * self.position.liquidity as f64 / 2f64.powi(128)
+        };
+
+        (
+            owed(fee_growth_inside.0, self.fee_growth_inside.0),
+            owed(fee_growth_inside.1, self.fee_growth_inside.1),
+        )
+    }
+}
+
+/// Positions and liquidity cash flows of a single wallet, recorded by the [`Engine`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Holdings {
+    /// Open positions, one per tick range.
+    pub positions: Vec<PositionRecord>,
+
+    /// Net raw amounts of token0 and token1 deposited into positions.
+    pub deposited: (f64, f64),
+
+    /// Raw amounts of token0 and token1 earned in fees and realized by modifying positions.
+    pub fees: (f64, f64),
+
+    /// Actions executed through [`Engine::apply`].
+    pub actions: Vec<ActionRecord>,
+}
+
+impl Holdings {
+    /// Returns the liquidity held over a range.
+    pub fn liquidity(&self, tick_lower: i32, tick_upper: i32) -> u128 {
+        self.positions
+            .iter()
+            .find(|record| {
+                record.position.tick_lower == tick_lower && record.position.tick_upper == tick_upper
+            })
+            .map_or(0, |record| record.position.liquidity)
+    }
+
+    /// Returns the liquidity held over each open range.
+    pub fn open_positions(&self) -> Vec<Position> {
+        self.positions
+            .iter()
+            .map(|record| record.position)
+            .collect()
+    }
+
+    /// Record a liquidity modification of `delta` over a range.
+    ///
+    /// `amounts` are the raw token amounts backing `|delta|` liquidity at the time of the modification,
+    /// `before` the fee growth inside the range before it, used to realize the fees of an existing position, and
+    /// `after` the fee growth inside the range after it, used as the new checkpoint.
+    pub(crate) fn record(
+        &mut self,
+        tick_lower: i32,
+        tick_upper: i32,
+        delta: i128,
+        amounts: (f64, f64),
+        before: (U256, U256),
+        after: (U256, U256),
+    ) {
+        let sign = delta.signum() as f64;
+        self.deposited.0 += sign * amounts.0;
+        self.deposited.1 += sign * amounts.1;
+
+        let existing = self.positions.iter().position(|record| {
+            record.position.tick_lower == tick_lower && record.position.tick_upper == tick_upper
+        });
+
+        let liquidity = match existing {
+            Some(idx) => {
+                let record = self.positions.remove(idx);
+                let fees = record.fees_owed(before);
+                self.fees.0 += fees.0;
+                self.fees.1 += fees.1;
+
+                record.position.liquidity.saturating_add_signed(delta)
+            }
+            None => delta.max(0) as u128,
+        };
+
+        if liquidity > 0 {
+            self.positions.push(PositionRecord {
+                position: Position::new(tick_lower, tick_upper, liquidity),
+                fee_growth_inside: after,
+            });
+        }
+    }
+}
+
+impl Engine {
+    /// Returns the positions and liquidity cash flows recorded for a wallet.
+    pub fn holdings(&self, owner: Address) -> Holdings {
+        self.ledger
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&owner)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Provide the liquidity of a [`Position`] to the pool.
+    pub async fn open_position(
+        &self,
+        position: &Position,
+        hook_data: Bytes,
+        provider: AnvilProvider,
+    ) -> Result<(), ArenaError> {
+        let (tick_lower, tick_upper) = position.ticks()?;
+
+        self.modify_liquidity(
+            I256::try_from(position.liquidity)
+                .map_err(|_| ArenaError::Custom("liquidity overflows int256".to_string()))?,
+            tick_lower,
+            tick_upper,
+            hook_data,
+            provider,
+        )
+        .await
+    }
+
+    /// Withdraw the liquidity of a [`Position`] from the pool.
+    pub async fn close_position(
+        &self,
+        position: &Position,
+        hook_data: Bytes,
+        provider: AnvilProvider,
+    ) -> Result<(), ArenaError> {
+        let (tick_lower, tick_upper) = position.ticks()?;
+
+        self.modify_liquidity(
+            -I256::try_from(position.liquidity)
+                .map_err(|_| ArenaError::Custom("liquidity overflows int256".to_string()))?,
+            tick_lower,
+            tick_upper,
+            hook_data,
+            provider,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_ranges() {
+        let full = Position::full_range(60, 1);
+        assert_eq!((full.tick_lower, full.tick_upper), (-887220, 887220));
+
+        let around = Position::around(-95, 100, 10, 1);
+        assert_eq!((around.tick_lower, around.tick_upper), (-200, 10));
+        assert!(around.contains(-95));
+        assert!(!around.contains(10));
+
+        let (amount0, amount1) = Position::new(-1000, 1000, 1_000_000).amounts(1.0);
+        assert!((amount0 - amount1).abs() < 1e-6);
+
+        let (amount0, amount1) = around.amounts(2.0);
+        assert_eq!(amount0, 0.0);
+        assert!(amount1 > 0.0);
+
+        let mut holdings = Holdings::default();
+        let q128 = U256::from(1) << 128;
+
+        holdings.record(
+            -10,
+            10,
+            100,
+            (5.0, 5.0),
+            (U256::ZERO, U256::ZERO),
+            (q128, U256::ZERO),
+        );
+        holdings.record(
+            -10,
+            10,
+            -40,
+            (2.0, 2.0),
+            (q128 * U256::from(3), U256::ZERO),
+            (U256::ZERO, U256::ZERO),
+        );
+        assert_eq!(holdings.open_positions(), vec![Position::new(-10, 10, 60)]);
+        assert_eq!(holdings.deposited, (3.0, 3.0));
+        assert_eq!(holdings.fees, (200.0, 0.0));
+
+        holdings.record(
+            -10,
+            10,
+            -60,
+            (3.0, 3.0),
+            (U256::ZERO, U256::ZERO),
+            (U256::ZERO, U256::ZERO),
+        );
+        assert!(holdings.positions.is_empty());
+
+        let edge = Position::around(MAX_TICK - 1, 100, 60, 1);
+        assert_eq!(edge.tick_upper, 887220);
+        assert!(edge.tick_lower < edge.tick_upper);
+    }
+}
@@ -0,0 +1,136 @@
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Independent random number streams handed out by the [`RngManager`].
+///
+/// Every component draws from its own stream, so changing how much randomness one component consumes never shifts the
+/// draws seen by another. This is what makes common random numbers work across parameterizations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    /// Stream driving the price feed.
+    Feed,
+
+    /// Stream for the strategy at the given index.
+    Strategy(usize),
+
+    /// Stream for the arbitrageur.
+    Arbitrageur,
+
+    /// Stream for the noise trader at the given index.
+    NoiseTrader(usize),
+
+    /// Stream driving the feed of the additional pool at the given index.
+    PoolFeed(usize),
+
+    /// Stream for the noise trader at the second index trading on the additional pool at the first index.
+    PoolNoiseTrader(usize, usize),
+
+    /// Stream for the agent at the given index.
+    Agent(usize),
+
+    /// Stream drawing the order agents act in, when it is randomized.
+    AgentOrder,
+
+    /// Stream driving the feed of the gas market, see [`crate::engine::gas::GasMarket::Feed`].
+    GasMarket,
+}
+
+impl Stream {
+    fn id(&self) -> u64 {
+        match self {
+            Stream::Feed => 0,
+            Stream::Arbitrageur => 1,
+            Stream::Strategy(idx) => (2 + *idx as u64) << 1,
+            Stream::NoiseTrader(idx) => ((2 + *idx as u64) << 1) | 1,
+            Stream::PoolFeed(idx) => (1 << 63) | *idx as u64,
+            Stream::PoolNoiseTrader(pool, idx) => (1 << 61) | ((*pool as u64) << 32) | *idx as u64,
+            Stream::Agent(idx) => (1 << 62) | *idx as u64,
+            Stream::AgentOrder => 2,
+            Stream::GasMarket => 3,
+        }
+    }
+}
+
+/// Coordinates seeding of every stochastic component of a simulation, across Monte Carlo paths.
+///
+/// Runs sharing a manager seed use common random numbers: path `n` sees the same draws regardless of the simulation's
+/// other parameters. With antithetic sampling enabled, paths `2k` and `2k + 1` share random streams and feeds of the odd
+/// path negate their shocks.
+#[derive(Debug, Clone)]
+pub struct RngManager {
+    seed: u64,
+    antithetic: bool,
+}
+
+impl RngManager {
+    /// Public constructor function for a new [`RngManager`].
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            antithetic: false,
+        }
+    }
+
+    /// Enable antithetic sampling across pairs of paths.
+    pub fn with_antithetic(mut self) -> Self {
+        self.antithetic = true;
+        self
+    }
+
+    /// Returns the seed of the manager.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns whether the given path is the antithetic counterpart of its pair.
+    pub fn is_antithetic(&self, path: usize) -> bool {
+        self.antithetic && path % 2 == 1
+    }
+
+    /// Returns a seeded RNG for a stream of a given Monte Carlo path.
+    pub fn rng(&self, path: usize, stream: Stream) -> StdRng {
+        let path = if self.antithetic { path / 2 } else { path } as u64;
+        StdRng::seed_from_u64(mix(mix(self.seed ^ mix(path)) ^ stream.id()))
+    }
+
+    /// Returns a seeded RNG for a stream of a given Monte Carlo path, for a simulation resumed at `step`, to initialize
+    /// components with.
+    ///
+    /// At step 0 this is the same generator as [`RngManager::rng`]. Draws made while stepping come from
+    /// [`RngManager::rng_step`] instead, so they do not depend on where the simulation resumed.
+    pub fn rng_at(&self, path: usize, stream: Stream, step: usize) -> StdRng {
+        if step == 0 {
+            return self.rng(path, stream);
+        }
+
+        self.derive(path, stream, step as u64)
+    }
+
+    /// Returns a seeded RNG for the draws a stream of a given Monte Carlo path makes during `step`.
+    ///
+    /// Generators cannot be serialized, so every stream is reseeded from the step it draws for. A simulation resumed
+    /// from a checkpoint at any step therefore draws exactly like an uninterrupted one. The generators of consecutive
+    /// steps are independent, and distinct from those of [`RngManager::rng_at`].
+    pub fn rng_step(&self, path: usize, stream: Stream, step: usize) -> StdRng {
+        self.derive(path, stream, !(step as u64))
+    }
+
+    /// Returns a seeded RNG for the draws a stream of a given Monte Carlo path makes during warm-up step `step`, see
+    /// [`crate::config::Config::warmup_steps`], independent of those of [`RngManager::rng_step`].
+    pub fn rng_warmup(&self, path: usize, stream: Stream, step: usize) -> StdRng {
+        self.derive(path, stream, (1 << 62) | step as u64)
+    }
+
+    /// Returns the generator of a stream of a given Monte Carlo path derived from `salt`.
+    fn derive(&self, path: usize, stream: Stream, salt: u64) -> StdRng {
+        let path = if self.antithetic { path / 2 } else { path } as u64;
+        StdRng::seed_from_u64(mix(mix(mix(self.seed ^ mix(path)) ^ stream.id()) ^ salt))
+    }
+}
+
+/// SplitMix64 finalizer, used to derive well-distributed seeds from structured inputs.
+fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,285 @@
+use alloy::{primitives::U256, providers::WalletProvider};
+use serde::{Deserialize, Serialize};
+
+use super::{portfolio::Portfolio, position::Holdings, *};
+use crate::Signal;
+
+/// Valuation of a strategy wallet at a single point in time, in terms of token1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Mark {
+    /// Value of the wallet's balances, open positions and fees earned.
+    pub value: f64,
+
+    /// Value of the fees earned so far, realized or not.
+    pub fees: f64,
+
+    /// Value the wallet would have had it held the tokens it deposited into positions instead.
+    pub hodl: f64,
+}
+
+impl Mark {
+    /// Value the wallet behind `provider`, including the positions and fees recorded by the engine.
+    pub async fn fetch(
+        engine: &Engine,
+        signal: &Signal,
+        provider: AnvilProvider,
+    ) -> Result<Self, ArenaError> {
+        let holdings = engine.holdings(provider.default_signer_address());
+        let portfolio =
+            Portfolio::fetch(provider.clone(), signal, &holdings.open_positions()).await?;
+        let owed = Self::owed(&holdings, signal, provider).await?;
+
+        Ok(Self::new(&portfolio, &holdings, owed))
+    }
+
+    /// Value the wallet behind `provider` across several pools trading the same tokens, each given by its engine and
+    /// signal, the first one being the main pool.
+    ///
+    /// The wallet balances are valued once, at the mark price of the main pool, while the positions and fees on every
+    /// other pool are valued at the mark price of their own pool.
+    pub async fn fetch_pools(
+        pools: &[(&Engine, Signal)],
+        provider: AnvilProvider,
+    ) -> Result<Self, ArenaError> {
+        let Some(((engine, signal), others)) = pools.split_first() else {
+            return Ok(Self::default());
+        };
+
+        let mut mark = Self::fetch(engine, signal, provider.clone()).await?;
+        for (engine, signal) in others {
+            let holdings = engine.holdings(provider.default_signer_address());
+            if holdings.positions.is_empty() {
+                continue;
+            }
+
+            let mut portfolio =
+                Portfolio::fetch(provider.clone(), signal, &holdings.open_positions()).await?;
+            portfolio.balance0 = U256::ZERO;
+            portfolio.balance1 = U256::ZERO;
+            let owed = Self::owed(&holdings, signal, provider.clone()).await?;
+
+            let pool = Self::new(&portfolio, &holdings, owed);
+            mark.value += pool.value;
+            mark.fees += pool.fees;
+            mark.hodl += pool.hodl;
+        }
+
+        Ok(mark)
+    }
+
+    /// Raw amounts of token0 and token1 owed in fees to the positions of `holdings` on the pool of `signal`.
+    async fn owed(
+        holdings: &Holdings,
+        signal: &Signal,
+        provider: AnvilProvider,
+    ) -> Result<(f64, f64), ArenaError> {
+        let mut owed = (0.0, 0.0);
+        for record in &holdings.positions {
+            let fees = record.fees_owed(
+                pool::fee_growth_inside(
+                    signal.manager,
+                    &signal.pool,
+                    record.position.tick_lower,
+                    record.position.tick_upper,
+                    signal.tick.as_i32(),
+                    provider.clone(),
+                )
+                .await?,
+            );
+
+            owed.0 += fees.0;
+            owed.1 += fees.1;
+        }
+
+        Ok(owed)
+    }
+
+    /// Value a wallet holding `portfolio`, whose positions have the cash flows of `holdings` and are owed the raw
+    /// amounts `owed` in fees.
+    ///
+    /// The wallet pays for its positions and collects their fees, so its balances are net of deposits and include the
+    /// fees collected, which the value held instead of the positions excludes.
+    pub fn new(portfolio: &Portfolio, holdings: &Holdings, owed: (f64, f64)) -> Self {
+        let collected = portfolio.value_of(holdings.fees);
+        let owed = portfolio.value_of(owed);
+        let balances =
+            portfolio.value_of((f64::from(portfolio.balance0), f64::from(portfolio.balance1)));
+
+        Self {
+            value: portfolio.value() + owed,
+            fees: collected + owed,
+            hodl: balances - collected + portfolio.value_of(holdings.deposited),
+        }
+    }
+}
+
+/// Performance metrics of a single strategy over a run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    /// Marks taken after initialization and after every step.
+    pub marks: Vec<Mark>,
+
+    /// Change in value between the first and last mark.
+    pub total_pnl: f64,
+
+    /// Largest relative decline in value from a previous peak.
+    pub max_drawdown: f64,
+
+    /// Mean over standard deviation of the per-step returns, not annualized.
+    pub sharpe: f64,
+
+    /// Value of the fees earned over the run.
+    pub fee_income: f64,
+
+    /// Value lost by providing liquidity rather than holding the deposited tokens, excluding fees.
+    pub impermanent_loss: f64,
+
+    /// Gas used by the strategy's transactions.
+    pub gas_used: u128,
+}
+
+impl Score {
+    /// Compute the metrics of a series of marks.
+    pub fn from_marks(marks: Vec<Mark>) -> Self {
+        let (Some(first), Some(last)) = (marks.first(), marks.last()) else {
+            return Self::default();
+        };
+
+        let returns: Vec<f64> = marks
+            .windows(2)
+            .filter(|pair| pair[0].value != 0.0)
+            .map(|pair| pair[1].value / pair[0].value - 1.0)
+            .collect();
+
+        let mean = returns.iter().sum::<f64>() / returns.len().max(1) as f64;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>()
+            / returns.len().saturating_sub(1).max(1) as f64;
+        let sharpe = if variance > 0.0 {
+            mean / variance.sqrt()
+        } else {
+            0.0
+        };
+
+        let mut peak = f64::MIN;
+        let mut max_drawdown: f64 = 0.0;
+        for mark in &marks {
+            peak = peak.max(mark.value);
+            if peak > 0.0 {
+                max_drawdown = max_drawdown.max((peak - mark.value) / peak);
+            }
+        }
+
+        Self {
+            total_pnl: last.value - first.value,
+            max_drawdown,
+            sharpe,
+            fee_income: last.fees - first.fees,
+            impermanent_loss: last.hodl - (last.value - last.fees),
+            marks,
+            ..Self::default()
+        }
+    }
+}
+
+/// Scores of every strategy in a run, in the order they were added to the arena.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Scorecard {
+    /// Score of each strategy.
+    pub strategies: Vec<Score>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_metrics() {
+        let mark = |value: f64, fees: f64| Mark {
+            value,
+            fees,
+            hodl: 100.0,
+        };
+
+        let score = Score::from_marks(vec![
+            mark(100.0, 0.0),
+            mark(110.0, 1.0),
+            mark(88.0, 2.0),
+            mark(99.0, 3.0),
+        ]);
+
+        assert_eq!(score.total_pnl, -1.0);
+        assert!((score.max_drawdown - 0.2).abs() < 1e-12);
+        assert_eq!(score.fee_income, 3.0);
+        assert_eq!(score.impermanent_loss, 4.0);
+        assert!(score.sharpe.is_finite());
+
+        assert_eq!(Score::from_marks(Vec::new()), Score::default());
+    }
+
+    #[test]
+    fn test_mint_burn_mark() {
+        use alloy::primitives::{Address, U256};
+
+        use crate::engine::position::Position;
+
+        let e18 = 1e18;
+        let portfolio = |balance0: f64, balance1: f64, positions: Vec<Position>| Portfolio {
+            owner: Address::ZERO,
+            balance0: U256::from(balance0 as u128),
+            balance1: U256::from(balance1 as u128),
+            decimals: (18, 18),
+            positions,
+            sqrt_price: 1.0,
+            mark_price: 1.0,
+        };
+        let position = Position::new(-600, 600, 10u128.pow(20));
+        let amounts = position.amounts(1.0);
+
+        let mut holdings = Holdings::default();
+        let start = Mark::new(
+            &portfolio(10.0 * e18, 10.0 * e18, Vec::new()),
+            &holdings,
+            (0.0, 0.0),
+        );
+
+        // minting debits the wallet by the amounts backing the position
+        holdings.record(
+            -600,
+            600,
+            10i128.pow(20),
+            amounts,
+            (U256::ZERO, U256::ZERO),
+            (U256::ZERO, U256::ZERO),
+        );
+        let minted = Mark::new(
+            &portfolio(
+                10.0 * e18 - amounts.0,
+                10.0 * e18 - amounts.1,
+                vec![position],
+            ),
+            &holdings,
+            (0.0, 0.0),
+        );
+
+        // burning at the same price pays them back
+        holdings.record(
+            -600,
+            600,
+            -10i128.pow(20),
+            amounts,
+            (U256::ZERO, U256::ZERO),
+            (U256::ZERO, U256::ZERO),
+        );
+        let burned = Mark::new(
+            &portfolio(10.0 * e18, 10.0 * e18, Vec::new()),
+            &holdings,
+            (0.0, 0.0),
+        );
+
+        let score = Score::from_marks(vec![start, minted, burned]);
+        assert!(score.total_pnl.abs() < 1e-6);
+        assert!((minted.value - start.value).abs() < 1e-6);
+        assert!(score.impermanent_loss.abs() < 1e-6);
+        assert_eq!(score.fee_income, 0.0);
+    }
+}
@@ -0,0 +1,120 @@
+use std::{collections::HashMap, sync::PoisonError};
+
+use alloy::primitives::{Address, U256};
+
+use super::*;
+use crate::backend::Backend;
+
+/// A point the chain and an [`Engine`] can be rewound to, taken with [`take`].
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// Id of the snapshot on the [`Backend`].
+    pub id: U256,
+
+    /// Positions of every wallet when the snapshot was taken.
+    ledger: HashMap<Address, Holdings>,
+
+    /// Fills and marks of the arbitrageur when the snapshot was taken.
+    arbitrage: ArbitrageLedger,
+}
+
+impl Snapshot {
+    /// Capture the in-memory state of `engine` along with the chain snapshot `id`.
+    fn capture(id: U256, engine: &Engine) -> Self {
+        Self {
+            id,
+            ledger: engine
+                .ledger
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone(),
+            arbitrage: engine.arbitrage_ledger(),
+        }
+    }
+
+    /// Restore the in-memory state of `engine` to the captured one.
+    fn restore(self, engine: &Engine) {
+        *engine.ledger.lock().unwrap_or_else(PoisonError::into_inner) = self.ledger;
+        *engine
+            .arbitrage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = self.arbitrage;
+    }
+}
+
+/// Snapshot the chain, and the positions and arbitrage fills tracked by `engine`, so a candidate action can be tried and observed before
+/// being rolled back with [`revert`], e.g. to read the [`crate::Signal`] a hypothetical rebalance would leave behind.
+pub async fn take(
+    engine: &Engine,
+    backend: &(impl Backend + ?Sized),
+) -> Result<Snapshot, ArenaError> {
+    Ok(Snapshot::capture(backend.snapshot().await?, engine))
+}
+
+/// Rewind the chain, and the positions and arbitrage fills tracked by `engine`, to `snapshot`.
+///
+/// Reverting consumes the snapshot on the backend, so a strategy trying several actions from the same state takes a new
+/// snapshot after every revert. Snapshots taken after `snapshot` are discarded.
+pub async fn revert(
+    snapshot: Snapshot,
+    engine: &Engine,
+    backend: &(impl Backend + ?Sized),
+) -> Result<(), ArenaError> {
+    if !backend.revert(snapshot.id).await? {
+        return Err(ArenaError::Custom(format!(
+            "snapshot {} does not exist",
+            snapshot.id
+        )));
+    }
+
+    snapshot.restore(engine);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::I256;
+
+    use super::*;
+    use crate::engine::arbitrageur::{ArbitrageFill, ArbitrageTrade};
+
+    #[test]
+    fn test_snapshot_restore() {
+        let engine = Engine::new(Address::ZERO);
+        let owner = Address::repeat_byte(1);
+        let fill = |step| {
+            let trade = ArbitrageTrade {
+                step,
+                zero_for_one: true,
+                amount0: I256::try_from(-10).unwrap(),
+                amount1: I256::try_from(11).unwrap(),
+            };
+            ArbitrageFill::new(&trade, (0, 0), 1.0, 100_000, 1)
+        };
+
+        engine.record_fill(fill(0));
+        engine.record_mark(0, 1.0);
+        engine
+            .ledger
+            .lock()
+            .unwrap()
+            .insert(owner, Holdings::default());
+
+        let snapshot = Snapshot::capture(U256::from(1), &engine);
+        let (holdings, arbitrage) = (
+            engine.ledger.lock().unwrap().clone(),
+            engine.arbitrage_ledger(),
+        );
+
+        engine.record_fill(fill(1));
+        engine.record_mark(1, 1.1);
+        engine.ledger.lock().unwrap().clear();
+        assert_eq!(engine.arbitrage_ledger().fills.len(), 2);
+
+        snapshot.restore(&engine);
+        assert_eq!(*engine.ledger.lock().unwrap(), holdings);
+        assert_eq!(engine.arbitrage_ledger(), arbitrage);
+        assert_eq!(engine.arbitrage_ledger().fills, vec![fill(0)]);
+    }
+}
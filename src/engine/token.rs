@@ -0,0 +1,109 @@
+use alloy::{
+    primitives::{keccak256, Address, U256},
+    sol_types::SolValue,
+};
+
+use super::*;
+use crate::{backend, config::TokenConfig, types::token::ArenaToken};
+
+/// Deploy the pair of tokens configured by `configs` for the pool of the controller at `controller`, returning token0
+/// and token1.
+///
+/// Each token takes the name, symbol and decimals of its config as constructor arguments, and is deployed through the
+/// deployment proxy, see [`hook::create2`], with a salt derived from `controller`, so a deployment at deterministic
+/// addresses deploys its tokens at the same addresses too. Pool keys order their currencies by address, so the salt of
+/// token1 is the first placing it after token0.
+pub(crate) async fn deploy_pair(
+    controller: Address,
+    configs: [&TokenConfig; 2],
+    provider: AnvilProvider,
+) -> Result<(Address, Address), ArenaError> {
+    let init_code = |config: &TokenConfig| {
+        ArenaToken::deploy_builder(
+            provider.clone(),
+            config.name.clone(),
+            config.symbol.clone(),
+            config.decimals,
+        )
+        .calldata()
+        .to_vec()
+    };
+    let salt = |idx: u64, attempt: u64| {
+        keccak256((controller, U256::from(idx), U256::from(attempt)).abi_encode())
+    };
+
+    let token0 = hook::create2(salt(0, 0), &init_code(configs[0]), provider.clone()).await?;
+
+    let init_code = init_code(configs[1]);
+    let salt = (0..)
+        .map(|attempt| salt(1, attempt))
+        .find(|salt| hook::create2_address(*salt, &init_code) > token0)
+        .expect("half of all salts place token1 after token0");
+    let token1 = hook::create2(salt, &init_code, provider).await?;
+
+    Ok((token0, token1))
+}
+
+/// Mint `amount` of `token` to `to` from the wallet behind `provider`.
+pub(crate) async fn mint(
+    token: Address,
+    to: Address,
+    amount: U256,
+    provider: AnvilProvider,
+) -> Result<(), ArenaError> {
+    ArenaToken::new(token, provider.clone())
+        .mint(to, amount)
+        .nonce(
+            provider
+                .get_transaction_count(provider.default_signer_address())
+                .await?,
+        )
+        .send()
+        .await?
+        .watch()
+        .await?;
+
+    Ok(())
+}
+
+/// Approve `spender` to spend any amount of the `currency` of the wallet behind `provider`, unless it already may.
+///
+/// The native currency is paid with the call, so it needs no approval.
+pub(crate) async fn approve(
+    currency: Address,
+    spender: Address,
+    provider: AnvilProvider,
+) -> Result<(), ArenaError> {
+    if currency.is_zero() {
+        return Ok(());
+    }
+
+    let owner = provider.default_signer_address();
+    let token = ArenaToken::new(currency, provider.clone());
+    // the allowance of an unlimited approval only decreases by what the spender spent
+    if backend::read(
+        &provider,
+        currency,
+        ArenaToken::allowanceCall {
+            _0: owner,
+            _1: spender,
+        },
+    )
+    .await?
+    ._0 >= U256::MAX >> 1
+    {
+        return Ok(());
+    }
+
+    token
+        .approve(spender, U256::MAX)
+        .nonce(provider.get_transaction_count(owner).await?)
+        .send()
+        .await
+        .map_err(ArenaError::ContractError)?
+        .watch()
+        .await
+        .map_err(ArenaError::PendingTransactionError)?;
+
+    Ok(())
+}
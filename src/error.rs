@@ -11,7 +11,123 @@ pub enum ArenaError {
     #[error("alloy pending transaction error {0}")]
     PendingTransactionError(#[from] alloy::providers::PendingTransactionError),
 
+    /// RPC request to the Anvil node failed.
+    #[error("alloy transport error {0}")]
+    RpcError(#[from] alloy::transports::TransportError),
+
     /// Conversion error when parsing ether values.
     #[error("alloy conversion error {0}")]
     ConversionError(#[from] alloy::primitives::utils::UnitsError),
+
+    /// Reading or writing a file failed.
+    #[error("io error {0}")]
+    IoError(#[from] std::io::Error),
+
+    /// Serializing or deserializing JSON failed.
+    #[error("serde json error {0}")]
+    SerdeError(#[from] serde_json::Error),
+
+    /// Parsing a TOML file failed.
+    #[error("toml error {0}")]
+    TomlError(#[from] toml::de::Error),
+
+    /// HTTP request to an external data source failed.
+    #[error("http error {0}")]
+    HttpError(#[from] alloy::transports::http::reqwest::Error),
+
+    /// Subgraph query returned errors instead of data.
+    #[error("subgraph error {0}")]
+    SubgraphError(String),
+
+    /// Writing CSV output failed.
+    #[error("csv error {0}")]
+    CsvError(#[from] csv::Error),
+
+    /// A simulation component panicked.
+    #[error("panic {0}")]
+    Panic(String),
+
+    /// The arena or simulation configuration is invalid.
+    #[error("{0}")]
+    InvalidConfig(#[from] BuildError),
+
+    /// A replayed run diverged from its recording.
+    #[error("replay error {0}")]
+    ReplayError(String),
+
+    /// A strategy requested an action that cannot be executed.
+    #[error("invalid action {0}")]
+    InvalidAction(String),
+
+    /// Loading or calling a WebAssembly strategy failed.
+    #[cfg(feature = "wasm")]
+    #[error("wasm error {0}")]
+    WasmError(String),
+
+    /// Calling a Python strategy failed.
+    #[cfg(feature = "pyo3")]
+    #[error("python error {0}")]
+    PythonError(String),
+
+    /// Writing Parquet output failed.
+    #[cfg(feature = "arrow")]
+    #[error("parquet error {0}")]
+    ParquetError(String),
+
+    /// Rendering a chart failed.
+    #[cfg(feature = "plot")]
+    #[error("plot error {0}")]
+    PlotError(String),
+
+    /// A feed was given data it cannot replay.
+    #[error("feed error {0}")]
+    FeedError(String),
+
+    /// Custom error raised by a user-defined component.
+    #[error("{0}")]
+    Custom(String),
+
+    /// A simulation component failed, with the step at which it failed.
+    #[error("{component} failed at {}: {source}", step.map_or("initialization".to_string(), |step| format!("step {step}")))]
+    StepError {
+        /// Step at which the failure occurred, or `None` during initialization.
+        step: Option<usize>,
+
+        /// Name of the failing component.
+        component: String,
+
+        /// Underlying error.
+        #[source]
+        source: Box<ArenaError>,
+    },
+}
+
+/// Problems found validating the configuration of an arena or simulation.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("invalid configuration: {}", problems.join("; "))]
+pub struct BuildError {
+    /// Every problem found, in a human readable form.
+    pub problems: Vec<String>,
+}
+
+impl BuildError {
+    /// Returns an error listing `problems`, if there are any.
+    pub fn from_problems(problems: Vec<String>) -> Result<(), Self> {
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(Self { problems })
+        }
+    }
+}
+
+impl ArenaError {
+    /// Attach the failing component and simulation step to an error.
+    pub fn at_step(self, step: Option<usize>, component: impl Into<String>) -> Self {
+        ArenaError::StepError {
+            step,
+            component: component.into(),
+            source: Box::new(self),
+        }
+    }
 }
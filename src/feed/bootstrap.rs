@@ -0,0 +1,139 @@
+use rand::{rngs::StdRng, Rng};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{restore_into, sign, snapshot_of, Feed};
+use crate::error::ArenaError;
+
+/// Feed generating synthetic paths by resampling blocks of historical log returns.
+///
+/// This is a circular block bootstrap: each block starts at a uniformly random index of the return series and wraps
+/// around its end, so realistic fat tails and short-range autocorrelation are preserved within blocks. Resampling draws
+/// from the simulation's RNG, so paths are reproducible under [`crate::arena::ArenaBuilder::with_seed`].
+///
+/// Each return spans the sampling interval `dt` of the historical series. [`Feed::step`] applies one return, and
+/// [`Feed::advance`] applies as many as fit in the given time, carrying the remainder over to the next call.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BootstrapFeed {
+    current_value: f64,
+
+    /// Historical log returns that are resampled.
+    returns: Vec<f64>,
+
+    /// Time between consecutive prices of the historical series.
+    dt: f64,
+
+    /// Number of consecutive returns in each resampled block.
+    block_length: usize,
+
+    /// Time advanced since the last return was applied.
+    elapsed: f64,
+
+    block_start: usize,
+    block_offset: usize,
+
+    /// Whether resampled returns are negated.
+    antithetic: bool,
+}
+
+impl BootstrapFeed {
+    /// Public constructor function for a new [`BootstrapFeed`] from a historical price series sampled every `dt`.
+    ///
+    /// The feed starts at `initial_value`, which defaults to the last price of the series. Returns an error if the
+    /// series has fewer than two prices or a price that is not positive, or if `dt` or `block_length` is not positive.
+    pub fn new(
+        prices: &[f64],
+        dt: f64,
+        block_length: usize,
+        initial_value: Option<f64>,
+    ) -> Result<Self, ArenaError> {
+        if prices.len() < 2 {
+            return Err(ArenaError::FeedError(
+                "at least two prices are required to bootstrap returns".to_string(),
+            ));
+        }
+        if let Some(price) = prices
+            .iter()
+            .find(|price| !(price.is_finite() && **price > 0.0))
+        {
+            return Err(ArenaError::FeedError(format!(
+                "cannot bootstrap returns from the price {price}"
+            )));
+        }
+        if !(dt.is_finite() && dt > 0.0) {
+            return Err(ArenaError::FeedError(format!(
+                "sampling interval must be positive, got {dt}"
+            )));
+        }
+        if block_length == 0 {
+            return Err(ArenaError::FeedError(
+                "block length must be positive".to_string(),
+            ));
+        }
+
+        let returns = prices
+            .windows(2)
+            .map(|window| (window[1] / window[0]).ln())
+            .collect();
+
+        Ok(Self {
+            current_value: initial_value.unwrap_or(prices[prices.len() - 1]),
+            returns,
+            dt,
+            block_length,
+            elapsed: 0.0,
+            block_start: 0,
+            block_offset: block_length,
+            antithetic: false,
+        })
+    }
+
+    /// Apply the next resampled return.
+    fn next_return(&mut self, rng: &mut StdRng) {
+        if self.block_offset >= self.block_length {
+            self.block_start = rng.gen_range(0..self.returns.len());
+            self.block_offset = 0;
+        }
+
+        let idx = (self.block_start + self.block_offset) % self.returns.len();
+        self.block_offset += 1;
+
+        self.current_value *= (sign(self.antithetic) * self.returns[idx]).exp();
+    }
+}
+
+impl Feed for BootstrapFeed {
+    fn current_value(&self) -> f64 {
+        self.current_value
+    }
+
+    fn step(&mut self, rng: &mut StdRng) -> f64 {
+        self.next_return(rng);
+        self.current_value
+    }
+
+    fn advance(&mut self, dt: f64, rng: &mut StdRng) -> f64 {
+        self.elapsed += dt;
+
+        // compare with a tolerance so that fractions of the sampling interval add up to whole returns
+        while self.elapsed >= self.dt * (1.0 - 1e-9) {
+            self.elapsed -= self.dt;
+            self.next_return(rng);
+        }
+        self.elapsed = self.elapsed.max(0.0);
+
+        self.current_value
+    }
+
+    fn set_antithetic(&mut self, antithetic: bool) {
+        self.antithetic = antithetic;
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        snapshot_of(self)
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        restore_into(self, state)
+    }
+}
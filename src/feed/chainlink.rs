@@ -0,0 +1,189 @@
+use std::{fs::File, path::Path};
+
+use alloy::{
+    primitives::{Address, Signed, Uint, U256},
+    providers::ProviderBuilder,
+    transports::http::reqwest::Url,
+};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use serde_json::Value;
+
+use super::{restore_into, snapshot_of, Feed};
+use crate::{error::ArenaError, types::chainlink::AggregatorV3Interface};
+
+/// A single Chainlink oracle round.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RoundData {
+    /// Identifier of the round.
+    pub round_id: u128,
+
+    /// Answer of the round, already scaled by the aggregator's decimals.
+    pub answer: f64,
+
+    /// Unix timestamp at which the round was last updated.
+    pub updated_at: u64,
+}
+
+impl RoundData {
+    /// Returns the round of a raw aggregator answer, scaled down by `scale`, or an error if the answer is not a
+    /// positive price or the round was never updated.
+    pub fn from_raw(
+        round_id: Uint<80, 2>,
+        answer: Signed<256, 4>,
+        updated_at: U256,
+        scale: f64,
+    ) -> Result<Self, ArenaError> {
+        let invalid = |reason: &str| ArenaError::FeedError(format!("round {round_id} {reason}"));
+
+        if !answer.is_positive() {
+            return Err(invalid(&format!("has a non-positive answer {answer}")));
+        }
+        let updated_at = u64::try_from(updated_at)
+            .ok()
+            .filter(|&updated_at| updated_at > 0)
+            .ok_or_else(|| invalid(&format!("has an invalid update timestamp {updated_at}")))?;
+        let answer = answer
+            .to_string()
+            .parse::<f64>()
+            .map_err(|e| invalid(&format!("has an unreadable answer: {e}")))?;
+
+        Ok(Self {
+            round_id: round_id.to::<u128>(),
+            answer: answer / scale,
+            updated_at,
+        })
+    }
+}
+
+/// Feed that replays historical Chainlink `latestRoundData` answers.
+///
+/// The feed keeps an internal clock, starting at the first round's timestamp, which is moved forward by
+/// `seconds_per_step` on each [`Feed::step`] (or by `dt` seconds on [`Feed::advance`]). The current value is the answer
+/// of the latest round updated at or before the clock, so oracle update cadence is preserved.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChainlinkFeed {
+    rounds: Vec<RoundData>,
+
+    /// Seconds of oracle time replayed per simulation step.
+    seconds_per_step: f64,
+
+    clock: f64,
+    cursor: usize,
+}
+
+impl ChainlinkFeed {
+    /// Public constructor function for a new [`ChainlinkFeed`] from a set of rounds.
+    ///
+    /// Rounds are sorted by their update timestamp.
+    pub fn new(mut rounds: Vec<RoundData>, seconds_per_step: f64) -> Self {
+        rounds.sort_by_key(|round| round.updated_at);
+        let clock = rounds.first().map_or(0.0, |round| round.updated_at as f64);
+
+        Self {
+            rounds,
+            seconds_per_step,
+            clock,
+            cursor: 0,
+        }
+    }
+
+    /// Load rounds previously cached with [`ChainlinkFeed::save_json`].
+    pub fn from_json(path: impl AsRef<Path>, seconds_per_step: f64) -> Result<Self, ArenaError> {
+        let rounds: Vec<RoundData> = serde_json::from_reader(File::open(path)?)?;
+        Ok(Self::new(rounds, seconds_per_step))
+    }
+
+    /// Fetch the most recent `rounds` answers of the aggregator at `aggregator` from an RPC endpoint.
+    ///
+    /// Rounds are walked backwards from `latestRoundData` until `rounds` answers are collected or a round is not
+    /// available, e.g. at an aggregator phase boundary.
+    pub async fn fetch(
+        rpc_url: Url,
+        aggregator: Address,
+        rounds: usize,
+        seconds_per_step: f64,
+    ) -> Result<Self, ArenaError> {
+        let provider = ProviderBuilder::new().on_http(rpc_url);
+        let aggregator = AggregatorV3Interface::new(aggregator, provider);
+
+        let scale = 10f64.powi(aggregator.decimals().call().await?._0 as i32);
+
+        let latest = aggregator.latestRoundData().call().await?;
+        let mut round_id = latest.roundId;
+        let mut data = Vec::with_capacity(rounds);
+
+        while data.len() < rounds {
+            let Ok(round) = aggregator.getRoundData(round_id).call().await else {
+                break;
+            };
+
+            if round.updatedAt.is_zero() {
+                break;
+            }
+
+            data.push(RoundData::from_raw(
+                round.roundId,
+                round.answer,
+                round.updatedAt,
+                scale,
+            )?);
+
+            if round_id <= Uint::from(1) {
+                break;
+            }
+
+            round_id -= Uint::from(1);
+        }
+
+        Ok(Self::new(data, seconds_per_step))
+    }
+
+    /// Cache the rounds of this feed to a JSON file, to be loaded with [`ChainlinkFeed::from_json`].
+    pub fn save_json(&self, path: impl AsRef<Path>) -> Result<(), ArenaError> {
+        serde_json::to_writer_pretty(File::create(path)?, &self.rounds)?;
+        Ok(())
+    }
+
+    /// Returns the rounds replayed by this feed.
+    pub fn rounds(&self) -> &[RoundData] {
+        &self.rounds
+    }
+
+    fn seek(&mut self, dt: f64) -> f64 {
+        self.clock += dt;
+
+        while self.cursor + 1 < self.rounds.len()
+            && self.rounds[self.cursor + 1].updated_at as f64 <= self.clock
+        {
+            self.cursor += 1;
+        }
+
+        self.current_value()
+    }
+}
+
+impl Feed for ChainlinkFeed {
+    fn current_value(&self) -> f64 {
+        self.rounds
+            .get(self.cursor)
+            .map_or(0.0, |round| round.answer)
+    }
+
+    fn step(&mut self, _rng: &mut StdRng) -> f64 {
+        self.seek(self.seconds_per_step)
+    }
+
+    fn advance(&mut self, dt: f64, _rng: &mut StdRng) -> f64 {
+        self.seek(dt)
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        snapshot_of(self)
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        restore_into(self, state)
+    }
+}
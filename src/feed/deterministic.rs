@@ -0,0 +1,196 @@
+use std::f64::consts::PI;
+
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{restore_into, snapshot_of, Feed};
+use crate::error::ArenaError;
+
+/// Feed that always returns the same value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConstantFeed {
+    value: f64,
+}
+
+impl ConstantFeed {
+    /// Public constructor function for a new [`ConstantFeed`].
+    pub fn new(value: f64) -> Self {
+        Self { value }
+    }
+}
+
+impl Feed for ConstantFeed {
+    fn current_value(&self) -> f64 {
+        self.value
+    }
+
+    fn step(&mut self, _rng: &mut StdRng) -> f64 {
+        self.value
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        snapshot_of(self)
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        restore_into(self, state)
+    }
+}
+
+/// Feed that holds a level and jumps to new levels at given steps.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepFeed {
+    value: f64,
+
+    /// Pairs of `(step, value)`, sorted by step, at which the feed jumps to `value`.
+    jumps: Vec<(usize, f64)>,
+
+    current_step: usize,
+}
+
+impl StepFeed {
+    /// Public constructor function for a new [`StepFeed`].
+    ///
+    /// The feed starts at `initial_value` and takes the value of each jump once `step` steps have elapsed.
+    pub fn new(initial_value: f64, mut jumps: Vec<(usize, f64)>) -> Self {
+        jumps.sort_by_key(|(step, _)| *step);
+        Self {
+            value: initial_value,
+            jumps,
+            current_step: 0,
+        }
+    }
+}
+
+impl Feed for StepFeed {
+    fn current_value(&self) -> f64 {
+        self.value
+    }
+
+    fn step(&mut self, _rng: &mut StdRng) -> f64 {
+        self.current_step += 1;
+
+        if let Some((_, value)) = self
+            .jumps
+            .iter()
+            .rev()
+            .find(|(step, _)| *step <= self.current_step)
+        {
+            self.value = *value;
+        }
+
+        self.value
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        snapshot_of(self)
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        restore_into(self, state)
+    }
+}
+
+/// Feed that oscillates sinusoidally around a mean.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SineFeed {
+    /// Level the feed oscillates around.
+    mean: f64,
+
+    /// Amplitude of the oscillation.
+    amplitude: f64,
+
+    /// Period of the oscillation, in units of time.
+    period: f64,
+
+    /// Default time step, used by [`Feed::step`].
+    dt: f64,
+
+    time: f64,
+}
+
+impl SineFeed {
+    /// Public constructor function for a new [`SineFeed`].
+    pub fn new(mean: f64, amplitude: f64, period: f64, dt: f64) -> Self {
+        Self {
+            mean,
+            amplitude,
+            period,
+            dt,
+            time: 0.0,
+        }
+    }
+}
+
+impl Feed for SineFeed {
+    fn current_value(&self) -> f64 {
+        self.mean + self.amplitude * (2.0 * PI * self.time / self.period).sin()
+    }
+
+    fn step(&mut self, rng: &mut StdRng) -> f64 {
+        self.advance(self.dt, rng)
+    }
+
+    fn advance(&mut self, dt: f64, _rng: &mut StdRng) -> f64 {
+        self.time += dt;
+        self.current_value()
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        snapshot_of(self)
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        restore_into(self, state)
+    }
+}
+
+/// Feed that moves linearly from an initial value at a constant slope.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RampFeed {
+    initial_value: f64,
+
+    /// Change in value per unit of time.
+    slope: f64,
+
+    /// Default time step, used by [`Feed::step`].
+    dt: f64,
+
+    time: f64,
+}
+
+impl RampFeed {
+    /// Public constructor function for a new [`RampFeed`].
+    pub fn new(initial_value: f64, slope: f64, dt: f64) -> Self {
+        Self {
+            initial_value,
+            slope,
+            dt,
+            time: 0.0,
+        }
+    }
+}
+
+impl Feed for RampFeed {
+    fn current_value(&self) -> f64 {
+        self.initial_value + self.slope * self.time
+    }
+
+    fn step(&mut self, rng: &mut StdRng) -> f64 {
+        self.advance(self.dt, rng)
+    }
+
+    fn advance(&mut self, dt: f64, _rng: &mut StdRng) -> f64 {
+        self.time += dt;
+        self.current_value()
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        snapshot_of(self)
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        restore_into(self, state)
+    }
+}
@@ -0,0 +1,138 @@
+use rand::{distributions::Open01, rngs::StdRng, Rng};
+use rand_distr::{Distribution, StandardNormal, StudentT};
+use serde::{Deserialize, Serialize};
+
+use crate::error::ArenaError;
+
+/// Distribution of the random shocks driving a diffusion feed.
+///
+/// Every variant is scaled to unit variance, so swapping distributions fattens tails without changing volatility.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Innovation {
+    /// Standard normal shocks.
+    #[default]
+    Gaussian,
+
+    /// Student-t shocks with the given degrees of freedom.
+    StudentT(DegreesOfFreedom),
+
+    /// Laplace (double exponential) shocks.
+    Laplace,
+}
+
+/// Degrees of freedom of Student-t shocks, which must be greater than two for their variance to be finite.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct DegreesOfFreedom(f64);
+
+impl DegreesOfFreedom {
+    /// Public constructor function for new [`DegreesOfFreedom`], failing unless `df` is greater than two.
+    pub fn new(df: f64) -> Result<Self, ArenaError> {
+        if df > 2.0 && df.is_finite() {
+            Ok(Self(df))
+        } else {
+            Err(ArenaError::FeedError(format!(
+                "Student-t shocks need more than two degrees of freedom, got {df}"
+            )))
+        }
+    }
+
+    /// Returns the degrees of freedom.
+    pub fn get(&self) -> f64 {
+        self.0
+    }
+}
+
+impl TryFrom<f64> for DegreesOfFreedom {
+    type Error = ArenaError;
+
+    fn try_from(df: f64) -> Result<Self, Self::Error> {
+        Self::new(df)
+    }
+}
+
+impl From<DegreesOfFreedom> for f64 {
+    fn from(df: DegreesOfFreedom) -> Self {
+        df.0
+    }
+}
+
+impl Innovation {
+    /// Returns Student-t shocks with `df` degrees of freedom, failing unless `df` is greater than two.
+    pub fn student_t(df: f64) -> Result<Self, ArenaError> {
+        Ok(Innovation::StudentT(DegreesOfFreedom::new(df)?))
+    }
+
+    /// Draws a unit variance shock.
+    pub fn sample(&self, rng: &mut StdRng) -> f64 {
+        match self {
+            Innovation::Gaussian => StandardNormal.sample(rng),
+            Innovation::StudentT(df) => {
+                let df = df.get();
+                let t: f64 = StudentT::new(df)
+                    .expect("more than two degrees of freedom are valid")
+                    .sample(rng);
+                t * ((df - 2.0) / df).sqrt()
+            }
+            Innovation::Laplace => laplace(rng.sample(Open01)),
+        }
+    }
+}
+
+/// Returns the unit variance Laplace shock at the quantile `u`, in the open interval `(0, 1)`.
+fn laplace(u: f64) -> f64 {
+    // inverse transform sampling with scale 1 / sqrt(2), giving unit variance
+    let u = u - 0.5;
+    -u.signum() * (1.0 - 2.0 * u.abs()).ln() / std::f64::consts::SQRT_2
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn test_student_t_degrees_of_freedom() {
+        for df in [2.0, 1.0, 0.0, -3.0, f64::NAN, f64::INFINITY] {
+            assert!(Innovation::student_t(df).is_err());
+            assert!(
+                serde_json::from_str::<Innovation>(&format!(r#"{{"StudentT": {df}}}"#)).is_err()
+            );
+        }
+
+        let innovation = Innovation::student_t(5.0).unwrap();
+        let json = serde_json::to_string(&innovation).unwrap();
+        assert_eq!(json, r#"{"StudentT":5.0}"#);
+        assert_eq!(
+            serde_json::from_str::<Innovation>(&json).unwrap(),
+            innovation
+        );
+    }
+
+    #[test]
+    fn test_unit_variance() {
+        let mut rng = StdRng::seed_from_u64(11);
+
+        for innovation in [
+            Innovation::Gaussian,
+            Innovation::student_t(8.0).unwrap(),
+            Innovation::Laplace,
+        ] {
+            let draws: Vec<f64> = (0..100_000).map(|_| innovation.sample(&mut rng)).collect();
+            assert!(draws.iter().all(|draw| draw.is_finite()));
+
+            let variance = draws.iter().map(|draw| draw * draw).sum::<f64>() / draws.len() as f64;
+            assert!((variance - 1.0).abs() < 0.05, "{innovation:?}: {variance}");
+        }
+    }
+
+    #[test]
+    fn test_laplace_tails() {
+        // the quantiles closest to the ends of the open interval stay finite
+        assert!(laplace(f64::EPSILON).is_finite());
+        assert!(laplace(1.0 - f64::EPSILON).is_finite());
+        assert_eq!(laplace(0.5), 0.0);
+        assert!((laplace(0.75) + laplace(0.25)).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,190 @@
+use std::{future::Future, time::Duration};
+
+use alloy::{
+    primitives::{Address, Uint},
+    providers::{ProviderBuilder, RootProvider},
+    transports::http::{reqwest::Url, Client, Http},
+};
+use rand::rngs::StdRng;
+use tokio::{sync::watch, task::JoinHandle};
+
+use super::Feed;
+use crate::{error::ArenaError, types::pool::IUniswapV3Pool};
+
+/// Feed reporting the latest value pushed through a channel, for live prices from any external source, such as an
+/// exchange websocket, in a run paced with [`crate::Config::with_real_time`].
+///
+/// The feed keeps its last value once every sender is dropped.
+#[derive(Debug)]
+pub struct ChannelFeed {
+    value: watch::Receiver<f64>,
+}
+
+impl ChannelFeed {
+    /// Public constructor function for a new [`ChannelFeed`] starting at `initial_value`, along with the sender
+    /// updating it.
+    pub fn new(initial_value: f64) -> (Self, watch::Sender<f64>) {
+        let (sender, value) = watch::channel(initial_value);
+        (Self { value }, sender)
+    }
+}
+
+impl Feed for ChannelFeed {
+    fn current_value(&self) -> f64 {
+        *self.value.borrow()
+    }
+
+    fn step(&mut self, _rng: &mut StdRng) -> f64 {
+        self.current_value()
+    }
+}
+
+/// Feed that polls the spot price of a live, deployed pool exposing `slot0()`.
+///
+/// A background task polls the pool every `interval` and the feed reports the latest observed price, so strategies
+/// can be paper-traded against a live market inside the arena loop. The task stops when the feed is dropped.
+#[derive(Debug)]
+pub struct LivePoolFeed {
+    price: watch::Receiver<f64>,
+    poller: JoinHandle<()>,
+}
+
+impl LivePoolFeed {
+    /// Fetch the pool's current price and start polling it every `interval`.
+    ///
+    /// `decimals` are the decimals of token0 and token1, used to convert `sqrtPriceX96` to a human readable price.
+    pub async fn spawn(
+        rpc_url: Url,
+        pool: Address,
+        decimals: (u8, u8),
+        interval: Duration,
+    ) -> Result<Self, ArenaError> {
+        let provider = ProviderBuilder::new().on_http(rpc_url);
+        let pool = IUniswapV3Pool::new(pool, provider);
+        let scale = 10f64.powi(decimals.0 as i32 - decimals.1 as i32);
+
+        Self::poll(move || fetch_price(pool.clone(), scale), interval).await
+    }
+
+    /// Take a first price from `fetch`, failing if it does, then keep calling it every `interval` in the background.
+    async fn poll<F, Fut>(mut fetch: F, interval: Duration) -> Result<Self, ArenaError>
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<f64, ArenaError>> + Send,
+    {
+        let (sender, price) = watch::channel(fetch().await?);
+
+        let poller = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+
+            loop {
+                ticker.tick().await;
+
+                // transient RPC failures keep the last observed price
+                if let Ok(latest) = fetch().await {
+                    if sender.send(latest).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(Self { price, poller })
+    }
+}
+
+async fn fetch_price(
+    pool: IUniswapV3Pool::IUniswapV3PoolInstance<Http<Client>, RootProvider<Http<Client>>>,
+    scale: f64,
+) -> Result<f64, ArenaError> {
+    price_of(pool.slot0().call().await?.sqrtPriceX96, scale)
+}
+
+/// Returns the price of token0 in terms of token1 at `sqrt_price_x96`, scaled by `scale` for the token decimals.
+fn price_of(sqrt_price_x96: Uint<160, 3>, scale: f64) -> Result<f64, ArenaError> {
+    let sqrt_price =
+        sqrt_price_x96.to_string().parse::<f64>().map_err(|e| {
+            ArenaError::FeedError(format!("invalid pool price {sqrt_price_x96}: {e}"))
+        })? / 2f64.powi(96);
+
+    Ok(sqrt_price.powi(2) * scale)
+}
+
+impl Feed for LivePoolFeed {
+    fn current_value(&self) -> f64 {
+        *self.price.borrow()
+    }
+
+    fn step(&mut self, _rng: &mut StdRng) -> f64 {
+        self.current_value()
+    }
+}
+
+impl Drop for LivePoolFeed {
+    fn drop(&mut self) {
+        self.poller.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn test_price_of() {
+        let one = Uint::from(1u128 << 96);
+        assert_eq!(price_of(one, 1.0).unwrap(), 1.0);
+        assert_eq!(price_of(one * Uint::from(2), 1e-12).unwrap(), 4e-12);
+    }
+
+    #[tokio::test]
+    async fn test_live_pool_feed() {
+        // the first fetch must succeed
+        assert!(LivePoolFeed::poll(
+            || async { Err(ArenaError::FeedError("unreachable".to_string())) },
+            Duration::from_millis(1),
+        )
+        .await
+        .is_err());
+
+        // prices 1, then a failed poll, then 2 onwards
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let mut feed = LivePoolFeed::poll(
+            move || {
+                let call = counter.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    match call {
+                        0 => Ok(1.0),
+                        1 => Err(ArenaError::FeedError("transient".to_string())),
+                        _ => Ok(2.0),
+                    }
+                }
+            },
+            Duration::from_millis(1),
+        )
+        .await
+        .unwrap();
+
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(feed.current_value(), 1.0);
+        while feed.step(&mut rng) != 2.0 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert!(calls.load(Ordering::SeqCst) >= 3);
+
+        // dropping the feed stops polling
+        drop(feed);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let stopped = calls.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), stopped);
+    }
+}
@@ -0,0 +1,780 @@
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand_distr::{ChiSquared, Distribution, Normal, Poisson};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::ArenaError;
+
+/// Combinators that wrap a [`Feed`] and transform its output.
+pub mod transform;
+
+/// Feed replaying historical Chainlink oracle rounds.
+pub mod chainlink;
+
+/// Deterministic feeds for tests and examples.
+pub mod deterministic;
+
+/// Feed replaying historical swaps of a Uniswap pool from a subgraph.
+pub mod subgraph;
+
+/// Time-varying process parameters.
+pub mod schedule;
+
+/// Feed resampling historical returns.
+pub mod bootstrap;
+
+/// Stochastic order arrival processes.
+pub mod orderflow;
+
+/// Feed defined by a user-supplied closure.
+pub mod closure;
+
+/// Feed polling the price of a live on-chain pool.
+pub mod live;
+
+/// Shock distributions for diffusion feeds.
+pub mod innovation;
+
+use innovation::Innovation;
+
+use schedule::Schedule;
+
+/// Represents an arbitrary price feed.
+pub trait Feed {
+    /// Returns the current value of the feed.
+    fn current_value(&self) -> f64;
+
+    /// Advances the feed by one step using the simulation's seeded RNG and returns the new value.
+    fn step(&mut self, rng: &mut StdRng) -> f64;
+
+    /// Advances the feed by an explicit time increment `dt`, in the unit of its own default time step, and returns the
+    /// new value.
+    ///
+    /// Feeds that have no notion of time fall back to [`Feed::step`].
+    fn advance(&mut self, _dt: f64, rng: &mut StdRng) -> f64 {
+        self.step(rng)
+    }
+
+    /// Make the feed negate its random shocks, producing the antithetic counterpart of the path it would otherwise
+    /// generate from the same RNG.
+    ///
+    /// Feeds without random shocks can rely on the default, which does nothing.
+    fn set_antithetic(&mut self, _antithetic: bool) {}
+
+    /// Returns a serializable blob of the feed's internal state, to be passed to [`Feed::restore`].
+    ///
+    /// Feeds that cannot be snapshotted, e.g. live feeds, can rely on the default, which returns an error so that a
+    /// checkpoint is never written, or resumed, without their state.
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        Err(ArenaError::Custom(
+            "feed does not support snapshots".to_string(),
+        ))
+    }
+
+    /// Restores the feed's internal state from a blob previously returned by [`Feed::snapshot`].
+    ///
+    /// Returns an error by default, like [`Feed::snapshot`].
+    fn restore(&mut self, _state: Value) -> Result<(), ArenaError> {
+        Err(ArenaError::Custom(
+            "feed does not support snapshots".to_string(),
+        ))
+    }
+
+    /// Returns auxiliary values exposed to strategies through [`crate::Signal::metadata`], e.g. latent state of the
+    /// process.
+    fn metadata(&self) -> HashMap<String, Value> {
+        HashMap::new()
+    }
+}
+
+/// Serializes a feed's full state, for feeds whose state is the feed itself.
+pub(crate) fn snapshot_of<T: Serialize>(feed: &T) -> Result<Value, ArenaError> {
+    Ok(serde_json::to_value(feed)?)
+}
+
+/// Returns the factor applied to random shocks of a feed, given whether it is antithetic.
+pub(crate) fn sign(antithetic: bool) -> f64 {
+    if antithetic {
+        -1.0
+    } else {
+        1.0
+    }
+}
+
+/// Replaces a feed with a state previously produced by [`snapshot_of`].
+pub(crate) fn restore_into<T: DeserializeOwned>(
+    feed: &mut T,
+    state: Value,
+) -> Result<(), ArenaError> {
+    *feed = serde_json::from_value(state)?;
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+/// Implementation of an Ornstein-Uhlenbeck process using a Euler-Maruyama discretization scheme.
+///
+/// Each parameter is a [`Schedule`], so it can be a fixed scalar or vary over the process time, e.g. to model a
+/// scheduled token unlock.
+pub struct OrnsteinUhlenbeck {
+    current_value: f64,
+
+    /// Current time of the process.
+    time: f64,
+
+    /// Mean reversion rate.
+    theta: Schedule,
+
+    /// Long-term mean.
+    mu: Schedule,
+
+    /// Volatility.
+    sigma: Schedule,
+
+    /// Default time step, used by [`Feed::step`].
+    dt: f64,
+
+    /// Whether random shocks are negated.
+    antithetic: bool,
+
+    /// Distribution of random shocks.
+    innovation: Innovation,
+}
+
+impl OrnsteinUhlenbeck {
+    /// Public constructor function for a new [`OrnsteinUhlenbeck`].
+    pub fn new(
+        initial_value: f64,
+        theta: impl Into<Schedule>,
+        mu: impl Into<Schedule>,
+        sigma: impl Into<Schedule>,
+        dt: f64,
+    ) -> Self {
+        OrnsteinUhlenbeck {
+            current_value: initial_value,
+            time: 0.0,
+            theta: theta.into(),
+            mu: mu.into(),
+            sigma: sigma.into(),
+            dt,
+            antithetic: false,
+            innovation: Innovation::default(),
+        }
+    }
+
+    /// Set the distribution of the process' random shocks.
+    pub fn with_innovation(mut self, innovation: Innovation) -> Self {
+        self.innovation = innovation;
+        self
+    }
+}
+
+impl Feed for OrnsteinUhlenbeck {
+    fn current_value(&self) -> f64 {
+        self.current_value
+    }
+
+    fn step(&mut self, rng: &mut StdRng) -> f64 {
+        self.advance(self.dt, rng)
+    }
+
+    fn advance(&mut self, dt: f64, rng: &mut StdRng) -> f64 {
+        let theta = self.theta.at(self.time);
+        let mu = self.mu.at(self.time);
+        let sigma = self.sigma.at(self.time);
+
+        let drift = theta * (mu - self.current_value) * dt;
+        let randomness = sigma * dt.sqrt() * sign(self.antithetic) * self.innovation.sample(rng);
+
+        self.current_value += drift + randomness;
+        self.time += dt;
+        self.current_value
+    }
+
+    fn set_antithetic(&mut self, antithetic: bool) {
+        self.antithetic = antithetic;
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        Ok(json!({ "current_value": self.current_value, "time": self.time }))
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        self.current_value = serde_json::from_value(state["current_value"].clone())?;
+        self.time = serde_json::from_value(state["time"].clone())?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Implementation of a geometric Brownian motion using a Euler-Maruyama discretization scheme.
+pub struct GeometricBrownianMotion {
+    /// The initial value of the process.
+    pub initial_value: f64,
+
+    /// The current value of the process.
+    pub current_value: f64,
+
+    /// The current time in the process, incremented with each step by the time step `dt`.
+    pub current_time: f64,
+
+    /// The drift coefficient.
+    pub mu: f64,
+
+    /// The volatility coefficient.
+    pub sigma: f64,
+
+    /// The default time step size used for advancing the process with [`Feed::step`].
+    pub dt: f64,
+
+    /// Whether random shocks are negated.
+    #[serde(default)]
+    pub antithetic: bool,
+
+    /// Distribution of random shocks.
+    #[serde(default)]
+    pub innovation: Innovation,
+}
+
+impl GeometricBrownianMotion {
+    /// Public constructor function for a new [`GeometricBrownianMotion`].
+    pub fn new(initial_value: f64, mu: f64, sigma: f64, dt: f64) -> Self {
+        GeometricBrownianMotion {
+            initial_value,
+            current_value: initial_value,
+            current_time: 0.0,
+            mu,
+            sigma,
+            dt,
+            antithetic: false,
+            innovation: Innovation::default(),
+        }
+    }
+
+    /// Set the distribution of the process' random shocks.
+    pub fn with_innovation(mut self, innovation: Innovation) -> Self {
+        self.innovation = innovation;
+        self
+    }
+}
+
+impl Feed for GeometricBrownianMotion {
+    fn current_value(&self) -> f64 {
+        self.current_value
+    }
+
+    fn step(&mut self, rng: &mut StdRng) -> f64 {
+        self.advance(self.dt, rng)
+    }
+
+    fn advance(&mut self, dt: f64, rng: &mut StdRng) -> f64 {
+        let wiener_process = sign(self.antithetic) * self.innovation.sample(rng) * dt.sqrt();
+
+        let drift = (self.mu - 0.5 * self.sigma.powi(2)) * dt;
+
+        let volatility = self.sigma * wiener_process;
+
+        let change = drift + volatility;
+
+        self.current_value *= (change).exp();
+        self.current_time += dt;
+        self.current_value
+    }
+
+    fn set_antithetic(&mut self, antithetic: bool) {
+        self.antithetic = antithetic;
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        snapshot_of(self)
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        restore_into(self, state)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Implementation of a Cox-Ingersoll-Ross process, sampled exactly from its noncentral chi-squared transition density.
+///
+/// The process is guaranteed to stay non-negative, which makes it suitable for rate or funding-like series.
+pub struct CoxIngersollRoss {
+    current_value: f64,
+
+    /// Mean reversion rate.
+    kappa: f64,
+
+    /// Long-term mean.
+    theta: f64,
+
+    /// Volatility.
+    sigma: f64,
+
+    /// Default time step, used by [`Feed::step`].
+    dt: f64,
+}
+
+impl CoxIngersollRoss {
+    /// Public constructor function for a new [`CoxIngersollRoss`].
+    pub fn new(initial_value: f64, kappa: f64, theta: f64, sigma: f64, dt: f64) -> Self {
+        CoxIngersollRoss {
+            current_value: initial_value.max(0.0),
+            kappa,
+            theta,
+            sigma,
+            dt,
+        }
+    }
+}
+
+impl Feed for CoxIngersollRoss {
+    fn current_value(&self) -> f64 {
+        self.current_value
+    }
+
+    fn step(&mut self, rng: &mut StdRng) -> f64 {
+        self.advance(self.dt, rng)
+    }
+
+    fn advance(&mut self, dt: f64, rng: &mut StdRng) -> f64 {
+        let decay = (-self.kappa * dt).exp();
+
+        if self.sigma == 0.0 {
+            self.current_value = self.current_value * decay + self.theta * (1.0 - decay);
+            return self.current_value;
+        }
+
+        // scale of the transition density, with its kappa -> 0 limit
+        let c = if self.kappa == 0.0 {
+            self.sigma.powi(2) * dt / 4.0
+        } else {
+            self.sigma.powi(2) * (1.0 - decay) / (4.0 * self.kappa)
+        };
+
+        let degrees_of_freedom = 4.0 * self.kappa * self.theta / self.sigma.powi(2);
+        let noncentrality = self.current_value * decay / c;
+
+        // a noncentral chi-squared variate is a chi-squared variate with Poisson distributed extra degrees of freedom
+        let extra = if noncentrality > 0.0 {
+            Poisson::new(noncentrality / 2.0).unwrap().sample(rng)
+        } else {
+            0.0
+        };
+
+        let k = degrees_of_freedom + 2.0 * extra;
+        let sample = if k > 0.0 {
+            ChiSquared::new(k).unwrap().sample(rng)
+        } else {
+            0.0
+        };
+
+        self.current_value = c * sample;
+        self.current_value
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        snapshot_of(self)
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        restore_into(self, state)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Implementation of the SABR stochastic volatility model using a log-Euler scheme for the volatility and an absorbing
+/// Euler scheme for the forward.
+///
+/// The feed's value is the forward, and the current stochastic volatility is available through
+/// [`Sabr::volatility`]. [`Sabr::implied_volatility`] returns Hagan's implied volatility approximation, so
+/// option-hedging strategies can quote against the same model the market is simulated with.
+pub struct Sabr {
+    current_value: f64,
+
+    /// Current stochastic volatility.
+    alpha: f64,
+
+    /// Elasticity of the forward's volatility.
+    beta: f64,
+
+    /// Volatility of volatility.
+    nu: f64,
+
+    /// Correlation between the forward and volatility shocks.
+    rho: f64,
+
+    /// Default time step, used by [`Feed::step`].
+    dt: f64,
+
+    /// Whether random shocks are negated.
+    antithetic: bool,
+}
+
+impl Sabr {
+    /// Public constructor function for a new [`Sabr`].
+    pub fn new(initial_value: f64, alpha: f64, beta: f64, nu: f64, rho: f64, dt: f64) -> Self {
+        Sabr {
+            current_value: initial_value,
+            alpha,
+            beta,
+            nu,
+            rho,
+            dt,
+            antithetic: false,
+        }
+    }
+
+    /// Returns the current stochastic volatility.
+    pub fn volatility(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Returns Hagan's lognormal implied volatility for an option struck at `strike` expiring in `expiry` time units,
+    /// given the current forward and volatility.
+    pub fn implied_volatility(&self, strike: f64, expiry: f64) -> f64 {
+        let (f, k) = (self.current_value, strike);
+        let (alpha, beta, nu, rho) = (self.alpha, self.beta, self.nu, self.rho);
+        let one_minus_beta = 1.0 - beta;
+
+        let fk = (f * k).powf(one_minus_beta / 2.0);
+        let log_fk = (f / k).ln();
+
+        let correction = 1.0
+            + (one_minus_beta.powi(2) / 24.0 * alpha.powi(2) / fk.powi(2)
+                + rho * beta * nu * alpha / (4.0 * fk)
+                + (2.0 - 3.0 * rho.powi(2)) / 24.0 * nu.powi(2))
+                * expiry;
+
+        let denominator = fk
+            * (1.0
+                + one_minus_beta.powi(2) / 24.0 * log_fk.powi(2)
+                + one_minus_beta.powi(4) / 1920.0 * log_fk.powi(4));
+
+        let z = nu / alpha * fk * log_fk;
+        let z_over_x = if z.abs() < 1e-12 {
+            1.0
+        } else {
+            z / (((1.0 - 2.0 * rho * z + z.powi(2)).sqrt() + z - rho) / (1.0 - rho)).ln()
+        };
+
+        alpha / denominator * z_over_x * correction
+    }
+}
+
+impl Feed for Sabr {
+    fn current_value(&self) -> f64 {
+        self.current_value
+    }
+
+    fn step(&mut self, rng: &mut StdRng) -> f64 {
+        self.advance(self.dt, rng)
+    }
+
+    fn advance(&mut self, dt: f64, rng: &mut StdRng) -> f64 {
+        let normal = Normal::new(0.0, 1.0).unwrap();
+
+        let z1 = sign(self.antithetic) * normal.sample(rng);
+        let z2 = self.rho * z1
+            + (1.0 - self.rho.powi(2)).sqrt() * sign(self.antithetic) * normal.sample(rng);
+
+        let forward_shock = self.alpha * self.current_value.powf(self.beta) * dt.sqrt() * z1;
+
+        self.alpha *= (self.nu * dt.sqrt() * z2 - 0.5 * self.nu.powi(2) * dt).exp();
+        self.current_value = (self.current_value + forward_shock).max(0.0);
+        self.current_value
+    }
+
+    fn set_antithetic(&mut self, antithetic: bool) {
+        self.antithetic = antithetic;
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        snapshot_of(self)
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        restore_into(self, state)
+    }
+
+    fn metadata(&self) -> HashMap<String, Value> {
+        HashMap::from([("alpha".to_string(), Value::from(self.alpha))])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use alloy::primitives::{Signed, Uint, U256};
+
+    use super::{
+        chainlink::RoundData,
+        deterministic::*,
+        schedule::Schedule,
+        subgraph::{RawSwap, SwapCursor},
+        transform::*,
+        *,
+    };
+    use crate::engine::rng::{RngManager, Stream};
+
+    #[test]
+    fn test_seeded_feeds_are_reproducible() {
+        let mut a = OrnsteinUhlenbeck::new(1.0, 0.1, 1.0, 0.1, 0.1);
+        let mut b = OrnsteinUhlenbeck::new(1.0, 0.1, 1.0, 0.1, 0.1);
+
+        let mut rng_a = StdRng::seed_from_u64(42);
+        let mut rng_b = StdRng::seed_from_u64(42);
+
+        for _ in 0..100 {
+            assert_eq!(a.step(&mut rng_a), b.step(&mut rng_b));
+        }
+    }
+
+    #[test]
+    fn test_transformers_compose() {
+        // zero volatility makes the process deterministic: 1.0 -> 1.5 -> 1.75
+        let inner = Box::new(OrnsteinUhlenbeck::new(1.0, 1.0, 2.0, 0.0, 0.5));
+        let mut feed = Lagged::new(Box::new(Scaled::new(inner, 2.0)), 1);
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert_eq!(feed.current_value(), 2.0);
+        assert_eq!(feed.step(&mut rng), 2.0);
+        assert_eq!(feed.step(&mut rng), 3.0);
+        assert_eq!(feed.step(&mut rng), 3.5);
+    }
+
+    #[test]
+    fn test_deterministic_feeds() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let mut constant = ConstantFeed::new(3.0);
+        assert_eq!(constant.step(&mut rng), 3.0);
+
+        let mut step = StepFeed::new(1.0, vec![(2, 5.0), (4, 0.5)]);
+        let values: Vec<f64> = (0..5).map(|_| step.step(&mut rng)).collect();
+        assert_eq!(values, vec![1.0, 5.0, 5.0, 0.5, 0.5]);
+
+        let mut ramp = RampFeed::new(1.0, 0.5, 1.0);
+        assert_eq!(ramp.step(&mut rng), 1.5);
+        assert_eq!(ramp.advance(2.0, &mut rng), 2.5);
+
+        let mut sine = SineFeed::new(1.0, 0.5, 4.0, 1.0);
+        assert!((sine.step(&mut rng) - 1.5).abs() < 1e-12);
+        assert!((sine.step(&mut rng) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_channel_feed() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (mut feed, sender) = live::ChannelFeed::new(1.0);
+
+        assert_eq!(feed.step(&mut rng), 1.0);
+        sender.send(1.25).unwrap();
+        assert_eq!(feed.step(&mut rng), 1.25);
+        drop(sender);
+        assert_eq!(feed.step(&mut rng), 1.25);
+
+        // a live feed cannot be checkpointed
+        assert!(feed.snapshot().is_err());
+        assert!(feed.restore(Value::Null).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let new_feed = || {
+            Smoothed::new(
+                Box::new(GeometricBrownianMotion::new(1.0, 0.05, 0.2, 0.1)),
+                0.5,
+            )
+        };
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut feed = new_feed();
+        feed.step(&mut rng);
+
+        let state = feed.snapshot().unwrap();
+        let mut resumed_rng = rng.clone();
+        let expected = feed.step(&mut rng);
+
+        let mut restored = new_feed();
+        restored.restore(state).unwrap();
+        assert_eq!(restored.step(&mut resumed_rng), expected);
+    }
+
+    #[test]
+    fn test_scheduled_ou_parameters() {
+        // zero volatility and full reversion per unit time make the process track the scheduled mean
+        let mut feed = OrnsteinUhlenbeck::new(
+            1.0,
+            1.0,
+            Schedule::piecewise(vec![(0.0, 1.0), (2.0, 3.0)]),
+            0.0,
+            1.0,
+        );
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let values: Vec<f64> = (0..4).map(|_| feed.step(&mut rng)).collect();
+        assert_eq!(values, vec![1.0, 1.0, 3.0, 3.0]);
+    }
+
+    #[test]
+    fn test_antithetic_paths() {
+        let manager = RngManager::new(3).with_antithetic();
+
+        let mut feeds = [0, 1].map(|path| {
+            let mut feed = OrnsteinUhlenbeck::new(1.0, 0.0, 1.0, 0.1, 1.0);
+            feed.set_antithetic(manager.is_antithetic(path));
+            (feed, manager.rng(path, Stream::Feed))
+        });
+
+        for _ in 0..10 {
+            let [(a, rng_a), (b, rng_b)] = &mut feeds;
+            assert!((a.step(rng_a) - 1.0 + b.step(rng_b) - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_cir_is_non_negative() {
+        let mut feed = CoxIngersollRoss::new(0.01, 0.5, 0.02, 0.5, 0.1);
+        let mut rng = StdRng::seed_from_u64(11);
+
+        for _ in 0..10_000 {
+            assert!(feed.step(&mut rng) >= 0.0);
+        }
+    }
+
+    #[test]
+    fn test_sabr_lognormal_limit() {
+        // with beta = 1 and no vol of vol, SABR reduces to a lognormal model with flat implied volatility
+        let feed = Sabr::new(100.0, 0.2, 1.0, 0.0, 0.0, 1.0);
+
+        for strike in [80.0, 100.0, 120.0] {
+            assert!((feed.implied_volatility(strike, 1.0) - 0.2).abs() < 1e-9);
+        }
+
+        // the volatility state is published through wrapping feeds
+        let feed = Scaled::new(Box::new(feed), 2.0);
+        assert_eq!(feed.metadata()["alpha"], 0.2);
+    }
+
+    #[test]
+    fn test_round_from_raw() {
+        let round = RoundData::from_raw(
+            Uint::from(7),
+            Signed::try_from(250_000_000_000i64).unwrap(),
+            U256::from(1_700_000_000u64),
+            1e8,
+        )
+        .unwrap();
+        assert_eq!(
+            round,
+            RoundData {
+                round_id: 7,
+                answer: 2500.0,
+                updated_at: 1_700_000_000,
+            }
+        );
+
+        let invalid = [
+            (Signed::ZERO, U256::from(1)),
+            (Signed::try_from(-1).unwrap(), U256::from(1)),
+            (Signed::ONE, U256::ZERO),
+            (Signed::ONE, U256::MAX),
+        ];
+        for (answer, updated_at) in invalid {
+            assert!(matches!(
+                RoundData::from_raw(Uint::from(1), answer, updated_at, 1.0),
+                Err(ArenaError::FeedError(_))
+            ));
+        }
+
+        let path = std::env::temp_dir().join(format!("arena-rounds-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"[{"round_id": 1, "answer": "n/a", "updated_at": 1}]"#,
+        )
+        .unwrap();
+        assert!(chainlink::ChainlinkFeed::from_json(&path, 1.0).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_swap_paging() {
+        let swap = |id: usize, timestamp: u64| RawSwap {
+            id: format!("0x{id:02x}"),
+            timestamp: timestamp.to_string(),
+            sqrt_price_x96: "79228162514264337593543950336".to_string(),
+            amount0: "1".to_string(),
+            amount1: "-1".to_string(),
+        };
+        let swaps = [(0, 1), (1, 2), (2, 2), (3, 2), (4, 2), (5, 3)].map(|(id, ts)| swap(id, ts));
+
+        // answers queries like the subgraph, two swaps per page
+        let query = |cursor: &SwapCursor| -> Vec<RawSwap> {
+            swaps
+                .iter()
+                .filter(|swap| swap.timestamp.parse::<u64>().unwrap() >= cursor.from)
+                .filter(|swap| !cursor.seen.contains(&swap.id))
+                .take(2)
+                .cloned()
+                .collect()
+        };
+
+        // swaps sharing a timestamp span three pages, and none after the first timestamp is skipped
+        let mut cursor = SwapCursor::after(0);
+        let mut ids = Vec::new();
+        loop {
+            let (page, more) = cursor.advance(query(&cursor), 2).unwrap();
+            ids.extend(page.into_iter().map(|swap| swap.id));
+            if !more {
+                break;
+            }
+        }
+        let expected: Vec<String> = swaps.iter().map(|swap| swap.id.clone()).collect();
+        assert_eq!(ids, expected);
+
+        let record = swaps[0].record(1.0).unwrap();
+        assert_eq!(
+            (record.timestamp, record.price, record.amount0),
+            (1, 1.0, 1.0)
+        );
+
+        let mut bad = swap(9, 1);
+        bad.amount1 = "NaN".to_string();
+        assert!(bad.record(1.0).is_err());
+        bad.timestamp = "yesterday".to_string();
+        assert!(bad.record(1.0).is_err());
+        assert!(SwapCursor::after(0).advance(vec![bad], 2).is_err());
+    }
+
+    #[test]
+    fn test_bootstrap_feed() {
+        let prices = [1.0, 1.1, 0.99, 1.05, 1.2];
+        assert!(bootstrap::BootstrapFeed::new(&prices[..1], 1.0, 2, None).is_err());
+        assert!(bootstrap::BootstrapFeed::new(&[1.0, 0.0], 1.0, 2, None).is_err());
+        assert!(bootstrap::BootstrapFeed::new(&prices, 0.0, 2, None).is_err());
+        assert!(bootstrap::BootstrapFeed::new(&prices, 1.0, 0, None).is_err());
+
+        let feed = bootstrap::BootstrapFeed::new(&prices, 0.5, 2, Some(1.0)).unwrap();
+
+        // advancing by the sampling interval is a step, by two intervals two steps, and by half of one every other step
+        let (mut stepped, mut advanced, mut halved) = (feed.clone(), feed.clone(), feed);
+        let (mut rng_a, mut rng_b, mut rng_c) = (
+            StdRng::seed_from_u64(3),
+            StdRng::seed_from_u64(3),
+            StdRng::seed_from_u64(3),
+        );
+        for _ in 0..5 {
+            stepped.step(&mut rng_a);
+            let value = stepped.step(&mut rng_a);
+
+            assert_eq!(advanced.advance(1.0, &mut rng_b), value);
+
+            halved.advance(0.25, &mut rng_c);
+            halved.advance(0.25, &mut rng_c);
+            halved.advance(0.25, &mut rng_c);
+            assert_eq!(halved.advance(0.25, &mut rng_c), value);
+        }
+    }
+}
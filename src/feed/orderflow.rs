@@ -0,0 +1,148 @@
+use rand::{rngs::StdRng, Rng};
+use rand_distr::{Distribution, Exp, Poisson};
+
+use crate::error::ArenaError;
+
+/// A market order arriving during a simulation step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Order {
+    /// Whether the order sells token0 for token1.
+    pub zero_for_one: bool,
+
+    /// Size of the order, in units of the input token.
+    pub amount: f64,
+}
+
+/// Represents a stochastic process of order arrivals.
+pub trait OrderFlow: Send {
+    /// Returns the orders arriving during one step, or an error if the parameters of the process are invalid.
+    fn orders(&mut self, rng: &mut StdRng) -> Result<Vec<Order>, ArenaError>;
+}
+
+/// Compound Poisson order flow.
+///
+/// The number of orders per step is Poisson distributed, each order's size is exponentially distributed, and its
+/// direction is an independent coin flip. This models uninformed flow which generates fees for liquidity providers.
+#[derive(Debug, Clone)]
+pub struct PoissonOrderFlow {
+    /// Expected number of orders per step.
+    pub rate: f64,
+
+    /// Mean order size, in units of the input token.
+    pub mean_size: f64,
+
+    /// Probability that an order sells token0 for token1.
+    pub sell_probability: f64,
+}
+
+impl PoissonOrderFlow {
+    /// Public constructor function for a new [`PoissonOrderFlow`] with balanced order directions, returning an error
+    /// if the parameters are invalid, see [`PoissonOrderFlow::validate`].
+    pub fn new(rate: f64, mean_size: f64) -> Result<Self, ArenaError> {
+        let flow = Self {
+            rate,
+            mean_size,
+            sell_probability: 0.5,
+        };
+        flow.validate()?;
+
+        Ok(flow)
+    }
+
+    /// Set the probability that an order sells token0 for token1, returning an error if it is outside `[0, 1]`.
+    pub fn with_sell_probability(mut self, sell_probability: f64) -> Result<Self, ArenaError> {
+        self.sell_probability = sell_probability;
+        self.validate()?;
+
+        Ok(self)
+    }
+
+    /// Returns an error if the rate is negative or not finite, the mean size is not positive and finite, or the sell
+    /// probability is outside `[0, 1]`.
+    pub fn validate(&self) -> Result<(), ArenaError> {
+        if !self.rate.is_finite() || self.rate < 0.0 {
+            return Err(ArenaError::Custom(format!(
+                "order rate must be finite and non-negative, got {}",
+                self.rate
+            )));
+        }
+        if !self.mean_size.is_finite() || self.mean_size <= 0.0 {
+            return Err(ArenaError::Custom(format!(
+                "mean order size must be finite and positive, got {}",
+                self.mean_size
+            )));
+        }
+        if !(0.0..=1.0).contains(&self.sell_probability) {
+            return Err(ArenaError::Custom(format!(
+                "sell probability must be within [0, 1], got {}",
+                self.sell_probability
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl OrderFlow for PoissonOrderFlow {
+    fn orders(&mut self, rng: &mut StdRng) -> Result<Vec<Order>, ArenaError> {
+        self.validate()?;
+        if self.rate == 0.0 {
+            return Ok(Vec::new());
+        }
+
+        let count = Poisson::new(self.rate)
+            .map_err(|e| ArenaError::Custom(format!("invalid order rate: {e}")))?
+            .sample(rng) as usize;
+        let size = Exp::new(1.0 / self.mean_size)
+            .map_err(|e| ArenaError::Custom(format!("invalid mean order size: {e}")))?;
+
+        Ok((0..count)
+            .map(|_| Order {
+                zero_for_one: rng.gen_bool(self.sell_probability),
+                amount: size.sample(rng),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn test_poisson_order_flow() {
+        assert!(PoissonOrderFlow::new(-1.0, 1.0).is_err());
+        assert!(PoissonOrderFlow::new(f64::NAN, 1.0).is_err());
+        assert!(PoissonOrderFlow::new(1.0, 0.0).is_err());
+        assert!(PoissonOrderFlow::new(1.0, f64::INFINITY).is_err());
+        assert!(PoissonOrderFlow::new(1.0, 1.0)
+            .unwrap()
+            .with_sell_probability(f64::NAN)
+            .is_err());
+
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // parameters set directly are checked when orders are drawn
+        let mut flow = PoissonOrderFlow::new(1.0, 1.0).unwrap();
+        flow.sell_probability = 1.5;
+        assert!(flow.orders(&mut rng).is_err());
+
+        let mut idle = PoissonOrderFlow::new(0.0, 1.0).unwrap();
+        assert!(idle.orders(&mut rng).unwrap().is_empty());
+
+        let mut sells = PoissonOrderFlow::new(20.0, 2.0)
+            .unwrap()
+            .with_sell_probability(1.0)
+            .unwrap();
+        let orders: Vec<Order> = (0..50)
+            .flat_map(|_| sells.orders(&mut rng).unwrap())
+            .collect();
+        assert!(orders.len() > 500);
+        assert!(orders.iter().all(|order| order.zero_for_one));
+
+        let mean = orders.iter().map(|order| order.amount).sum::<f64>() / orders.len() as f64;
+        assert!((mean - 2.0).abs() < 0.3);
+    }
+}
@@ -0,0 +1,59 @@
+use std::{fmt, sync::Arc};
+
+/// A process parameter that may vary over the simulated time.
+#[derive(Clone)]
+pub enum Schedule {
+    /// A fixed value.
+    Constant(f64),
+
+    /// Piecewise-constant values, given as `(start_time, value)` pairs sorted by start time.
+    ///
+    /// Before the first start time, the first value is used.
+    Piecewise(Vec<(f64, f64)>),
+
+    /// An arbitrary function of time.
+    Function(Arc<dyn Fn(f64) -> f64 + Send + Sync>),
+}
+
+impl Schedule {
+    /// Public constructor function for a piecewise-constant [`Schedule`].
+    pub fn piecewise(mut points: Vec<(f64, f64)>) -> Self {
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Schedule::Piecewise(points)
+    }
+
+    /// Public constructor function for a [`Schedule`] given by a closure of time.
+    pub fn function(f: impl Fn(f64) -> f64 + Send + Sync + 'static) -> Self {
+        Schedule::Function(Arc::new(f))
+    }
+
+    /// Returns the value of the parameter at time `t`.
+    pub fn at(&self, t: f64) -> f64 {
+        match self {
+            Schedule::Constant(value) => *value,
+            Schedule::Piecewise(points) => points
+                .iter()
+                .rev()
+                .find(|(start, _)| *start <= t)
+                .or(points.first())
+                .map_or(0.0, |(_, value)| *value),
+            Schedule::Function(f) => f(t),
+        }
+    }
+}
+
+impl From<f64> for Schedule {
+    fn from(value: f64) -> Self {
+        Schedule::Constant(value)
+    }
+}
+
+impl fmt::Debug for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Schedule::Constant(value) => f.debug_tuple("Constant").field(value).finish(),
+            Schedule::Piecewise(points) => f.debug_tuple("Piecewise").field(points).finish(),
+            Schedule::Function(_) => f.write_str("Function(..)"),
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use alloy::transports::http::reqwest::{Client, Url};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::{restore_into, snapshot_of, Feed};
+use crate::error::ArenaError;
+
+/// Maximum number of entities a subgraph returns per query.
+const PAGE_SIZE: usize = 1000;
+
+const SWAPS_QUERY: &str = r#"
+query Swaps($pool: String!, $from: BigInt!, $to: BigInt!, $seen: [ID!]!, $first: Int!) {
+    swaps(
+        first: $first
+        orderBy: timestamp
+        orderDirection: asc
+        where: { pool: $pool, timestamp_gte: $from, timestamp_lte: $to, id_not_in: $seen }
+    ) {
+        id
+        timestamp
+        sqrtPriceX96
+        amount0
+        amount1
+    }
+}
+"#;
+
+/// A single historical swap, as indexed by a Uniswap subgraph.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SwapRecord {
+    /// Unix timestamp of the block the swap was included in.
+    pub timestamp: u64,
+
+    /// Pool price of token0 in terms of token1 after the swap, adjusted for token decimals.
+    pub price: f64,
+
+    /// Amount of token0 exchanged, from the pool's perspective.
+    pub amount0: f64,
+
+    /// Amount of token1 exchanged, from the pool's perspective.
+    pub amount1: f64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RawSwap {
+    pub(crate) id: String,
+    pub(crate) timestamp: String,
+    pub(crate) sqrt_price_x96: String,
+    pub(crate) amount0: String,
+    pub(crate) amount1: String,
+}
+
+impl RawSwap {
+    /// Returns the record of the swap, with its price scaled by `scale`, or an error if a field is not a number.
+    pub(crate) fn record(&self, scale: f64) -> Result<SwapRecord, ArenaError> {
+        let invalid = |field: &str, value: &str| {
+            ArenaError::SubgraphError(format!("swap {} has an invalid {field} {value:?}", self.id))
+        };
+        let number = |field: &str, value: &str| {
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())
+                .ok_or_else(|| invalid(field, value))
+        };
+
+        let sqrt_price = number("sqrtPriceX96", &self.sqrt_price_x96)? / 2f64.powi(96);
+
+        Ok(SwapRecord {
+            timestamp: self
+                .timestamp
+                .parse()
+                .map_err(|_| invalid("timestamp", &self.timestamp))?,
+            price: sqrt_price.powi(2) * scale,
+            amount0: number("amount0", &self.amount0)?,
+            amount1: number("amount1", &self.amount1)?,
+        })
+    }
+}
+
+/// Position of a paged query over the swaps of a pool, ordered by timestamp.
+///
+/// Pages are queried from the timestamp of the last swap taken, inclusively, excluding the swaps already taken at that
+/// timestamp, so swaps sharing a timestamp across a page boundary are neither dropped nor duplicated.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SwapCursor {
+    /// Lowest timestamp of the next page.
+    pub(crate) from: u64,
+
+    /// Ids of the swaps already taken at `from`.
+    pub(crate) seen: Vec<String>,
+}
+
+impl SwapCursor {
+    /// Public constructor function for a new [`SwapCursor`] over the swaps after `from`.
+    pub(crate) fn after(from: u64) -> Self {
+        Self {
+            from: from + 1,
+            seen: Vec::new(),
+        }
+    }
+
+    /// Move past `page`, returning its swaps not taken before, in order, and whether another page may follow, i.e.
+    /// whether the page holds `page_size` swaps.
+    pub(crate) fn advance(
+        &mut self,
+        page: Vec<RawSwap>,
+        page_size: usize,
+    ) -> Result<(Vec<RawSwap>, bool), ArenaError> {
+        let full_page = page.len() == page_size;
+
+        let mut taken = Vec::with_capacity(page.len());
+        for swap in page {
+            if self.seen.contains(&swap.id) {
+                continue;
+            }
+
+            let timestamp: u64 = swap.timestamp.parse().map_err(|_| {
+                ArenaError::SubgraphError(format!(
+                    "swap {} has an invalid timestamp {:?}",
+                    swap.id, swap.timestamp
+                ))
+            })?;
+            if timestamp != self.from {
+                self.from = timestamp;
+                self.seen.clear();
+            }
+
+            self.seen.push(swap.id.clone());
+            taken.push(swap);
+        }
+
+        let more = full_page && !taken.is_empty();
+        Ok((taken, more))
+    }
+}
+
+#[derive(Deserialize)]
+struct SwapsData {
+    swaps: Vec<RawSwap>,
+}
+
+#[derive(Deserialize)]
+struct GraphQlResponse {
+    data: Option<SwapsData>,
+    errors: Option<Value>,
+}
+
+/// Feed that replays the post-swap price path of a real Uniswap v3/v4 pool, pulled from a subgraph.
+///
+/// Like [`super::chainlink::ChainlinkFeed`], the feed keeps an internal clock starting at the first swap which is moved
+/// forward by `seconds_per_step` each [`Feed::step`], and reports the price after the latest swap at or before the clock.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SubgraphFeed {
+    swaps: Vec<SwapRecord>,
+
+    /// Seconds of market time replayed per simulation step.
+    seconds_per_step: f64,
+
+    clock: f64,
+    cursor: usize,
+}
+
+impl SubgraphFeed {
+    /// Public constructor function for a new [`SubgraphFeed`] from a set of swaps.
+    ///
+    /// Swaps are sorted by their timestamp.
+    pub fn new(mut swaps: Vec<SwapRecord>, seconds_per_step: f64) -> Self {
+        swaps.sort_by_key(|swap| swap.timestamp);
+        let clock = swaps.first().map_or(0.0, |swap| swap.timestamp as f64);
+
+        Self {
+            swaps,
+            seconds_per_step,
+            clock,
+            cursor: 0,
+        }
+    }
+
+    /// Fetch every swap of `pool` between the unix timestamps `from` and `to` from a subgraph endpoint.
+    ///
+    /// `decimals` are the decimals of token0 and token1, used to convert `sqrtPriceX96` to a human readable price.
+    pub async fn fetch(
+        subgraph_url: Url,
+        pool: &str,
+        from: u64,
+        to: u64,
+        decimals: (u8, u8),
+        seconds_per_step: f64,
+    ) -> Result<Self, ArenaError> {
+        let client = Client::new();
+        let scale = 10f64.powi(decimals.0 as i32 - decimals.1 as i32);
+
+        let mut swaps = Vec::new();
+        let mut cursor = SwapCursor::after(from);
+
+        loop {
+            let body = json!({
+                "query": SWAPS_QUERY,
+                "variables": {
+                    "pool": pool.to_lowercase(),
+                    "from": cursor.from.to_string(),
+                    "to": to.to_string(),
+                    "seen": cursor.seen,
+                    "first": PAGE_SIZE,
+                },
+            });
+
+            let response = client
+                .post(subgraph_url.clone())
+                .header("content-type", "application/json")
+                .body(body.to_string())
+                .send()
+                .await?
+                .text()
+                .await?;
+
+            let response: GraphQlResponse = serde_json::from_str(&response)?;
+            let page = match response.data {
+                Some(data) => data.swaps,
+                None => {
+                    return Err(ArenaError::SubgraphError(
+                        response.errors.unwrap_or_default().to_string(),
+                    ))
+                }
+            };
+
+            let (page, more) = cursor.advance(page, PAGE_SIZE)?;
+            for swap in &page {
+                swaps.push(swap.record(scale)?);
+            }
+
+            if !more {
+                break;
+            }
+        }
+
+        Ok(Self::new(swaps, seconds_per_step))
+    }
+
+    /// Returns the swaps replayed by this feed.
+    pub fn swaps(&self) -> &[SwapRecord] {
+        &self.swaps
+    }
+
+    fn seek(&mut self, dt: f64) -> f64 {
+        self.clock += dt;
+
+        while self.cursor + 1 < self.swaps.len()
+            && self.swaps[self.cursor + 1].timestamp as f64 <= self.clock
+        {
+            self.cursor += 1;
+        }
+
+        self.current_value()
+    }
+}
+
+impl Feed for SubgraphFeed {
+    fn current_value(&self) -> f64 {
+        self.swaps.get(self.cursor).map_or(0.0, |swap| swap.price)
+    }
+
+    fn step(&mut self, _rng: &mut StdRng) -> f64 {
+        self.seek(self.seconds_per_step)
+    }
+
+    fn advance(&mut self, dt: f64, _rng: &mut StdRng) -> f64 {
+        self.seek(dt)
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        snapshot_of(self)
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        restore_into(self, state)
+    }
+}
@@ -0,0 +1,432 @@
+use std::collections::{HashMap, VecDeque};
+
+use rand::rngs::StdRng;
+use serde_json::{json, Value};
+
+use super::Feed;
+use crate::error::ArenaError;
+
+/// Wraps a [`Feed`] and multiplies its output by a constant factor.
+pub struct Scaled {
+    inner: Box<dyn Feed>,
+
+    /// Multiplicative factor applied to the inner feed.
+    factor: f64,
+}
+
+impl Scaled {
+    /// Public constructor function for a new [`Scaled`] feed.
+    pub fn new(inner: Box<dyn Feed>, factor: f64) -> Self {
+        Self { inner, factor }
+    }
+}
+
+impl Feed for Scaled {
+    fn current_value(&self) -> f64 {
+        self.inner.current_value() * self.factor
+    }
+
+    fn step(&mut self, rng: &mut StdRng) -> f64 {
+        self.inner.step(rng) * self.factor
+    }
+
+    fn advance(&mut self, dt: f64, rng: &mut StdRng) -> f64 {
+        self.inner.advance(dt, rng) * self.factor
+    }
+
+    fn set_antithetic(&mut self, antithetic: bool) {
+        self.inner.set_antithetic(antithetic);
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        Ok(json!({ "inner": self.inner.snapshot()? }))
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        self.inner.restore(state["inner"].clone())
+    }
+
+    fn metadata(&self) -> HashMap<String, Value> {
+        self.inner.metadata()
+    }
+}
+
+/// Wraps a [`Feed`] and adds a constant offset to its output.
+pub struct Shifted {
+    inner: Box<dyn Feed>,
+
+    /// Additive offset applied to the inner feed.
+    offset: f64,
+}
+
+impl Shifted {
+    /// Public constructor function for a new [`Shifted`] feed.
+    pub fn new(inner: Box<dyn Feed>, offset: f64) -> Self {
+        Self { inner, offset }
+    }
+}
+
+impl Feed for Shifted {
+    fn current_value(&self) -> f64 {
+        self.inner.current_value() + self.offset
+    }
+
+    fn step(&mut self, rng: &mut StdRng) -> f64 {
+        self.inner.step(rng) + self.offset
+    }
+
+    fn advance(&mut self, dt: f64, rng: &mut StdRng) -> f64 {
+        self.inner.advance(dt, rng) + self.offset
+    }
+
+    fn set_antithetic(&mut self, antithetic: bool) {
+        self.inner.set_antithetic(antithetic);
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        Ok(json!({ "inner": self.inner.snapshot()? }))
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        self.inner.restore(state["inner"].clone())
+    }
+
+    fn metadata(&self) -> HashMap<String, Value> {
+        self.inner.metadata()
+    }
+}
+
+/// Wraps a [`Feed`] and clamps its output to the range `[min, max]`.
+pub struct Clamped {
+    inner: Box<dyn Feed>,
+
+    /// Lower bound of the output.
+    min: f64,
+
+    /// Upper bound of the output.
+    max: f64,
+}
+
+impl Clamped {
+    /// Public constructor function for a new [`Clamped`] feed.
+    pub fn new(inner: Box<dyn Feed>, min: f64, max: f64) -> Self {
+        Self { inner, min, max }
+    }
+}
+
+impl Feed for Clamped {
+    fn current_value(&self) -> f64 {
+        self.inner.current_value().clamp(self.min, self.max)
+    }
+
+    fn step(&mut self, rng: &mut StdRng) -> f64 {
+        self.inner.step(rng).clamp(self.min, self.max)
+    }
+
+    fn advance(&mut self, dt: f64, rng: &mut StdRng) -> f64 {
+        self.inner.advance(dt, rng).clamp(self.min, self.max)
+    }
+
+    fn set_antithetic(&mut self, antithetic: bool) {
+        self.inner.set_antithetic(antithetic);
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        Ok(json!({ "inner": self.inner.snapshot()? }))
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        self.inner.restore(state["inner"].clone())
+    }
+
+    fn metadata(&self) -> HashMap<String, Value> {
+        self.inner.metadata()
+    }
+}
+
+/// Wraps a [`Feed`] and delays its output by a fixed number of steps.
+///
+/// Until enough history has accumulated, the inner feed's initial value is returned.
+pub struct Lagged {
+    inner: Box<dyn Feed>,
+
+    /// Number of steps to delay the output by.
+    lag: usize,
+
+    history: VecDeque<f64>,
+}
+
+impl Lagged {
+    /// Public constructor function for a new [`Lagged`] feed.
+    pub fn new(inner: Box<dyn Feed>, lag: usize) -> Self {
+        let history = VecDeque::from(vec![inner.current_value(); lag + 1]);
+        Self {
+            inner,
+            lag,
+            history,
+        }
+    }
+
+    fn push(&mut self, value: f64) -> f64 {
+        self.history.push_back(value);
+        while self.history.len() > self.lag + 1 {
+            self.history.pop_front();
+        }
+        self.current_value()
+    }
+}
+
+impl Feed for Lagged {
+    fn current_value(&self) -> f64 {
+        self.history[0]
+    }
+
+    fn step(&mut self, rng: &mut StdRng) -> f64 {
+        let value = self.inner.step(rng);
+        self.push(value)
+    }
+
+    fn advance(&mut self, dt: f64, rng: &mut StdRng) -> f64 {
+        let value = self.inner.advance(dt, rng);
+        self.push(value)
+    }
+
+    fn set_antithetic(&mut self, antithetic: bool) {
+        self.inner.set_antithetic(antithetic);
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        Ok(json!({ "inner": self.inner.snapshot()?, "history": self.history }))
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        self.history = serde_json::from_value(state["history"].clone())?;
+        self.inner.restore(state["inner"].clone())
+    }
+
+    fn metadata(&self) -> HashMap<String, Value> {
+        self.inner.metadata()
+    }
+}
+
+/// Wraps a [`Feed`] and smooths its output with an exponentially weighted moving average.
+pub struct Smoothed {
+    inner: Box<dyn Feed>,
+
+    /// Weight of the newest observation, in `(0, 1]`.
+    alpha: f64,
+
+    value: f64,
+}
+
+impl Smoothed {
+    /// Public constructor function for a new [`Smoothed`] feed.
+    pub fn new(inner: Box<dyn Feed>, alpha: f64) -> Self {
+        let value = inner.current_value();
+        Self {
+            inner,
+            alpha,
+            value,
+        }
+    }
+
+    fn push(&mut self, value: f64) -> f64 {
+        self.value = self.alpha * value + (1.0 - self.alpha) * self.value;
+        self.value
+    }
+}
+
+impl Feed for Smoothed {
+    fn current_value(&self) -> f64 {
+        self.value
+    }
+
+    fn step(&mut self, rng: &mut StdRng) -> f64 {
+        let value = self.inner.step(rng);
+        self.push(value)
+    }
+
+    fn advance(&mut self, dt: f64, rng: &mut StdRng) -> f64 {
+        let value = self.inner.advance(dt, rng);
+        self.push(value)
+    }
+
+    fn set_antithetic(&mut self, antithetic: bool) {
+        self.inner.set_antithetic(antithetic);
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        Ok(json!({ "inner": self.inner.snapshot()?, "value": self.value }))
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        self.value = serde_json::from_value(state["value"].clone())?;
+        self.inner.restore(state["inner"].clone())
+    }
+
+    fn metadata(&self) -> HashMap<String, Value> {
+        self.inner.metadata()
+    }
+}
+
+/// Wraps a [`Feed`] and rescales its log returns so their realized volatility over a rolling window hits a target.
+///
+/// This allows strategies to be compared fairly across underlying processes with different volatilities. Until the
+/// window holds at least two returns, the inner feed's returns are passed through unscaled.
+pub struct VolatilityTargeted {
+    inner: Box<dyn Feed>,
+
+    /// Target standard deviation of log returns per step.
+    target: f64,
+
+    /// Number of recent inner returns used to estimate realized volatility.
+    window: usize,
+
+    returns: VecDeque<f64>,
+    last_inner: f64,
+    value: f64,
+}
+
+impl VolatilityTargeted {
+    /// Public constructor function for a new [`VolatilityTargeted`] feed.
+    pub fn new(inner: Box<dyn Feed>, target: f64, window: usize) -> Self {
+        let value = inner.current_value();
+        Self {
+            inner,
+            target,
+            window,
+            returns: VecDeque::with_capacity(window),
+            last_inner: value,
+            value,
+        }
+    }
+
+    fn push(&mut self, inner_value: f64) -> f64 {
+        let log_return = (inner_value / self.last_inner).ln();
+        self.last_inner = inner_value;
+
+        self.returns.push_back(log_return);
+        while self.returns.len() > self.window {
+            self.returns.pop_front();
+        }
+
+        let scale = match realized_volatility(&self.returns) {
+            Some(realized) if realized > 0.0 => self.target / realized,
+            _ => 1.0,
+        };
+
+        self.value *= (log_return * scale).exp();
+        self.value
+    }
+}
+
+/// Returns the sample standard deviation of a series, if it has at least two elements.
+fn realized_volatility(returns: &VecDeque<f64>) -> Option<f64> {
+    if returns.len() < 2 {
+        return None;
+    }
+
+    let n = returns.len() as f64;
+    let mean = returns.iter().sum::<f64>() / n;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+    Some(variance.sqrt())
+}
+
+impl Feed for VolatilityTargeted {
+    fn current_value(&self) -> f64 {
+        self.value
+    }
+
+    fn step(&mut self, rng: &mut StdRng) -> f64 {
+        let value = self.inner.step(rng);
+        self.push(value)
+    }
+
+    fn advance(&mut self, dt: f64, rng: &mut StdRng) -> f64 {
+        let value = self.inner.advance(dt, rng);
+        self.push(value)
+    }
+
+    fn set_antithetic(&mut self, antithetic: bool) {
+        self.inner.set_antithetic(antithetic);
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        Ok(json!({
+            "inner": self.inner.snapshot()?,
+            "returns": self.returns,
+            "last_inner": self.last_inner,
+            "value": self.value,
+        }))
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        self.returns = serde_json::from_value(state["returns"].clone())?;
+        self.last_inner = serde_json::from_value(state["last_inner"].clone())?;
+        self.value = serde_json::from_value(state["value"].clone())?;
+        self.inner.restore(state["inner"].clone())
+    }
+
+    fn metadata(&self) -> HashMap<String, Value> {
+        self.inner.metadata()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::feed::GeometricBrownianMotion;
+
+    /// Returns the realized volatility of the log returns of `values`.
+    fn volatility(values: &[f64]) -> f64 {
+        let returns = values
+            .windows(2)
+            .map(|window| (window[1] / window[0]).ln())
+            .collect();
+        realized_volatility(&returns).unwrap()
+    }
+
+    #[test]
+    fn test_volatility_targeted() {
+        let mut rng = StdRng::seed_from_u64(5);
+
+        // the inner process moves 10% per step, the target is 1%
+        let inner = GeometricBrownianMotion::new(100.0, 0.0, 0.1, 1.0);
+        let mut feed = VolatilityTargeted::new(Box::new(inner), 0.01, 50);
+        assert_eq!(feed.current_value(), 100.0);
+
+        // the first return passes through unscaled, as there is no estimate yet
+        let mut reference = GeometricBrownianMotion::new(100.0, 0.0, 0.1, 1.0);
+        assert_eq!(
+            feed.step(&mut rng),
+            reference.step(&mut StdRng::seed_from_u64(5))
+        );
+
+        let values: Vec<f64> = (0..2_000).map(|_| feed.step(&mut rng)).collect();
+        let realized = volatility(&values[100..]);
+        assert!((realized - 0.01).abs() < 0.002, "{realized}");
+
+        // a restored feed continues on the same path
+        let state = feed.snapshot().unwrap();
+        let mut restored = VolatilityTargeted::new(
+            Box::new(GeometricBrownianMotion::new(1.0, 0.0, 0.1, 1.0)),
+            0.01,
+            50,
+        );
+        restored.restore(state).unwrap();
+        let mut rng_restored = rng.clone();
+        for _ in 0..10 {
+            assert_eq!(feed.step(&mut rng), restored.step(&mut rng_restored));
+        }
+    }
+
+    #[test]
+    fn test_realized_volatility() {
+        assert_eq!(realized_volatility(&VecDeque::from([0.1])), None);
+        let returns = VecDeque::from([0.01, -0.01, 0.01, -0.01]);
+        assert!((realized_volatility(&returns).unwrap() - (0.0004f64 / 3.0).sqrt()).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::{vec_deque, VecDeque};
+
+use alloy::primitives::{Signed, Uint};
+use serde::{Deserialize, Serialize};
+
+/// Default number of steps retained by a [`History`].
+pub const DEFAULT_RETENTION: usize = 1000;
+
+/// Pool state observed at the end of a simulation step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Step at which the state was observed.
+    pub step: usize,
+
+    /// Value of the price feed.
+    pub feed_value: f64,
+
+    /// Theoretical value of the pool.
+    pub lex_price: Uint<256, 4>,
+
+    /// Tick of the pool.
+    pub tick: Signed<24, 1>,
+
+    /// Price of the pool.
+    pub sqrt_price_x96: Uint<160, 3>,
+
+    /// In-range liquidity of the pool.
+    pub liquidity: u128,
+
+    /// Whether the state was observed during warm-up, before strategies were initialized, in which case
+    /// [`HistoryEntry::step`] counts warm-up steps. See [`crate::Config::warmup_steps`].
+    #[serde(default)]
+    pub warmup: bool,
+}
+
+/// Rolling window of the pool state at previous steps, so strategies don't have to track it themselves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct History {
+    entries: VecDeque<HistoryEntry>,
+
+    /// Maximum number of steps retained.
+    retention: usize,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(DEFAULT_RETENTION)
+    }
+}
+
+impl History {
+    /// Public constructor function for a new [`History`] retaining at most `retention` steps.
+    pub fn new(retention: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(retention.min(DEFAULT_RETENTION)),
+            retention,
+        }
+    }
+
+    /// Record the state of a step, evicting the oldest entry if the retention window is full.
+    pub fn push(&mut self, entry: HistoryEntry) {
+        if self.retention == 0 {
+            return;
+        }
+
+        if self.entries.len() == self.retention {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back(entry);
+    }
+
+    /// Returns the retained entries, from oldest to newest.
+    pub fn iter(&self) -> vec_deque::Iter<'_, HistoryEntry> {
+        self.entries.iter()
+    }
+
+    /// Returns the most recent entry.
+    pub fn latest(&self) -> Option<&HistoryEntry> {
+        self.entries.back()
+    }
+
+    /// Returns the entry `n` steps before the most recent one.
+    pub fn lookback(&self, n: usize) -> Option<&HistoryEntry> {
+        self.entries
+            .len()
+            .checked_sub(n + 1)
+            .and_then(|idx| self.entries.get(idx))
+    }
+
+    /// Returns the retained feed values, from oldest to newest.
+    pub fn feed_values(&self) -> Vec<f64> {
+        self.entries.iter().map(|entry| entry.feed_value).collect()
+    }
+
+    /// Returns the retained pool ticks, from oldest to newest.
+    pub fn ticks(&self) -> Vec<Signed<24, 1>> {
+        self.entries.iter().map(|entry| entry.tick).collect()
+    }
+
+    /// Returns the number of retained entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether no entries are retained.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the maximum number of steps retained.
+    pub fn retention(&self) -> usize {
+        self.retention
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(step: usize) -> HistoryEntry {
+        HistoryEntry {
+            step,
+            feed_value: step as f64,
+            lex_price: Uint::from(step),
+            tick: Signed::ZERO,
+            sqrt_price_x96: Uint::ZERO,
+            liquidity: 0,
+            warmup: false,
+        }
+    }
+
+    #[test]
+    fn test_retention_window() {
+        let mut history = History::new(3);
+
+        for step in 0..5 {
+            history.push(entry(step));
+        }
+
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.feed_values(), vec![2.0, 3.0, 4.0]);
+        assert_eq!(history.latest().map(|e| e.step), Some(4));
+        assert_eq!(history.lookback(2).map(|e| e.step), Some(2));
+        assert!(history.lookback(3).is_none());
+
+        let mut empty = History::new(0);
+        empty.push(entry(0));
+        assert!(empty.is_empty());
+    }
+}
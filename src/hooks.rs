@@ -0,0 +1,197 @@
+use async_trait::async_trait;
+
+use crate::{engine::Engine, error::ArenaError, summary::RunSummary, AnvilProvider, Signal};
+
+/// Custom behavior injected at fixed points of a run, such as extra funding, parameter changes or assertions, without
+/// writing a full [`crate::strategy::Strategy`].
+///
+/// Hooks act through the admin wallet, and every method does nothing by default.
+#[async_trait]
+pub trait Hooks: Send {
+    /// Called at the start of every step, before the feed advances.
+    async fn on_step_start(
+        &mut self,
+        _step: usize,
+        _signal: &Signal,
+        _provider: AnvilProvider,
+        _engine: Engine,
+    ) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    /// Called at the end of every step, once strategies have acted and the inspector observed the pool.
+    async fn on_step_end(
+        &mut self,
+        _step: usize,
+        _signal: &Signal,
+        _provider: AnvilProvider,
+        _engine: Engine,
+    ) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    /// Called once the run completed, with its summary.
+    async fn on_run_end(
+        &mut self,
+        _summary: &RunSummary,
+        _provider: AnvilProvider,
+    ) -> Result<(), ArenaError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        network::EthereumWallet,
+        primitives::{Address, Signed, Uint},
+        providers::ProviderBuilder,
+        rpc::client::ClientBuilder,
+        signers::local::PrivateKeySigner,
+    };
+
+    use super::*;
+    use crate::{engine::score::Scorecard, PoolKey};
+
+    /// Records the hook calls it receives, and fails on the configured step.
+    #[derive(Default)]
+    struct Recorder {
+        calls: Vec<String>,
+        fail_on: Option<usize>,
+    }
+
+    #[async_trait]
+    impl Hooks for Recorder {
+        async fn on_step_start(
+            &mut self,
+            step: usize,
+            signal: &Signal,
+            _provider: AnvilProvider,
+            _engine: Engine,
+        ) -> Result<(), ArenaError> {
+            assert_eq!(signal.step, Some(step));
+            self.calls.push(format!("start {step}"));
+
+            match self.fail_on {
+                Some(fail_on) if fail_on == step => {
+                    Err(ArenaError::Custom(format!("failed at {step}")))
+                }
+                _ => Ok(()),
+            }
+        }
+
+        async fn on_step_end(
+            &mut self,
+            step: usize,
+            _signal: &Signal,
+            _provider: AnvilProvider,
+            _engine: Engine,
+        ) -> Result<(), ArenaError> {
+            self.calls.push(format!("end {step}"));
+            Ok(())
+        }
+
+        async fn on_run_end(
+            &mut self,
+            summary: &RunSummary,
+            _provider: AnvilProvider,
+        ) -> Result<(), ArenaError> {
+            self.calls.push(format!("run {}", summary.steps));
+            Ok(())
+        }
+    }
+
+    /// Relies on every default method.
+    struct Defaults;
+
+    impl Hooks for Defaults {}
+
+    /// Returns a provider for a node that is never contacted, since the hooks under test make no requests.
+    fn provider() -> AnvilProvider {
+        ProviderBuilder::new()
+            .with_recommended_fillers()
+            .wallet(EthereumWallet::from(PrivateKeySigner::random()))
+            .on_client(
+                ClientBuilder::default()
+                    .http("http://127.0.0.1:1".parse().unwrap())
+                    .boxed(),
+            )
+    }
+
+    fn signal(step: usize) -> Signal {
+        Signal::new(
+            Uint::from(1),
+            Some(step),
+            Signed::ZERO,
+            Uint::from(79228162514264337593543950336_u128),
+            Address::ZERO,
+            PoolKey {
+                currency0: Address::ZERO,
+                currency1: Address::repeat_byte(1),
+                fee: Uint::from(3000),
+                tickSpacing: Signed::try_from(60).unwrap(),
+                hooks: Address::ZERO,
+            },
+            Address::ZERO,
+            1.0,
+            Address::ZERO,
+        )
+    }
+
+    fn summary(steps: usize) -> RunSummary {
+        RunSummary {
+            steps,
+            interrupted: false,
+            stopped: None,
+            pools: Vec::new(),
+            balances: Vec::new(),
+            scorecard: Scorecard::default(),
+            jit: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hooks() {
+        let engine = Engine::new(Address::ZERO);
+
+        let mut hooks: Vec<Box<dyn Hooks>> = vec![
+            Box::new(Defaults),
+            Box::new(Recorder {
+                fail_on: Some(1),
+                ..Default::default()
+            }),
+        ];
+        for step in 0..2 {
+            for (idx, hooks) in hooks.iter_mut().enumerate() {
+                let result = hooks
+                    .on_step_start(step, &signal(step), provider(), engine.clone())
+                    .await;
+                assert_eq!(result.is_err(), idx == 1 && step == 1);
+            }
+        }
+
+        let mut recorder = Recorder::default();
+        for step in 0..2 {
+            recorder
+                .on_step_start(step, &signal(step), provider(), engine.clone())
+                .await
+                .unwrap();
+            recorder
+                .on_step_end(step, &signal(step), provider(), engine.clone())
+                .await
+                .unwrap();
+        }
+        recorder.on_run_end(&summary(2), provider()).await.unwrap();
+        assert_eq!(
+            recorder.calls,
+            vec!["start 0", "end 0", "start 1", "end 1", "run 2"]
+        );
+
+        let mut defaults = Defaults;
+        assert!(defaults
+            .on_step_end(0, &signal(0), provider(), engine.clone())
+            .await
+            .is_ok());
+        assert!(defaults.on_run_end(&summary(0), provider()).await.is_ok());
+    }
+}
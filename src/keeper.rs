@@ -0,0 +1,337 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use alloy::{
+    primitives::{Address, Bytes},
+    providers::{Provider, WalletProvider},
+    rpc::types::{TransactionReceipt, TransactionRequest},
+};
+use async_trait::async_trait;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+use crate::{agent::Agent, engine::Engine, error::ArenaError, AnvilProvider, Signal};
+
+/// An actor maintaining a protocol, such as a liquidator closing unhealthy positions or a keeper updating an oracle,
+/// which checks a condition every step and sends a transaction whenever it is met.
+///
+/// Keepers are registered as agents by wrapping them in a [`KeeperAgent`], which sends their transactions from the
+/// agent's wallet and records their activity in a [`KeeperReport`].
+#[async_trait]
+pub trait Keeper: Send {
+    /// Initialize the keeper, before the first step of a run.
+    async fn init(
+        &mut self,
+        _signal: &Signal,
+        _provider: AnvilProvider,
+        _engine: &Engine,
+    ) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    /// Check the condition of the keeper at a step, returning the transaction to send if it is met.
+    ///
+    /// The sender and nonce of the transaction are filled in by the [`KeeperAgent`], as is its gas limit unless set.
+    async fn check(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: &Engine,
+    ) -> Result<Option<TransactionRequest>, ArenaError>;
+}
+
+/// Activity of a [`Keeper`] over a run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeeperReport {
+    /// Number of steps the condition was checked at.
+    pub checks: usize,
+
+    /// Number of transactions sent.
+    pub executions: usize,
+
+    /// Number of transactions sent that reverted.
+    pub reverts: usize,
+
+    /// Gas used by the transactions sent.
+    pub gas_used: u128,
+}
+
+impl KeeperReport {
+    /// Record the receipt of a transaction sent by the keeper.
+    pub fn record(&mut self, receipt: &TransactionReceipt) {
+        self.executions += 1;
+        self.reverts += usize::from(!receipt.status());
+        self.gas_used += receipt.gas_used;
+    }
+
+    /// Returns the share of checks at which the condition was met.
+    pub fn hit_rate(&self) -> f64 {
+        if self.checks > 0 {
+            self.executions as f64 / self.checks as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// A [`Keeper`] acting as an [`Agent`], checking its condition once per step at its position in the agent ordering.
+pub struct KeeperAgent<K> {
+    /// The wrapped keeper.
+    pub inner: K,
+
+    /// Gas limit of transactions the keeper leaves it unset on. An explicit limit skips gas estimation, which fails on
+    /// a reverting call, so reverting transactions are still mined and counted in [`KeeperReport::reverts`].
+    pub gas_limit: u128,
+
+    report: Arc<Mutex<KeeperReport>>,
+}
+
+impl<K> KeeperAgent<K> {
+    /// Default [`KeeperAgent::gas_limit`].
+    pub const DEFAULT_GAS_LIMIT: u128 = 1_000_000;
+
+    /// Public constructor function for a new [`KeeperAgent`] around `inner`.
+    pub fn new(inner: K) -> Self {
+        Self {
+            inner,
+            gas_limit: Self::DEFAULT_GAS_LIMIT,
+            report: Arc::default(),
+        }
+    }
+
+    /// Set the gas limit of transactions the keeper leaves it unset on.
+    pub fn with_gas_limit(mut self, gas_limit: u128) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    /// Fill in the sender, nonce and, unless set, gas limit of a transaction of the keeper.
+    fn prepare(&self, tx: TransactionRequest, owner: Address, nonce: u64) -> TransactionRequest {
+        let gas_limit = tx.gas.unwrap_or(self.gas_limit);
+
+        tx.from(owner).nonce(nonce).gas_limit(gas_limit)
+    }
+
+    /// Returns a handle to the activity of the keeper, which stays readable once the agent is added to an arena.
+    pub fn report(&self) -> Arc<Mutex<KeeperReport>> {
+        self.report.clone()
+    }
+}
+
+#[async_trait]
+impl<K: Keeper> Agent for KeeperAgent<K> {
+    async fn init(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        *self.report.lock().unwrap_or_else(PoisonError::into_inner) = KeeperReport::default();
+        self.inner.init(signal, provider, &engine).await
+    }
+
+    async fn act(
+        &mut self,
+        signal: &Signal,
+        provider: AnvilProvider,
+        engine: Engine,
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        let tx = self.inner.check(signal, provider.clone(), &engine).await?;
+        self.report
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .checks += 1;
+
+        let Some(tx) = tx else {
+            return Ok(());
+        };
+
+        let owner = provider.default_signer_address();
+        let nonce = provider.get_transaction_count(owner).await?;
+        let receipt = provider
+            .send_transaction(self.prepare(tx, owner, nonce))
+            .await?
+            .get_receipt()
+            .await
+            .map_err(ArenaError::PendingTransactionError)?;
+
+        self.report
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .record(&receipt);
+
+        Ok(())
+    }
+}
+
+/// Example [`Keeper`] poking the hook of the pool on a schedule, e.g. to update a TWAP accumulator that only advances
+/// when called.
+#[derive(Debug, Clone)]
+pub struct HookPoker {
+    /// Calldata of the call to the hook.
+    pub calldata: Bytes,
+
+    /// Number of steps between two calls.
+    pub cadence: usize,
+
+    /// Contract called instead of the hook of the pool.
+    pub target: Option<Address>,
+}
+
+impl HookPoker {
+    /// Public constructor function for a new [`HookPoker`] calling the hook of the pool with `calldata` every `cadence`
+    /// steps, starting with the first step.
+    pub fn new(calldata: Bytes, cadence: usize) -> Self {
+        Self {
+            calldata,
+            cadence: cadence.max(1),
+            target: None,
+        }
+    }
+
+    /// Call `target` instead of the hook of the pool.
+    pub fn with_target(mut self, target: Address) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    /// Returns the contract to call for the pool of `signal`, or `None` if it has no hook and no target was set.
+    pub fn target(&self, signal: &Signal) -> Option<Address> {
+        self.target
+            .or(Some(signal.pool.hooks))
+            .filter(|target| !target.is_zero())
+    }
+
+    /// Returns whether the hook is due at `step`.
+    pub fn is_due(&self, step: usize) -> bool {
+        step.is_multiple_of(self.cadence)
+    }
+}
+
+#[async_trait]
+impl Keeper for HookPoker {
+    async fn check(
+        &mut self,
+        signal: &Signal,
+        _provider: AnvilProvider,
+        _engine: &Engine,
+    ) -> Result<Option<TransactionRequest>, ArenaError> {
+        if !signal.step.is_some_and(|step| self.is_due(step)) {
+            return Ok(None);
+        }
+
+        Ok(self.target(signal).map(|target| {
+            TransactionRequest::default()
+                .to(target)
+                .input(self.calldata.clone().into())
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Signed, Uint, B256, U256};
+
+    use super::*;
+    use crate::types::controller::ArenaController::PoolKey;
+
+    #[test]
+    fn test_hook_poker() {
+        let signal = |hooks: Address| {
+            Signal::new(
+                U256::ZERO,
+                Some(0),
+                Signed::ZERO,
+                Uint::ZERO,
+                Address::ZERO,
+                PoolKey {
+                    currency0: Address::ZERO,
+                    currency1: Address::ZERO,
+                    fee: Uint::ZERO,
+                    tickSpacing: Signed::ZERO,
+                    hooks,
+                },
+                Address::ZERO,
+                0.0,
+                Address::ZERO,
+            )
+        };
+
+        let poker = HookPoker::new(Bytes::new(), 3);
+        assert!(poker.is_due(0) && poker.is_due(6));
+        assert!(!poker.is_due(4));
+        assert_eq!(poker.target(&signal(Address::ZERO)), None);
+        assert_eq!(
+            poker.target(&signal(Address::repeat_byte(1))),
+            Some(Address::repeat_byte(1))
+        );
+
+        let poker = poker.with_target(Address::repeat_byte(2));
+        assert_eq!(
+            poker.target(&signal(Address::repeat_byte(1))),
+            Some(Address::repeat_byte(2))
+        );
+
+        let report = KeeperReport {
+            checks: 4,
+            executions: 1,
+            ..KeeperReport::default()
+        };
+        assert_eq!(report.hit_rate(), 0.25);
+        assert_eq!(KeeperReport::default().hit_rate(), 0.0);
+    }
+
+    /// Returns the receipt of a transaction that used `gas_used` gas and succeeded or reverted.
+    fn receipt(success: bool, gas_used: u128) -> TransactionReceipt {
+        serde_json::from_value(serde_json::json!({
+            "type": "0x2",
+            "status": if success { "0x1" } else { "0x0" },
+            "cumulativeGasUsed": "0x0",
+            "logs": [],
+            "logsBloom": format!("0x{}", "0".repeat(512)),
+            "transactionHash": B256::repeat_byte(1),
+            "transactionIndex": "0x0",
+            "blockHash": B256::repeat_byte(2),
+            "blockNumber": "0x1",
+            "gasUsed": format!("{gas_used:#x}"),
+            "effectiveGasPrice": "0x1",
+            "from": Address::repeat_byte(1),
+            "to": Address::repeat_byte(9),
+            "contractAddress": null,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_keeper_agent() {
+        let agent = KeeperAgent::new(HookPoker::new(Bytes::new(), 1));
+        let owner = Address::repeat_byte(1);
+
+        let tx = agent.prepare(TransactionRequest::default(), owner, 3);
+        assert_eq!(tx.from, Some(owner));
+        assert_eq!(tx.nonce, Some(3));
+        assert_eq!(tx.gas, Some(KeeperAgent::<HookPoker>::DEFAULT_GAS_LIMIT));
+
+        let tx = agent.prepare(TransactionRequest::default().gas_limit(50_000), owner, 3);
+        assert_eq!(tx.gas, Some(50_000));
+        let agent = agent.with_gas_limit(200_000);
+        assert_eq!(
+            agent.prepare(TransactionRequest::default(), owner, 0).gas,
+            Some(200_000)
+        );
+
+        let mut report = KeeperReport::default();
+        report.record(&receipt(true, 30_000));
+        report.record(&receipt(false, 200_000));
+        assert_eq!(
+            report,
+            KeeperReport {
+                checks: 0,
+                executions: 2,
+                reverts: 1,
+                gas_used: 230_000,
+            }
+        );
+    }
+}
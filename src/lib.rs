@@ -13,60 +13,299 @@ pub mod feed;
 /// Defines the base strategy trait.
 pub mod strategy;
 
+/// Contains reference strategy implementations.
+pub mod strategies;
+
 /// Defines core simulation logic types, such as an [`Arbitrageur`].
 pub mod engine;
 
 /// Contains error types for Arena.
 pub mod error;
+
+/// Defines the chain the arena executes on, an Anvil node or an in-process EVM.
+pub mod backend;
+
+/// Defines the history of past signals available to strategies.
+pub mod history;
+
+/// Saving and restoring the state of a simulation in progress.
+pub mod checkpoint;
+
+/// Recording runs and replaying them transaction by transaction.
+pub mod replay;
+
+/// Recording the call traces of selected transactions of a run.
+#[cfg(feature = "trace")]
+pub mod trace;
+
+/// Defines the summary returned by a simulation run.
+pub mod summary;
+
+/// Defines hooks injecting custom behavior into the steps of a run.
+pub mod hooks;
+
+/// Defines the agent trait for market participants acting once per step.
+pub mod agent;
+
+/// Defines keepers, agents sending transactions whenever a condition on the market is met.
+pub mod keeper;
+
+/// Storing runs, their configuration and outputs in a local results directory.
+pub mod registry;
+
+/// Grid searches running a scenario over every combination of parameter values.
+pub mod sweep;
+
+/// Statistics of inspector outputs across the runs of a Monte Carlo batch.
+pub mod aggregate;
+
+/// Items referenced by the code generated by [`strategy::ArenaStrategy`].
+#[doc(hidden)]
+pub mod __private {
+    pub use async_trait::async_trait;
+    pub use rand::rngs::StdRng;
+}
+
+extern crate self as arena_core;
+use std::collections::HashMap;
+
 use alloy::{
     network::{Ethereum, EthereumWallet},
     node_bindings::{Anvil, AnvilInstance},
-    primitives::{Address, Bytes, Signed, Uint},
+    primitives::{Address, Bytes, Signed, Uint, U256},
     providers::{
         fillers::{ChainIdFiller, FillProvider, GasFiller, JoinFill, NonceFiller, WalletFiller},
         Identity, RootProvider,
     },
-    transports::http::{Client, Http},
+    transports::BoxTransport,
 };
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::types::controller::ArenaController::PoolKey;
+use crate::engine::{
+    arbitrageur::{ArbitrageTrade, Inventory},
+    events::PoolEvent,
+    pool::PoolState,
+};
 pub use crate::{
     arena::{Arena, ArenaBuilder},
     config::Config,
     engine::{
-        arbitrageur::{Arbitrageur, EmptyArbitrageur},
-        inspector::{EmptyInspector, Inspector, LogMessage, Logger},
+        arbitrageur::{
+            Arbitrageur, Arrival, CrossPoolArbitrageur, DefaultArbitrageur, EmptyArbitrageur,
+            Intermittent,
+        },
+        inspector::{
+            Backpressure, BroadcastInspector, BufferedInspector, CsvInspector, EmptyInspector,
+            Inspector, LogMessage, Logger, NdjsonInspector, SaveData,
+        },
         Engine,
     },
-    feed::{Feed, GeometricBrownianMotion, OrnsteinUhlenbeck},
+    feed::{CoxIngersollRoss, Feed, GeometricBrownianMotion, OrnsteinUhlenbeck, Sabr},
+    history::History,
     strategy::Strategy,
+    types::controller::ArenaController::PoolKey,
 };
 
-/// Provider type that includes all necessary fillers to execute transactions on an [`Anvil`] node.
+/// Provider type that includes all necessary fillers to execute transactions on an [`Anvil`] node, over any of the
+/// transports of [`arena::Transport`].
 pub type AnvilProvider = FillProvider<
     JoinFill<
         JoinFill<JoinFill<JoinFill<Identity, GasFiller>, NonceFiller>, ChainIdFiller>,
         WalletFiller<EthereumWallet>,
     >,
-    RootProvider<Http<Client>>,
-    Http<Client>,
+    RootProvider<BoxTransport>,
+    BoxTransport,
     Ethereum,
 >;
 
 mod types {
+    // `PoolKey` is re-exported, and its fields are named by the contract ABI
+    #[allow(missing_docs)]
     pub mod controller {
         use alloy_sol_macro::sol;
         sol! {
             #[sol(rpc)]
-            #[derive(Debug)]
+            #[derive(Debug, serde::Serialize, serde::Deserialize)]
             ArenaController,
             "src/artifacts/ArenaController.json"
         }
     }
+
+    pub mod manager {
+        use alloy_sol_macro::sol;
+        sol! {
+            #[sol(rpc)]
+            interface IExtsload {
+                function extsload(bytes32 slot) external view returns (bytes32 value);
+                function extsload(bytes32 startSlot, uint256 nSlots) external view returns (bytes32[] memory values);
+            }
+
+            struct PoolKey {
+                address currency0;
+                address currency1;
+                uint24 fee;
+                int24 tickSpacing;
+                address hooks;
+            }
+
+            #[sol(rpc)]
+            interface IPoolManager {
+                function initialize(PoolKey memory key, uint160 sqrtPriceX96, bytes calldata hookData)
+                    external
+                    returns (int24 tick);
+
+                event Initialize(
+                    bytes32 indexed id,
+                    address indexed currency0,
+                    address indexed currency1,
+                    uint24 fee,
+                    int24 tickSpacing,
+                    address hooks,
+                    uint160 sqrtPriceX96,
+                    int24 tick
+                );
+
+                event Swap(
+                    bytes32 indexed id,
+                    address indexed sender,
+                    int128 amount0,
+                    int128 amount1,
+                    uint160 sqrtPriceX96,
+                    uint128 liquidity,
+                    int24 tick,
+                    uint24 fee
+                );
+
+                event ModifyLiquidity(
+                    bytes32 indexed id,
+                    address indexed sender,
+                    int24 tickLower,
+                    int24 tickUpper,
+                    int256 liquidityDelta
+                );
+            }
+        }
+    }
+
+    // the solmate `ERC20` pool tokens, taking their name, symbol and decimals as constructor arguments
+    #[allow(missing_docs, clippy::too_many_arguments)]
+    pub mod token {
+        use alloy_sol_macro::sol;
+        sol! {
+            #[sol(rpc)]
+            ArenaToken,
+            "src/artifacts/ArenaToken.json"
+        }
+    }
+
+    // the v4-core test routers deployed by the controller, which settle with their caller
+    #[allow(missing_docs)]
+    pub mod router {
+        use alloy_sol_macro::sol;
+        sol! {
+            struct PoolKey {
+                address currency0;
+                address currency1;
+                uint24 fee;
+                int24 tickSpacing;
+                address hooks;
+            }
+
+            struct SwapParams {
+                bool zeroForOne;
+                int256 amountSpecified;
+                uint160 sqrtPriceLimitX96;
+            }
+
+            struct TestSettings {
+                bool takeClaims;
+                bool settleUsingBurn;
+            }
+
+            struct ModifyLiquidityParams {
+                int24 tickLower;
+                int24 tickUpper;
+                int256 liquidityDelta;
+                bytes32 salt;
+            }
+
+            #[sol(rpc)]
+            interface PoolSwapTest {
+                function swap(
+                    PoolKey memory key,
+                    SwapParams memory params,
+                    TestSettings memory testSettings,
+                    bytes memory hookData
+                ) external payable returns (int256 delta);
+            }
+
+            #[sol(rpc)]
+            interface PoolModifyLiquidityTest {
+                function modifyLiquidity(
+                    PoolKey memory key,
+                    ModifyLiquidityParams memory params,
+                    bytes memory hookData
+                ) external payable returns (int256 delta);
+            }
+        }
+    }
+
+    // the liquid exchange deployed by the controller, which pays out of its own balances at a price it is told
+    #[allow(missing_docs)]
+    pub mod exchange {
+        use alloy_sol_macro::sol;
+        sol! {
+            #[sol(rpc)]
+            LiquidExchange,
+            "src/artifacts/LiquidExchange.json"
+        }
+    }
+
+    pub mod pool {
+        use alloy_sol_macro::sol;
+        sol! {
+            #[sol(rpc)]
+            interface IUniswapV3Pool {
+                function slot0()
+                    external
+                    view
+                    returns (
+                        uint160 sqrtPriceX96,
+                        int24 tick,
+                        uint16 observationIndex,
+                        uint16 observationCardinality,
+                        uint16 observationCardinalityNext,
+                        uint8 feeProtocol,
+                        bool unlocked
+                    );
+            }
+        }
+    }
+
+    pub mod chainlink {
+        use alloy_sol_macro::sol;
+        sol! {
+            #[sol(rpc)]
+            interface AggregatorV3Interface {
+                function decimals() external view returns (uint8);
+
+                function latestRoundData()
+                    external
+                    view
+                    returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound);
+
+                function getRoundData(uint80 _roundId)
+                    external
+                    view
+                    returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound);
+            }
+        }
+    }
 }
 
 /// A signal that is passed to a [`Strategy`] to provide information about the current state of the pool.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Signal {
     /// Current theoretical value of the pool.
     pub lex_price: Uint<256, 4>,
@@ -80,6 +319,42 @@ pub struct Signal {
     /// Current price of the pool.
     pub sqrt_price_x96: Uint<160, 3>,
 
+    /// Current in-range liquidity of the pool.
+    pub liquidity: u128,
+
+    /// Decimals of token0 and token1.
+    pub decimals: (u8, u8),
+
+    /// Fee growth and token balances of the pool.
+    pub pool_state: PoolState,
+
+    /// Relative deviation of the pool price from the `LiquidExchange` price, see [`engine::pool::divergence`].
+    pub divergence: f64,
+
+    /// Token balances the arbitrageur trades from.
+    pub arbitrageur_inventory: Inventory,
+
+    /// Latest swap executed by the arbitrageur, if any.
+    pub last_arbitrage: Option<ArbitrageTrade>,
+
+    /// Events the pool emitted since the previous step, oldest first.
+    pub events: Vec<PoolEvent>,
+
+    /// Number of the latest block when the signal was read.
+    pub block_number: u64,
+
+    /// Timestamp of the latest block, in seconds.
+    pub timestamp: u64,
+
+    /// Base fee of the latest block, in wei.
+    pub base_fee: Option<u128>,
+
+    /// Gas price the node suggests for a transaction landing in the next block, in wei, see [`Signal::gas_cost`].
+    pub gas_price: u128,
+
+    /// Auxiliary values published by simulation components, such as [`Feed::metadata`], keyed by name.
+    pub metadata: HashMap<String, Value>,
+
     /// Pool manager.
     pub manager: Address,
 
@@ -94,6 +369,15 @@ pub struct Signal {
 
     /// The arena controller.
     pub controller: Address,
+
+    /// Pool state observed at previous steps.
+    pub history: History,
+
+    /// Gas used so far by the transactions of the strategy receiving this signal.
+    pub gas_used: u128,
+
+    /// Transactions waiting in the mempool, only populated when [`Config::mempool`] is set.
+    pub pending: Vec<alloy::rpc::types::Transaction>,
 }
 
 impl Signal {
@@ -115,28 +399,111 @@ impl Signal {
             step,
             tick,
             sqrt_price_x96,
+            liquidity: 0,
+            decimals: (18, 18),
+            pool_state: PoolState::default(),
+            divergence: engine::pool::divergence(U256::from(sqrt_price_x96), lex_price),
+            arbitrageur_inventory: Inventory::default(),
+            last_arbitrage: None,
+            events: Vec::new(),
+            block_number: 0,
+            timestamp: 0,
+            base_fee: None,
+            gas_price: 0,
+            metadata: HashMap::new(),
             manager,
             pool,
             fetcher,
             current_value,
             controller,
+            history: History::default(),
+            gas_used: 0,
+            pending: Vec::new(),
         }
     }
+
+    /// Price of token0 in terms of token1 in the pool, in whole tokens.
+    pub fn price(&self) -> f64 {
+        (f64::from(self.sqrt_price_x96) / 2f64.powi(96)).powi(2)
+            * 10f64.powi(self.decimals.0 as i32 - self.decimals.1 as i32)
+    }
+
+    /// Returns the estimated cost of a transaction using `gas`, in wei, at [`Signal::gas_price`].
+    pub fn gas_cost(&self, gas: u64) -> u128 {
+        gas as u128 * self.gas_price
+    }
+
+    /// Returns the metadata value under `key`, deserialized into `T`.
+    pub fn metadata_as<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.metadata
+            .get(key)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+}
+
+/// Snapshot of every pool in the simulation, read at the same block and passed to [`Strategy::process_market`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketSignal {
+    /// Current step of the simulation.
+    pub step: Option<usize>,
+
+    /// Current value of the feed driving each pool, in the order of [`MarketSignal::pools`].
+    pub feed_values: Vec<f64>,
+
+    /// State of each pool.
+    pub pools: Vec<Signal>,
+}
+
+impl MarketSignal {
+    /// Public constructor function for a new [`MarketSignal`] from the signals of every pool.
+    pub fn new(step: Option<usize>, pools: Vec<Signal>) -> Self {
+        Self {
+            step,
+            feed_values: pools.iter().map(|signal| signal.current_value).collect(),
+            pools,
+        }
+    }
+
+    /// Returns the state of the pool with the given key.
+    pub fn pool(&self, key: &PoolKey) -> Option<&Signal> {
+        let id = engine::pool::pool_id(key);
+
+        self.pools
+            .iter()
+            .find(|signal| engine::pool::pool_id(&signal.pool) == id)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use alloy::primitives::{Signed, Uint, I256};
+    use std::sync::{Arc, Mutex};
+
+    use alloy::{
+        eips::BlockNumberOrTag,
+        primitives::{utils::parse_ether, Signed, Uint, I256},
+        providers::{Provider, ProviderBuilder, RootProvider},
+        transports::http::{Client, Http},
+    };
     use async_trait::async_trait;
-    use rug::{ops::Pow, Float};
+    use rand::rngs::StdRng;
 
     use super::*;
     use crate::{
+        agent::ArbitrageurAgent,
         arena::{Arena, ArenaBuilder},
-        config::Config,
-        engine::{arbitrageur::FixedArbitrageur, inspector::EmptyInspector},
-        feed::OrnsteinUhlenbeck,
+        config::{Config, PoolConfig},
+        engine::{
+            arbitrageur::{ArbitrageFill, DefaultArbitrageur, FixedArbitrageur},
+            inspector::EmptyInspector,
+            noise::NoiseTrader,
+            pnl::AgentMark,
+        },
+        error::ArenaError,
+        feed::{deterministic::ConstantFeed, orderflow::PoissonOrderFlow, OrnsteinUhlenbeck},
+        replay::RecordedDecision,
+        strategies::PassiveLiquidity,
         strategy::Strategy,
+        summary::RunSummary,
     };
 
     struct StrategyMock;
@@ -144,12 +511,13 @@ mod tests {
     #[async_trait]
     impl<T> Strategy<T> for StrategyMock {
         async fn init(
-            &self,
+            &mut self,
             provider: AnvilProvider,
             _signal: Signal,
             _inspector: &mut Box<dyn Inspector<T>>,
             engine: Engine,
-        ) {
+            _rng: &mut StdRng,
+        ) -> Result<(), ArenaError> {
             engine
                 .modify_liquidity(
                     I256::try_from(10000000).unwrap(),
@@ -159,15 +527,16 @@ mod tests {
                     provider,
                 )
                 .await
-                .unwrap();
         }
         async fn process(
-            &self,
+            &mut self,
             _provider: AnvilProvider,
             _signal: Signal,
             _inspector: &mut Box<dyn Inspector<T>>,
             _engine: Engine,
-        ) {
+            _rng: &mut StdRng,
+        ) -> Result<(), ArenaError> {
+            Ok(())
         }
     }
 
@@ -182,7 +551,8 @@ mod tests {
             .with_arbitrageur(Box::new(FixedArbitrageur {
                 depth: Signed::try_from(10000).unwrap(),
             }))
-            .build();
+            .build()
+            .unwrap();
 
         arena
             .run(Config::new(
@@ -198,4 +568,797 @@ mod tests {
             .await
             .unwrap();
     }
+
+    #[cfg(feature = "revm")]
+    #[tokio::test]
+    async fn test_arena_revm() {
+        let mut arena: Arena<_> = ArenaBuilder::new()
+            .with_revm()
+            .with_strategy(Box::new(StrategyMock))
+            .with_feed(Box::new(OrnsteinUhlenbeck::new(1.0, 0.1, 1.0, 0.1, 0.1)))
+            .with_inspector(Box::new(EmptyInspector {}))
+            .with_arbitrageur(Box::new(FixedArbitrageur {
+                depth: Signed::try_from(10000).unwrap(),
+            }))
+            .with_seed(0)
+            .build()
+            .unwrap();
+        assert!(arena.env.is_none());
+
+        let summary = arena
+            .run(Config::new(
+                20,
+                Uint::from(0),
+                Signed::try_from(2).unwrap(),
+                Bytes::new(),
+                Uint::from(79228162514264337593543950336_u128),
+                Uint::from(0),
+                Uint::from(1),
+                Address::ZERO,
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(summary.steps, 20);
+        assert_eq!(summary.balances.len(), 1);
+    }
+
+    #[derive(Clone, Default)]
+    struct SignalLog {
+        signals: Arc<Mutex<Vec<Signal>>>,
+    }
+
+    impl Inspector<()> for SignalLog {
+        fn log(&mut self, _value: ()) -> Result<(), ArenaError> {
+            Ok(())
+        }
+
+        fn inspect(&self, _step: usize) -> Option<()> {
+            None
+        }
+
+        fn save(&self) -> Result<(), ArenaError> {
+            Ok(())
+        }
+
+        fn on_step(&mut self, signal: &Signal) -> Result<(), ArenaError> {
+            self.signals.lock().unwrap().push(signal.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_decimals() {
+        let log = SignalLog::default();
+
+        // a USDC/WETH pool priced in whole tokens, with the feed 10% above the initial pool price
+        let mut arena: Arena<()> = ArenaBuilder::new()
+            .with_token("USD Coin", "USDC", 6, U256::from(1_000_000_000_000_u64))
+            .with_token("Wrapped Ether", "WETH", 18, parse_ether("1000").unwrap())
+            .with_strategy(Box::new(StrategyMock))
+            .with_feed(Box::new(ConstantFeed::new(0.00055)))
+            .with_inspector(Box::new(log.clone()))
+            .with_seed(0)
+            .build()
+            .unwrap();
+
+        arena
+            .run(
+                Config::new(
+                    1,
+                    Uint::from(0),
+                    Signed::try_from(2).unwrap(),
+                    Bytes::new(),
+                    Uint::from(79228162514264337593543950336_u128),
+                    Uint::from(0),
+                    Uint::from(1),
+                    Address::ZERO,
+                )
+                .with_price(0.0005),
+            )
+            .await
+            .unwrap();
+
+        let signals = log.signals.lock().unwrap();
+        let signal = &signals[0];
+
+        assert_eq!(signal.decimals, (6, 18));
+        assert_eq!(
+            signal.sqrt_price_x96,
+            engine::pool::sqrt_price_x96(0.0005, (6, 18)).unwrap()
+        );
+
+        // 0.00055 WETH per USDC is 0.00055 * 10^12 raw units of WETH per raw unit of USDC, WAD-scaled
+        assert_eq!(signal.lex_price, parse_ether("550000000").unwrap());
+        assert!(
+            (signal.divergence - (0.0005 / 0.00055 - 1.0)).abs() < 1e-9,
+            "divergence of {}",
+            signal.divergence
+        );
+    }
+
+    #[derive(Clone, Default)]
+    struct ArbitrageLog {
+        fills: Arc<Mutex<Vec<ArbitrageFill>>>,
+        divergences: Arc<Mutex<Vec<f64>>>,
+        marked: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Inspector<()> for ArbitrageLog {
+        fn log(&mut self, _value: ()) -> Result<(), ArenaError> {
+            Ok(())
+        }
+
+        fn inspect(&self, _step: usize) -> Option<()> {
+            None
+        }
+
+        fn save(&self) -> Result<(), ArenaError> {
+            Ok(())
+        }
+
+        fn on_step(&mut self, signal: &Signal) -> Result<(), ArenaError> {
+            self.divergences.lock().unwrap().push(signal.divergence);
+            Ok(())
+        }
+
+        fn on_arbitrage(&mut self, _pool: usize, fill: &ArbitrageFill) -> Result<(), ArenaError> {
+            self.fills.lock().unwrap().push(*fill);
+            Ok(())
+        }
+
+        fn marks_agents(&self) -> bool {
+            true
+        }
+
+        fn on_marks(
+            &mut self,
+            _step: Option<usize>,
+            marks: &[AgentMark],
+        ) -> Result<(), ArenaError> {
+            self.marked
+                .lock()
+                .unwrap()
+                .extend(marks.iter().map(|mark| mark.name.clone()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_default_arbitrageur() {
+        let log = ArbitrageLog::default();
+
+        let mut arena: Arena<()> = ArenaBuilder::new()
+            .with_strategy(Box::new(StrategyMock))
+            .with_feed(Box::new(OrnsteinUhlenbeck::new(1.0, 0.1, 1.0, 0.1, 0.1)))
+            .with_inspector(Box::new(log.clone()))
+            .with_arbitrageur(Box::new(DefaultArbitrageur::new()))
+            .with_seed(0)
+            .build()
+            .unwrap();
+
+        arena
+            .run(Config::new(
+                20,
+                Uint::from(0),
+                Signed::try_from(2).unwrap(),
+                Bytes::new(),
+                Uint::from(79228162514264337593543950336_u128),
+                Uint::from(0),
+                Uint::from(1),
+                Address::ZERO,
+            ))
+            .await
+            .unwrap();
+
+        // without a fee or gas cost the arbitrageur swaps the pool to the liquid exchange price at every update
+        assert!(!log.fills.lock().unwrap().is_empty());
+        for divergence in log.divergences.lock().unwrap().iter() {
+            assert!(
+                divergence.abs() < 1e-3,
+                "pool left at a divergence of {divergence}"
+            );
+        }
+
+        // the arbitrageur is marked alongside the strategy
+        let marked = log.marked.lock().unwrap();
+        assert!(marked.iter().any(|name| name == "strategy 0"));
+        assert!(marked.iter().any(|name| name == "arbitrageur"));
+    }
+
+    /// Step, feed value and pool price seen at the end of a step.
+    type Price = (Option<usize>, f64, Uint<160, 3>);
+
+    #[derive(Clone, Default)]
+    struct PriceLog {
+        prices: Arc<Mutex<Vec<Price>>>,
+    }
+
+    impl Inspector<()> for PriceLog {
+        fn log(&mut self, _value: ()) -> Result<(), ArenaError> {
+            Ok(())
+        }
+
+        fn inspect(&self, _step: usize) -> Option<()> {
+            None
+        }
+
+        fn save(&self) -> Result<(), ArenaError> {
+            Ok(())
+        }
+
+        fn on_step(&mut self, signal: &Signal) -> Result<(), ArenaError> {
+            self.prices.lock().unwrap().push((
+                signal.step,
+                signal.current_value,
+                signal.sqrt_price_x96,
+            ));
+            Ok(())
+        }
+
+        fn reset(&mut self) -> Result<(), ArenaError> {
+            self.prices.lock().unwrap().clear();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume() {
+        let arena = |log: &PriceLog| -> Arena<()> {
+            ArenaBuilder::new()
+                .with_strategy(Box::new(StrategyMock))
+                .with_feed(Box::new(OrnsteinUhlenbeck::new(1.0, 0.1, 1.0, 0.1, 0.1)))
+                .with_inspector(Box::new(log.clone()))
+                .with_arbitrageur(Box::new(DefaultArbitrageur::new()))
+                .with_noise_trader(NoiseTrader::new(Box::new(
+                    PoissonOrderFlow::new(0.5, 0.001).unwrap(),
+                )))
+                .with_seed(7)
+                .build()
+                .unwrap()
+        };
+        let config = |steps| {
+            Config::new(
+                steps,
+                Uint::from(0),
+                Signed::try_from(2).unwrap(),
+                Bytes::new(),
+                Uint::from(79228162514264337593543950336_u128),
+                Uint::from(0),
+                Uint::from(1),
+                Address::ZERO,
+            )
+        };
+        let path = std::env::temp_dir().join(format!("arena-resume-{}.json", std::process::id()));
+
+        let straight = PriceLog::default();
+        arena(&straight).run(config(10)).await.unwrap();
+
+        let interrupted = PriceLog::default();
+        arena(&interrupted)
+            .run(config(5).with_checkpoints(5, &path).unwrap())
+            .await
+            .unwrap();
+
+        let resumed = PriceLog::default();
+        let mut continued = arena(&resumed);
+        continued.resume(&path).await.unwrap();
+        continued.run(config(10)).await.unwrap();
+        std::fs::remove_file(&path).ok();
+
+        // the run resumed at step 5 sees the same feed and pool as the uninterrupted run from there on
+        let straight = straight.prices.lock().unwrap();
+        assert_eq!(straight.len(), 10);
+        assert_eq!(*resumed.prices.lock().unwrap(), straight[5..]);
+        assert_eq!(*interrupted.prices.lock().unwrap(), straight[..5]);
+    }
+
+    #[tokio::test]
+    async fn test_seed() {
+        let run = |seed: u64| async move {
+            let log = PriceLog::default();
+            let mut arena: Arena<()> = ArenaBuilder::new()
+                .with_strategy(Box::new(StrategyMock))
+                .with_feed(Box::new(OrnsteinUhlenbeck::new(1.0, 0.1, 1.0, 0.1, 0.1)))
+                .with_inspector(Box::new(log.clone()))
+                .with_arbitrageur(Box::new(DefaultArbitrageur::new()))
+                .with_noise_trader(NoiseTrader::new(Box::new(
+                    PoissonOrderFlow::new(0.5, 0.001).unwrap(),
+                )))
+                .with_seed(seed)
+                .build()
+                .unwrap();
+
+            arena
+                .run(Config::new(
+                    10,
+                    Uint::from(0),
+                    Signed::try_from(2).unwrap(),
+                    Bytes::new(),
+                    Uint::from(79228162514264337593543950336_u128),
+                    Uint::from(0),
+                    Uint::from(1),
+                    Address::ZERO,
+                ))
+                .await
+                .unwrap();
+
+            let prices = log.prices.lock().unwrap().clone();
+            prices
+        };
+
+        // the same seed replays the feed and every trade, down to the pool price
+        let first = run(3).await;
+        assert_eq!(first.len(), 10);
+        assert_eq!(first, run(3).await);
+
+        let other = run(4).await;
+        assert!(first.iter().zip(&other).any(|(a, b)| a.1 != b.1));
+    }
+
+    #[tokio::test]
+    async fn test_deployment_reuse() {
+        let log = PriceLog::default();
+        let mut arena: Arena<()> = ArenaBuilder::new()
+            .with_strategy(Box::new(PassiveLiquidity::new(10000000)))
+            .with_feed(Box::new(OrnsteinUhlenbeck::new(1.0, 0.1, 1.0, 0.1, 0.1)))
+            .with_inspector(Box::new(log.clone()))
+            .with_arbitrageur(Box::new(DefaultArbitrageur::new()))
+            .with_noise_trader(NoiseTrader::new(Box::new(
+                PoissonOrderFlow::new(0.5, 0.001).unwrap(),
+            )))
+            .with_deployment_reuse()
+            .with_seed(3)
+            .build()
+            .unwrap();
+        let config = || {
+            Config::new(
+                10,
+                Uint::from(0),
+                Signed::try_from(2).unwrap(),
+                Bytes::new(),
+                Uint::from(79228162514264337593543950336_u128),
+                Uint::from(0),
+                Uint::from(1),
+                Address::ZERO,
+            )
+        };
+
+        arena.run(config()).await.unwrap();
+        let deployed = log.prices.lock().unwrap().clone();
+        assert_eq!(deployed.len(), 10);
+
+        // every run reverted to the deployment starts over from the same strategy and inspector state, so it sees
+        // exactly what the run that deployed it saw, rather than that run followed by its own
+        for _ in 0..2 {
+            arena.run(config()).await.unwrap();
+            assert_eq!(*log.prices.lock().unwrap(), deployed);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_addresses() {
+        let log = PriceLog::default();
+        let mut arena: Arena<()> = ArenaBuilder::new()
+            .with_strategy(Box::new(StrategyMock))
+            .with_feed(Box::new(OrnsteinUhlenbeck::new(1.0, 0.1, 1.0, 0.1, 0.1)))
+            .with_pool(
+                PoolConfig::new(
+                    Uint::from(3000),
+                    Signed::try_from(60).unwrap(),
+                    Uint::from(79228162514264337593543950336_u128),
+                    U256::from(1),
+                ),
+                Box::new(OrnsteinUhlenbeck::new(1.0, 0.1, 1.0, 0.1, 0.1)),
+            )
+            .with_inspector(Box::new(log.clone()))
+            .with_arbitrageur(Box::new(FixedArbitrageur {
+                depth: Signed::try_from(10000).unwrap(),
+            }))
+            .with_deterministic_addresses()
+            .with_seed(0)
+            .build()
+            .unwrap();
+        let config = || {
+            Config::new(
+                5,
+                Uint::from(0),
+                Signed::try_from(2).unwrap(),
+                Bytes::new(),
+                Uint::from(79228162514264337593543950336_u128),
+                Uint::from(0),
+                Uint::from(1),
+                Address::ZERO,
+            )
+        };
+
+        let first = arena.run(config()).await.unwrap();
+        let block = ProviderBuilder::new()
+            .on_http(arena.env.as_ref().unwrap().endpoint_url())
+            .get_block_number()
+            .await
+            .unwrap();
+        let second = arena.run(config()).await.unwrap();
+
+        // the second run deploys on the chain the first left behind, at the same addresses
+        let addresses = |summary: &RunSummary| -> Vec<_> {
+            summary
+                .pools
+                .iter()
+                .map(|pool| {
+                    (
+                        pool.controller,
+                        pool.manager,
+                        pool.fetcher,
+                        engine::pool::pool_id(&pool.key),
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(first.pools.len(), 2);
+        assert_eq!(addresses(&first), addresses(&second));
+        assert!(
+            ProviderBuilder::new()
+                .on_http(arena.env.as_ref().unwrap().endpoint_url())
+                .get_block_number()
+                .await
+                .unwrap()
+                > block
+        );
+
+        // and its contracts start over, so it sees what the first run saw
+        let prices = log.prices.lock().unwrap();
+        assert_eq!(prices.len(), 10);
+        assert_eq!(prices[..5], prices[5..]);
+    }
+
+    /// Strategy keeping the history it is initialized with and the seconds the chain clock advanced by before then.
+    #[derive(Clone, Default)]
+    struct WarmupProbe {
+        history: Arc<Mutex<Vec<history::HistoryEntry>>>,
+        elapsed: Arc<Mutex<u64>>,
+    }
+
+    #[async_trait]
+    impl<T> Strategy<T> for WarmupProbe {
+        async fn init(
+            &mut self,
+            provider: AnvilProvider,
+            signal: Signal,
+            _inspector: &mut Box<dyn Inspector<T>>,
+            _engine: Engine,
+            _rng: &mut StdRng,
+        ) -> Result<(), ArenaError> {
+            let timestamp = |block| {
+                let provider = provider.clone();
+                async move {
+                    provider
+                        .get_block_by_number(block, false)
+                        .await
+                        .unwrap()
+                        .unwrap()
+                        .header
+                        .timestamp
+                }
+            };
+            *self.elapsed.lock().unwrap() = timestamp(BlockNumberOrTag::Latest).await
+                - timestamp(BlockNumberOrTag::Earliest).await;
+            *self.history.lock().unwrap() = signal.history.iter().cloned().collect();
+            Ok(())
+        }
+
+        async fn process(
+            &mut self,
+            _provider: AnvilProvider,
+            _signal: Signal,
+            _inspector: &mut Box<dyn Inspector<T>>,
+            _engine: Engine,
+            _rng: &mut StdRng,
+        ) -> Result<(), ArenaError> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_warmup() {
+        let probe = WarmupProbe::default();
+        let log = ArbitrageLog::default();
+        let mut arena: Arena<()> = ArenaBuilder::new()
+            .with_strategy(Box::new(StrategyMock))
+            .with_strategy(Box::new(probe.clone()))
+            .with_feed(Box::new(OrnsteinUhlenbeck::new(1.0, 0.1, 1.0, 0.1, 0.1)))
+            .with_inspector(Box::new(log.clone()))
+            .with_arbitrageur(Box::new(DefaultArbitrageur::new()))
+            .with_noise_trader(NoiseTrader::new(Box::new(
+                PoissonOrderFlow::new(2.0, 0.001).unwrap(),
+            )))
+            .with_seed(0)
+            .build()
+            .unwrap();
+
+        arena
+            .run(
+                Config::new(
+                    4,
+                    Uint::from(0),
+                    Signed::try_from(2).unwrap(),
+                    Bytes::new(),
+                    Uint::from(79228162514264337593543950336_u128),
+                    Uint::from(0),
+                    Uint::from(1),
+                    Address::ZERO,
+                )
+                .with_warmup(3)
+                .with_seconds_per_step(60.0)
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // strategies start from the history of every warm-up step, each of which moved the chain clock a step on
+        let history = probe.history.lock().unwrap();
+        assert_eq!(history.len(), 3);
+        for (step, entry) in history.iter().enumerate() {
+            assert!(entry.warmup);
+            assert_eq!(entry.step, step);
+        }
+        assert!(*probe.elapsed.lock().unwrap() >= 180);
+
+        // while the inspector only sees the steps of the run, and nobody is marked during warm-up
+        assert_eq!(log.divergences.lock().unwrap().len(), 4);
+        let marked = log.marked.lock().unwrap();
+        assert_eq!(
+            marked
+                .iter()
+                .filter(|name| *name == "noise trader 0")
+                .count(),
+            5
+        );
+    }
+
+    #[tokio::test]
+    async fn test_agent_order() {
+        let orders = |random: bool| async move {
+            let mut builder = ArenaBuilder::new()
+                .with_strategy(Box::new(StrategyMock))
+                .with_feed(Box::new(OrnsteinUhlenbeck::new(1.0, 0.1, 1.0, 0.1, 0.1)))
+                .with_inspector(Box::new(EmptyInspector {}))
+                .with_arbitrageur(Box::new(FixedArbitrageur {
+                    depth: Signed::try_from(10000).unwrap(),
+                }))
+                .with_agent(
+                    Box::new(NoiseTrader::new(Box::new(
+                        PoissonOrderFlow::new(0.5, 0.001).unwrap(),
+                    ))),
+                    2,
+                )
+                .with_agent(
+                    Box::new(ArbitrageurAgent(Box::new(DefaultArbitrageur::new()))),
+                    0,
+                )
+                .with_agent(
+                    Box::new(NoiseTrader::new(Box::new(
+                        PoissonOrderFlow::new(1.0, 0.002).unwrap(),
+                    ))),
+                    1,
+                )
+                .with_recording()
+                .with_seed(5);
+            if random {
+                builder = builder.with_random_agent_order();
+            }
+
+            let mut arena: Arena<f64> = builder.build().unwrap();
+            arena
+                .run(Config::new(
+                    10,
+                    Uint::from(0),
+                    Signed::try_from(2).unwrap(),
+                    Bytes::new(),
+                    Uint::from(79228162514264337593543950336_u128),
+                    Uint::from(0),
+                    Uint::from(1),
+                    Address::ZERO,
+                ))
+                .await
+                .unwrap();
+
+            arena
+                .replay_log
+                .unwrap()
+                .steps
+                .into_iter()
+                .map(|step| {
+                    step.agents
+                        .into_iter()
+                        .map(|decision| decision.agent)
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // the arbitrageur and noise traders act as agents by increasing priority at every step
+        let ordered = orders(false).await;
+        assert_eq!(ordered.len(), 10);
+        assert!(ordered.iter().all(|order| *order == vec![1, 2, 0]));
+
+        // or in an order drawn every step, the same for the same seed
+        let random = orders(true).await;
+        assert_eq!(random, orders(true).await);
+        assert!(random.iter().any(|order| *order != random[0]));
+        for order in &random {
+            let mut sorted = order.clone();
+            sorted.sort();
+            assert_eq!(sorted, vec![0, 1, 2]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay() {
+        let mut arena: Arena<f64> = ArenaBuilder::new()
+            .with_strategy(Box::new(StrategyMock))
+            .with_feed(Box::new(OrnsteinUhlenbeck::new(1.0, 0.1, 1.0, 0.1, 0.1)))
+            .with_inspector(Box::new(EmptyInspector {}))
+            .with_arbitrageur(Box::new(DefaultArbitrageur::new()))
+            .with_agent(
+                Box::new(NoiseTrader::new(Box::new(
+                    PoissonOrderFlow::new(0.5, 0.001).unwrap(),
+                ))),
+                0,
+            )
+            .with_anvil(|anvil| anvil.args(["--chain-id", "4242"]))
+            .with_recording()
+            .with_seed(0)
+            .build()
+            .unwrap();
+
+        arena
+            .run(Config::new(
+                5,
+                Uint::from(0),
+                Signed::try_from(2).unwrap(),
+                Bytes::new(),
+                Uint::from(79228162514264337593543950336_u128),
+                Uint::from(0),
+                Uint::from(1),
+                Address::ZERO,
+            ))
+            .await
+            .unwrap();
+
+        let log = arena.replay_log.as_ref().unwrap();
+        assert_eq!(log.steps.len(), 5);
+        for step in &log.steps {
+            assert_eq!(step.agents, vec![RecordedDecision { agent: 0, bid: 0 }]);
+        }
+
+        // the replay runs on a node with the chain id of the recording, and ends in the same state
+        let replay = arena.replay().unwrap();
+        replay.run().await.unwrap();
+        assert_eq!(replay.provider().get_chain_id().await.unwrap(), 4242);
+
+        let latest = |provider: RootProvider<Http<Client>>| async move {
+            provider
+                .get_block_by_number(BlockNumberOrTag::Latest, false)
+                .await
+                .unwrap()
+                .unwrap()
+                .header
+        };
+        let recorded =
+            latest(ProviderBuilder::new().on_http(arena.env.as_ref().unwrap().endpoint_url()))
+                .await;
+        let replayed = latest(replay.provider().clone()).await;
+        assert_eq!(replayed.number, recorded.number);
+        assert_eq!(replayed.state_root, recorded.state_root);
+    }
+
+    /// Strategy swapping past its price limit at step 2, which the pool reverts.
+    #[cfg(feature = "trace")]
+    struct RevertingStrategy;
+
+    #[cfg(feature = "trace")]
+    #[async_trait]
+    impl<T> Strategy<T> for RevertingStrategy {
+        async fn init(
+            &mut self,
+            _provider: AnvilProvider,
+            _signal: Signal,
+            _inspector: &mut Box<dyn Inspector<T>>,
+            _engine: Engine,
+            _rng: &mut StdRng,
+        ) -> Result<(), ArenaError> {
+            Ok(())
+        }
+
+        async fn process(
+            &mut self,
+            provider: AnvilProvider,
+            signal: Signal,
+            _inspector: &mut Box<dyn Inspector<T>>,
+            engine: Engine,
+            _rng: &mut StdRng,
+        ) -> Result<(), ArenaError> {
+            if signal.step != Some(2) {
+                return Ok(());
+            }
+
+            // selling token0 requires a limit below the price of the pool
+            engine
+                .swap(
+                    true,
+                    I256::try_from(-1000).unwrap(),
+                    Some(engine::pool::MAX_PRICE_LIMIT),
+                    provider,
+                )
+                .await
+        }
+    }
+
+    #[cfg(feature = "trace")]
+    #[tokio::test]
+    async fn test_trace_reverts() {
+        let mut arena: Arena<f64> = ArenaBuilder::new()
+            .with_strategy(Box::new(RevertingStrategy))
+            .with_feed(Box::new(OrnsteinUhlenbeck::new(1.0, 0.1, 1.0, 0.1, 0.1)))
+            .with_inspector(Box::new(EmptyInspector {}))
+            .with_tracing(trace::TraceSelection::new().with_reverts())
+            .with_seed(0)
+            .build()
+            .unwrap();
+
+        let error = arena
+            .run(Config::new(
+                5,
+                Uint::from(0),
+                Signed::try_from(2).unwrap(),
+                Bytes::new(),
+                Uint::from(79228162514264337593543950336_u128),
+                Uint::from(0),
+                Uint::from(1),
+                Address::ZERO,
+            ))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ArenaError::StepError { step: Some(2), .. }));
+
+        // the reverting swap is mined rather than failing at gas estimation, and traced before the run returns
+        let log = arena.trace_log.as_ref().unwrap();
+        let reverts: Vec<_> = log.reverts().collect();
+        assert_eq!(reverts.len(), 1);
+        assert_eq!(reverts[0].step, 2);
+        assert_eq!(reverts[0].component, "strategy 0");
+        assert!(reverts[0].trace.failure().is_some());
+    }
+
+    #[test]
+    fn test_signal_serde() {
+        let mut signal = Signal::new(
+            Uint::from(5),
+            Some(3),
+            Signed::try_from(-120).unwrap(),
+            Uint::from(79228162514264337593543950336_u128),
+            Address::ZERO,
+            PoolKey {
+                currency0: Address::ZERO,
+                currency1: Address::repeat_byte(1),
+                fee: Uint::from(3000),
+                tickSpacing: Signed::try_from(60).unwrap(),
+                hooks: Address::ZERO,
+            },
+            Address::ZERO,
+            1.5,
+            Address::ZERO,
+        );
+        signal.liquidity = u128::MAX;
+
+        let json = serde_json::to_string(&signal).unwrap();
+        let decoded: Signal = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(decoded.step, Some(3));
+        assert_eq!(decoded.tick, signal.tick);
+        assert_eq!(decoded.liquidity, u128::MAX);
+        assert_eq!(decoded.pool.currency1, signal.pool.currency1);
+        assert_eq!(decoded.pool.tickSpacing, signal.pool.tickSpacing);
+    }
 }
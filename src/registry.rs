@@ -0,0 +1,281 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{engine::score::Scorecard, error::ArenaError, summary::RunSummary, Config};
+
+/// File of a run directory holding its [`RunRecord`].
+const RECORD_FILE: &str = "run.json";
+
+/// Outcome of a registered run, stored once it completed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunOutcome {
+    /// See [`RunSummary::steps`].
+    pub steps: usize,
+
+    /// See [`RunSummary::interrupted`].
+    pub interrupted: bool,
+
+    /// See [`RunSummary::stopped`].
+    pub stopped: Option<String>,
+
+    /// See [`RunSummary::scorecard`].
+    pub scorecard: Scorecard,
+}
+
+impl From<&RunSummary> for RunOutcome {
+    fn from(summary: &RunSummary) -> Self {
+        Self {
+            steps: summary.steps,
+            interrupted: summary.interrupted,
+            stopped: summary.stopped.clone(),
+            scorecard: summary.scorecard.clone(),
+        }
+    }
+}
+
+/// Everything stored about a run by a [`RunRegistry`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Unique id of the run, which is also the name of its directory.
+    pub id: String,
+
+    /// Name the run was registered under.
+    pub name: String,
+
+    /// Time the run was registered at, in milliseconds since the Unix epoch.
+    pub created_at: u64,
+
+    /// Seed of the simulation, see [`crate::engine::rng::RngManager::seed`].
+    pub seed: Option<u64>,
+
+    /// Output of `git describe` in the working directory when the run was registered, if it is a repository.
+    pub git_describe: Option<String>,
+
+    /// Configuration of the run, see [`Config::describe`].
+    pub config: Value,
+
+    /// Files stored alongside the record, relative to the run directory.
+    pub artifacts: Vec<String>,
+
+    /// Outcome of the run, once it completed.
+    pub outcome: Option<RunOutcome>,
+}
+
+/// Local store of simulation runs, each under its own directory of a results directory, so past runs can be listed
+/// and loaded for comparison.
+#[derive(Debug, Clone)]
+pub struct RunRegistry {
+    root: PathBuf,
+}
+
+impl RunRegistry {
+    /// Open the registry stored in `root`, creating the directory if needed.
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self, ArenaError> {
+        let root = root.into();
+        fs::create_dir_all(&root)?;
+
+        Ok(Self { root })
+    }
+
+    /// Returns the results directory of the registry.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the directory of the run with the given id.
+    pub fn dir(&self, id: &str) -> PathBuf {
+        self.root.join(id)
+    }
+
+    /// Register a new run called `name`, simulating `config` from `seed`, and return its record.
+    ///
+    /// The id of the run is `name` followed by the registration time, made unique if several runs of the same name are
+    /// registered within a millisecond.
+    pub fn create(
+        &self,
+        name: &str,
+        config: &Config,
+        seed: Option<u64>,
+    ) -> Result<RunRecord, ArenaError> {
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let slug: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+
+        let mut attempt = 0;
+        let id = loop {
+            let id = match attempt {
+                0 => format!("{slug}-{created_at}"),
+                n => format!("{slug}-{created_at}-{n}"),
+            };
+            match fs::create_dir(self.dir(&id)) {
+                Ok(()) => break id,
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => attempt += 1,
+                Err(e) => return Err(e.into()),
+            }
+        };
+
+        let record = RunRecord {
+            id,
+            name: name.to_string(),
+            created_at,
+            seed,
+            git_describe: git_describe(),
+            config: config.describe(),
+            artifacts: Vec::new(),
+            outcome: None,
+        };
+        self.save(&record)?;
+
+        Ok(record)
+    }
+
+    /// Copy the file at `path`, such as the output of an inspector, into the directory of the run.
+    ///
+    /// Returns the path of the copy.
+    pub fn add_artifact(
+        &self,
+        record: &mut RunRecord,
+        path: impl AsRef<Path>,
+    ) -> Result<PathBuf, ArenaError> {
+        let path = path.as_ref();
+        let name = path
+            .file_name()
+            .ok_or_else(|| ArenaError::Custom(format!("{} is not a file", path.display())))?
+            .to_string_lossy()
+            .into_owned();
+
+        let copy = self.dir(&record.id).join(&name);
+        fs::copy(path, &copy)?;
+
+        if !record.artifacts.contains(&name) {
+            record.artifacts.push(name);
+        }
+        self.save(record)?;
+
+        Ok(copy)
+    }
+
+    /// Write `output` as JSON to the file `name` of the run directory.
+    ///
+    /// Returns the path of the file.
+    pub fn add_output(
+        &self,
+        record: &mut RunRecord,
+        name: &str,
+        output: &impl Serialize,
+    ) -> Result<PathBuf, ArenaError> {
+        let path = self.dir(&record.id).join(name);
+        fs::write(&path, serde_json::to_vec_pretty(output)?)?;
+
+        if !record.artifacts.iter().any(|artifact| artifact == name) {
+            record.artifacts.push(name.to_string());
+        }
+        self.save(record)?;
+
+        Ok(path)
+    }
+
+    /// Store the outcome of the run.
+    pub fn finish(&self, record: &mut RunRecord, summary: &RunSummary) -> Result<(), ArenaError> {
+        record.outcome = Some(RunOutcome::from(summary));
+        self.save(record)
+    }
+
+    /// Write the record to the directory of its run.
+    pub fn save(&self, record: &RunRecord) -> Result<(), ArenaError> {
+        fs::write(
+            self.dir(&record.id).join(RECORD_FILE),
+            serde_json::to_vec_pretty(record)?,
+        )?;
+
+        Ok(())
+    }
+
+    /// Load the record of the run with the given id.
+    pub fn load(&self, id: &str) -> Result<RunRecord, ArenaError> {
+        Ok(serde_json::from_slice(&fs::read(
+            self.dir(id).join(RECORD_FILE),
+        )?)?)
+    }
+
+    /// Returns the records of every run in the registry, oldest first.
+    ///
+    /// Directories of the results directory without a record are skipped.
+    pub fn list(&self) -> Result<Vec<RunRecord>, ArenaError> {
+        let mut records = Vec::new();
+        for entry in fs::read_dir(&self.root)? {
+            let path = entry?.path().join(RECORD_FILE);
+            if path.is_file() {
+                records.push(serde_json::from_slice::<RunRecord>(&fs::read(path)?)?);
+            }
+        }
+
+        records.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+        Ok(records)
+    }
+}
+
+/// Returns the output of `git describe` in the working directory, if git is installed and it is a repository.
+fn git_describe() -> Option<String> {
+    let output = Command::new("git")
+        .args(["describe", "--always", "--dirty", "--tags"])
+        .output()
+        .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, Bytes, Signed, Uint, U256};
+
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        let root = std::env::temp_dir().join(format!("arena-registry-{}", std::process::id()));
+        let registry = RunRegistry::new(&root).unwrap();
+        let config = Config::new(
+            10,
+            U256::ZERO,
+            Signed::try_from(2).unwrap(),
+            Bytes::new(),
+            Uint::from(79228162514264337593543950336_u128),
+            Uint::from(0),
+            U256::from(1),
+            Address::ZERO,
+        );
+
+        let mut first = registry.create("base case", &config, Some(7)).unwrap();
+        let second = registry.create("base case", &config, None).unwrap();
+        assert_ne!(first.id, second.id);
+        assert!(first.id.starts_with("base-case-"));
+        assert_eq!(first.config["steps"], 10);
+
+        registry
+            .add_output(&mut first, "marks.json", &vec![1.0, 2.0])
+            .unwrap();
+        assert_eq!(registry.load(&first.id).unwrap(), first);
+        assert_eq!(first.artifacts, vec!["marks.json".to_string()]);
+
+        let ids: Vec<String> = registry.list().unwrap().into_iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec![first.id, second.id]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,319 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use alloy::{
+    consensus::TxEnvelope,
+    eips::{eip2718::Encodable2718, BlockNumberOrTag},
+    node_bindings::{Anvil, AnvilInstance},
+    primitives::{Address, Bytes, B256, U256},
+    providers::{ext::AnvilApi, Provider, ProviderBuilder, RootProvider},
+    transports::{
+        http::{Client, Http},
+        Transport,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::ArenaError;
+
+/// A transaction mined during a recorded run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedTransaction {
+    /// Hash of the transaction.
+    pub hash: B256,
+
+    /// Sender of the transaction.
+    pub from: Address,
+
+    /// Simulation component that sent the transaction, e.g. `strategy 0` or `arbitrageur`, or `arena` for the feed
+    /// updates and deployments sent from the admin wallet.
+    pub component: String,
+
+    /// The signed transaction, EIP-2718 encoded.
+    pub raw: Bytes,
+
+    /// Gas used by the transaction.
+    pub gas_used: u128,
+
+    /// Whether the transaction succeeded.
+    pub status: bool,
+}
+
+/// A block mined during a recorded run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedBlock {
+    /// Number of the block.
+    pub number: u64,
+
+    /// Timestamp of the block, in seconds.
+    pub timestamp: u64,
+
+    /// Transactions of the block, in order.
+    pub transactions: Vec<RecordedTransaction>,
+}
+
+/// Decision of an agent during a recorded step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedDecision {
+    /// Index of the agent, in the order agents were added to the arena.
+    pub agent: usize,
+
+    /// Bid of the agent for its position in the order agents act in, in wei, zero unless agents are ordered by a
+    /// [`crate::engine::auction::BlockBuilder`].
+    pub bid: u128,
+}
+
+/// Everything that happened during a simulation step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedStep {
+    /// The step.
+    pub step: usize,
+
+    /// Values of the feeds in the order they advanced, as `(pool, value)` pairs where pool `0` is the pool of the
+    /// [`crate::Config`] and pool `i` is the `i`-th additional pool.
+    pub feed_values: Vec<(usize, f64)>,
+
+    /// Agents that acted during the step, in the order they acted in.
+    #[serde(default)]
+    pub agents: Vec<RecordedDecision>,
+
+    /// Blocks mined during the step.
+    pub blocks: Vec<RecordedBlock>,
+}
+
+/// Log of a run, recorded by an arena built with [`crate::arena::ArenaBuilder::with_recording`].
+///
+/// The log starts from the chain state once the simulation is set up, and holds every transaction mined from then on,
+/// so a [`ReplayArena`] can re-execute it without the strategies, feeds or arbitrageur that produced it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReplayLog {
+    /// State of the chain before the first recorded step, as returned by `anvil_dumpState`.
+    pub genesis: Bytes,
+
+    /// Recorded steps, in order.
+    pub steps: Vec<RecordedStep>,
+}
+
+impl ReplayLog {
+    /// Write the log to `path` as JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ArenaError> {
+        fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Read a log previously written with [`ReplayLog::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ArenaError> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// Returns every transaction of the log sent by `component`, in order.
+    pub fn transactions_of<'a>(
+        &'a self,
+        component: &'a str,
+    ) -> impl Iterator<Item = &'a RecordedTransaction> + 'a {
+        self.steps
+            .iter()
+            .flat_map(|step| &step.blocks)
+            .flat_map(|block| &block.transactions)
+            .filter(move |transaction| transaction.component == component)
+    }
+
+    /// Read the blocks after `from_block` up to the latest one, attributing transactions to components by sender.
+    pub(crate) async fn blocks_since<T: Transport + Clone, P: Provider<T>>(
+        provider: &P,
+        from_block: u64,
+        components: &HashMap<Address, String>,
+    ) -> Result<Vec<RecordedBlock>, ArenaError> {
+        let mut blocks = Vec::new();
+
+        for number in from_block + 1..=provider.get_block_number().await? {
+            let Some(block) = provider
+                .get_block_by_number(BlockNumberOrTag::Number(number), true)
+                .await?
+            else {
+                continue;
+            };
+            let receipts = provider
+                .get_block_receipts(BlockNumberOrTag::Number(number).into())
+                .await?
+                .unwrap_or_default();
+
+            let mut transactions = Vec::new();
+            for (transaction, receipt) in block
+                .transactions
+                .as_transactions()
+                .unwrap_or_default()
+                .iter()
+                .zip(receipts)
+            {
+                let envelope = TxEnvelope::try_from(transaction.clone())
+                    .map_err(|e| ArenaError::ReplayError(e.to_string()))?;
+
+                transactions.push(RecordedTransaction {
+                    hash: transaction.hash,
+                    from: transaction.from,
+                    component: components
+                        .get(&transaction.from)
+                        .cloned()
+                        .unwrap_or_else(|| transaction.from.to_string()),
+                    raw: envelope.encoded_2718().into(),
+                    gas_used: receipt.gas_used,
+                    status: receipt.status(),
+                });
+            }
+
+            blocks.push(RecordedBlock {
+                number,
+                timestamp: block.header.timestamp,
+                transactions,
+            });
+        }
+
+        Ok(blocks)
+    }
+}
+
+/// Re-executes a [`ReplayLog`] on a fresh Anvil node, checking that every transaction has the same outcome.
+///
+/// This makes a run that misbehaved reproducible for debugging, and a recorded run usable as a regression test of the
+/// contracts it interacts with.
+pub struct ReplayArena {
+    /// The underlying Anvil execution environment.
+    pub env: AnvilInstance,
+
+    /// The log being replayed.
+    pub log: ReplayLog,
+
+    provider: RootProvider<Http<Client>>,
+}
+
+impl ReplayArena {
+    /// Public constructor function for a new [`ReplayArena`] replaying `log` on a newly spawned Anvil node.
+    ///
+    /// Transactions are signed for the chain id of the recording node, so a log recorded on a node configured with
+    /// [`crate::arena::ArenaBuilder::with_anvil`] is replayed with [`ReplayArena::with_anvil`] or
+    /// [`crate::arena::Arena::replay`] instead.
+    pub fn new(log: ReplayLog) -> Self {
+        let env = Anvil::default().spawn();
+        let provider = ProviderBuilder::new().on_http(env.endpoint().parse().unwrap());
+
+        Self { env, log, provider }
+    }
+
+    /// Public constructor function for a new [`ReplayArena`] replaying `log` on an Anvil node spawned from `anvil`, the
+    /// configuration of the recording node, e.g. its chain id or fork. The node listens on a free port rather than the
+    /// one configured, which the recording node may still hold.
+    pub fn with_anvil(log: ReplayLog, anvil: Anvil) -> Result<Self, ArenaError> {
+        let env = anvil
+            .port(0u16)
+            .try_spawn()
+            .map_err(|e| ArenaError::ReplayError(format!("could not spawn anvil: {e}")))?;
+        let provider = ProviderBuilder::new().on_http(env.endpoint_url());
+
+        Ok(Self { env, log, provider })
+    }
+
+    /// Returns the provider of the node the log is replayed on, to inspect its state after a replay.
+    pub fn provider(&self) -> &RootProvider<Http<Client>> {
+        &self.provider
+    }
+
+    /// Replay every recorded block, mining each one with its recorded timestamp and transactions.
+    ///
+    /// Returns a [`ArenaError::ReplayError`] at the first transaction whose hash, gas used or status differs from the
+    /// recording.
+    pub async fn run(&self) -> Result<(), ArenaError> {
+        self.provider
+            .anvil_load_state(self.log.genesis.clone())
+            .await?;
+        self.provider.anvil_set_auto_mine(false).await?;
+
+        for step in &self.log.steps {
+            for block in &step.blocks {
+                self.provider
+                    .anvil_set_next_block_timestamp(block.timestamp)
+                    .await?;
+
+                // transactions sit in the mempool until the block is mined below
+                for transaction in &block.transactions {
+                    let _ = self.provider.send_raw_transaction(&transaction.raw).await?;
+                }
+
+                self.provider.anvil_mine(Some(U256::from(1)), None).await?;
+
+                let receipts = self
+                    .provider
+                    .get_block_receipts(BlockNumberOrTag::Latest.into())
+                    .await?
+                    .unwrap_or_default();
+
+                if receipts.len() != block.transactions.len() {
+                    return Err(ArenaError::ReplayError(format!(
+                        "step {}, block {}: expected {} transactions, mined {}",
+                        step.step,
+                        block.number,
+                        block.transactions.len(),
+                        receipts.len()
+                    )));
+                }
+
+                for (transaction, receipt) in block.transactions.iter().zip(receipts) {
+                    if receipt.transaction_hash != transaction.hash
+                        || receipt.gas_used != transaction.gas_used
+                        || receipt.status() != transaction.status
+                    {
+                        return Err(ArenaError::ReplayError(format!(
+                            "step {}, block {}: transaction {} from {} diverged",
+                            step.step, block.number, transaction.hash, transaction.component
+                        )));
+                    }
+                }
+            }
+        }
+
+        self.provider.anvil_set_auto_mine(true).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_log() {
+        let transaction = |component: &str, nonce: u8| RecordedTransaction {
+            hash: B256::repeat_byte(nonce),
+            from: Address::ZERO,
+            component: component.to_string(),
+            raw: Bytes::from(vec![nonce]),
+            gas_used: 21000,
+            status: true,
+        };
+
+        let log = ReplayLog {
+            genesis: Bytes::from(vec![1, 2, 3]),
+            steps: vec![RecordedStep {
+                step: 0,
+                feed_values: vec![(0, 1.5), (1, 2.5)],
+                agents: vec![RecordedDecision { agent: 1, bid: 5 }],
+                blocks: vec![RecordedBlock {
+                    number: 7,
+                    timestamp: 100,
+                    transactions: vec![
+                        transaction("arena", 1),
+                        transaction("strategy 0", 2),
+                        transaction("arena", 3),
+                    ],
+                }],
+            }],
+        };
+
+        let decoded: ReplayLog =
+            serde_json::from_slice(&serde_json::to_vec(&log).unwrap()).unwrap();
+        assert_eq!(decoded, log);
+
+        let hashes: Vec<_> = log.transactions_of("arena").map(|tx| tx.hash).collect();
+        assert_eq!(hashes, vec![B256::repeat_byte(1), B256::repeat_byte(3)]);
+    }
+}
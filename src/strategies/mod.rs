@@ -0,0 +1,37 @@
+/// Passive full-range liquidity provision.
+pub mod passive;
+
+/// Range liquidity provision recentred around the price.
+pub mod rebalancing;
+
+/// Strategies loaded from WebAssembly modules at runtime.
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// Strategies written in Python.
+#[cfg(feature = "pyo3")]
+pub mod python;
+
+pub use passive::PassiveLiquidity;
+#[cfg(feature = "pyo3")]
+pub use python::PyStrategy;
+pub use rebalancing::RangeRebalancing;
+#[cfg(feature = "wasm")]
+pub use wasm::WasmStrategy;
+
+/// JSON view of a signal handed to strategies written outside of Rust.
+#[cfg(any(feature = "wasm", feature = "pyo3"))]
+pub(crate) fn signal_json(signal: &crate::Signal) -> serde_json::Value {
+    serde_json::json!({
+        "step": signal.step,
+        "tick": signal.tick.as_i32(),
+        "sqrt_price_x96": signal.sqrt_price_x96.to_string(),
+        "liquidity": signal.liquidity.to_string(),
+        "lex_price": signal.lex_price.to_string(),
+        "current_value": signal.current_value,
+        "block_number": signal.block_number,
+        "timestamp": signal.timestamp,
+        "tick_spacing": signal.pool.tickSpacing.as_i32(),
+        "fee": signal.pool.fee.to::<u32>(),
+    })
+}
@@ -0,0 +1,110 @@
+use alloy::primitives::Bytes;
+use async_trait::async_trait;
+use rand::rngs::StdRng;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    engine::{inspector::Inspector, position::Position, Engine},
+    error::ArenaError,
+    strategy::{FromConfig, Strategy},
+    AnvilProvider, Signal,
+};
+
+/// Strategy that mints a full-range position at initialization and holds it for the rest of the simulation.
+///
+/// This is the usual benchmark for active liquidity management strategies.
+#[derive(Debug, Clone)]
+pub struct PassiveLiquidity {
+    /// Liquidity provided over the full range.
+    pub liquidity: u128,
+
+    /// Hook data passed when minting the position.
+    pub hook_data: Bytes,
+
+    position: Option<Position>,
+}
+
+impl PassiveLiquidity {
+    /// Public constructor function for a new [`PassiveLiquidity`] strategy.
+    pub fn new(liquidity: u128) -> Self {
+        Self {
+            liquidity,
+            hook_data: Bytes::new(),
+            position: None,
+        }
+    }
+
+    /// Set the hook data passed when minting the position.
+    pub fn with_hook_data(mut self, hook_data: Bytes) -> Self {
+        self.hook_data = hook_data;
+        self
+    }
+
+    /// Returns the position held by the strategy, once minted.
+    pub fn position(&self) -> Option<&Position> {
+        self.position.as_ref()
+    }
+}
+
+/// Parameters of a [`PassiveLiquidity`] strategy, as read from a [`crate::Config`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct PassiveLiquidityParams {
+    /// [`PassiveLiquidity::liquidity`]
+    pub liquidity: u128,
+
+    /// [`PassiveLiquidity::hook_data`]
+    #[serde(default)]
+    pub hook_data: Bytes,
+}
+
+impl FromConfig for PassiveLiquidity {
+    type Params = PassiveLiquidityParams;
+
+    fn from_params(params: Self::Params) -> Result<Self, ArenaError> {
+        Ok(Self::new(params.liquidity).with_hook_data(params.hook_data))
+    }
+}
+
+#[async_trait]
+impl<V> Strategy<V> for PassiveLiquidity {
+    async fn init(
+        &mut self,
+        provider: AnvilProvider,
+        signal: Signal,
+        _inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        let position = Position::full_range(signal.pool.tickSpacing.as_i32(), self.liquidity);
+
+        engine
+            .open_position(&position, self.hook_data.clone(), provider)
+            .await?;
+
+        self.position = Some(position);
+
+        Ok(())
+    }
+
+    async fn process(
+        &mut self,
+        _provider: AnvilProvider,
+        _signal: Signal,
+        _inspector: &mut Box<dyn Inspector<V>>,
+        _engine: Engine,
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        Ok(json!({ "position": self.position }))
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        self.position = serde_json::from_value(state["position"].clone())?;
+
+        Ok(())
+    }
+}
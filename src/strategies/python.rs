@@ -0,0 +1,232 @@
+use alloy::primitives::Bytes;
+use async_trait::async_trait;
+use pyo3::{prelude::*, types::PyTuple};
+use rand::rngs::StdRng;
+
+use crate::{
+    engine::{action::Action, inspector::Inspector, Engine},
+    error::ArenaError,
+    strategies::signal_json,
+    strategy::Strategy,
+    AnvilProvider, Signal,
+};
+
+/// Strategy implemented by a Python object.
+///
+/// The object may define `init(signal)` and `process(signal)` methods. Each receives the signal as the `dict` form of
+/// the JSON object built by `strategies::signal_json`: fields of the [`Signal`] such as `tick`, `sqrt_price_x96` and
+/// `lex_price`, plus the `tick_spacing` and `fee` of the pool, where large integers are decimal strings. Each returns
+/// `None` or a list of actions for the engine to execute. Actions are dicts in serde's externally tagged form, e.g.
+/// `{"swap": {"zero_for_one": True, "amount_in": 1000}}`.
+pub struct PyStrategy {
+    object: Py<PyAny>,
+
+    /// Hook data passed when modifying liquidity.
+    pub hook_data: Bytes,
+}
+
+impl PyStrategy {
+    /// Public constructor function for a new [`PyStrategy`] wrapping a Python object.
+    pub fn new(object: Py<PyAny>) -> Self {
+        pyo3::prepare_freethreaded_python();
+
+        Self {
+            object,
+            hook_data: Bytes::new(),
+        }
+    }
+
+    /// Import `module` and instantiate `class` from it with no arguments.
+    pub fn from_module(module: &str, class: &str) -> Result<Self, ArenaError> {
+        pyo3::prepare_freethreaded_python();
+
+        Python::with_gil(|py| {
+            let object = py.import_bound(module)?.getattr(class)?.call0()?.unbind();
+
+            Ok(Self::new(object))
+        })
+        .map_err(python_error)
+    }
+
+    /// Set the hook data passed when modifying liquidity.
+    pub fn with_hook_data(mut self, hook_data: Bytes) -> Self {
+        self.hook_data = hook_data;
+        self
+    }
+
+    /// Call a method of the wrapped object with a signal, returning the actions it produced.
+    ///
+    /// Returns no actions if the method is not defined or returns `None`.
+    pub fn call(&self, method: &str, signal: &Signal) -> Result<Vec<Action>, ArenaError> {
+        let output = Python::with_gil(|py| -> PyResult<Option<String>> {
+            let object = self.object.bind(py);
+
+            if !object.hasattr(method)? {
+                return Ok(None);
+            }
+
+            let json = py.import_bound("json")?;
+            let signal = json.call_method1("loads", (signal_json(signal).to_string(),))?;
+
+            let actions = object.call_method1(method, PyTuple::new_bound(py, [signal]))?;
+            if actions.is_none() {
+                return Ok(None);
+            }
+
+            Ok(Some(json.call_method1("dumps", (actions,))?.extract()?))
+        })
+        .map_err(python_error)?;
+
+        match output {
+            Some(output) => Ok(serde_json::from_str(&output)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn run(
+        &mut self,
+        method: &str,
+        provider: AnvilProvider,
+        signal: Signal,
+        engine: Engine,
+    ) -> Result<(), ArenaError> {
+        let actions = self.call(method, &signal)?;
+
+        engine
+            .apply(&actions, &signal, self.hook_data.clone(), provider)
+            .await
+    }
+}
+
+#[async_trait]
+impl<V> Strategy<V> for PyStrategy {
+    async fn init(
+        &mut self,
+        provider: AnvilProvider,
+        signal: Signal,
+        _inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        self.run("init", provider, signal, engine).await
+    }
+
+    async fn process(
+        &mut self,
+        provider: AnvilProvider,
+        signal: Signal,
+        _inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        self.run("process", provider, signal, engine).await
+    }
+}
+
+fn python_error(e: PyErr) -> ArenaError {
+    ArenaError::PythonError(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, Signed, Uint, U256};
+    use pyo3::types::PyDict;
+
+    use super::*;
+    use crate::types::controller::ArenaController::PoolKey;
+
+    /// Returns a strategy wrapping an instance of the class `Strategy` defined by `source`.
+    fn strategy(source: &str) -> PyStrategy {
+        pyo3::prepare_freethreaded_python();
+
+        let object = Python::with_gil(|py| {
+            let globals = PyDict::new_bound(py);
+            py.run_bound(source, Some(&globals), None).unwrap();
+            globals
+                .get_item("Strategy")
+                .unwrap()
+                .unwrap()
+                .call0()
+                .unwrap()
+                .unbind()
+        });
+
+        PyStrategy::new(object)
+    }
+
+    fn signal() -> Signal {
+        Signal::new(
+            U256::from(10).pow(U256::from(18)),
+            Some(4),
+            Signed::try_from(120).unwrap(),
+            Uint::from(79228162514264337593543950336_u128),
+            Address::ZERO,
+            PoolKey {
+                currency0: Address::ZERO,
+                currency1: Address::repeat_byte(1),
+                fee: Uint::from(3000),
+                tickSpacing: Signed::try_from(60).unwrap(),
+                hooks: Address::ZERO,
+            },
+            Address::ZERO,
+            1.0,
+            Address::ZERO,
+        )
+    }
+
+    #[test]
+    fn test_py_strategy() {
+        let strategy = strategy(
+            r#"
+class Strategy:
+    def process(self, signal):
+        if signal["step"] % 2:
+            return None
+        spacing = signal["tick_spacing"]
+        return [{"add_liquidity": {
+            "tick_lower": signal["tick"] - spacing,
+            "tick_upper": signal["tick"] + spacing,
+            "liquidity": int(signal["sqrt_price_x96"]) >> 90,
+        }}]
+"#,
+        );
+
+        assert_eq!(
+            strategy.call("process", &signal()).unwrap(),
+            vec![Action::AddLiquidity {
+                tick_lower: 60,
+                tick_upper: 180,
+                liquidity: 64,
+            }]
+        );
+
+        // undefined methods and `None` produce no actions
+        assert!(strategy.call("init", &signal()).unwrap().is_empty());
+        let mut odd = signal();
+        odd.step = Some(5);
+        assert!(strategy.call("process", &odd).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_py_strategy_errors() {
+        let strategy = strategy(
+            r#"
+class Strategy:
+    def init(self, signal):
+        raise ValueError("no liquidity")
+
+    def process(self, signal):
+        return [{"withdraw_everything": {}}]
+"#,
+        );
+
+        assert!(matches!(
+            strategy.call("init", &signal()),
+            Err(ArenaError::PythonError(message)) if message.contains("no liquidity")
+        ));
+        assert!(matches!(
+            strategy.call("process", &signal()),
+            Err(ArenaError::SerdeError(_))
+        ));
+    }
+}
@@ -0,0 +1,302 @@
+use alloy::primitives::Bytes;
+use async_trait::async_trait;
+use rand::rngs::StdRng;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    engine::{
+        inspector::Inspector,
+        position::{Position, MAX_TICK},
+        Engine,
+    },
+    error::ArenaError,
+    strategy::{FromConfig, Strategy},
+    AnvilProvider, Signal,
+};
+
+/// Strategy that keeps a position within `±width` of the current price and recentres it once the price drifts too far.
+#[derive(Debug, Clone)]
+pub struct RangeRebalancing {
+    /// Liquidity provided over the range.
+    pub liquidity: u128,
+
+    /// Relative half-width of the range around the price, e.g. `0.05` for ±5%.
+    pub width: f64,
+
+    /// Fraction of the half-width the tick may drift from the centre of the range before rebalancing.
+    ///
+    /// A threshold of `1.0` rebalances once the price exits the range.
+    pub threshold: f64,
+
+    /// Minimum number of steps between rebalances.
+    pub cooldown: usize,
+
+    /// Hook data passed when modifying the position.
+    pub hook_data: Bytes,
+
+    position: Option<Position>,
+    center: i32,
+    last_rebalance: Option<usize>,
+    rebalances: usize,
+}
+
+impl RangeRebalancing {
+    /// Public constructor function for a new [`RangeRebalancing`] strategy, rebalancing as soon as the price exits the range.
+    pub fn new(liquidity: u128, width: f64) -> Self {
+        Self {
+            liquidity,
+            width,
+            threshold: 1.0,
+            cooldown: 0,
+            hook_data: Bytes::new(),
+            position: None,
+            center: 0,
+            last_rebalance: None,
+            rebalances: 0,
+        }
+    }
+
+    /// Set the fraction of the half-width the price may drift before rebalancing.
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Set the minimum number of steps between rebalances.
+    pub fn with_cooldown(mut self, cooldown: usize) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Set the hook data passed when modifying the position.
+    pub fn with_hook_data(mut self, hook_data: Bytes) -> Self {
+        self.hook_data = hook_data;
+        self
+    }
+
+    /// Returns the position currently held by the strategy.
+    pub fn position(&self) -> Option<&Position> {
+        self.position.as_ref()
+    }
+
+    /// Returns the number of rebalances performed so far.
+    pub fn rebalances(&self) -> usize {
+        self.rebalances
+    }
+
+    /// Half-width of the range in ticks.
+    fn width_ticks(&self) -> i32 {
+        let ticks = (1.0 + self.width).ln() / 1.0001f64.ln();
+
+        (ticks.round() as i32).clamp(1, MAX_TICK)
+    }
+
+    fn should_rebalance(&self, tick: i32, step: Option<usize>) -> bool {
+        let cooled_down = match (self.last_rebalance, step) {
+            (Some(last), Some(step)) => step >= last + self.cooldown,
+            _ => true,
+        };
+
+        let drift = (tick - self.center).unsigned_abs() as f64;
+
+        cooled_down && drift >= self.threshold * self.width_ticks() as f64
+    }
+
+    async fn recenter(
+        &mut self,
+        tick: i32,
+        tick_spacing: i32,
+        provider: AnvilProvider,
+        engine: &Engine,
+    ) -> Result<(), ArenaError> {
+        if let Some(position) = self.position.take() {
+            engine
+                .close_position(&position, self.hook_data.clone(), provider.clone())
+                .await?;
+        }
+
+        let position = Position::around(tick, self.width_ticks(), tick_spacing, self.liquidity);
+
+        engine
+            .open_position(&position, self.hook_data.clone(), provider)
+            .await?;
+
+        self.position = Some(position);
+        self.center = tick;
+
+        Ok(())
+    }
+}
+
+/// Parameters of a [`RangeRebalancing`] strategy, as read from a [`crate::Config`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct RangeRebalancingParams {
+    /// [`RangeRebalancing::liquidity`]
+    pub liquidity: u128,
+
+    /// [`RangeRebalancing::width`]
+    pub width: f64,
+
+    /// [`RangeRebalancing::threshold`]
+    #[serde(default = "default_threshold")]
+    pub threshold: f64,
+
+    /// [`RangeRebalancing::cooldown`]
+    #[serde(default)]
+    pub cooldown: usize,
+
+    /// [`RangeRebalancing::hook_data`]
+    #[serde(default)]
+    pub hook_data: Bytes,
+}
+
+fn default_threshold() -> f64 {
+    1.0
+}
+
+impl FromConfig for RangeRebalancing {
+    type Params = RangeRebalancingParams;
+
+    fn from_params(params: Self::Params) -> Result<Self, ArenaError> {
+        Ok(Self::new(params.liquidity, params.width)
+            .with_threshold(params.threshold)
+            .with_cooldown(params.cooldown)
+            .with_hook_data(params.hook_data))
+    }
+}
+
+#[async_trait]
+impl<V> Strategy<V> for RangeRebalancing {
+    async fn init(
+        &mut self,
+        provider: AnvilProvider,
+        signal: Signal,
+        _inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        self.recenter(
+            signal.tick.as_i32(),
+            signal.pool.tickSpacing.as_i32(),
+            provider,
+            &engine,
+        )
+        .await
+    }
+
+    async fn process(
+        &mut self,
+        provider: AnvilProvider,
+        signal: Signal,
+        _inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        let tick = signal.tick.as_i32();
+
+        if !self.should_rebalance(tick, signal.step) {
+            return Ok(());
+        }
+
+        self.recenter(tick, signal.pool.tickSpacing.as_i32(), provider, &engine)
+            .await?;
+
+        self.last_rebalance = signal.step;
+        self.rebalances += 1;
+
+        Ok(())
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        Ok(json!({
+            "position": self.position,
+            "center": self.center,
+            "last_rebalance": self.last_rebalance,
+            "rebalances": self.rebalances,
+        }))
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        self.position = serde_json::from_value(state["position"].clone())?;
+        self.center = serde_json::from_value(state["center"].clone())?;
+        self.last_rebalance = serde_json::from_value(state["last_rebalance"].clone())?;
+        self.rebalances = serde_json::from_value(state["rebalances"].clone())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, Signed, Uint};
+
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_rebalance_trigger() {
+        let mut strategy = RangeRebalancing::new(1, 0.01)
+            .with_threshold(0.5)
+            .with_cooldown(10);
+
+        // ±1% is roughly 100 ticks either side
+        assert_eq!(strategy.width_ticks(), 100);
+
+        assert!(!strategy.should_rebalance(49, Some(0)));
+        assert!(strategy.should_rebalance(-50, Some(0)));
+
+        strategy.last_rebalance = Some(5);
+        assert!(!strategy.should_rebalance(80, Some(14)));
+        assert!(strategy.should_rebalance(80, Some(15)));
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = Config::new(
+            1,
+            Uint::from(0),
+            Signed::try_from(2).unwrap(),
+            Bytes::new(),
+            Uint::from(79228162514264337593543950336_u128),
+            Uint::from(0),
+            Uint::from(1),
+            Address::ZERO,
+        )
+        .with_strategy_params_toml(
+            r#"
+            [strategy.params]
+            liquidity = 1000
+            width = 0.05
+            cooldown = 10
+            "#,
+        )
+        .unwrap();
+
+        let strategy = RangeRebalancing::from_config(&config).unwrap();
+
+        assert_eq!(strategy.liquidity, 1000);
+        assert_eq!(strategy.width, 0.05);
+        assert_eq!(strategy.threshold, 1.0);
+        assert_eq!(strategy.cooldown, 10);
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let mut strategy = RangeRebalancing::new(1, 0.01);
+        strategy.position = Some(Position::around(120, 100, 10, 1));
+        strategy.center = 120;
+        strategy.last_rebalance = Some(7);
+        strategy.rebalances = 3;
+
+        let state = Strategy::<()>::snapshot(&strategy).unwrap();
+
+        let mut restored = RangeRebalancing::new(1, 0.01);
+        Strategy::<()>::restore(&mut restored, state).unwrap();
+
+        assert_eq!(restored.position, strategy.position);
+        assert_eq!(restored.center, 120);
+        assert_eq!(restored.last_rebalance, Some(7));
+        assert_eq!(restored.rebalances, 3);
+    }
+}
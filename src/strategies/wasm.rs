@@ -0,0 +1,297 @@
+use std::path::Path;
+
+use alloy::primitives::Bytes;
+use async_trait::async_trait;
+use rand::rngs::StdRng;
+use wasmi::{Config, Instance, Linker, Memory, Module, Store, TypedFunc};
+
+use crate::{
+    engine::{action::Action, inspector::Inspector, Engine},
+    error::ArenaError,
+    strategies::signal_json,
+    strategy::Strategy,
+    AnvilProvider, Signal,
+};
+
+/// Strategy compiled to WebAssembly and loaded at runtime.
+///
+/// Guests run sandboxed and never touch the chain directly. Instead, the host hands them the current signal and
+/// executes the [`Action`]s they return. A guest module exports:
+///
+/// - `memory`: its linear memory.
+/// - `alloc(len: i32) -> i32`: allocates `len` bytes for the host to write into.
+/// - `process(ptr: i32, len: i32) -> i64`: reads a JSON signal from `ptr` and returns the location of a JSON array of
+///   actions, packed as `ptr << 32 | len`.
+/// - `init(ptr: i32, len: i32) -> i64` (optional): same as `process`, called once on startup.
+///
+/// The signal is the JSON object built by `strategies::signal_json`: fields of the [`Signal`] such as `tick`,
+/// `sqrt_price_x96` and `lex_price`, plus the `tick_spacing` and `fee` of the pool. Large integers are encoded as
+/// decimal strings. Actions use serde's externally tagged representation, e.g.
+/// `{"swap": {"zero_for_one": true, "amount_in": 1000}}`, and are limited to adding or removing liquidity and swapping:
+/// guests cannot send [`Action::Custom`] calldata.
+///
+/// Every call into the guest is metered, and fails once it runs out of its fuel budget, see
+/// [`WasmStrategy::with_fuel`].
+pub struct WasmStrategy {
+    store: Store<()>,
+    instance: Instance,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+
+    /// Fuel available to every call into the guest, roughly one unit per instruction executed.
+    pub fuel: u64,
+
+    /// Hook data passed when modifying liquidity.
+    pub hook_data: Bytes,
+}
+
+impl WasmStrategy {
+    /// Fuel available to every call into a guest unless set with [`WasmStrategy::with_fuel`].
+    pub const DEFAULT_FUEL: u64 = 100_000_000;
+
+    /// Load a strategy from the bytes of a WebAssembly module.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ArenaError> {
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = wasmi::Engine::new(&config);
+        let module = Module::new(&engine, bytes).map_err(wasm_error)?;
+
+        let mut store = Store::new(&engine, ());
+        refuel(&mut store, Self::DEFAULT_FUEL)?;
+        let instance = Linker::<()>::new(&engine)
+            .instantiate(&mut store, &module)
+            .map_err(wasm_error)?
+            .start(&mut store)
+            .map_err(wasm_error)?;
+
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or_else(|| ArenaError::WasmError("module does not export `memory`".to_string()))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "alloc")
+            .map_err(wasm_error)?;
+
+        Ok(Self {
+            store,
+            instance,
+            memory,
+            alloc,
+            fuel: Self::DEFAULT_FUEL,
+            hook_data: Bytes::new(),
+        })
+    }
+
+    /// Load a strategy from a `.wasm` file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ArenaError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Set the fuel available to every call into the guest.
+    pub fn with_fuel(mut self, fuel: u64) -> Self {
+        self.fuel = fuel;
+        self
+    }
+
+    /// Set the hook data passed when modifying liquidity.
+    pub fn with_hook_data(mut self, hook_data: Bytes) -> Self {
+        self.hook_data = hook_data;
+        self
+    }
+
+    /// Call an exported entrypoint with a signal, returning the actions it produced.
+    ///
+    /// Returns no actions if the entrypoint is not exported, and an error if the guest runs out of fuel, returns a
+    /// location outside of its memory, or asks for an action it is not allowed.
+    pub fn call(&mut self, entrypoint: &str, signal: &Signal) -> Result<Vec<Action>, ArenaError> {
+        let Ok(func) = self
+            .instance
+            .get_typed_func::<(i32, i32), i64>(&self.store, entrypoint)
+        else {
+            return Ok(Vec::new());
+        };
+
+        let input = signal_json(signal).to_string().into_bytes();
+        let len = i32::try_from(input.len())
+            .map_err(|_| ArenaError::WasmError("signal too large".to_string()))?;
+
+        refuel(&mut self.store, self.fuel)?;
+        let ptr = self.alloc.call(&mut self.store, len).map_err(wasm_error)?;
+        self.memory
+            .write(&mut self.store, ptr as u32 as usize, &input)
+            .map_err(wasm_error)?;
+
+        let packed = func.call(&mut self.store, (ptr, len)).map_err(wasm_error)? as u64;
+
+        // the output is read in place, so a bogus length cannot make the host allocate more than the guest's memory
+        let (ptr, len) = ((packed >> 32) as usize, (packed & u32::MAX as u64) as usize);
+        let output = self
+            .memory
+            .data(&self.store)
+            .get(ptr..ptr.saturating_add(len))
+            .ok_or_else(|| {
+                ArenaError::WasmError(format!(
+                    "output of {len} bytes at {ptr} is outside of the guest's memory"
+                ))
+            })?;
+
+        let actions: Vec<Action> = serde_json::from_slice(output)?;
+        if actions
+            .iter()
+            .any(|action| matches!(action, Action::Custom { .. }))
+        {
+            return Err(ArenaError::WasmError(
+                "guests cannot send custom calldata".to_string(),
+            ));
+        }
+
+        Ok(actions)
+    }
+
+    async fn run(
+        &mut self,
+        entrypoint: &str,
+        provider: AnvilProvider,
+        signal: Signal,
+        engine: Engine,
+    ) -> Result<(), ArenaError> {
+        let actions = self.call(entrypoint, &signal)?;
+
+        engine
+            .apply(&actions, &signal, self.hook_data.clone(), provider)
+            .await
+    }
+}
+
+#[async_trait]
+impl<V> Strategy<V> for WasmStrategy {
+    async fn init(
+        &mut self,
+        provider: AnvilProvider,
+        signal: Signal,
+        _inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        self.run("init", provider, signal, engine).await
+    }
+
+    async fn process(
+        &mut self,
+        provider: AnvilProvider,
+        signal: Signal,
+        _inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        self.run("process", provider, signal, engine).await
+    }
+}
+
+/// Leave exactly `fuel` for the next executions in `store`.
+fn refuel(store: &mut Store<()>, fuel: u64) -> Result<(), ArenaError> {
+    let remaining = store.consume_fuel(0).map_err(wasm_error)?;
+
+    if remaining < fuel {
+        store.add_fuel(fuel - remaining).map_err(wasm_error)
+    } else {
+        store
+            .consume_fuel(remaining - fuel)
+            .map(|_| ())
+            .map_err(wasm_error)
+    }
+}
+
+fn wasm_error(e: impl std::fmt::Display) -> ArenaError {
+    ArenaError::WasmError(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, Signed, Uint, U256};
+
+    use super::*;
+    use crate::types::controller::ArenaController::PoolKey;
+
+    /// Returns a guest whose `process` runs `body`, with the JSON `output` stored at offset 1024.
+    fn guest(output: &str, body: &str) -> WasmStrategy {
+        let wat = format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (data (i32.const 1024) "{}")
+                (func (export "alloc") (param i32) (result i32) i32.const 2048)
+                (func (export "process") (param i32 i32) (result i64) {body}))"#,
+            output.replace('"', "\\22")
+        );
+
+        WasmStrategy::from_bytes(&wat::parse_str(wat).unwrap()).unwrap()
+    }
+
+    /// Returns the body of a `process` returning `len` bytes at offset 1024.
+    fn returning(len: u64) -> String {
+        format!("i64.const {}", 1024u64 << 32 | len)
+    }
+
+    fn signal() -> Signal {
+        Signal::new(
+            U256::from(10).pow(U256::from(18)),
+            Some(0),
+            Signed::ZERO,
+            Uint::from(79228162514264337593543950336_u128),
+            Address::ZERO,
+            PoolKey {
+                currency0: Address::ZERO,
+                currency1: Address::repeat_byte(1),
+                fee: Uint::from(3000),
+                tickSpacing: Signed::try_from(60).unwrap(),
+                hooks: Address::ZERO,
+            },
+            Address::ZERO,
+            1.0,
+            Address::ZERO,
+        )
+    }
+
+    #[test]
+    fn test_wasm_strategy() {
+        let swap = r#"[{"swap":{"zero_for_one":true,"amount_in":1000}}]"#;
+        let mut strategy = guest(swap, &returning(swap.len() as u64));
+
+        assert_eq!(
+            strategy.call("process", &signal()).unwrap(),
+            vec![Action::Swap {
+                zero_for_one: true,
+                amount_in: 1000,
+            }]
+        );
+        assert!(strategy.call("init", &signal()).unwrap().is_empty());
+
+        // every call gets a fresh budget
+        assert!(strategy.call("process", &signal()).is_ok());
+    }
+
+    #[test]
+    fn test_wasm_strategy_limits() {
+        // a guest that never returns runs out of fuel
+        let mut spinning = guest("", "(loop (br 0)) unreachable").with_fuel(10_000);
+        assert!(matches!(
+            spinning.call("process", &signal()),
+            Err(ArenaError::WasmError(_))
+        ));
+
+        // an output longer than the guest's memory is refused before it is read
+        let mut overflowing = guest("[]", &returning(u32::MAX as u64));
+        assert!(matches!(
+            overflowing.call("process", &signal()),
+            Err(ArenaError::WasmError(_))
+        ));
+
+        // custom calldata is not among the actions guests may take
+        let custom =
+            r#"[{"custom":{"to":"0x0000000000000000000000000000000000000001","calldata":"0x"}}]"#;
+        let mut calling = guest(custom, &returning(custom.len() as u64));
+        assert!(matches!(
+            calling.call("process", &signal()),
+            Err(ArenaError::WasmError(_))
+        ));
+    }
+}
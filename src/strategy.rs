@@ -1,25 +1,388 @@
+use alloy::primitives::B256;
 use async_trait::async_trait;
+use rand::rngs::StdRng;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
 
 use super::*;
+use crate::{config::Config, engine::action::Action, error::ArenaError};
+
+/// Combinators for assembling strategies from smaller ones.
+pub mod compose;
+
+pub use arena_macros::ArenaStrategy;
 
 /// Represents a strategy that can be run in an [`Arena`].
 #[async_trait]
-pub trait Strategy<V> {
+pub trait Strategy<V>: Send {
     /// Initialization function for ths strategy to be run upon simulation startup.
+    ///
+    /// `rng` is the strategy's own random number generator, seeded from the simulation seed or from
+    /// [`ArenaBuilder::with_strategy_seeded`]. Every [`Strategy::process`] call gets a generator of its step from the
+    /// same seed, so a resumed run draws like an uninterrupted one.
     async fn init(
-        &self,
+        &mut self,
         provider: AnvilProvider,
         signal: Signal,
         inspector: &mut Box<dyn Inspector<V>>,
         engine: Engine,
-    );
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError>;
 
     /// Processing function for the strategy to be run each simulation step.
     async fn process(
-        &self,
+        &mut self,
         provider: AnvilProvider,
         signal: Signal,
         inspector: &mut Box<dyn Inspector<V>>,
         engine: Engine,
-    );
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError>;
+
+    /// Processing function receiving a snapshot of every pool, called each step before [`Strategy::process`].
+    ///
+    /// Strategies acting across pools implement this instead of [`Strategy::process`]. It is called regardless of
+    /// [`Strategy::subscriptions`], and does nothing by default.
+    async fn process_market(
+        &mut self,
+        _provider: AnvilProvider,
+        _market: MarketSignal,
+        _inspector: &mut Box<dyn Inspector<V>>,
+        _engine: Engine,
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        Ok(())
+    }
+
+    /// Ids of the pools this strategy receives signals for, as returned by [`crate::engine::pool::pool_id`].
+    ///
+    /// Strategies subscribe to every pool by default.
+    fn subscriptions(&self) -> Option<Vec<B256>> {
+        None
+    }
+
+    /// Returns whether this strategy receives signals for the pool with id `pool`.
+    fn is_subscribed(&self, pool: &B256) -> bool {
+        self.subscriptions()
+            .is_none_or(|pools| pools.contains(pool))
+    }
+
+    /// Returns a serializable blob of the strategy's internal state, to be passed to [`Strategy::restore`].
+    ///
+    /// This is what [`crate::arena::Arena::checkpoint`] saves of the strategy. Strategies that cannot be snapshotted can
+    /// rely on the default, which returns an error so that a checkpoint is never written, or resumed, without their
+    /// state. Strategies without internal state return [`Value::Null`].
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        Err(ArenaError::Custom(
+            "strategy does not support snapshots".to_string(),
+        ))
+    }
+
+    /// Restores the strategy's internal state from a blob previously returned by [`Strategy::snapshot`].
+    ///
+    /// Returns an error by default, like [`Strategy::snapshot`].
+    fn restore(&mut self, _state: Value) -> Result<(), ArenaError> {
+        Err(ArenaError::Custom(
+            "strategy does not support snapshots".to_string(),
+        ))
+    }
+}
+
+/// Strategy that can be constructed from the parameters carried by a [`Config`].
+///
+/// This keeps the constants of an experiment out of its code, e.g. in the `[strategy.params]` table of a TOML file
+/// loaded with [`Config::with_strategy_params_file`].
+pub trait FromConfig: Sized {
+    /// Parameters deserialized from [`Config::strategy_params`].
+    type Params: DeserializeOwned;
+
+    /// Construct the strategy from its parameters.
+    fn from_params(params: Self::Params) -> Result<Self, ArenaError>;
+
+    /// Construct the strategy from the parameters carried by `config`.
+    fn from_config(config: &Config) -> Result<Self, ArenaError> {
+        let params = serde_json::from_value(config.strategy_params.clone())?;
+        Self::from_params(params)
+    }
+}
+
+/// Predicate deciding whether a [`Scheduled`] strategy acts at a given step.
+pub type SchedulePredicate = Box<dyn Fn(usize) -> bool + Send + Sync>;
+
+/// Wrapper around a [`Strategy`] that only processes on the steps accepted by a predicate.
+///
+/// The wrapped strategy is always initialized, and the feed and arbitrageur keep advancing on the steps it skips.
+pub struct Scheduled<S> {
+    /// The wrapped strategy.
+    pub inner: S,
+
+    predicate: SchedulePredicate,
+}
+
+impl<S> Scheduled<S> {
+    /// Process `inner` every `cadence` steps, starting with the first step.
+    pub fn every(inner: S, cadence: usize) -> Self {
+        let cadence = cadence.max(1);
+
+        Self::when(inner, move |step| step % cadence == 0)
+    }
+
+    /// Process `inner` on the steps for which `predicate` returns true.
+    pub fn when(inner: S, predicate: impl Fn(usize) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            inner,
+            predicate: Box::new(predicate),
+        }
+    }
+
+    /// Returns whether the wrapped strategy acts at `step`.
+    pub fn is_active(&self, step: usize) -> bool {
+        (self.predicate)(step)
+    }
+}
+
+#[async_trait]
+impl<V, S> Strategy<V> for Scheduled<S>
+where
+    S: Strategy<V> + Send,
+{
+    async fn init(
+        &mut self,
+        provider: AnvilProvider,
+        signal: Signal,
+        inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        self.inner
+            .init(provider, signal, inspector, engine, rng)
+            .await
+    }
+
+    async fn process(
+        &mut self,
+        provider: AnvilProvider,
+        signal: Signal,
+        inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        if signal.step.is_some_and(|step| !self.is_active(step)) {
+            return Ok(());
+        }
+
+        self.inner
+            .process(provider, signal, inspector, engine, rng)
+            .await
+    }
+
+    async fn process_market(
+        &mut self,
+        provider: AnvilProvider,
+        market: MarketSignal,
+        inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        if market.step.is_some_and(|step| !self.is_active(step)) {
+            return Ok(());
+        }
+
+        self.inner
+            .process_market(provider, market, inspector, engine, rng)
+            .await
+    }
+
+    fn subscriptions(&self) -> Option<Vec<B256>> {
+        self.inner.subscriptions()
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        self.inner.snapshot()
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        self.inner.restore(state)
+    }
+}
+
+/// Strategy that describes what it wants to do as a list of [`Action`]s instead of sending transactions itself.
+///
+/// Action strategies never see a provider, so they can be unit tested with hand-built signals. Wrap one in
+/// [`Actions`] to run it in an [`Arena`].
+pub trait ActionStrategy {
+    /// Actions to perform upon simulation startup.
+    fn init(&mut self, signal: &Signal) -> Result<Vec<Action>, ArenaError>;
+
+    /// Actions to perform each simulation step.
+    fn process(&mut self, signal: &Signal) -> Result<Vec<Action>, ArenaError>;
+
+    /// Hook data passed when modifying liquidity.
+    fn hook_data(&self) -> Bytes {
+        Bytes::new()
+    }
+}
+
+/// Adaptor running an [`ActionStrategy`] as a [`Strategy`], validating, executing and recording its actions through
+/// [`Engine::apply`].
+pub struct Actions<S>(pub S);
+
+#[async_trait]
+impl<V, S> Strategy<V> for Actions<S>
+where
+    S: ActionStrategy + Send,
+{
+    async fn init(
+        &mut self,
+        provider: AnvilProvider,
+        signal: Signal,
+        _inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        let actions = self.0.init(&signal)?;
+
+        engine
+            .apply(&actions, &signal, self.0.hook_data(), provider)
+            .await
+    }
+
+    async fn process(
+        &mut self,
+        provider: AnvilProvider,
+        signal: Signal,
+        _inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        _rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        let actions = self.0.process(&signal)?;
+
+        engine
+            .apply(&actions, &signal, self.0.hook_data(), provider)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{engine::position::Holdings, types::controller::ArenaController::PoolKey};
+
+    #[test]
+    fn test_schedules() {
+        let every = Scheduled::every((), 3);
+        assert_eq!(
+            (0..7)
+                .filter(|step| every.is_active(*step))
+                .collect::<Vec<_>>(),
+            vec![0, 3, 6]
+        );
+
+        let when = Scheduled::when((), |step| step >= 5);
+        assert!(!when.is_active(4));
+        assert!(when.is_active(5));
+    }
+
+    struct Buyer;
+
+    impl ActionStrategy for Buyer {
+        fn init(&mut self, _signal: &Signal) -> Result<Vec<Action>, ArenaError> {
+            Ok(vec![Action::AddLiquidity {
+                tick_lower: -60,
+                tick_upper: 60,
+                liquidity: 1000,
+            }])
+        }
+
+        fn process(&mut self, signal: &Signal) -> Result<Vec<Action>, ArenaError> {
+            if signal.current_value > 1.0 {
+                return Ok(Vec::new());
+            }
+
+            Ok(vec![Action::Swap {
+                zero_for_one: false,
+                amount_in: 10,
+            }])
+        }
+    }
+
+    #[test]
+    fn test_action_strategy() {
+        let mut signal = Signal::new(
+            Uint::ZERO,
+            Some(0),
+            Signed::ZERO,
+            Uint::ZERO,
+            Address::ZERO,
+            PoolKey {
+                currency0: Address::ZERO,
+                currency1: Address::ZERO,
+                fee: Uint::ZERO,
+                tickSpacing: Signed::try_from(60).unwrap(),
+                hooks: Address::ZERO,
+            },
+            Address::ZERO,
+            0.9,
+            Address::ZERO,
+        );
+
+        let mut strategy = Buyer;
+        let holdings = Holdings::default();
+
+        for action in strategy.init(&signal).unwrap() {
+            action.validate(&signal, &holdings).unwrap();
+        }
+        assert_eq!(strategy.process(&signal).unwrap().len(), 1);
+
+        signal.current_value = 1.1;
+        assert!(strategy.process(&signal).unwrap().is_empty());
+
+        signal.pool.tickSpacing = Signed::try_from(50).unwrap();
+        assert!(strategy.init(&signal).unwrap()[0]
+            .validate(&signal, &holdings)
+            .is_err());
+
+        let remove = Action::RemoveLiquidity {
+            tick_lower: -50,
+            tick_upper: 50,
+            liquidity: 1,
+        };
+        assert!(remove.validate(&signal, &holdings).is_err());
+    }
+
+    #[derive(ArenaStrategy, serde::Deserialize)]
+    #[arena(from_config)]
+    struct Holder {
+        liquidity: u128,
+    }
+
+    impl Holder {
+        async fn on_init(
+            &mut self,
+            _provider: AnvilProvider,
+            _signal: Signal,
+            _engine: Engine,
+        ) -> Result<(), ArenaError> {
+            Ok(())
+        }
+
+        async fn on_step(
+            &mut self,
+            _provider: AnvilProvider,
+            _signal: Signal,
+            _engine: Engine,
+        ) -> Result<(), ArenaError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_derive_strategy() {
+        let params = serde_json::from_value(serde_json::json!({ "liquidity": 5 })).unwrap();
+        let holder = Holder::from_params(params).unwrap();
+        assert_eq!(holder.liquidity, 5);
+
+        let strategy: Box<dyn Strategy<()>> = holder.boxed();
+        assert!(strategy.subscriptions().is_none());
+    }
 }
@@ -0,0 +1,303 @@
+use alloy::primitives::B256;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::*;
+
+/// Predicate over the current [`Signal`], used by [`Conditional`].
+pub type SignalPredicate = Box<dyn Fn(&Signal) -> bool + Send + Sync>;
+
+/// Union of pool subscriptions, which subscribes to every pool if any of them does.
+fn union(subscriptions: impl IntoIterator<Item = Option<Vec<B256>>>) -> Option<Vec<B256>> {
+    let mut pools = Vec::new();
+
+    for subscription in subscriptions {
+        for pool in subscription? {
+            if !pools.contains(&pool) {
+                pools.push(pool);
+            }
+        }
+    }
+
+    Some(pools)
+}
+
+/// Runs a list of strategies one after another, sharing the same wallet.
+///
+/// Execution stops at the first strategy that returns an error.
+pub struct Sequence<V>(pub Vec<Box<dyn Strategy<V> + Send>>);
+
+#[async_trait]
+impl<V> Strategy<V> for Sequence<V> {
+    async fn init(
+        &mut self,
+        provider: AnvilProvider,
+        signal: Signal,
+        inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        for strategy in self.0.iter_mut() {
+            strategy
+                .init(
+                    provider.clone(),
+                    signal.clone(),
+                    inspector,
+                    engine.clone(),
+                    rng,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn process(
+        &mut self,
+        provider: AnvilProvider,
+        signal: Signal,
+        inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        for strategy in self.0.iter_mut() {
+            strategy
+                .process(
+                    provider.clone(),
+                    signal.clone(),
+                    inspector,
+                    engine.clone(),
+                    rng,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn process_market(
+        &mut self,
+        provider: AnvilProvider,
+        market: MarketSignal,
+        inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        for strategy in self.0.iter_mut() {
+            strategy
+                .process_market(
+                    provider.clone(),
+                    market.clone(),
+                    inspector,
+                    engine.clone(),
+                    rng,
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    fn subscriptions(&self) -> Option<Vec<B256>> {
+        union(self.0.iter().map(|strategy| strategy.subscriptions()))
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        Ok(Value::Array(
+            self.0
+                .iter()
+                .map(|strategy| strategy.snapshot())
+                .collect::<Result<_, _>>()?,
+        ))
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        let states: Vec<Value> = serde_json::from_value(state)?;
+
+        for (strategy, state) in self.0.iter_mut().zip(states) {
+            strategy.restore(state)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Processes the first strategy when the predicate holds for the current signal, and the second otherwise.
+///
+/// Both strategies are initialized.
+pub struct Conditional<A, B>(pub SignalPredicate, pub A, pub B);
+
+impl<A, B> Conditional<A, B> {
+    /// Public constructor function for a new [`Conditional`] strategy.
+    pub fn new(predicate: impl Fn(&Signal) -> bool + Send + Sync + 'static, a: A, b: B) -> Self {
+        Self(Box::new(predicate), a, b)
+    }
+}
+
+#[async_trait]
+impl<V, A, B> Strategy<V> for Conditional<A, B>
+where
+    A: Strategy<V> + Send,
+    B: Strategy<V> + Send,
+{
+    async fn init(
+        &mut self,
+        provider: AnvilProvider,
+        signal: Signal,
+        inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        self.1
+            .init(
+                provider.clone(),
+                signal.clone(),
+                inspector,
+                engine.clone(),
+                rng,
+            )
+            .await?;
+        self.2.init(provider, signal, inspector, engine, rng).await
+    }
+
+    async fn process(
+        &mut self,
+        provider: AnvilProvider,
+        signal: Signal,
+        inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        if (self.0)(&signal) {
+            self.1
+                .process(provider, signal, inspector, engine, rng)
+                .await
+        } else {
+            self.2
+                .process(provider, signal, inspector, engine, rng)
+                .await
+        }
+    }
+
+    async fn process_market(
+        &mut self,
+        provider: AnvilProvider,
+        market: MarketSignal,
+        inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        // the predicate is evaluated on the first pool, matching single-pool simulations
+        if market.pools.first().is_some_and(|signal| (self.0)(signal)) {
+            self.1
+                .process_market(provider, market, inspector, engine, rng)
+                .await
+        } else {
+            self.2
+                .process_market(provider, market, inspector, engine, rng)
+                .await
+        }
+    }
+
+    fn subscriptions(&self) -> Option<Vec<B256>> {
+        union([self.1.subscriptions(), self.2.subscriptions()])
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        Ok(json!({ "a": self.1.snapshot()?, "b": self.2.snapshot()? }))
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        self.1.restore(state["a"].clone())?;
+        self.2.restore(state["b"].clone())
+    }
+}
+
+/// Forwards only every `n`-th call to `process` to the wrapped strategy, starting with the first.
+///
+/// Unlike [`Scheduled`], which acts on simulation steps, this counts the calls it receives, so it composes with
+/// [`Conditional`] to throttle a branch by how often it is taken.
+pub struct Throttled<S> {
+    /// The wrapped strategy.
+    pub inner: S,
+
+    /// Number of calls per forwarded call.
+    pub n: usize,
+
+    calls: usize,
+}
+
+impl<S> Throttled<S> {
+    /// Public constructor function for a new [`Throttled`] strategy.
+    pub fn new(inner: S, n: usize) -> Self {
+        Self {
+            inner,
+            n: n.max(1),
+            calls: 0,
+        }
+    }
+}
+
+#[async_trait]
+impl<V, S> Strategy<V> for Throttled<S>
+where
+    S: Strategy<V> + Send,
+{
+    async fn init(
+        &mut self,
+        provider: AnvilProvider,
+        signal: Signal,
+        inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        self.inner
+            .init(provider, signal, inspector, engine, rng)
+            .await
+    }
+
+    async fn process(
+        &mut self,
+        provider: AnvilProvider,
+        signal: Signal,
+        inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        let call = self.calls;
+        self.calls += 1;
+
+        if !call.is_multiple_of(self.n) {
+            return Ok(());
+        }
+
+        self.inner
+            .process(provider, signal, inspector, engine, rng)
+            .await
+    }
+    async fn process_market(
+        &mut self,
+        provider: AnvilProvider,
+        market: MarketSignal,
+        inspector: &mut Box<dyn Inspector<V>>,
+        engine: Engine,
+        rng: &mut StdRng,
+    ) -> Result<(), ArenaError> {
+        self.inner
+            .process_market(provider, market, inspector, engine, rng)
+            .await
+    }
+
+    fn subscriptions(&self) -> Option<Vec<B256>> {
+        self.inner.subscriptions()
+    }
+
+    fn snapshot(&self) -> Result<Value, ArenaError> {
+        Ok(json!({ "inner": self.inner.snapshot()?, "calls": self.calls }))
+    }
+
+    fn restore(&mut self, state: Value) -> Result<(), ArenaError> {
+        self.calls = serde_json::from_value(state["calls"].clone())?;
+        self.inner.restore(state["inner"].clone())
+    }
+}
@@ -0,0 +1,91 @@
+use alloy::primitives::{Address, U256};
+
+use crate::{
+    backend,
+    engine::{arbitrageur::Inventory, jit::JitReport, score::Scorecard, Engine},
+    error::ArenaError,
+    types::controller::ArenaController::{self, PoolKey},
+    AnvilProvider,
+};
+
+/// Contracts deployed for a pool.
+#[derive(Debug, Clone)]
+pub struct PoolDeployment {
+    /// Controller acting on the pool.
+    pub controller: Address,
+
+    /// `PoolManager` holding the pool.
+    pub manager: Address,
+
+    /// Fetcher reading the pool state.
+    pub fetcher: Address,
+
+    /// Key of the pool.
+    pub key: PoolKey,
+
+    /// Decimals of token0 and token1.
+    pub decimals: (u8, u8),
+}
+
+impl PoolDeployment {
+    /// Read the contracts deployed around the pool an [`Engine`] acts on.
+    pub(crate) async fn fetch(
+        engine: &Engine,
+        provider: AnvilProvider,
+    ) -> Result<Self, ArenaError> {
+        let state = backend::read(
+            &provider,
+            engine.controller,
+            ArenaController::constructSignalCall {},
+        )
+        .await?
+        ._0;
+
+        Ok(Self {
+            controller: engine.controller,
+            manager: state.manager,
+            fetcher: state.fetcher,
+            key: engine.pool_key(provider).await?,
+            decimals: engine.decimals,
+        })
+    }
+}
+
+/// Balances of a strategy wallet at the end of a run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletBalances {
+    /// Address of the wallet.
+    pub owner: Address,
+
+    /// Ether balance, in wei.
+    pub ether: U256,
+
+    /// Balances of the tokens of every pool, indexed like [`RunSummary::pools`].
+    pub tokens: Vec<Inventory>,
+}
+
+/// Outcome of [`crate::arena::Arena::run`].
+#[derive(Debug, Clone)]
+pub struct RunSummary {
+    /// Number of steps executed by the run, which excludes the steps before a resumed checkpoint.
+    pub steps: usize,
+
+    /// Whether the run stopped early through an [`crate::arena::Interrupt`].
+    pub interrupted: bool,
+
+    /// Why the run ended early, if one of [`crate::Config::stop_conditions`] was met.
+    pub stopped: Option<String>,
+
+    /// Contracts deployed for every pool, the pool of the [`crate::Config`] first.
+    pub pools: Vec<PoolDeployment>,
+
+    /// Final balances of every strategy wallet, in the order strategies were added to the arena.
+    pub balances: Vec<WalletBalances>,
+
+    /// Performance metrics of every strategy.
+    pub scorecard: Scorecard,
+
+    /// Profit of the just-in-time liquidity provider and the fees it took from passive liquidity providers, if the
+    /// arena has one, see [`crate::arena::ArenaBuilder::with_jit_liquidity`].
+    pub jit: Option<JitReport>,
+}
@@ -0,0 +1,310 @@
+use std::{collections::BTreeMap, fs::File, future::Future, path::Path};
+
+use alloy::primitives::Uint;
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{error::ArenaError, summary::RunSummary, Config};
+
+/// Name of the parameter setting [`Config::pool_fee`].
+pub const FEE_TIER: &str = "fee_tier";
+
+/// Name of the parameter holding the volatility of the feed, for the scenario to build its feed from.
+pub const VOLATILITY: &str = "volatility";
+
+/// Prefix of the parameters setting a key of [`Config::strategy_params`].
+pub const STRATEGY_PARAM_PREFIX: &str = "strategy.";
+
+/// Point of a parameter grid, the value of every swept parameter keyed by its name.
+pub type Params = BTreeMap<String, Value>;
+
+/// Grid search over simulation parameters, running a scenario at every point of the Cartesian product of the values
+/// of each parameter.
+pub struct Sweep {
+    grid: Vec<(String, Vec<Value>)>,
+
+    /// Number of points run concurrently.
+    pub parallelism: usize,
+}
+
+impl Default for Sweep {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sweep {
+    /// Public constructor function for a new [`Sweep`] over no parameters, running points one at a time.
+    pub fn new() -> Self {
+        Self {
+            grid: Vec::new(),
+            parallelism: 1,
+        }
+    }
+
+    /// Sweep the parameter `name` over `values`.
+    ///
+    /// Parameters other than [`FEE_TIER`] and those prefixed with [`STRATEGY_PARAM_PREFIX`] are not applied to the
+    /// config, and are read by the scenario instead.
+    pub fn with_param<T: Into<Value>>(
+        mut self,
+        name: &str,
+        values: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.grid.push((
+            name.to_string(),
+            values.into_iter().map(Into::into).collect(),
+        ));
+        self
+    }
+
+    /// Sweep the pool fee over `fees`, in hundredths of a bip.
+    pub fn with_fee_tiers(self, fees: impl IntoIterator<Item = u32>) -> Self {
+        self.with_param(FEE_TIER, fees)
+    }
+
+    /// Sweep the volatility of the feed over `volatilities`, available to the scenario as [`VOLATILITY`].
+    pub fn with_volatilities(self, volatilities: impl IntoIterator<Item = f64>) -> Self {
+        self.with_param(VOLATILITY, volatilities)
+    }
+
+    /// Sweep the key `key` of the strategy parameters over `values`.
+    pub fn with_strategy_param<T: Into<Value>>(
+        self,
+        key: &str,
+        values: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.with_param(&format!("{STRATEGY_PARAM_PREFIX}{key}"), values)
+    }
+
+    /// Run up to `parallelism` points concurrently, each on its own arena.
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism.max(1);
+        self
+    }
+
+    /// Returns every point of the grid, the last parameter varying fastest.
+    pub fn points(&self) -> Vec<Params> {
+        self.grid
+            .iter()
+            .fold(vec![Params::new()], |points, (name, values)| {
+                points
+                    .iter()
+                    .flat_map(|point| {
+                        values.iter().map(move |value| {
+                            let mut point = point.clone();
+                            point.insert(name.clone(), value.clone());
+                            point
+                        })
+                    })
+                    .collect()
+            })
+    }
+
+    /// Apply the fee tier and strategy parameters of `params` to `config`.
+    pub fn apply(params: &Params, mut config: Config) -> Result<Config, ArenaError> {
+        for (name, value) in params {
+            if name == FEE_TIER {
+                let fee = value
+                    .as_u64()
+                    .ok_or_else(|| ArenaError::Custom(format!("invalid fee tier {value}")))?;
+                config.pool_fee = Uint::try_from(fee)
+                    .map_err(|_| ArenaError::Custom(format!("invalid fee tier {value}")))?;
+            } else if let Some(key) = name.strip_prefix(STRATEGY_PARAM_PREFIX) {
+                if !config.strategy_params.is_object() {
+                    config.strategy_params = Value::Object(Default::default());
+                }
+                config.strategy_params[key] = value.clone();
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Run `scenario` at every point of the grid, with the config returned by `base` and the point applied to it, see
+    /// [`Sweep::apply`].
+    ///
+    /// The scenario builds an arena for the point, e.g. with a feed of the swept [`VOLATILITY`], and runs the config on
+    /// it. A point whose scenario fails is reported in [`SweepResults::failures`] rather than ending the sweep.
+    pub async fn run<F, Fut>(&self, base: impl Fn() -> Config, scenario: F) -> SweepResults
+    where
+        F: Fn(Params, Config) -> Fut,
+        Fut: Future<Output = Result<RunSummary, ArenaError>>,
+    {
+        let runs = self.points().into_iter().enumerate().map(|(run, params)| {
+            let config = Self::apply(&params, base());
+            let scenario = &scenario;
+            async move {
+                let outcome = match config {
+                    Ok(config) => scenario(params.clone(), config).await,
+                    Err(e) => Err(e),
+                };
+                (run, params, outcome)
+            }
+        });
+
+        let mut outcomes: Vec<_> = stream::iter(runs)
+            .buffer_unordered(self.parallelism)
+            .collect()
+            .await;
+        outcomes.sort_by_key(|(run, _, _)| *run);
+
+        let mut results = SweepResults {
+            params: self.grid.iter().map(|(name, _)| name.clone()).collect(),
+            ..SweepResults::default()
+        };
+        for (run, params, outcome) in outcomes {
+            match outcome {
+                Ok(summary) => results.push(run, &params, &summary),
+                Err(e) => results.failures.push(SweepFailure {
+                    run,
+                    params,
+                    error: e.to_string(),
+                }),
+            }
+        }
+
+        results
+    }
+}
+
+/// Value of a metric of one strategy at one point of a [`Sweep`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SweepRow {
+    /// Index of the point in [`Sweep::points`].
+    pub run: usize,
+
+    /// Parameters of the point.
+    pub params: Params,
+
+    /// Index of the strategy, or `None` for metrics of the whole run.
+    pub strategy: Option<usize>,
+
+    /// Name of the metric.
+    pub metric: String,
+
+    /// Value of the metric.
+    pub value: f64,
+}
+
+/// Point of a [`Sweep`] whose scenario failed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SweepFailure {
+    /// Index of the point in [`Sweep::points`].
+    pub run: usize,
+
+    /// Parameters of the point.
+    pub params: Params,
+
+    /// Error returned by the scenario.
+    pub error: String,
+}
+
+/// Results of a [`Sweep`], in long format: one row per point, strategy and metric.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SweepResults {
+    /// Names of the swept parameters.
+    pub params: Vec<String>,
+
+    /// Metrics of every successful point, ordered by point.
+    pub rows: Vec<SweepRow>,
+
+    /// Points whose scenario failed.
+    pub failures: Vec<SweepFailure>,
+}
+
+impl SweepResults {
+    /// Add the metrics of the run of point `run`.
+    pub fn push(&mut self, run: usize, params: &Params, summary: &RunSummary) {
+        let mut row = |strategy, metric: &str, value| {
+            self.rows.push(SweepRow {
+                run,
+                params: params.clone(),
+                strategy,
+                metric: metric.to_string(),
+                value,
+            })
+        };
+
+        row(None, "steps", summary.steps as f64);
+        for (idx, score) in summary.scorecard.strategies.iter().enumerate() {
+            row(Some(idx), "total_pnl", score.total_pnl);
+            row(Some(idx), "max_drawdown", score.max_drawdown);
+            row(Some(idx), "sharpe", score.sharpe);
+            row(Some(idx), "fee_income", score.fee_income);
+            row(Some(idx), "impermanent_loss", score.impermanent_loss);
+            row(Some(idx), "gas_used", score.gas_used as f64);
+        }
+    }
+
+    /// Write the rows to `path` as CSV, with a column per swept parameter followed by `run`, `strategy`, `metric` and
+    /// `value`.
+    pub fn write_csv(&self, path: impl AsRef<Path>) -> Result<(), ArenaError> {
+        let mut writer = csv::Writer::from_writer(File::create(path)?);
+
+        let mut header = self.params.clone();
+        header.extend(["run", "strategy", "metric", "value"].map(String::from));
+        writer.write_record(&header)?;
+
+        for row in &self.rows {
+            let mut record: Vec<String> = self
+                .params
+                .iter()
+                .map(|name| match row.params.get(name) {
+                    Some(Value::String(value)) => value.clone(),
+                    Some(value) => value.to_string(),
+                    None => String::new(),
+                })
+                .collect();
+            record.push(row.run.to_string());
+            record.push(row.strategy.map(|idx| idx.to_string()).unwrap_or_default());
+            record.push(row.metric.clone());
+            record.push(row.value.to_string());
+            writer.write_record(&record)?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, Bytes, Signed, U256};
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_sweep_points() {
+        let sweep = Sweep::new()
+            .with_fee_tiers([500, 3000])
+            .with_volatilities([0.1, 0.2, 0.4])
+            .with_strategy_param("width", [60]);
+
+        let points = sweep.points();
+        assert_eq!(points.len(), 6);
+        assert_eq!(points[1][FEE_TIER], 500);
+        assert_eq!(points[1][VOLATILITY], 0.2);
+        assert_eq!(points[3][FEE_TIER], 3000);
+        assert_eq!(points[3][VOLATILITY], 0.1);
+
+        let config = Sweep::apply(
+            &points[3],
+            Config::new(
+                10,
+                U256::ZERO,
+                Signed::try_from(2).unwrap(),
+                Bytes::new(),
+                Uint::from(79228162514264337593543950336_u128),
+                Uint::from(0),
+                U256::from(1),
+                Address::ZERO,
+            ),
+        )
+        .unwrap();
+        assert_eq!(config.pool_fee, Uint::from(3000));
+        assert_eq!(config.strategy_params, json!({ "width": 60 }));
+    }
+}
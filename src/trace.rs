@@ -0,0 +1,578 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+};
+
+use alloy::{
+    eips::BlockNumberOrTag,
+    primitives::{Address, Bytes, B256, U256},
+    providers::Provider,
+    transports::Transport,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{error::ArenaError, types::manager::IPoolManager};
+
+/// Transactions whose call traces are recorded by an arena built with
+/// [`crate::arena::ArenaBuilder::with_tracing`]. A transaction is traced if it matches any of the criteria.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceSelection {
+    /// Trace transactions that reverted. Transactions participants send through the [`crate::engine::Engine`] are
+    /// mined even if they revert, see [`crate::engine::REVERT_GAS_LIMIT`].
+    pub reverts: bool,
+
+    /// Trace transactions swapping on, or modifying the liquidity of, a pool with hooks, which call into its hooks.
+    pub hook_calls: bool,
+
+    /// Trace every transaction sent by these components, named as in
+    /// [`crate::replay::RecordedTransaction::component`], e.g. `strategy 0`.
+    pub components: Vec<String>,
+}
+
+impl TraceSelection {
+    /// Public constructor function for a new [`TraceSelection`] selecting no transactions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trace transactions that reverted.
+    pub fn with_reverts(mut self) -> Self {
+        self.reverts = true;
+        self
+    }
+
+    /// Trace transactions calling into the hooks of a pool.
+    pub fn with_hook_calls(mut self) -> Self {
+        self.hook_calls = true;
+        self
+    }
+
+    /// Trace every transaction sent by `component`.
+    pub fn with_component(mut self, component: &str) -> Self {
+        self.components.push(component.to_string());
+        self
+    }
+}
+
+/// A call of a transaction trace, with the calls it made, as reported by the `callTracer` of the node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    /// Kind of call, e.g. `CALL`, `DELEGATECALL`, `STATICCALL` or `CREATE2`.
+    #[serde(rename = "type")]
+    pub kind: String,
+
+    /// Caller.
+    pub from: Address,
+
+    /// Callee, or the created contract.
+    #[serde(default)]
+    pub to: Option<Address>,
+
+    /// Ether sent with the call.
+    #[serde(default)]
+    pub value: Option<U256>,
+
+    /// Gas made available to the call.
+    #[serde(default)]
+    pub gas: U256,
+
+    /// Gas used by the call.
+    #[serde(default)]
+    pub gas_used: U256,
+
+    /// Calldata of the call.
+    #[serde(default)]
+    pub input: Bytes,
+
+    /// Data returned by the call, or its revert data.
+    #[serde(default)]
+    pub output: Option<Bytes>,
+
+    /// Error the call failed with, e.g. `execution reverted`.
+    #[serde(default)]
+    pub error: Option<String>,
+
+    /// Decoded revert reason, if the call reverted with a string.
+    #[serde(default)]
+    pub revert_reason: Option<String>,
+
+    /// Calls made by the call, in order.
+    #[serde(default)]
+    pub calls: Vec<CallFrame>,
+}
+
+impl CallFrame {
+    /// Returns whether the call failed.
+    pub fn failed(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Returns the 4-byte selector of the call, if its calldata has one.
+    pub fn selector(&self) -> Option<[u8; 4]> {
+        self.input
+            .get(..4)
+            .map(|selector| selector.try_into().unwrap())
+    }
+
+    /// Returns the call and every call below it, depth first in the order they were made.
+    pub fn frames(&self) -> Vec<&CallFrame> {
+        let mut frames = vec![self];
+        for call in &self.calls {
+            frames.extend(call.frames());
+        }
+
+        frames
+    }
+
+    /// Returns the calls to `address` at any depth, in the order they were made, e.g. the callbacks into a hook.
+    pub fn calls_to(&self, address: Address) -> Vec<&CallFrame> {
+        self.frames()
+            .into_iter()
+            .filter(|frame| frame.to == Some(address))
+            .collect()
+    }
+
+    /// Returns the deepest failed call, where a revert originated.
+    pub fn failure(&self) -> Option<&CallFrame> {
+        if !self.failed() {
+            return None;
+        }
+
+        self.calls
+            .iter()
+            .rev()
+            .find_map(|call| call.failure())
+            .or(Some(self))
+    }
+}
+
+/// Call trace of a transaction mined during a run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionTrace {
+    /// Step the transaction was mined at.
+    pub step: usize,
+
+    /// Block the transaction was mined in.
+    pub block: u64,
+
+    /// Hash of the transaction.
+    pub hash: B256,
+
+    /// Sender of the transaction.
+    pub from: Address,
+
+    /// Simulation component that sent the transaction, named as in
+    /// [`crate::replay::RecordedTransaction::component`].
+    pub component: String,
+
+    /// Whether the transaction succeeded.
+    pub status: bool,
+
+    /// Call trace of the transaction.
+    pub trace: CallFrame,
+}
+
+/// Call traces of the transactions of a run selected by a [`TraceSelection`], recorded by an arena built with
+/// [`crate::arena::ArenaBuilder::with_tracing`].
+///
+/// Traces are taken with the `debug_traceTransaction` method of the node once each step is mined, so a step that
+/// behaved unexpectedly can be inspected call by call without rerunning it under a debugger. A step failing on a
+/// reverted transaction has the blocks it mined traced before the run returns its error, so the revert is in the log.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceLog {
+    /// Transactions traced.
+    pub selection: TraceSelection,
+
+    /// Traces of the last run, in the order the transactions were mined.
+    pub traces: Vec<TransactionTrace>,
+
+    /// Step whose blocks are traced next.
+    #[serde(skip)]
+    pub(crate) step: usize,
+
+    /// Latest block traced.
+    #[serde(skip)]
+    pub(crate) traced_block: u64,
+
+    /// Ids of the pools with hooks, whose swaps and liquidity changes call into them.
+    #[serde(skip)]
+    pub(crate) hooked: HashSet<B256>,
+}
+
+impl TraceLog {
+    /// Public constructor function for a new, empty [`TraceLog`] tracing the transactions matching `selection`.
+    pub fn new(selection: TraceSelection) -> Self {
+        Self {
+            selection,
+            ..Self::default()
+        }
+    }
+
+    /// Forget the traces of the previous run and start tracing after `block`, attributing swaps and liquidity changes
+    /// on the pools of `hooked` to hooks.
+    pub(crate) fn start(&mut self, block: u64, hooked: HashSet<B256>) {
+        self.traces.clear();
+        self.step = 0;
+        self.traced_block = block;
+        self.hooked = hooked;
+    }
+
+    /// Write the log to `path` as JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ArenaError> {
+        fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Read a log previously written with [`TraceLog::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ArenaError> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// Returns the traces of the transactions mined at `step`, in order.
+    pub fn traces_at(&self, step: usize) -> impl Iterator<Item = &TransactionTrace> {
+        self.traces.iter().filter(move |trace| trace.step == step)
+    }
+
+    /// Returns the traces of the transactions that reverted, in order.
+    pub fn reverts(&self) -> impl Iterator<Item = &TransactionTrace> {
+        self.traces.iter().filter(|trace| !trace.status)
+    }
+
+    /// Trace the selected transactions of the blocks mined since the latest block traced, as mined at the current step,
+    /// attributing transactions to components by sender.
+    pub(crate) async fn trace_since<T: Transport + Clone, P: Provider<T>>(
+        &mut self,
+        provider: &P,
+        components: &HashMap<Address, String>,
+    ) -> Result<(), ArenaError> {
+        let latest = provider.get_block_number().await?;
+
+        for number in self.traced_block + 1..=latest {
+            let receipts = provider
+                .get_block_receipts(BlockNumberOrTag::Number(number).into())
+                .await?
+                .unwrap_or_default();
+
+            for receipt in receipts {
+                let component = components
+                    .get(&receipt.from)
+                    .cloned()
+                    .unwrap_or_else(|| receipt.from.to_string());
+                let hook_call = self.selection.hook_calls
+                    && receipt.inner.logs().iter().any(|log| {
+                        let id = if let Ok(swap) = log.log_decode::<IPoolManager::Swap>() {
+                            swap.inner.data.id
+                        } else if let Ok(modification) =
+                            log.log_decode::<IPoolManager::ModifyLiquidity>()
+                        {
+                            modification.inner.data.id
+                        } else {
+                            return false;
+                        };
+
+                        self.hooked.contains(&id)
+                    });
+
+                if !(self.selection.reverts && !receipt.status()
+                    || hook_call
+                    || self.selection.components.contains(&component))
+                {
+                    continue;
+                }
+
+                let trace = provider
+                    .client()
+                    .request(
+                        "debug_traceTransaction",
+                        (receipt.transaction_hash, json!({ "tracer": "callTracer" })),
+                    )
+                    .await?;
+
+                self.traces.push(TransactionTrace {
+                    step: self.step,
+                    block: number,
+                    hash: receipt.transaction_hash,
+                    from: receipt.from,
+                    component,
+                    status: receipt.status(),
+                    trace,
+                });
+            }
+            self.traced_block = number;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use alloy::{
+        primitives::{address, Signed, Uint},
+        providers::ProviderBuilder,
+        sol_types::SolEvent,
+    };
+    use serde_json::Value;
+
+    use super::*;
+
+    /// Serve the JSON-RPC requests of one test on a local port through `respond`, returning the url of the node.
+    fn serve(respond: fn(&str, &Value) -> Value) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                thread::spawn(move || {
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    loop {
+                        let mut length = 0;
+                        let mut line = String::new();
+                        while reader.read_line(&mut line).unwrap_or(0) > 2 {
+                            if let Some(value) = line.to_lowercase().strip_prefix("content-length:")
+                            {
+                                length = value.trim().parse().unwrap();
+                            }
+                            line.clear();
+                        }
+                        if length == 0 {
+                            return;
+                        }
+
+                        let mut body = vec![0; length];
+                        reader.read_exact(&mut body).unwrap();
+                        let request: Value = serde_json::from_slice(&body).unwrap();
+                        let response = json!({
+                            "jsonrpc": "2.0",
+                            "id": request["id"],
+                            "result": respond(request["method"].as_str().unwrap(), &request["params"]),
+                        })
+                        .to_string();
+
+                        write!(
+                            stream,
+                            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{response}",
+                            response.len()
+                        )
+                        .unwrap();
+                    }
+                });
+            }
+        });
+
+        url
+    }
+
+    /// Returns a receipt of the transaction `hash` from `from`, mined in `block` with `logs`.
+    fn receipt(hash: B256, block: u64, from: Address, status: bool, logs: Vec<Value>) -> Value {
+        json!({
+            "transactionHash": hash,
+            "transactionIndex": "0x0",
+            "blockHash": B256::with_last_byte(block as u8),
+            "blockNumber": format!("{block:#x}"),
+            "from": from,
+            "to": Address::repeat_byte(0xee),
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x1",
+            "contractAddress": null,
+            "logs": logs,
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "type": "0x2",
+            "status": if status { "0x1" } else { "0x0" },
+        })
+    }
+
+    /// Answers for a node whose block 1 holds a revert of `strategy 0` and a transaction of the arbitrageur, and
+    /// whose block 2 holds a swap of `agent 0` on a pool with hooks.
+    fn node(method: &str, params: &Value) -> Value {
+        match method {
+            "eth_blockNumber" => json!("0x2"),
+            "eth_getBlockReceipts" => match params[0].as_str().unwrap() {
+                "0x1" => json!([
+                    receipt(
+                        B256::repeat_byte(1),
+                        1,
+                        Address::repeat_byte(1),
+                        false,
+                        vec![]
+                    ),
+                    receipt(
+                        B256::repeat_byte(2),
+                        1,
+                        Address::repeat_byte(2),
+                        true,
+                        vec![]
+                    ),
+                ]),
+                _ => {
+                    let swap = IPoolManager::Swap {
+                        id: B256::repeat_byte(0xaa),
+                        sender: Address::repeat_byte(0xee),
+                        amount0: -1,
+                        amount1: 1,
+                        sqrtPriceX96: Uint::from(1),
+                        liquidity: 1,
+                        tick: Signed::ZERO,
+                        fee: Uint::from(3000),
+                    }
+                    .encode_log_data();
+                    let log = json!({
+                        "address": Address::repeat_byte(0xdd),
+                        "topics": swap.topics(),
+                        "data": swap.data,
+                        "blockHash": B256::with_last_byte(2),
+                        "blockNumber": "0x2",
+                        "transactionHash": B256::repeat_byte(3),
+                        "transactionIndex": "0x0",
+                        "logIndex": "0x0",
+                        "removed": false,
+                    });
+                    json!([receipt(
+                        B256::repeat_byte(3),
+                        2,
+                        Address::repeat_byte(3),
+                        true,
+                        vec![log]
+                    )])
+                }
+            },
+            "debug_traceTransaction" => json!({
+                "type": "CALL",
+                "from": Address::repeat_byte(1),
+                "to": Address::repeat_byte(0xee),
+                "input": params[0],
+                "error": if params[0] == json!(B256::repeat_byte(1)) { json!("execution reverted") } else { Value::Null },
+            }),
+            method => panic!("unexpected request {method}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_trace_since() {
+        let provider = ProviderBuilder::new().on_http(serve(node).parse().unwrap());
+        let components: HashMap<Address, String> = [
+            (Address::repeat_byte(1), "strategy 0".to_string()),
+            (Address::repeat_byte(2), "arbitrageur".to_string()),
+            (Address::repeat_byte(3), "agent 0".to_string()),
+        ]
+        .into_iter()
+        .collect();
+
+        // the revert and the swap on the pool with hooks are traced, as mined at the current step
+        let mut log = TraceLog::new(TraceSelection::new().with_reverts().with_hook_calls());
+        log.start(0, [B256::repeat_byte(0xaa)].into_iter().collect());
+        log.step = 4;
+        log.trace_since(&provider, &components).await.unwrap();
+
+        let traced: Vec<(&str, bool, usize, u64)> = log
+            .traces
+            .iter()
+            .map(|trace| {
+                (
+                    trace.component.as_str(),
+                    trace.status,
+                    trace.step,
+                    trace.block,
+                )
+            })
+            .collect();
+        assert_eq!(
+            traced,
+            vec![("strategy 0", false, 4, 1), ("agent 0", true, 4, 2)]
+        );
+        assert!(log.reverts().all(|trace| trace.trace.failed()));
+        assert_eq!(log.traced_block, 2);
+
+        // blocks already traced are not traced again
+        log.trace_since(&provider, &components).await.unwrap();
+        assert_eq!(log.traces.len(), 2);
+
+        // a swap on a pool without hooks is no hook call, while selected components are traced whatever they did
+        let mut log = TraceLog::new(
+            TraceSelection::new()
+                .with_hook_calls()
+                .with_component("arbitrageur"),
+        );
+        log.start(0, HashSet::new());
+        log.trace_since(&provider, &components).await.unwrap();
+        assert_eq!(log.traces.len(), 1);
+        assert_eq!(log.traces[0].hash, B256::repeat_byte(2));
+    }
+
+    #[test]
+    fn test_trace_log() {
+        let hook = address!("0000000000000000000000000000000000000a80");
+        let trace: CallFrame = serde_json::from_value(json!({
+            "type": "CALL",
+            "from": "0x0000000000000000000000000000000000000001",
+            "to": "0x0000000000000000000000000000000000000002",
+            "value": "0x0",
+            "gas": "0x1e8480",
+            "gasUsed": "0x5208",
+            "input": "0xf3cd914c",
+            "output": "0x08c379a0",
+            "error": "execution reverted",
+            "calls": [
+                {
+                    "type": "STATICCALL",
+                    "from": "0x0000000000000000000000000000000000000002",
+                    "to": "0x0000000000000000000000000000000000000003",
+                    "gas": "0x100",
+                    "gasUsed": "0x10",
+                    "input": "0x"
+                },
+                {
+                    "type": "CALL",
+                    "from": "0x0000000000000000000000000000000000000002",
+                    "to": hook,
+                    "gas": "0x100",
+                    "gasUsed": "0x100",
+                    "input": "0x575e24b4",
+                    "error": "execution reverted",
+                    "revertReason": "price out of bounds"
+                }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(trace.frames().len(), 3);
+        assert_eq!(trace.selector(), Some([0xf3, 0xcd, 0x91, 0x4c]));
+        assert_eq!(trace.calls_to(hook).len(), 1);
+        assert_eq!(
+            trace.failure().unwrap().revert_reason.as_deref(),
+            Some("price out of bounds")
+        );
+        assert!(trace.calls[0].failure().is_none());
+
+        let mut log = TraceLog::new(TraceSelection::new().with_reverts());
+        log.traces.push(TransactionTrace {
+            step: 3,
+            block: 10,
+            hash: B256::repeat_byte(1),
+            from: Address::repeat_byte(1),
+            component: "strategy 0".to_string(),
+            status: false,
+            trace,
+        });
+
+        let path = std::env::temp_dir().join(format!("arena-trace-{}.json", std::process::id()));
+        log.save(&path).unwrap();
+        let loaded = TraceLog::load(&path).unwrap();
+        assert_eq!(loaded, log);
+        assert_eq!(loaded.traces_at(3).count(), 1);
+        assert_eq!(loaded.reverts().count(), 1);
+
+        fs::remove_file(path).unwrap();
+    }
+}